@@ -6,8 +6,14 @@
  */
 
 import WebSocket from 'ws';
-
-const IPC_URL = 'ws://127.0.0.1:17432';
+import { readFileSync } from 'fs';
+import { homedir } from 'os';
+import { join } from 'path';
+
+const DEFAULT_IPC_PORT = 17432;
+const IPC_TOKEN_PATH = join(homedir(), '.sidstack', 'ipc-token');
+// Written by the app at startup with the ports it listens on
+const PORTS_PATH = join(homedir(), '.sidstack', 'ports.json');
 const REQUEST_TIMEOUT = 10000; // 10 seconds
 
 interface IpcRequest {
@@ -191,7 +197,7 @@ class TeamIpcClient {
         this.ws = null;
       }
 
-      const ws = new WebSocket(IPC_URL);
+      const ws = new WebSocket(ipcUrl());
 
       const connectTimeout = setTimeout(() => {
         ws.close();
@@ -201,8 +207,23 @@ class TeamIpcClient {
       ws.on('open', () => {
         clearTimeout(connectTimeout);
         this.ws = ws;
-        this.connecting = null;
-        resolve();
+        // Authenticate before any other request
+        this.send('auth.hello', {
+          token: readIpcToken(),
+          role: process.env.SIDSTACK_AGENT_ROLE || 'mcp',
+          // Team members act as themselves (set when the app launches them)
+          client_id: process.env.SIDSTACK_MEMBER_ID,
+        }).then(
+          () => {
+            this.connecting = null;
+            resolve();
+          },
+          (error: Error) => {
+            this.connecting = null;
+            ws.close();
+            reject(new Error(`Agent Manager rejected authentication: ${error.message}`));
+          }
+        );
       });
 
       ws.on('error', (error) => {
@@ -243,7 +264,10 @@ class TeamIpcClient {
    */
   async request(method: string, params?: Record<string, unknown>): Promise<unknown> {
     await this.ensureConnected();
+    return this.send(method, params);
+  }
 
+  private send(method: string, params?: Record<string, unknown>): Promise<unknown> {
     const id = `team-${++this.messageCounter}-${Date.now()}`;
     const request: IpcRequest = { id, method, params };
 
@@ -270,6 +294,34 @@ class TeamIpcClient {
   }
 }
 
+/**
+ * Read the IPC auth token written by the Agent Manager at startup
+ */
+function readIpcToken(): string {
+  try {
+    return readFileSync(IPC_TOKEN_PATH, 'utf-8').trim();
+  } catch {
+    return '';
+  }
+}
+
+/**
+ * URL of the Agent Manager's IPC server, from the ports file it writes at
+ * startup (the default port if there is none)
+ */
+function ipcUrl(): string {
+  let port = DEFAULT_IPC_PORT;
+  try {
+    const ports = JSON.parse(readFileSync(PORTS_PATH, 'utf-8')) as { ipc?: number };
+    if (typeof ports.ipc === 'number') {
+      port = ports.ipc;
+    }
+  } catch {
+    // No ports file: the app predates it or hasn't started yet
+  }
+  return `ws://127.0.0.1:${port}`;
+}
+
 // Singleton instance
 const client = new TeamIpcClient();
 
@@ -357,8 +409,14 @@ export async function updateMemberTask(
   phase?: string,
   progress?: number
 ): Promise<void> {
-  await client.request('team_update_member_task', {
-    projectPath, teamId, memberId, taskId, specId, phase, progress
+  await client.request('team.updateMemberTask', {
+    project_path: projectPath,
+    team_id: teamId,
+    member_id: memberId,
+    task_id: taskId,
+    spec_id: specId,
+    phase,
+    progress,
   });
 }
 
@@ -366,7 +424,7 @@ export async function getMembers(
   projectPath: string,
   teamId: string
 ): Promise<MemberWithState[]> {
-  const result = await client.request('team_get_members', { projectPath, teamId });
+  const result = await client.request('team.getMembers', { project_path: projectPath, team_id: teamId });
   return result as MemberWithState[];
 }
 
@@ -437,5 +495,5 @@ export async function memberHeartbeat(
   teamId: string,
   memberId: string
 ): Promise<void> {
-  await client.request('team_member_heartbeat', { projectPath, teamId, memberId });
+  await client.request('team.heartbeat', { project_path: projectPath, team_id: teamId, member_id: memberId });
 }
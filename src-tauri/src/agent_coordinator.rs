@@ -9,6 +9,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::{RwLock, broadcast};
@@ -133,7 +134,8 @@ pub struct AgentCoordinator {
     message_queue: RwLock<Vec<AgentMessage>>,
     message_tx: broadcast::Sender<AgentMessage>,
     orchestrator_id: RwLock<Option<String>>,
-    max_concurrent_workers: usize,
+    /// Changed live when config.yaml changes (see `config::watch`)
+    max_concurrent_workers: AtomicUsize,
 }
 
 impl AgentCoordinator {
@@ -144,10 +146,15 @@ impl AgentCoordinator {
             message_queue: RwLock::new(Vec::new()),
             message_tx,
             orchestrator_id: RwLock::new(None),
-            max_concurrent_workers,
+            max_concurrent_workers: AtomicUsize::new(max_concurrent_workers),
         }
     }
 
+    /// Change the worker limit; workers already registered are kept
+    pub fn set_max_workers(&self, max_workers: usize) {
+        self.max_concurrent_workers.store(max_workers, Ordering::Relaxed);
+    }
+
     /// Register an agent with the coordinator
     pub async fn register_agent(
         &self,
@@ -172,10 +179,11 @@ impl AgentCoordinator {
                 .values()
                 .filter(|a| matches!(a.role, AgentRole::Worker | AgentRole::Specialist(_)))
                 .count();
-            if worker_count >= self.max_concurrent_workers {
+            let max_workers = self.max_concurrent_workers.load(Ordering::Relaxed);
+            if worker_count >= max_workers {
                 return Err(format!(
                     "Maximum concurrent workers ({}) reached",
-                    self.max_concurrent_workers
+                    max_workers
                 ));
             }
         }
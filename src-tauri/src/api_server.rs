@@ -9,7 +9,11 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 
-const API_SERVER_PORT: u16 = 19432;
+use crate::process_reaper;
+use crate::session_tracker::SharedSessionTracker;
+
+/// Name the API server is tracked under for orphan cleanup
+const SERVICE_NAME: &str = "api-server";
 
 /// Shared state for the API server process
 pub struct ApiServerState {
@@ -28,7 +32,7 @@ pub fn create_api_server_state() -> SharedApiServerState {
     Arc::new(Mutex::new(ApiServerState::new()))
 }
 
-/// Check if the API server is already running on port 19432
+/// Check if the API server is already running on the given port
 fn is_port_in_use(port: u16) -> bool {
     TcpStream::connect_timeout(
         &format!("127.0.0.1:{}", port).parse().unwrap(),
@@ -118,10 +122,15 @@ fn enriched_path() -> String {
 }
 
 /// Start the API server process
-pub async fn start_api_server(state: SharedApiServerState) -> Result<(), String> {
+pub async fn start_api_server(
+    state: SharedApiServerState,
+    tracker: SharedSessionTracker,
+) -> Result<(), String> {
+    let port = crate::config::ports().api_server;
+
     // Check if already running on port
-    if is_port_in_use(API_SERVER_PORT) {
-        eprintln!("[ApiServer] Port {} already in use, assuming API server is running", API_SERVER_PORT);
+    if is_port_in_use(port) {
+        eprintln!("[ApiServer] Port {} already in use, assuming API server is running", port);
         return Ok(());
     }
 
@@ -133,11 +142,13 @@ pub async fn start_api_server(state: SharedApiServerState) -> Result<(), String>
     let pnpm = resolve_command("pnpm");
     eprintln!("[ApiServer] Using pnpm at: {}", pnpm);
 
-    // Use pnpm to start the API server (using 'start' which runs built dist)
-    let child = Command::new(&pnpm)
+    // Use pnpm to start the API server (using 'start' which runs built dist),
+    // in its own process group so the node process under pnpm is stopped with it
+    let child = process_reaper::own_group(&mut Command::new(&pnpm))
         .args(["--filter", "@sidstack/api-server", "start"])
         .current_dir(&project_root)
         .env("PATH", enriched_path())
+        .env("API_PORT", port.to_string())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
@@ -145,6 +156,9 @@ pub async fn start_api_server(state: SharedApiServerState) -> Result<(), String>
 
     let pid = child.id();
     eprintln!("[ApiServer] Started API server with PID {}", pid);
+    if let Ok(mut tracker) = tracker.lock() {
+        tracker.add_service(SERVICE_NAME, pid);
+    }
 
     // Store the process handle
     {
@@ -155,8 +169,8 @@ pub async fn start_api_server(state: SharedApiServerState) -> Result<(), String>
     // Wait for server to be ready (poll port)
     let max_retries = 30; // 3 seconds
     for i in 0..max_retries {
-        if is_port_in_use(API_SERVER_PORT) {
-            eprintln!("[ApiServer] API server ready on port {}", API_SERVER_PORT);
+        if is_port_in_use(port) {
+            eprintln!("[ApiServer] API server ready on port {}", port);
             return Ok(());
         }
         tokio::time::sleep(Duration::from_millis(100)).await;
@@ -169,35 +183,28 @@ pub async fn start_api_server(state: SharedApiServerState) -> Result<(), String>
     Ok(())
 }
 
-/// Stop the API server process
-pub async fn stop_api_server(state: SharedApiServerState) {
+/// Stop the API server process and its process group
+pub async fn stop_api_server(state: SharedApiServerState, tracker: SharedSessionTracker) {
     let mut state = state.lock().await;
 
     if let Some(mut child) = state.process.take() {
         let pid = child.id();
         eprintln!("[ApiServer] Stopping API server (PID {})", pid);
 
-        // Try graceful shutdown first
-        #[cfg(unix)]
-        {
-            // Send SIGTERM
-            unsafe {
-                libc::kill(pid as i32, libc::SIGTERM);
-            }
+        // SIGTERM the group, SIGKILL whatever outlives the grace period
+        // (blocks for up to twice the grace period, so off the async runtime)
+        let stopped = tokio::task::spawn_blocking(move || {
+            process_reaper::terminate(pid, process_reaper::GRACE_PERIOD);
+            let _ = child.wait();
+        })
+        .await;
+        if let Err(e) = stopped {
+            eprintln!("[ApiServer] Failed to stop API server: {}", e);
         }
-
-        #[cfg(not(unix))]
-        {
-            let _ = child.kill();
+        if let Ok(mut tracker) = tracker.lock() {
+            tracker.remove_service(SERVICE_NAME, pid);
         }
 
-        // Wait a bit for graceful shutdown
-        std::thread::sleep(Duration::from_millis(500));
-
-        // Force kill if still running
-        let _ = child.kill();
-        let _ = child.wait();
-
         eprintln!("[ApiServer] API server stopped");
     }
 }
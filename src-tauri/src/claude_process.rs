@@ -19,6 +19,7 @@ use tauri::{AppHandle, Emitter};
 use tokio::sync::{broadcast, mpsc, RwLock};
 use uuid::Uuid;
 
+use crate::process_reaper;
 use crate::utils::{find_claude_cli, get_enhanced_path};
 
 /// Claude event types from stream-json output
@@ -149,6 +150,8 @@ pub enum ContentBlock {
     ToolResult {
         tool_use_id: String,
         content: serde_json::Value,
+        #[serde(default)]
+        is_error: Option<bool>,
     },
     #[serde(rename = "thinking")]
     Thinking {
@@ -194,6 +197,8 @@ pub struct ClaudeProcessInfo {
 /// Internal process data (for one-shot mode - legacy)
 struct ProcessData {
     pid: u32,
+    /// Process start time, to tell it apart from a later process with its PID
+    process_start: Option<String>,
     session_id: Option<String>,
     role: String,
     working_dir: String,
@@ -214,6 +219,8 @@ struct PersistentSession {
     claude_session_id: Option<String>,
     /// Process ID from OS
     pid: u32,
+    /// Process start time, to tell it apart from a later process with its PID
+    process_start: Option<String>,
     /// Agent role
     role: String,
     /// Working directory
@@ -224,6 +231,8 @@ struct PersistentSession {
     stdin_tx: mpsc::Sender<String>,
     /// Event broadcaster for internal subscribers
     event_tx: broadcast::Sender<ClaudeEvent>,
+    /// Stderr lines for internal subscribers
+    stderr_tx: broadcast::Sender<String>,
     /// When was this session created
     created_at: chrono::DateTime<chrono::Utc>,
 }
@@ -254,14 +263,11 @@ impl ClaudeProcessManager {
         max_turns: Option<u32>,
         app: AppHandle,
     ) -> Result<ClaudeProcessInfo, String> {
-        let process_id = Uuid::new_v4().to_string();
-
-        // Build command arguments
         // Note: --verbose is required when using -p with --output-format stream-json
         // --dangerously-skip-permissions auto-accepts tool permissions for agent terminals
         let mut args = vec![
             "-p".to_string(),
-            prompt.clone().unwrap_or_else(|| "Hello".to_string()),
+            prompt.unwrap_or_else(|| "Hello".to_string()),
             "--output-format".to_string(),
             "stream-json".to_string(),
             "--verbose".to_string(),
@@ -280,12 +286,61 @@ impl ClaudeProcessManager {
             args.push(turns.to_string());
         }
 
+        let (info, _) = self.spawn_process(role, working_dir, args, session_id, app).await?;
+        Ok(info)
+    }
+
+    /// Spawn a single-turn Claude process that cannot use any tool
+    ///
+    /// For prompts built from untrusted text (transcripts, logs): no tools are
+    /// offered, no MCP servers are loaded, and permissions stay at the
+    /// default mode, which denies any tool request in print mode. No
+    /// transcript is saved. The event receiver is subscribed before the
+    /// process starts.
+    pub async fn spawn_without_tools(
+        &self,
+        role: String,
+        working_dir: String,
+        prompt: String,
+        app: AppHandle,
+    ) -> Result<(ClaudeProcessInfo, broadcast::Receiver<ClaudeEvent>), String> {
+        let args = vec![
+            "-p".to_string(),
+            prompt,
+            "--output-format".to_string(),
+            "stream-json".to_string(),
+            "--verbose".to_string(),
+            "--max-turns".to_string(),
+            "1".to_string(),
+            "--tools".to_string(),
+            String::new(),
+            "--strict-mcp-config".to_string(),
+            "--permission-mode".to_string(),
+            "default".to_string(),
+            // One-shot runs must not show up as conversations of the project
+            "--no-session-persistence".to_string(),
+        ];
+        self.spawn_process(role, working_dir, args, None, app).await
+    }
+
+    /// Start the Claude CLI with `args` and stream its events
+    async fn spawn_process(
+        &self,
+        role: String,
+        working_dir: String,
+        args: Vec<String>,
+        session_id: Option<String>,
+        app: AppHandle,
+    ) -> Result<(ClaudeProcessInfo, broadcast::Receiver<ClaudeEvent>), String> {
+        let process_id = Uuid::new_v4().to_string();
+
         // Find Claude CLI path (GUI apps don't inherit shell PATH)
         let claude_path = find_claude_cli()
             .ok_or_else(|| "Claude CLI not found. Please install it via: npm install -g @anthropic-ai/claude-code".to_string())?;
 
-        // Spawn the process with enhanced PATH
-        let mut child = Command::new(&claude_path)
+        // Spawn the process with enhanced PATH, in its own process group so
+        // the tools Claude spawns are terminated with it
+        let mut child = process_reaper::own_group(&mut Command::new(&claude_path))
             .args(&args)
             .current_dir(&working_dir)
             .env("PATH", get_enhanced_path())
@@ -296,6 +351,7 @@ impl ClaudeProcessManager {
             .map_err(|e| format!("Failed to spawn Claude CLI at {:?}: {}", claude_path, e))?;
 
         let pid = child.id();
+        let process_start = process_reaper::start_time(pid);
         let stdout = child
             .stdout
             .take()
@@ -304,14 +360,17 @@ impl ClaudeProcessManager {
             .stderr
             .take()
             .ok_or_else(|| "Failed to capture stderr".to_string())?;
+        // Collect the exit status, so an exited process doesn't linger as a zombie
+        std::thread::spawn(move || child.wait());
 
         // Create broadcast channel for events
-        let (event_tx, _) = broadcast::channel(256);
+        let (event_tx, events) = broadcast::channel(256);
         let event_tx_clone = event_tx.clone();
 
         // Store process data
         let process_data = ProcessData {
             pid,
+            process_start,
             session_id: session_id.clone(),
             role: role.clone(),
             working_dir: working_dir.clone(),
@@ -405,7 +464,7 @@ impl ClaudeProcessManager {
             }
         }
 
-        Ok(ClaudeProcessInfo {
+        let info = ClaudeProcessInfo {
             id: process_id,
             session_id,
             role,
@@ -413,7 +472,8 @@ impl ClaudeProcessManager {
             status: ProcessStatus::Ready,
             pid,
             created_at: chrono::Utc::now().to_rfc3339(),
-        })
+        };
+        Ok((info, events))
     }
 
     /// Get process info by ID
@@ -452,18 +512,7 @@ impl ClaudeProcessManager {
         let mut processes = self.processes.write().await;
 
         if let Some(process) = processes.remove(process_id) {
-            #[cfg(unix)]
-            {
-                unsafe {
-                    libc::kill(process.pid as i32, libc::SIGTERM);
-                }
-            }
-            #[cfg(windows)]
-            {
-                let _ = Command::new("taskkill")
-                    .args(["/PID", &process.pid.to_string(), "/F"])
-                    .output();
-            }
+            process_reaper::terminate_started_in_background(process.pid, process.process_start);
             Ok(())
         } else {
             Err(format!("Process {} not found", process_id))
@@ -494,6 +543,23 @@ impl ClaudeProcessManager {
         terminal_id: Option<String>,
         resume_session_id: Option<String>,
         app: AppHandle,
+    ) -> Result<ClaudeProcessInfo, String> {
+        self.spawn_session_with_env(role, working_dir, initial_prompt, terminal_id, resume_session_id, Vec::new(), app)
+            .await
+    }
+
+    /// Spawn a persistent session with extra environment variables (passed on
+    /// to the MCP servers Claude starts)
+    #[allow(clippy::too_many_arguments)]
+    pub async fn spawn_session_with_env(
+        &self,
+        role: String,
+        working_dir: String,
+        initial_prompt: Option<String>,
+        terminal_id: Option<String>,
+        resume_session_id: Option<String>,
+        env: Vec<(String, String)>,
+        app: AppHandle,
     ) -> Result<ClaudeProcessInfo, String> {
         let session_id = Uuid::new_v4().to_string();
         let terminal_id_for_events = terminal_id.clone();
@@ -531,11 +597,13 @@ impl ClaudeProcessManager {
 
         println!("[ClaudeProcess] Spawning Claude at {:?} with args: {:?}", claude_path, args);
 
-        // Spawn the process with stdin kept open and enhanced PATH
-        let mut child = Command::new(&claude_path)
+        // Spawn the process with stdin kept open and enhanced PATH, in its
+        // own process group so the tools Claude spawns are terminated with it
+        let mut child = process_reaper::own_group(&mut Command::new(&claude_path))
             .args(&args)
             .current_dir(&working_dir)
             .env("PATH", get_enhanced_path())
+            .envs(env)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -543,6 +611,7 @@ impl ClaudeProcessManager {
             .map_err(|e| format!("Failed to spawn Claude CLI at {:?}: {}", claude_path, e))?;
 
         let pid = child.id();
+        let process_start = process_reaper::start_time(pid);
         let stdin = child
             .stdin
             .take()
@@ -555,12 +624,15 @@ impl ClaudeProcessManager {
             .stderr
             .take()
             .ok_or_else(|| "Failed to capture stderr".to_string())?;
+        // Collect the exit status, so an exited process doesn't linger as a zombie
+        std::thread::spawn(move || child.wait());
 
         // Create channel for stdin communication
         let (stdin_tx, mut stdin_rx) = mpsc::channel::<String>(32);
         // Create broadcast channel for events
         let (event_tx, _) = broadcast::channel(256);
         let event_tx_clone = event_tx.clone();
+        let (stderr_tx, _) = broadcast::channel(256);
 
         // Spawn stdin writer thread
         std::thread::spawn(move || {
@@ -662,10 +734,12 @@ impl ClaudeProcessManager {
         let app_stderr = app.clone();
         let session_id_for_stderr = session_id.clone();
         let terminal_id_for_stderr = terminal_id_for_events.clone();
+        let stderr_tx_reader = stderr_tx.clone();
         std::thread::spawn(move || {
             let reader = BufReader::new(stderr);
             for line in reader.lines() {
                 if let Ok(content) = line {
+                    let _ = stderr_tx_reader.send(content.clone());
                     let _ = app_stderr.emit(
                         "claude-stderr",
                         serde_json::json!({
@@ -683,11 +757,13 @@ impl ClaudeProcessManager {
             id: session_id.clone(),
             claude_session_id: None, // Will be set when we receive system.init
             pid,
+            process_start,
             role: role.clone(),
             working_dir: working_dir.clone(),
             status: ProcessStatus::Ready,
             stdin_tx: stdin_tx.clone(),
             event_tx,
+            stderr_tx,
             created_at: chrono::Utc::now(),
         };
 
@@ -777,7 +853,7 @@ impl ClaudeProcessManager {
     }
 
     /// Terminate a persistent session
-    /// Drops stdin (sends EOF) then kills the process
+    /// Drops stdin (sends EOF) then terminates the process and its children
     pub async fn terminate_session(&self, session_id: &str) -> Result<(), String> {
         let mut sessions = self.sessions.write().await;
 
@@ -785,19 +861,8 @@ impl ClaudeProcessManager {
             // Dropping stdin_tx closes the channel, which closes stdin, sending EOF
             drop(session.stdin_tx);
 
-            // Kill the process
-            #[cfg(unix)]
-            {
-                unsafe {
-                    libc::kill(session.pid as i32, libc::SIGTERM);
-                }
-            }
-            #[cfg(windows)]
-            {
-                let _ = Command::new("taskkill")
-                    .args(["/PID", &session.pid.to_string(), "/F"])
-                    .output();
-            }
+            // Terminate its process group (SIGTERM, then SIGKILL after the grace period)
+            process_reaper::terminate_started_in_background(session.pid, session.process_start);
             Ok(())
         } else {
             Err(format!("Session {} not found", session_id))
@@ -821,11 +886,22 @@ impl ClaudeProcessManager {
     }
 
     /// Subscribe to process events
-    #[allow(dead_code)]
     pub async fn subscribe(&self, process_id: &str) -> Option<broadcast::Receiver<ClaudeEvent>> {
         let processes = self.processes.read().await;
         processes.get(process_id).map(|p| p.event_tx.subscribe())
     }
+
+    /// Subscribe to persistent session events
+    pub async fn subscribe_session(&self, session_id: &str) -> Option<broadcast::Receiver<ClaudeEvent>> {
+        let sessions = self.sessions.read().await;
+        sessions.get(session_id).map(|s| s.event_tx.subscribe())
+    }
+
+    /// Subscribe to a persistent session's stderr lines
+    pub async fn subscribe_session_stderr(&self, session_id: &str) -> Option<broadcast::Receiver<String>> {
+        let sessions = self.sessions.read().await;
+        sessions.get(session_id).map(|s| s.stderr_tx.subscribe())
+    }
 }
 
 impl Default for ClaudeProcessManager {
@@ -935,23 +1011,21 @@ fn format_event_for_mcp(event: &ClaudeEvent) -> String {
 // Session History Loading
 // ============================================================================
 
-/// Convert working directory to Claude's project hash format
-/// Claude uses path with dashes: /Users/foo/bar -> -Users-foo-bar
-fn working_dir_to_claude_project_hash(working_dir: &str) -> String {
-    working_dir.replace('/', "-")
+/// Path of Claude's transcript for a session:
+/// ~/.claude/projects/<project-hash>/<session-id>.jsonl
+pub(crate) fn claude_transcript_path(working_dir: &str, claude_session_id: &str) -> std::path::PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".claude")
+        .join("projects")
+        .join(crate::project_identity::claude_project_dir_name(working_dir))
+        .join(format!("{}.jsonl", claude_session_id))
 }
 
 /// Load historical events from Claude's session file
 /// Returns parsed events from the .jsonl file
 pub fn load_session_history(working_dir: &str, claude_session_id: &str) -> Result<Vec<serde_json::Value>, String> {
-    let project_hash = working_dir_to_claude_project_hash(working_dir);
-    let claude_sessions_dir = dirs::home_dir()
-        .ok_or("Could not find home directory")?
-        .join(".claude")
-        .join("projects")
-        .join(&project_hash);
-
-    let session_file = claude_sessions_dir.join(format!("{}.jsonl", claude_session_id));
+    let session_file = claude_transcript_path(working_dir, claude_session_id);
 
     if !session_file.exists() {
         return Err(format!("Session file not found: {:?}", session_file));
@@ -1,16 +1,17 @@
 //! Claude Session Discovery
 //!
-//! Discovers and extracts Claude Code session IDs from ~/.claude/projects/
-//! for enabling session resume functionality.
+//! Discovers Claude Code session IDs from the transcripts in ~/.claude/projects/
+//! (indexed by `claude_transcripts`) for enabling session resume functionality.
 
 #![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use chrono::{DateTime, Utc};
 
+use crate::claude_transcripts::TranscriptInfo;
+
 /// Claude session info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClaudeSession {
@@ -20,12 +21,24 @@ pub struct ClaudeSession {
     pub last_modified: DateTime<Utc>,
 }
 
+impl From<TranscriptInfo> for ClaudeSession {
+    fn from(transcript: TranscriptInfo) -> Self {
+        Self {
+            session_id: transcript.session_id,
+            project_path: transcript.cwd.unwrap_or_else(|| "unknown".to_string()),
+            session_file: PathBuf::from(transcript.path),
+            last_modified: transcript.last_modified,
+        }
+    }
+}
+
 /// Error types for session discovery
 #[derive(Debug)]
 pub enum SessionDiscoveryError {
     IoError(std::io::Error),
     JsonError(serde_json::Error),
     NotFound(String),
+    IndexError(String),
 }
 
 impl std::fmt::Display for SessionDiscoveryError {
@@ -34,6 +47,7 @@ impl std::fmt::Display for SessionDiscoveryError {
             SessionDiscoveryError::IoError(e) => write!(f, "IO error: {}", e),
             SessionDiscoveryError::JsonError(e) => write!(f, "JSON error: {}", e),
             SessionDiscoveryError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            SessionDiscoveryError::IndexError(msg) => write!(f, "Transcript index error: {}", msg),
         }
     }
 }
@@ -50,15 +64,6 @@ impl From<serde_json::Error> for SessionDiscoveryError {
     }
 }
 
-/// Claude session data structure (from ~/.claude/projects/{hash}/.claude_session)
-#[derive(Debug, Deserialize)]
-struct ClaudeSessionFile {
-    #[serde(rename = "sessionId")]
-    session_id: Option<String>,
-    #[serde(rename = "projectPath")]
-    project_path: Option<String>,
-}
-
 /// Claude projects.json structure
 #[derive(Debug, Deserialize)]
 struct ClaudeProjectsIndex {
@@ -77,38 +82,11 @@ fn get_claude_dir() -> Option<PathBuf> {
     dirs::home_dir().map(|h| h.join(".claude"))
 }
 
-/// Discover all Claude sessions
+/// Discover all Claude sessions (one per transcript, newest first)
 pub fn discover_all_sessions() -> Result<Vec<ClaudeSession>, SessionDiscoveryError> {
-    let claude_dir = get_claude_dir()
-        .ok_or_else(|| SessionDiscoveryError::NotFound("Home directory not found".to_string()))?;
+    let transcripts = crate::claude_transcripts::list_all().map_err(SessionDiscoveryError::IndexError)?;
 
-    let projects_dir = claude_dir.join("projects");
-    if !projects_dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut sessions = Vec::new();
-
-    // Iterate through project directories
-    for entry in fs::read_dir(&projects_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_dir() {
-            // Look for .claude_session file
-            let session_file = path.join(".claude_session");
-            if session_file.exists() {
-                if let Ok(session) = read_session_file(&session_file) {
-                    sessions.push(session);
-                }
-            }
-        }
-    }
-
-    // Sort by last modified (newest first)
-    sessions.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
-
-    Ok(sessions)
+    Ok(transcripts.into_iter().map(ClaudeSession::from).collect())
 }
 
 /// Find sessions for a specific project path
@@ -131,32 +109,8 @@ pub fn find_latest_session(project_path: &str) -> Result<Option<ClaudeSession>,
 
 /// Get session by ID
 pub fn get_session_by_id(session_id: &str) -> Result<Option<ClaudeSession>, SessionDiscoveryError> {
-    let all_sessions = discover_all_sessions()?;
-    Ok(all_sessions.into_iter().find(|s| s.session_id == session_id))
-}
-
-/// Read a single session file
-fn read_session_file(path: &Path) -> Result<ClaudeSession, SessionDiscoveryError> {
-    let content = fs::read_to_string(path)?;
-    let session_data: ClaudeSessionFile = serde_json::from_str(&content)?;
-
-    let metadata = fs::metadata(path)?;
-    let modified: DateTime<Utc> = metadata.modified()
-        .map(|t| t.into())
-        .unwrap_or_else(|_| Utc::now());
-
-    let session_id = session_data.session_id
-        .ok_or_else(|| SessionDiscoveryError::NotFound("Session ID not found in file".to_string()))?;
-
-    let project_path = session_data.project_path
-        .unwrap_or_else(|| "unknown".to_string());
-
-    Ok(ClaudeSession {
-        session_id,
-        project_path,
-        session_file: path.to_path_buf(),
-        last_modified: modified,
-    })
+    let transcript = crate::claude_transcripts::find(session_id).map_err(SessionDiscoveryError::IndexError)?;
+    Ok(transcript.map(ClaudeSession::from))
 }
 
 /// Map terminal working directories to potential Claude sessions
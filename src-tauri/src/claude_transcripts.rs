@@ -0,0 +1,497 @@
+//! Claude Transcript Index
+//!
+//! Claude writes every conversation to
+//! ~/.claude/projects/{project_dir}/{session_id}.jsonl, where `project_dir` is
+//! the working directory with every non-alphanumeric character replaced by '-'
+//! (see `project_identity::claude_project_dir_name`). This module keeps an
+//! incremental index of those files in the app database (`db::transcripts`):
+//! - Session id, cwd, first prompt, model, turn count, cost and last-modified time
+//! - A file is read only from where the previous scan stopped; a file that
+//!   shrank is read again from the start
+//! - Transcripts whose file was deleted drop out of the index
+//!
+//! `refresh` always rescans; `list_all` reuses a scan younger than
+//! `RESCAN_INTERVAL`, and `find` only re-reads the session's own file once it
+//! is indexed.
+//!
+//! Cost is the sum of the `costUSD` Claude records on assistant entries;
+//! transcripts written by versions that don't record it report 0.
+
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::claude_process::ClaudeEvent;
+use crate::db;
+use crate::project_identity;
+
+/// First prompts are cut to this many characters
+const MAX_PROMPT_CHARS: usize = 300;
+/// How long a scan of ~/.claude/projects is reused by `list_all`
+const RESCAN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// When ~/.claude/projects was last scanned
+static LAST_SCAN: Mutex<Option<Instant>> = Mutex::new(None);
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Summary of one Claude conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptInfo {
+    /// Transcript file
+    pub path: String,
+    /// Id to pass to `claude --resume`
+    pub session_id: String,
+    /// Directory name under ~/.claude/projects
+    pub project_dir: String,
+    /// Working directory the conversation ran in
+    pub cwd: Option<String>,
+    pub first_prompt: Option<String>,
+    /// Model of the latest assistant reply
+    pub model: Option<String>,
+    /// Prompts typed by the user (tool results not counted)
+    pub turn_count: u32,
+    pub cost_usd: f64,
+    pub started_at: Option<DateTime<Utc>>,
+    pub last_modified: DateTime<Utc>,
+    pub size_bytes: u64,
+    /// End of the last complete line read
+    #[serde(skip)]
+    pub byte_offset: u64,
+}
+
+impl TranscriptInfo {
+    fn new(path: &Path, project_dir: &str) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            session_id: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            project_dir: project_dir.to_string(),
+            cwd: None,
+            first_prompt: None,
+            model: None,
+            turn_count: 0,
+            cost_usd: 0.0,
+            started_at: None,
+            last_modified: Utc::now(),
+            size_bytes: 0,
+            byte_offset: 0,
+        }
+    }
+
+    /// Whether the conversation ran in the project (or a directory inside it)
+    fn belongs_to(&self, project_path: &str, project_dir: &str) -> bool {
+        match &self.cwd {
+            Some(cwd) => {
+                cwd == project_path
+                    || cwd
+                        .strip_prefix(project_path)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            }
+            None => self.project_dir == project_dir,
+        }
+    }
+}
+
+/// Result of a scan
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshStats {
+    pub scanned: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+/// Text the user typed in a `user` entry (None for tool results)
+pub(crate) fn prompt_text(content: &Value) -> Option<String> {
+    let text = match content {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => return None,
+    };
+    let text = text.trim();
+    // Output of local slash commands is recorded as a user entry
+    if text.is_empty() || text.starts_with("<local-command-") {
+        return None;
+    }
+    Some(text.to_string())
+}
+
+/// Fold one transcript line into the summary
+fn apply_line(info: &mut TranscriptInfo, line: &str) {
+    let Ok(entry) = serde_json::from_str::<Value>(line) else {
+        return;
+    };
+    let flag = |name: &str| entry.get(name).and_then(Value::as_bool).unwrap_or(false);
+
+    if info.cwd.is_none() {
+        info.cwd = entry.get("cwd").and_then(Value::as_str).map(str::to_string);
+    }
+    if info.started_at.is_none() {
+        info.started_at = entry
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.with_timezone(&Utc));
+    }
+
+    let message = entry.get("message");
+    match entry.get("type").and_then(Value::as_str) {
+        Some("user") if !flag("isMeta") && !flag("isSidechain") => {
+            if let Some(text) = message.and_then(|m| m.get("content")).and_then(prompt_text) {
+                info.turn_count += 1;
+                if info.first_prompt.is_none() {
+                    info.first_prompt = Some(text.chars().take(MAX_PROMPT_CHARS).collect());
+                }
+            }
+        }
+        Some("assistant") => {
+            // Sub-agent replies cost money too, but don't set the model
+            info.cost_usd += entry.get("costUSD").and_then(Value::as_f64).unwrap_or(0.0);
+            let model = message.and_then(|m| m.get("model")).and_then(Value::as_str);
+            if let Some(model) = model.filter(|m| !flag("isSidechain") && !m.starts_with('<')) {
+                info.model = Some(model.to_string());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Read complete lines from `info.byte_offset` on
+fn read_from_offset(info: &mut TranscriptInfo, path: &Path) -> io::Result<()> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(info.byte_offset))?;
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        // Stop at EOF or at a line Claude is still writing
+        if read == 0 || !line.ends_with('\n') {
+            break;
+        }
+        info.byte_offset += read as u64;
+        apply_line(info, &line);
+    }
+    Ok(())
+}
+
+/// Events in the last `max_bytes` of a transcript, oldest first
+/// Entries the stream-json event types don't cover come back as `Unknown`
+pub fn tail_events(path: &Path, max_bytes: u64) -> Result<Vec<ClaudeEvent>, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open transcript: {}", e))?;
+    let len = file.metadata().map_err(|e| format!("Failed to read transcript: {}", e))?.len();
+    let start = len.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("Failed to read transcript: {}", e))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read transcript: {}", e))?;
+    let tail = String::from_utf8_lossy(&bytes);
+
+    // The first line is partial unless the whole file was read
+    Ok(tail
+        .lines()
+        .skip(if start > 0 { 1 } else { 0 })
+        .filter_map(|line| serde_json::from_str::<ClaudeEvent>(line).ok())
+        .collect())
+}
+
+// ============================================================================
+// Index
+// ============================================================================
+
+/// ~/.claude/projects
+pub fn claude_projects_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".claude")
+        .join("projects")
+}
+
+/// Bring the index up to date with ~/.claude/projects
+pub fn refresh() -> Result<RefreshStats, String> {
+    let stats = refresh_dir(&claude_projects_dir())?;
+    if let Ok(mut last) = LAST_SCAN.lock() {
+        *last = Some(Instant::now());
+    }
+    Ok(stats)
+}
+
+/// `refresh` unless the last scan is recent enough
+fn refresh_if_stale() -> Result<(), String> {
+    let fresh = LAST_SCAN
+        .lock()
+        .ok()
+        .and_then(|last| *last)
+        .is_some_and(|at| at.elapsed() < RESCAN_INTERVAL);
+    if !fresh {
+        refresh()?;
+    }
+    Ok(())
+}
+
+/// Read what was added to a transcript since `known` was indexed
+///
+/// None if the file is unchanged (or can't be read).
+fn update_entry(
+    known: Option<&TranscriptInfo>,
+    path: &Path,
+    project_dir: &str,
+    metadata: &fs::Metadata,
+) -> Option<TranscriptInfo> {
+    // Stored with microsecond precision
+    let modified = metadata
+        .modified()
+        .map(DateTime::<Utc>::from)
+        .unwrap_or_else(|_| Utc::now())
+        .trunc_subsecs(6);
+    let size = metadata.len();
+
+    let mut info = match known {
+        Some(known) if known.size_bytes == size && known.last_modified == modified => return None,
+        Some(known) if known.byte_offset <= size => known.clone(),
+        _ => TranscriptInfo::new(path, project_dir),
+    };
+    if let Err(e) = read_from_offset(&mut info, path) {
+        eprintln!("[ClaudeTranscripts] Failed to read {:?}: {}", path, e);
+        return None;
+    }
+    info.size_bytes = size;
+    info.last_modified = modified;
+    Some(info)
+}
+
+fn refresh_dir(projects_dir: &Path) -> Result<RefreshStats, String> {
+    let existing: HashMap<String, TranscriptInfo> = db::with_connection(|conn| db::transcripts::list_all(conn))?
+        .into_iter()
+        .filter(|info| Path::new(&info.path).starts_with(projects_dir))
+        .map(|info| (info.path.clone(), info))
+        .collect();
+
+    let mut stats = RefreshStats::default();
+    let mut seen = HashSet::new();
+    let mut updated = Vec::new();
+
+    // Files are read without holding the database
+    for (project_dir, dir) in project_identity::subdirs(projects_dir) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "jsonl") {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            stats.scanned += 1;
+            let key = path.to_string_lossy().to_string();
+            seen.insert(key.clone());
+
+            if let Some(info) = update_entry(existing.get(&key), &path, &project_dir, &metadata) {
+                updated.push(info);
+            }
+        }
+    }
+
+    let removed: Vec<String> = existing.into_keys().filter(|path| !seen.contains(path)).collect();
+    stats.updated = updated.len();
+    stats.removed = removed.len();
+    if !updated.is_empty() || !removed.is_empty() {
+        db::with_connection(|conn| db::transcripts::apply(conn, &updated, &removed))?;
+    }
+    Ok(stats)
+}
+
+/// All indexed transcripts, most recently modified first
+pub fn list_all() -> Result<Vec<TranscriptInfo>, String> {
+    refresh_if_stale()?;
+    db::with_connection(|conn| db::transcripts::list_all(conn))
+}
+
+/// Transcripts of conversations run in a project, most recent first
+pub fn list_for_project(project_path: &str) -> Result<Vec<TranscriptInfo>, String> {
+    let project = project_identity::canonical_path(project_path)?;
+    let project_dir = project_identity::claude_project_dir_name(&project);
+    Ok(list_all()?
+        .into_iter()
+        .filter(|info| info.belongs_to(&project, &project_dir))
+        .collect())
+}
+
+/// Transcript of a Claude session id
+///
+/// An indexed transcript is brought up to date on its own; the directory is
+/// only rescanned for sessions not indexed yet (or whose file moved).
+pub fn find(session_id: &str) -> Result<Option<TranscriptInfo>, String> {
+    if let Some(known) = db::with_connection(|conn| db::transcripts::get_by_session(conn, session_id))? {
+        let path = PathBuf::from(&known.path);
+        if let Ok(metadata) = fs::metadata(&path) {
+            return match update_entry(Some(&known), &path, &known.project_dir, &metadata) {
+                Some(info) => {
+                    db::with_connection(|conn| db::transcripts::apply(conn, std::slice::from_ref(&info), &[]))?;
+                    Ok(Some(info))
+                }
+                None => Ok(Some(known)),
+            };
+        }
+    }
+
+    refresh()?;
+    db::with_connection(|conn| db::transcripts::get_by_session(conn, session_id))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Tauri command: List past Claude conversations (of a project, or all)
+#[tauri::command]
+pub fn claude_transcripts_list(
+    project_path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<TranscriptInfo>, String> {
+    let mut transcripts = match project_path {
+        Some(path) => list_for_project(&path)?,
+        None => list_all()?,
+    };
+    if let Some(limit) = limit {
+        transcripts.truncate(limit);
+    }
+    Ok(transcripts)
+}
+
+/// Tauri command: Rescan ~/.claude/projects
+#[tauri::command]
+pub fn claude_transcripts_refresh() -> Result<RefreshStats, String> {
+    refresh()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_apply_lines() {
+        let mut info = TranscriptInfo::new(Path::new("/tmp/abc-123.jsonl"), "-tmp");
+        let lines = [
+            r#"{"type":"summary","summary":"Fix tests"}"#,
+            r#"{"type":"user","cwd":"/work/app","sessionId":"abc-123","timestamp":"2026-01-02T03:04:05.000Z","message":{"role":"user","content":"Fix the failing tests"}}"#,
+            r#"{"type":"assistant","costUSD":0.25,"message":{"model":"claude-sonnet-4-5","content":[]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","content":"ok"}]}}"#,
+            r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"Caveat: ..."}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"text","text":"Now commit"}]}}"#,
+            r#"{"type":"assistant","costUSD":0.5,"message":{"model":"<synthetic>","content":[]}}"#,
+        ];
+        for line in lines {
+            apply_line(&mut info, line);
+        }
+
+        assert_eq!(info.session_id, "abc-123");
+        assert_eq!(info.cwd.as_deref(), Some("/work/app"));
+        assert_eq!(info.first_prompt.as_deref(), Some("Fix the failing tests"));
+        assert_eq!(info.model.as_deref(), Some("claude-sonnet-4-5"));
+        assert_eq!(info.turn_count, 2);
+        assert_eq!(info.cost_usd, 0.75);
+        assert!(info.started_at.is_some());
+        assert!(info.belongs_to("/work/app", "-work-app"));
+        assert!(info.belongs_to("/work", "-work"));
+        assert!(!info.belongs_to("/work/application", "-work-application"));
+    }
+
+    #[test]
+    fn test_read_stops_at_partial_line() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("s.jsonl");
+        let mut file = File::create(&path).unwrap();
+        let lines = concat!(r#"{"type":"user","message":{"content":"one"}}"#, "\n", r#"{"type":"user","mess"#);
+        file.write_all(lines.as_bytes()).unwrap();
+
+        let mut info = TranscriptInfo::new(&path, "p");
+        read_from_offset(&mut info, &path).unwrap();
+        assert_eq!(info.turn_count, 1);
+
+        // The rest of the line arrives; only it is read
+        file.write_all(concat!(r#"age":{"content":"two"}}"#, "\n").as_bytes()).unwrap();
+        read_from_offset(&mut info, &path).unwrap();
+        assert_eq!(info.turn_count, 2);
+        assert_eq!(info.byte_offset, fs::metadata(&path).unwrap().len());
+    }
+
+    fn indexed(path: &Path) -> Option<TranscriptInfo> {
+        let key = path.to_string_lossy();
+        db::with_connection(|conn| db::transcripts::list_all(conn))
+            .unwrap()
+            .into_iter()
+            .find(|info| info.path == key)
+    }
+
+    #[test]
+    fn test_refresh_dir_rereads_truncated_file() {
+        crate::db::use_test_db();
+        let projects = tempdir().unwrap();
+        fs::create_dir(projects.path().join("-work-app")).unwrap();
+        let path = projects.path().join("-work-app").join("truncated-1.jsonl");
+        let one = r#"{"type":"user","cwd":"/work/app","message":{"content":"one"}}"#;
+        let two = r#"{"type":"user","message":{"content":"two"}}"#;
+        fs::write(&path, format!("{}\n{}\n", one, two)).unwrap();
+
+        let stats = refresh_dir(projects.path()).unwrap();
+        assert_eq!((stats.scanned, stats.updated, stats.removed), (1, 1, 0));
+        assert_eq!(indexed(&path).unwrap().turn_count, 2);
+
+        // Unchanged files aren't read again
+        assert_eq!(refresh_dir(projects.path()).unwrap().updated, 0);
+
+        // A rewritten, shorter file is read from the start
+        fs::write(&path, format!("{}\n", one)).unwrap();
+        assert_eq!(refresh_dir(projects.path()).unwrap().updated, 1);
+        let info = indexed(&path).unwrap();
+        assert_eq!(info.turn_count, 1);
+        assert_eq!(info.first_prompt.as_deref(), Some("one"));
+        assert_eq!(info.byte_offset, fs::metadata(&path).unwrap().len());
+    }
+
+    #[test]
+    fn test_refresh_dir_drops_deleted_file() {
+        crate::db::use_test_db();
+        let projects = tempdir().unwrap();
+        fs::create_dir(projects.path().join("-work-app")).unwrap();
+        let kept = projects.path().join("-work-app").join("kept-1.jsonl");
+        let deleted = projects.path().join("-work-app").join("deleted-1.jsonl");
+        for path in [&kept, &deleted] {
+            fs::write(path, "{\"type\":\"user\",\"message\":{\"content\":\"hi\"}}\n").unwrap();
+        }
+        assert_eq!(refresh_dir(projects.path()).unwrap().updated, 2);
+
+        fs::remove_file(&deleted).unwrap();
+        let stats = refresh_dir(projects.path()).unwrap();
+        assert_eq!((stats.scanned, stats.updated, stats.removed), (1, 0, 1));
+        assert!(indexed(&deleted).is_none());
+        assert!(indexed(&kept).is_some());
+    }
+}
@@ -133,6 +133,51 @@ pub async fn claude_spawn_session(
     Ok(result)
 }
 
+/// Resume a past Claude conversation of a project (the given Claude session,
+/// or the project's most recent one) in the directory it ran in
+#[tauri::command]
+pub async fn claude_resume_conversation(
+    project_path: String,
+    claude_session_id: Option<String>,
+    role: String,
+    terminal_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, SharedClaudeProcessManager>,
+    tracker: State<'_, SharedSessionTracker>,
+) -> Result<ClaudeProcessInfo, String> {
+    let transcripts = crate::claude_transcripts::list_for_project(&project_path)?;
+    let transcript = match claude_session_id {
+        Some(id) => transcripts
+            .into_iter()
+            .find(|t| t.session_id == id)
+            .ok_or_else(|| format!("Conversation {} not found for {}", id, project_path))?,
+        None => transcripts
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("No past conversations for {}", project_path))?,
+    };
+
+    // Claude looks the transcript up by working directory
+    let working_dir = transcript
+        .cwd
+        .filter(|cwd| std::path::Path::new(cwd).is_dir())
+        .unwrap_or(project_path);
+
+    claude_spawn_session(
+        SpawnSessionOptions {
+            role,
+            working_dir,
+            prompt: None,
+            terminal_id,
+            resume_session_id: Some(transcript.session_id),
+        },
+        app,
+        state,
+        tracker,
+    )
+    .await
+}
+
 /// Send input to a persistent Claude session
 /// Formats as NDJSON and writes to session's stdin
 #[tauri::command]
@@ -417,7 +417,7 @@ fn count_tasks_in_markdown(content: &str) -> (usize, usize) {
 }
 
 /// Parse tasks from markdown content
-fn parse_tasks_markdown(content: &str) -> Vec<OpenSpecTask> {
+pub(crate) fn parse_tasks_markdown(content: &str) -> Vec<OpenSpecTask> {
     let task_re = Regex::new(r"^\s*-\s*\[([ xX-])\]\s*\*\*([^*]+)\*\*(.*)$").unwrap();
     let phase_re = Regex::new(r"^###\s*Phase\s*\d+[:\s]*(.*)$").unwrap();
 
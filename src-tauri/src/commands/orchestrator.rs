@@ -376,7 +376,8 @@ pub async fn submit_request(_project_path: String, request: String) -> Result<Re
 
     // Create HTTP client
     let client = reqwest::Client::new();
-    let api_base = std::env::var("SIDSTACK_API_URL").unwrap_or_else(|_| "http://localhost:19432".to_string());
+    let api_base = std::env::var("SIDSTACK_API_URL")
+        .unwrap_or_else(|_| format!("http://localhost:{}", crate::config::ports().api_server));
 
     // Create parent task
     let parent_task_payload = serde_json::json!({
@@ -5,8 +5,14 @@ use crate::team_manager::{
 use crate::team_storage::{
     TeamStatus, MemberStatus, MemberTaskInfo, TerminalSessionInfo, RecoveryEvent,
 };
+use crate::team_templates::{self, TeamTemplateInfo, TemplateValidationResult};
+use crate::team_launcher::{self, TeamStartReport};
+use crate::claude_process::SharedClaudeProcessManager;
+use crate::session_tracker::SharedSessionTracker;
+use crate::recovery_watchdog::{self, SharedWatchdog, WatchdogCommand};
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
 
 /// Error response
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -228,9 +234,57 @@ pub async fn team_resume(
     let mut manager = state.lock().await;
     let (team, session_info) = manager.resume_team(&project_path, &team_id)
         .map_err(|e| TeamError::new(&e.to_string()))?;
+    // The caller launches the saved sessions itself
+    for terminal in session_info.iter().flat_map(|info| &info.terminals) {
+        manager.consume_saved_session(&project_path, &team_id, &terminal.member_id)
+            .map_err(|e| TeamError::new(&e.to_string()))?;
+    }
     Ok(ResumeTeamResult { team, session_info })
 }
 
+/// Start a team's agents, resuming their sessions if the team was stopped,
+/// and have the recovery watchdog monitor them
+#[tauri::command]
+pub async fn team_start(
+    app: AppHandle,
+    state: State<'_, SharedTeamManager>,
+    processes: State<'_, SharedClaudeProcessManager>,
+    tracker: State<'_, SharedSessionTracker>,
+    watchdog: State<'_, SharedWatchdog>,
+    project_path: String,
+    team_id: String,
+) -> Result<TeamStartReport, TeamError> {
+    let report = team_launcher::start(&project_path, &team_id, &app, &processes, &tracker, &state)
+        .await
+        .map_err(|e| TeamError::new(&e))?;
+    recovery_watchdog::send_watchdog_command(
+        &watchdog,
+        WatchdogCommand::StartMonitoring { team_id, project_path },
+    )
+    .await;
+    Ok(report)
+}
+
+/// Stop a team's agents and pause it, saving their sessions for `team_start`
+#[tauri::command]
+pub async fn team_stop(
+    state: State<'_, SharedTeamManager>,
+    processes: State<'_, SharedClaudeProcessManager>,
+    tracker: State<'_, SharedSessionTracker>,
+    watchdog: State<'_, SharedWatchdog>,
+    project_path: String,
+    team_id: String,
+) -> Result<crate::team_storage::SessionInfo, TeamError> {
+    recovery_watchdog::send_watchdog_command(
+        &watchdog,
+        WatchdogCommand::StopMonitoring { team_id: team_id.clone() },
+    )
+    .await;
+    team_launcher::stop(&project_path, &team_id, &processes, &tracker, &state)
+        .await
+        .map_err(|e| TeamError::new(&e))
+}
+
 // ===== Recovery Commands =====
 
 /// Report member failure
@@ -268,9 +322,13 @@ pub async fn team_get_recovery_context(
     team_id: String,
     member_id: String,
 ) -> Result<RecoveryContext, TeamError> {
-    let manager = state.lock().await;
-    manager.build_recovery_context(&project_path, &team_id, &member_id)
-        .map_err(|e| TeamError::new(&e.to_string()))
+    let inputs = {
+        let manager = state.lock().await;
+        manager.recovery_inputs(&project_path, &team_id, &member_id)
+            .map_err(|e| TeamError::new(&e.to_string()))?
+    };
+    crate::recovery_context::gather(inputs).await
+        .map_err(|e| TeamError::new(&e))
 }
 
 /// Get recovery history
@@ -298,3 +356,33 @@ pub async fn team_member_heartbeat(
     manager.record_heartbeat(&project_path, &team_id, &member_id)
         .map_err(|e| TeamError::new(&e.to_string()))
 }
+
+// ===== Team Template Commands =====
+
+/// List team templates (built in and from .sidstack/teams/)
+#[tauri::command]
+pub async fn team_template_list(project_path: String) -> Result<Vec<TeamTemplateInfo>, TeamError> {
+    team_templates::list(&project_path).map_err(|e| TeamError::new(&e))
+}
+
+/// Validate team template files
+#[tauri::command]
+pub async fn team_template_validate(project_path: String) -> Result<TemplateValidationResult, TeamError> {
+    team_templates::validate_all(&project_path).map_err(|e| TeamError::new(&e))
+}
+
+/// Create a team from a template, substituting its variables
+#[tauri::command]
+pub async fn team_create_from_template(
+    state: State<'_, SharedTeamManager>,
+    project_path: String,
+    template_id: String,
+    variables: Option<HashMap<String, String>>,
+    name: Option<String>,
+) -> Result<TeamData, TeamError> {
+    let input = team_templates::instantiate(&project_path, &template_id, &variables.unwrap_or_default(), name)
+        .map_err(|e| TeamError::new(&e))?;
+    let mut manager = state.lock().await;
+    manager.create_team(input)
+        .map_err(|e| TeamError::new(&e.to_string()))
+}
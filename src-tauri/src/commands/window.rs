@@ -1,6 +1,4 @@
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
 use tauri::{Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,30 +17,16 @@ pub struct WindowState {
     pub task_id: Option<String>,
 }
 
-fn get_window_state_path() -> PathBuf {
-    let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
-    path.push("sidstack");
-    fs::create_dir_all(&path).ok();
-    path.push("windows.json");
-    path
+fn load_window_state(label: &str) -> Option<WindowState> {
+    crate::db::with_connection(|conn| crate::db::windows::get(conn, label)).unwrap_or_else(|e| {
+        eprintln!("[Window] Failed to load window state: {}", e);
+        None
+    })
 }
 
-fn load_window_states() -> Vec<WindowState> {
-    let path = get_window_state_path();
-    if path.exists() {
-        if let Ok(content) = fs::read_to_string(&path) {
-            if let Ok(states) = serde_json::from_str(&content) {
-                return states;
-            }
-        }
-    }
-    Vec::new()
-}
-
-fn save_window_states(states: &[WindowState]) {
-    let path = get_window_state_path();
-    if let Ok(json) = serde_json::to_string_pretty(states) {
-        let _ = fs::write(&path, json);
+fn save_window_state(state: &WindowState) {
+    if let Err(e) = crate::db::with_connection(|conn| crate::db::windows::upsert(conn, state)) {
+        eprintln!("[Window] Failed to save window state: {}", e);
     }
 }
 
@@ -64,8 +48,7 @@ pub async fn open_task_window(
     }
 
     // Load saved position for this window
-    let states = load_window_states();
-    let saved_state = states.iter().find(|s| s.label == label);
+    let saved_state = load_window_state(&label);
 
     let mut builder = WebviewWindowBuilder::new(
         &app,
@@ -125,16 +108,7 @@ pub async fn save_window_position(
             },
         };
 
-        let mut states = load_window_states();
-
-        // Update or add state
-        if let Some(existing) = states.iter_mut().find(|s| s.label == label) {
-            *existing = window_state;
-        } else {
-            states.push(window_state);
-        }
-
-        save_window_states(&states);
+        save_window_state(&window_state);
     }
     Ok(())
 }
@@ -46,7 +46,8 @@ pub enum WorkspaceStatus {
     Archived,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Worktree settings (the `worktrees` section of config.yaml)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct WorkspaceConfig {
     pub shared_folders: Vec<String>,
     pub shared_files: Vec<String>,
@@ -80,7 +81,7 @@ pub struct WorkspaceStats {
 #[tauri::command]
 pub async fn list_workspaces(project_path: String) -> Result<Vec<Workspace>, WorkspaceError> {
     let repo = Repository::open(&project_path)?;
-    let config = WorkspaceConfig::default();
+    let config = crate::config::for_project(&project_path).worktrees;
 
     let worktree_root = Path::new(&project_path).join(&config.worktree_root);
 
@@ -135,7 +136,7 @@ pub async fn create_workspace(
     branch_name: Option<String>,
 ) -> Result<Workspace, WorkspaceError> {
     let repo = Repository::open(&project_path)?;
-    let config = WorkspaceConfig::default();
+    let config = crate::config::for_project(&project_path).worktrees;
 
     let worktree_name = format!("task-{}", task_id);
     let worktree_root = Path::new(&project_path).join(&config.worktree_root);
@@ -199,7 +200,7 @@ pub async fn delete_workspace(
     delete_branch: Option<bool>,
 ) -> Result<(), WorkspaceError> {
     let repo = Repository::open(&project_path)?;
-    let config = WorkspaceConfig::default();
+    let config = crate::config::for_project(&project_path).worktrees;
 
     let worktree_name = format!("task-{}", task_id);
     let worktree_root = Path::new(&project_path).join(&config.worktree_root);
@@ -335,21 +336,22 @@ fn create_shared_symlinks(
 
 /// Sync shared symlinks for an existing workspace
 #[tauri::command]
-pub async fn sync_shared_symlinks(workspace_path: String) -> Result<(), WorkspaceError> {
+pub async fn sync_shared_symlinks(
+    project_path: String,
+    workspace_path: String,
+) -> Result<(), WorkspaceError> {
+    let config = crate::config::for_project(&project_path).worktrees;
+    let worktree_root = Path::new(&project_path).join(&config.worktree_root);
     let worktree_path = Path::new(&workspace_path);
 
-    // Find the main project path (parent of .worktrees)
-    let project_path = worktree_path
-        .parent()
-        .and_then(|p| p.parent())
-        .ok_or_else(|| WorkspaceError::InvalidConfig("Cannot find project root".to_string()))?;
-
-    let config = WorkspaceConfig::default();
-    create_shared_symlinks(
-        &project_path.to_string_lossy(),
-        worktree_path,
-        &config,
-    )?;
+    if worktree_path.parent() != Some(worktree_root.as_path()) {
+        return Err(WorkspaceError::InvalidConfig(format!(
+            "{} is not a workspace of {}",
+            workspace_path, project_path
+        )));
+    }
+
+    create_shared_symlinks(&project_path, worktree_path, &config)?;
 
     Ok(())
 }
@@ -657,4 +659,36 @@ mod tests {
             assert!(claude_link.is_symlink(), "CLAUDE.md should be a symlink");
         }
     }
+
+    #[tokio::test]
+    async fn test_sync_shared_symlinks_with_nested_root() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let project_path = repo_path.to_string_lossy().to_string();
+        fs::create_dir_all(repo_path.join(".sidstack")).expect("Failed to create dir");
+        fs::write(
+            repo_path.join(".sidstack/config.yaml"),
+            "worktrees:\n  worktree_root: build/trees\n",
+        )
+        .expect("Failed to write config");
+        fs::write(repo_path.join("CLAUDE.md"), "# Claude").expect("Failed to write file");
+
+        let workspace = create_workspace(project_path.clone(), "700".to_string(), None)
+            .await
+            .expect("Failed to create workspace");
+        let worktree_path = Path::new(&workspace.worktree_path);
+        assert_eq!(worktree_path.parent(), Some(repo_path.join("build/trees").as_path()));
+
+        // A removed link points back at the project after a sync
+        let claude_link = worktree_path.join("CLAUDE.md");
+        let _ = fs::remove_file(&claude_link);
+        sync_shared_symlinks(project_path.clone(), workspace.worktree_path.clone())
+            .await
+            .expect("Failed to sync symlinks");
+        #[cfg(unix)]
+        assert_eq!(fs::read_link(&claude_link).unwrap(), repo_path.join("CLAUDE.md"));
+
+        // Only workspaces of the given project are touched
+        let outside = repo_path.join("elsewhere").to_string_lossy().to_string();
+        assert!(sync_shared_symlinks(project_path, outside).await.is_err());
+    }
 }
@@ -0,0 +1,498 @@
+//! SidStack Configuration
+//!
+//! Layered backend settings; each layer overrides the one below, key by key,
+//! so a file only needs the keys it changes:
+//! 1. Built-in defaults
+//! 2. ~/.sidstack/config.yaml (per user)
+//! 3. <project>/.sidstack/config.yaml (per project, meant to be committed)
+//!
+//! App-wide settings (ports, coordinator, watchdog, session cleanup, resource
+//! limits) are read without a project, so only the first two layers apply to
+//! them; worktree settings and failure patterns are read per project.
+//!
+//! Files are re-read when their modification time changes, so callers read
+//! `get()` / `for_project()` each time instead of caching values. A file that
+//! fails to parse or validate keeps its last good contents and the error is
+//! reported in `ConfigSnapshot.errors`. `watch` polls the files, emits
+//! `config-changed` and pushes live settings to running services. Ports are
+//! only read at startup.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+use crate::agent_coordinator::SharedAgentCoordinator;
+use crate::commands::workspace::WorkspaceConfig;
+use crate::failure_patterns::FailurePatternSettings;
+use crate::process_metrics::ResourceSettings;
+use crate::recovery_watchdog::{self, SharedWatchdog, WatchdogCommand, WatchdogConfig};
+
+const CONFIG_FILE: &str = "config.yaml";
+/// How often `watch` checks the config files for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Session storage settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionSettings {
+    /// Saved or archived sessions idle longer than this are deleted at startup
+    pub cleanup_after_days: u32,
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self { cleanup_after_days: 30 }
+    }
+}
+
+/// Agent coordinator settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CoordinatorSettings {
+    pub max_workers: usize,
+}
+
+impl Default for CoordinatorSettings {
+    fn default() -> Self {
+        Self { max_workers: 5 }
+    }
+}
+
+/// Local ports (read at startup)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PortSettings {
+    /// WebSocket IPC server (MCP clients)
+    pub ipc: u16,
+    /// Agent SDK sidecar
+    pub sidecar: u16,
+    /// API server (passed to it as API_PORT)
+    pub api_server: u16,
+}
+
+impl Default for PortSettings {
+    fn default() -> Self {
+        Self {
+            ipc: 17432,
+            sidecar: 17433,
+            api_server: 19432,
+        }
+    }
+}
+
+/// Everything config.yaml can set
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SidstackConfig {
+    /// Worktree root and the folders/files symlinked into each worktree
+    pub worktrees: WorkspaceConfig,
+    pub sessions: SessionSettings,
+    pub watchdog: WatchdogConfig,
+    pub coordinator: CoordinatorSettings,
+    pub ports: PortSettings,
+    /// Agent resource sampling and per-role limits
+    pub resources: ResourceSettings,
+    /// Failure signatures the watchdog looks for in agent output
+    pub failure_patterns: FailurePatternSettings,
+}
+
+impl SidstackConfig {
+    fn validate(&self) -> Result<(), String> {
+        // Joined onto the project path; an absolute or `..` root would put
+        // worktrees (and their removal) outside the repository
+        let root = Path::new(&self.worktrees.worktree_root);
+        let inside = !self.worktrees.worktree_root.trim().is_empty()
+            && root.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+            && root.components().any(|c| matches!(c, Component::Normal(_)));
+        if !inside {
+            return Err("worktrees.worktree_root must be a directory inside the project".to_string());
+        }
+        if self.coordinator.max_workers == 0 {
+            return Err("coordinator.max_workers must be at least 1".to_string());
+        }
+        if self.watchdog.check_interval_secs == 0 {
+            return Err("watchdog.check_interval_secs must be at least 1".to_string());
+        }
+        if self.resources.sample_interval_secs == 0 {
+            return Err("resources.sample_interval_secs must be at least 1".to_string());
+        }
+        self.failure_patterns.validate()?;
+        let ports = &self.ports;
+        if ports.ipc == ports.sidecar || ports.ipc == ports.api_server || ports.sidecar == ports.api_server {
+            return Err("ports.ipc, ports.sidecar and ports.api_server must differ".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Effective configuration and where it came from
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSnapshot {
+    pub config: SidstackConfig,
+    /// Files that contributed, lowest precedence first
+    pub sources: Vec<String>,
+    /// Files skipped (or kept at their last good contents) and why
+    pub errors: Vec<String>,
+}
+
+/// One config file as last read
+struct Layer {
+    modified: Option<SystemTime>,
+    /// Last contents that parsed (an empty object if none)
+    value: Value,
+    error: Option<String>,
+}
+
+/// Layers by file path
+static LAYERS: Mutex<Option<HashMap<PathBuf, Layer>>> = Mutex::new(None);
+
+/// Ports of this run, fixed on first use
+static PORTS: OnceLock<PortSettings> = OnceLock::new();
+
+// ============================================================================
+// Paths
+// ============================================================================
+
+/// ~/.sidstack/config.yaml
+pub fn user_config_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sidstack")
+        .join(CONFIG_FILE)
+}
+
+/// <project>/.sidstack/config.yaml
+pub fn project_config_path(project_path: &str) -> PathBuf {
+    Path::new(project_path).join(".sidstack").join(CONFIG_FILE)
+}
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn parse_file(path: &Path) -> Result<Value, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Failed to read: {}", e))?;
+    match serde_yaml::from_str::<Value>(&text).map_err(|e| format!("Invalid YAML: {}", e))? {
+        Value::Null => Ok(Value::Object(Map::new())),
+        value @ Value::Object(_) => Ok(value),
+        _ => Err("Top level must be a mapping".to_string()),
+    }
+}
+
+/// Current contents of a config file, re-read if it changed.
+/// Returns the value and the file's error, if any
+fn read_layer(path: &Path) -> (Value, Option<String>) {
+    let modified = modified_time(path);
+    let mut guard = LAYERS.lock().unwrap_or_else(|e| e.into_inner());
+    let layers = guard.get_or_insert_with(HashMap::new);
+
+    if let Some(layer) = layers.get(path) {
+        if layer.modified == modified {
+            return (layer.value.clone(), layer.error.clone());
+        }
+    }
+
+    let previous = layers.remove(path).map(|l| l.value);
+    let layer = match modified {
+        None => Layer {
+            modified,
+            value: Value::Object(Map::new()),
+            error: None,
+        },
+        Some(_) => match parse_file(path) {
+            Ok(value) => Layer {
+                modified,
+                value,
+                error: None,
+            },
+            Err(e) => {
+                eprintln!("[Config] {:?}: {}", path, e);
+                Layer {
+                    modified,
+                    value: previous.unwrap_or_else(|| Value::Object(Map::new())),
+                    error: Some(e),
+                }
+            }
+        },
+    };
+    let result = (layer.value.clone(), layer.error.clone());
+    layers.insert(path.to_path_buf(), layer);
+    result
+}
+
+/// Merge `overlay` into `base`: mappings key by key, anything else replaced
+fn merge(base: &mut Value, overlay: &Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (_, Value::Null) => {}
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
+/// Apply layers over the defaults, skipping any that don't produce a valid config
+fn resolve(layers: &[(String, Value)]) -> (SidstackConfig, Vec<String>, Vec<String>) {
+    let mut merged = serde_json::to_value(SidstackConfig::default()).expect("defaults serialize");
+    let mut config = SidstackConfig::default();
+    let mut sources = Vec::new();
+    let mut errors = Vec::new();
+
+    for (source, value) in layers {
+        if value.as_object().is_some_and(Map::is_empty) {
+            continue;
+        }
+        let mut candidate = merged.clone();
+        merge(&mut candidate, value);
+        let parsed = serde_json::from_value::<SidstackConfig>(candidate.clone())
+            .map_err(|e| e.to_string())
+            .and_then(|c| c.validate().map(|_| c));
+        match parsed {
+            Ok(parsed) => {
+                merged = candidate;
+                config = parsed;
+                sources.push(source.clone());
+            }
+            Err(e) => errors.push(format!("{}: {}", source, e)),
+        }
+    }
+    (config, sources, errors)
+}
+
+/// Effective configuration for a project (or app-wide, without one)
+pub fn load(project_path: Option<&str>) -> ConfigSnapshot {
+    // Tests don't see the user's file
+    let mut paths = Vec::new();
+    if !cfg!(test) {
+        paths.push(user_config_path());
+    }
+    if let Some(project) = project_path.filter(|p| !p.is_empty()) {
+        paths.push(project_config_path(project));
+    }
+
+    let mut layers = Vec::new();
+    let mut file_errors = Vec::new();
+    for path in paths {
+        let (value, error) = read_layer(&path);
+        let source = path.to_string_lossy().to_string();
+        if let Some(e) = error {
+            file_errors.push(format!("{}: {}", source, e));
+        }
+        layers.push((source, value));
+    }
+
+    let (config, sources, mut errors) = resolve(&layers);
+    file_errors.append(&mut errors);
+    ConfigSnapshot {
+        config,
+        sources,
+        errors: file_errors,
+    }
+}
+
+/// App-wide configuration (defaults + user file)
+pub fn get() -> SidstackConfig {
+    load(None).config
+}
+
+/// Ports the services of this run use
+///
+/// Read from the config once; changes take effect after a restart.
+pub fn ports() -> PortSettings {
+    PORTS.get_or_init(|| get().ports).clone()
+}
+
+/// ~/.sidstack/ports.json - the ports for clients outside the app (MCP server)
+pub fn ports_file_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sidstack")
+        .join("ports.json")
+}
+
+/// Publish this run's ports to `ports_file_path`
+pub fn write_ports_file() -> Result<PathBuf, String> {
+    let path = ports_file_path();
+    crate::persist::write_json(&path, &ports()).map_err(|e| format!("Failed to write ports file: {}", e))?;
+    Ok(path)
+}
+
+/// Configuration for a project (defaults + user file + project file)
+pub fn for_project(project_path: &str) -> SidstackConfig {
+    load(Some(project_path)).config
+}
+
+// ============================================================================
+// Hot Reload
+// ============================================================================
+
+/// Files whose modification time changed since they were last read
+fn changed_files() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = {
+        let guard = LAYERS.lock().unwrap_or_else(|e| e.into_inner());
+        guard.as_ref().map(|l| l.keys().cloned().collect()).unwrap_or_default()
+    };
+    let user = user_config_path();
+    if !paths.contains(&user) {
+        paths.push(user);
+    }
+
+    let guard = LAYERS.lock().unwrap_or_else(|e| e.into_inner());
+    paths
+        .into_iter()
+        .filter(|path| {
+            let cached = guard.as_ref().and_then(|l| l.get(path)).map(|l| l.modified);
+            cached != Some(modified_time(path))
+        })
+        .collect()
+}
+
+/// Poll the config files; on change, emit `config-changed` and apply the
+/// settings running services can take live (watchdog, coordinator)
+pub fn watch(app: AppHandle, watchdog: SharedWatchdog, coordinator: SharedAgentCoordinator) {
+    tauri::async_runtime::spawn(async move {
+        let mut current = get();
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let changed = changed_files();
+            if changed.is_empty() {
+                continue;
+            }
+
+            for path in &changed {
+                read_layer(path);
+                eprintln!("[Config] Reloaded {:?}", path);
+            }
+            let snapshot = load(None);
+            let _ = app.emit(
+                "config-changed",
+                serde_json::json!({
+                    "paths": changed,
+                    "errors": snapshot.errors,
+                }),
+            );
+
+            let next = snapshot.config;
+            if next.watchdog != current.watchdog {
+                recovery_watchdog::send_watchdog_command(&watchdog, WatchdogCommand::UpdateConfig(next.watchdog.clone()))
+                    .await;
+            }
+            if next.coordinator != current.coordinator {
+                coordinator.set_max_workers(next.coordinator.max_workers);
+            }
+            if next.ports != current.ports {
+                eprintln!("[Config] Port changes take effect after a restart");
+            }
+            current = next;
+        }
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Tauri command: Ports the services of this run listen on
+#[tauri::command]
+pub fn config_ports() -> PortSettings {
+    ports()
+}
+
+/// Tauri command: Effective configuration, for a project if given
+#[tauri::command]
+pub fn config_get(project_path: Option<String>) -> ConfigSnapshot {
+    load(project_path.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn yaml(text: &str) -> Value {
+        serde_yaml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_layers_override_key_by_key() {
+        let user = yaml("coordinator:\n  max_workers: 8\nsessions:\n  cleanup_after_days: 7\n");
+        let project = yaml("worktrees:\n  worktree_root: .trees\ncoordinator:\n  max_workers: 3\n");
+        let (config, sources, errors) =
+            resolve(&[("user".to_string(), user), ("project".to_string(), project)]);
+
+        assert!(errors.is_empty());
+        assert_eq!(sources, vec!["user", "project"]);
+        assert_eq!(config.coordinator.max_workers, 3);
+        assert_eq!(config.sessions.cleanup_after_days, 7);
+        assert_eq!(config.worktrees.worktree_root, ".trees");
+        // Untouched keys keep their defaults
+        assert_eq!(config.worktrees.shared_files, vec!["CLAUDE.md".to_string()]);
+        assert_eq!(config.ports, PortSettings::default());
+    }
+
+    #[test]
+    fn test_invalid_layer_is_skipped() {
+        let user = yaml("sessions:\n  cleanup_after_days: 14\n");
+        let bad_type = yaml("coordinator:\n  max_workers: lots\n");
+        let bad_value = yaml("watchdog:\n  check_interval_secs: 0\n");
+        let (config, sources, errors) = resolve(&[
+            ("user".to_string(), user),
+            ("bad-type".to_string(), bad_type),
+            ("bad-value".to_string(), bad_value),
+        ]);
+
+        assert_eq!(sources, vec!["user"]);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(config.sessions.cleanup_after_days, 14);
+        assert_eq!(config.coordinator.max_workers, 5);
+    }
+
+    #[test]
+    fn test_worktree_root_stays_inside_project() {
+        for root in ["", " ", ".", "/tmp/trees", "../trees", ".trees/../.."] {
+            let layer = yaml(&format!("worktrees:\n  worktree_root: {:?}\n", root));
+            let (config, _, errors) = resolve(&[("project".to_string(), layer)]);
+            assert_eq!(errors.len(), 1, "{:?} was accepted", root);
+            assert_eq!(config.worktrees.worktree_root, ".worktrees");
+        }
+
+        let layer = yaml("worktrees:\n  worktree_root: build/trees\n");
+        let (config, _, errors) = resolve(&[("project".to_string(), layer)]);
+        assert!(errors.is_empty());
+        assert_eq!(config.worktrees.worktree_root, "build/trees");
+    }
+
+    #[test]
+    fn test_reload_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        fs::write(&path, "coordinator:\n  max_workers: 2\n").unwrap();
+        assert_eq!(read_layer(&path).0["coordinator"]["max_workers"], 2);
+
+        // Broken edits keep the last good contents
+        fs::write(&path, "coordinator: [unclosed\n").unwrap();
+        let later = SystemTime::now() + Duration::from_secs(5);
+        File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+        let (value, error) = read_layer(&path);
+        assert!(error.is_some());
+        assert_eq!(value["coordinator"]["max_workers"], 2);
+    }
+}
@@ -0,0 +1,287 @@
+//! Legacy JSON Import
+//!
+//! One-time import of the JSON stores the database replaced:
+//! - Session metadata: sessions/{project_id}/{session_id}.meta.json
+//! - Teams: teams/{project_id}/{team_id}/team.json, state.json and history.json
+//! - Window geometry: {local data dir}/sidstack/windows.json
+//! - Processes to clean up after a crash: {local data dir}/sidstack/active_sessions.json
+//!
+//! Each source is imported in one transaction and recorded in
+//! `legacy_imports`, so it is never imported twice. Records already in the
+//! database win; the JSON files are left in place untouched.
+
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::{sessions, teams, tracked_processes, windows};
+use crate::commands::window::WindowState;
+use crate::persist;
+use crate::project_identity;
+use crate::session_storage::{self, SessionMeta};
+use crate::session_tracker::{TrackedService, TrackedSession};
+use crate::team_storage::{TeamConfig, TeamHistory, TeamState, TeamStorage};
+
+const SESSIONS_SOURCE: &str = "session-meta";
+const TEAMS_SOURCE: &str = "teams";
+const WINDOWS_SOURCE: &str = "windows";
+const TRACKER_SOURCE: &str = "active-sessions";
+/// Recovery events kept per team (as the JSON history did)
+const MAX_TEAM_EVENTS: usize = 100;
+
+/// Records imported by `import_all`
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub sessions: usize,
+    pub teams: usize,
+    pub windows: usize,
+    pub tracked_processes: usize,
+}
+
+/// active_sessions.json, as the session tracker wrote it
+#[derive(Deserialize)]
+struct TrackerFile {
+    app_pid: u32,
+    #[serde(default)]
+    sessions: HashMap<String, TrackedSession>,
+    #[serde(default)]
+    services: HashMap<String, TrackedService>,
+}
+
+/// Directory of the per-machine JSON files: {local data dir}/sidstack
+fn local_data_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("sidstack")
+}
+
+fn imported(conn: &Connection, source: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM legacy_imports WHERE source = ?1)",
+        params![source],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Failed to read legacy imports: {}", e))
+}
+
+fn import_once(
+    conn: &mut Connection,
+    source: &str,
+    import: impl FnOnce(&Connection) -> Result<usize, String>,
+) -> Result<usize, String> {
+    if imported(conn, source)? {
+        return Ok(0);
+    }
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start import of {}: {}", source, e))?;
+    let count = import(&tx)?;
+    tx.execute(
+        "INSERT INTO legacy_imports (source, records, imported_at) VALUES (?1, ?2, ?3)",
+        params![source, count as i64, chrono::Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| format!("Failed to record import of {}: {}", source, e))?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit import of {}: {}", source, e))?;
+    Ok(count)
+}
+
+/// Import every legacy store not imported yet
+pub fn import_all(conn: &mut Connection) -> Result<ImportReport, String> {
+    let sessions = import_once(conn, SESSIONS_SOURCE, |conn| {
+        import_sessions(conn, &session_storage::sessions_dir())
+    })?;
+    let teams = match TeamStorage::new() {
+        Ok(storage) => {
+            let base = storage.base_dir().to_path_buf();
+            import_once(conn, TEAMS_SOURCE, |conn| import_teams(conn, &base))?
+        }
+        Err(_) => 0,
+    };
+    let windows = import_once(conn, WINDOWS_SOURCE, |conn| {
+        import_windows(conn, &local_data_dir().join("windows.json"))
+    })?;
+    let tracked_processes = import_once(conn, TRACKER_SOURCE, |conn| {
+        import_tracked_processes(conn, &local_data_dir().join("active_sessions.json"))
+    })?;
+    Ok(ImportReport {
+        sessions,
+        teams,
+        windows,
+        tracked_processes,
+    })
+}
+
+/// Import `*.meta.json` files from each project directory
+pub fn import_sessions(conn: &Connection, sessions_dir: &Path) -> Result<usize, String> {
+    let mut count = 0;
+    for (_, dir) in project_identity::subdirs(sessions_dir) {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            if !entry.file_name().to_string_lossy().ends_with(".meta.json") {
+                continue;
+            }
+            let mut meta = match persist::read_json::<SessionMeta>(&entry.path()) {
+                Ok(Some(meta)) => meta,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("[Database] Skipping {:?}: {}", entry.path(), e);
+                    continue;
+                }
+            };
+            meta.project_hash = project_identity::project_id(&meta.project_path);
+            if sessions::insert_if_missing(conn, &meta)? {
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// Import team configs, states and histories from each project directory
+pub fn import_teams(conn: &Connection, teams_dir: &Path) -> Result<usize, String> {
+    let mut count = 0;
+    for (_, project_dir) in project_identity::subdirs(teams_dir) {
+        for (_, team_dir) in project_identity::subdirs(&project_dir) {
+            let config = match persist::read_json::<TeamConfig>(&team_dir.join("team.json")) {
+                Ok(Some(config)) => config,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("[Database] Skipping team {:?}: {}", team_dir, e);
+                    continue;
+                }
+            };
+            let project_hash = project_identity::project_id(&config.project_path);
+            if teams::get_config(conn, &project_hash, &config.id)?.is_some() {
+                continue;
+            }
+            teams::upsert_config(conn, &config)?;
+
+            if let Ok(Some(state)) = persist::read_json::<TeamState>(&team_dir.join("state.json")) {
+                teams::save_state(conn, &project_hash, &state)?;
+            }
+            if let Ok(Some(history)) = persist::read_json::<TeamHistory>(&team_dir.join("history.json")) {
+                for event in history.events.iter().take(MAX_TEAM_EVENTS).rev() {
+                    teams::add_event(conn, &config.id, event, MAX_TEAM_EVENTS)?;
+                }
+            }
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Import saved window states
+pub fn import_windows(conn: &Connection, path: &Path) -> Result<usize, String> {
+    let states = match persist::read_json::<Vec<WindowState>>(path) {
+        Ok(states) => states.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("[Database] Skipping {:?}: {}", path, e);
+            return Ok(0);
+        }
+    };
+    let mut count = 0;
+    for state in states {
+        if windows::get(conn, &state.label)?.is_none() {
+            windows::upsert(conn, &state)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Import the processes a crashed app may have left behind
+pub fn import_tracked_processes(conn: &Connection, path: &Path) -> Result<usize, String> {
+    let tracker = match persist::read_json::<TrackerFile>(path) {
+        Ok(Some(tracker)) => tracker,
+        Ok(None) => return Ok(0),
+        Err(e) => {
+            eprintln!("[Database] Skipping {:?}: {}", path, e);
+            return Ok(0);
+        }
+    };
+    for session in tracker.sessions.values() {
+        tracked_processes::upsert_session(conn, tracker.app_pid, session)?;
+    }
+    for service in tracker.services.values() {
+        tracked_processes::upsert_service(conn, tracker.app_pid, service)?;
+    }
+    Ok(tracker.sessions.len() + tracker.services.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_storage::SessionStatus;
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_import_sessions_once() {
+        crate::project_identity::use_test_mapping();
+        let dir = tempdir().unwrap();
+        let meta = SessionMeta {
+            session_id: "term-1".to_string(),
+            project_path: "/no/such/project".to_string(),
+            project_hash: "legacy".to_string(),
+            role: Some("dev".to_string()),
+            display_name: None,
+            claude_session_id: None,
+            created_at: Utc::now(),
+            last_active_at: Utc::now(),
+            status: SessionStatus::Saved,
+            log_size_bytes: 42,
+            title: None,
+            summary: None,
+            summary_updated_at: None,
+            summary_source: None,
+        };
+        persist::write_json(&dir.path().join("abc").join("term-1.meta.json"), &meta).unwrap();
+
+        let conn = crate::db::open_in_memory().unwrap();
+        assert_eq!(import_sessions(&conn, dir.path()).unwrap(), 1);
+        assert_eq!(import_sessions(&conn, dir.path()).unwrap(), 0);
+
+        let id = project_identity::project_id("/no/such/project");
+        let stored = sessions::get(&conn, &id, "term-1").unwrap().unwrap();
+        assert_eq!(stored.project_hash, id);
+        assert_eq!(stored.role.as_deref(), Some("dev"));
+        assert_eq!(stored.log_size_bytes, 42);
+    }
+
+    #[test]
+    fn test_import_tracked_processes() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("active_sessions.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "app_pid": 4242,
+                "sessions": {
+                    "s1": {
+                        "session_id": "s1",
+                        "pid": 5000,
+                        "terminal_id": null,
+                        "role": "dev",
+                        "cwd": "/tmp",
+                        "started_at": "2024-12-20T15:30:00Z"
+                    }
+                },
+                "updated_at": "2024-12-20T15:30:00Z"
+            }"#,
+        )
+        .unwrap();
+
+        let conn = crate::db::open_in_memory().unwrap();
+        assert_eq!(import_tracked_processes(&conn, &path).unwrap(), 1);
+
+        let sessions = tracked_processes::list_sessions(&conn).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!((sessions[0].0, sessions[0].1.pid), (4242, 5000));
+        assert!(sessions[0].1.process_start.is_none());
+        assert_eq!(import_tracked_processes(&conn, &dir.path().join("missing.json")).unwrap(), 0);
+    }
+}
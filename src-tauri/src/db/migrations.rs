@@ -0,0 +1,386 @@
+//! Schema Migrations
+//!
+//! Migrations run in version order, each in its own immediate transaction
+//! that also records it in `schema_version`, so concurrent openers (the app
+//! and the TypeScript processes) never apply one twice. Applied migrations
+//! must never be edited; schema changes go in a new migration at the end.
+
+use rusqlite::{Connection, Transaction, TransactionBehavior};
+
+/// One schema change
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub up: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+/// All migrations, oldest first
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "test_rooms",
+        up: test_rooms,
+    },
+    Migration {
+        version: 2,
+        name: "terminal_sessions",
+        up: terminal_sessions,
+    },
+    Migration {
+        version: 3,
+        name: "teams",
+        up: teams,
+    },
+    Migration {
+        version: 4,
+        name: "legacy_imports",
+        up: legacy_imports,
+    },
+    Migration {
+        version: 5,
+        name: "claude_transcripts",
+        up: claude_transcripts,
+    },
+    Migration {
+        version: 6,
+        name: "session_summaries",
+        up: session_summaries,
+    },
+    Migration {
+        version: 7,
+        name: "session_summary_source",
+        up: session_summary_source,
+    },
+    Migration {
+        version: 8,
+        name: "runtime_state",
+        up: runtime_state,
+    },
+];
+
+/// Version the current build migrates to
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Highest applied migration (0 for a new database)
+pub fn current_version(conn: &Connection) -> rusqlite::Result<u32> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Apply pending migrations. Returns how many were applied
+pub fn migrate(conn: &mut Connection) -> Result<u32, String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at TEXT NOT NULL
+        );",
+    )
+    .map_err(|e| format!("Failed to create schema_version table: {}", e))?;
+
+    let current = current_version(conn).map_err(|e| format!("Failed to read schema version: {}", e))?;
+    if current > latest_version() {
+        eprintln!(
+            "[Database] Schema version {} is newer than this build ({}); continuing",
+            current,
+            latest_version()
+        );
+    }
+
+    let mut applied = 0;
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| format!("Failed to start migration {}: {}", migration.version, e))?;
+
+        // Another process may have applied it while we waited for the lock
+        let done: bool = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM schema_version WHERE version = ?1)",
+                [migration.version],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to read schema version: {}", e))?;
+        if done {
+            continue;
+        }
+
+        (migration.up)(&tx).map_err(|e| {
+            format!("Migration {} ({}) failed: {}", migration.version, migration.name, e)
+        })?;
+        tx.execute(
+            "INSERT INTO schema_version (version, name, applied_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![migration.version, migration.name, chrono::Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| format!("Failed to record migration {}: {}", migration.version, e))?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit migration {}: {}", migration.version, e))?;
+        applied += 1;
+    }
+
+    Ok(applied)
+}
+
+fn has_column(tx: &Transaction, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut stmt = tx.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    for name in names {
+        if name? == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// ============================================================================
+// Migrations
+// ============================================================================
+
+/// Test Room tables, as @sidstack/shared creates them
+///
+/// The tables may already exist (created by the TypeScript side, possibly
+/// before `specId` was added), hence IF NOT EXISTS and the column check.
+fn test_rooms(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS test_rooms (
+            id TEXT PRIMARY KEY,
+            moduleId TEXT NOT NULL UNIQUE,
+            specId TEXT,
+            name TEXT NOT NULL,
+            description TEXT,
+            status TEXT DEFAULT 'active',
+            createdAt INTEGER NOT NULL,
+            updatedAt INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_test_rooms_module ON test_rooms(moduleId);
+
+        CREATE TABLE IF NOT EXISTS test_items (
+            id TEXT PRIMARY KEY,
+            roomId TEXT NOT NULL,
+            title TEXT NOT NULL,
+            description TEXT,
+            status TEXT DEFAULT 'pending',
+            orderIndex INTEGER DEFAULT 0,
+            resultNotes TEXT,
+            testedAt INTEGER,
+            createdAt INTEGER NOT NULL,
+            FOREIGN KEY (roomId) REFERENCES test_rooms(id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_test_items_room ON test_items(roomId);
+
+        CREATE TABLE IF NOT EXISTS test_messages (
+            id TEXT PRIMARY KEY,
+            roomId TEXT NOT NULL,
+            sender TEXT NOT NULL,
+            messageType TEXT NOT NULL,
+            content TEXT NOT NULL,
+            metadata TEXT,
+            createdAt INTEGER NOT NULL,
+            FOREIGN KEY (roomId) REFERENCES test_rooms(id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_test_messages_room ON test_messages(roomId);
+
+        CREATE TABLE IF NOT EXISTS test_artifacts (
+            id TEXT PRIMARY KEY,
+            roomId TEXT NOT NULL,
+            messageId TEXT,
+            name TEXT NOT NULL,
+            type TEXT NOT NULL,
+            path TEXT,
+            content TEXT,
+            createdAt INTEGER NOT NULL,
+            FOREIGN KEY (roomId) REFERENCES test_rooms(id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_test_artifacts_room ON test_artifacts(roomId);
+        ",
+    )?;
+
+    if !has_column(tx, "test_rooms", "specId")? {
+        tx.execute_batch("ALTER TABLE test_rooms ADD COLUMN specId TEXT")?;
+    }
+    Ok(())
+}
+
+/// Terminal session metadata (previously {session_id}.meta.json)
+fn terminal_sessions(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE terminal_sessions (
+            project_hash TEXT NOT NULL,
+            session_id TEXT NOT NULL,
+            project_path TEXT NOT NULL,
+            role TEXT,
+            display_name TEXT,
+            claude_session_id TEXT,
+            created_at TEXT NOT NULL,
+            last_active_at TEXT NOT NULL,
+            status TEXT NOT NULL,
+            log_size_bytes INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (project_hash, session_id)
+        );
+        CREATE INDEX idx_terminal_sessions_last_active ON terminal_sessions(last_active_at);
+        ",
+    )
+}
+
+/// Teams (previously teams.json, team.json, state.json and history.json)
+///
+/// Config and state are nested documents kept as JSON; the columns the
+/// index needs are duplicated alongside.
+fn teams(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE teams (
+            id TEXT PRIMARY KEY,
+            project_hash TEXT NOT NULL,
+            project_path TEXT NOT NULL,
+            name TEXT NOT NULL,
+            status TEXT NOT NULL,
+            last_active TEXT NOT NULL,
+            member_count INTEGER NOT NULL,
+            config TEXT NOT NULL,
+            state TEXT
+        );
+        CREATE INDEX idx_teams_project ON teams(project_hash);
+
+        CREATE TABLE team_events (
+            id TEXT PRIMARY KEY,
+            team_id TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            event TEXT NOT NULL,
+            FOREIGN KEY (team_id) REFERENCES teams(id)
+        );
+        CREATE INDEX idx_team_events_team ON team_events(team_id, timestamp);
+        ",
+    )
+}
+
+/// Which legacy JSON stores have been imported
+fn legacy_imports(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE legacy_imports (
+            source TEXT PRIMARY KEY,
+            records INTEGER NOT NULL,
+            imported_at TEXT NOT NULL
+        );
+        ",
+    )
+}
+
+/// Index of Claude's own transcripts (~/.claude/projects/*/*.jsonl)
+fn claude_transcripts(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE claude_transcripts (
+            path TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            project_dir TEXT NOT NULL,
+            cwd TEXT,
+            first_prompt TEXT,
+            model TEXT,
+            turn_count INTEGER NOT NULL DEFAULT 0,
+            cost_usd REAL NOT NULL DEFAULT 0,
+            started_at TEXT,
+            last_modified TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL,
+            byte_offset INTEGER NOT NULL
+        );
+        CREATE INDEX idx_claude_transcripts_session ON claude_transcripts(session_id);
+        CREATE INDEX idx_claude_transcripts_cwd ON claude_transcripts(cwd);
+        ",
+    )
+}
+
+/// Generated title and summary of terminal sessions (see `session_summary`)
+fn session_summaries(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        ALTER TABLE terminal_sessions ADD COLUMN title TEXT;
+        ALTER TABLE terminal_sessions ADD COLUMN summary TEXT;
+        ALTER TABLE terminal_sessions ADD COLUMN summary_updated_at TEXT;
+        ",
+    )
+}
+
+/// Which backend produced a session's summary (`heuristic` or `claude`)
+fn session_summary_source(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch("ALTER TABLE terminal_sessions ADD COLUMN summary_source TEXT;")
+}
+
+/// Window geometry (previously windows.json) and the processes each app run
+/// started (previously active_sessions.json)
+fn runtime_state(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE window_states (
+            label TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            x INTEGER,
+            y INTEGER,
+            width INTEGER,
+            height INTEGER,
+            task_id TEXT
+        );
+
+        CREATE TABLE tracked_sessions (
+            session_id TEXT PRIMARY KEY,
+            app_pid INTEGER NOT NULL,
+            pid INTEGER NOT NULL,
+            terminal_id TEXT,
+            role TEXT,
+            cwd TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            process_start TEXT
+        );
+
+        CREATE TABLE tracked_services (
+            name TEXT PRIMARY KEY,
+            app_pid INTEGER NOT NULL,
+            pid INTEGER NOT NULL,
+            started_at TEXT NOT NULL,
+            process_start TEXT
+        );
+        ",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_are_ordered() {
+        for pair in MIGRATIONS.windows(2) {
+            assert!(pair[0].version < pair[1].version);
+        }
+        assert_eq!(MIGRATIONS[0].version, 1);
+    }
+
+    #[test]
+    fn test_migrate_is_idempotent() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(migrate(&mut conn).unwrap(), MIGRATIONS.len() as u32);
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        assert_eq!(migrate(&mut conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_adds_missing_spec_id() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE test_rooms (id TEXT PRIMARY KEY, moduleId TEXT NOT NULL UNIQUE,
+             name TEXT NOT NULL, description TEXT, status TEXT DEFAULT 'active',
+             createdAt INTEGER NOT NULL, updatedAt INTEGER NOT NULL);",
+        )
+        .unwrap();
+        migrate(&mut conn).unwrap();
+        conn.execute("UPDATE test_rooms SET specId = NULL", []).unwrap();
+    }
+}
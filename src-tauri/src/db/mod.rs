@@ -0,0 +1,124 @@
+//! Application Database
+//!
+//! One versioned SQLite database for the app's persistent records, shared with
+//! the TypeScript side (@sidstack/shared opens the same file):
+//! - `migrations`: ordered schema migrations, tracked in `schema_version`
+//! - `legacy`: one-time import of the JSON stores this database replaces
+//! - `sessions`, `teams`, `test_rooms`, `transcripts`, `windows`,
+//!   `tracked_processes`: typed repositories
+//!
+//! Terminal logs stay on disk (see `session_storage`) and the search index
+//! keeps its own rebuildable database (see `session_search`).
+//!
+//! Database: ~/.sidstack/sidstack.db
+
+pub mod legacy;
+pub mod migrations;
+pub mod sessions;
+pub mod teams;
+pub mod test_rooms;
+pub mod tracked_processes;
+pub mod transcripts;
+pub mod windows;
+
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+const DB_FILE: &str = "sidstack.db";
+/// How long to wait for a lock held by another process
+const BUSY_TIMEOUT_MS: u32 = 5000;
+
+/// Shared connection, opened (and migrated) on first use
+static DB: Mutex<Option<Connection>> = Mutex::new(None);
+/// Database the shared connection opens instead of `db_path()`
+static DB_PATH_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// ~/.sidstack/sidstack.db
+pub fn db_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sidstack")
+        .join(DB_FILE)
+}
+
+/// Point the shared connection at another database file
+///
+/// An already open connection is closed; the next use opens the new file.
+#[cfg(test)]
+pub(crate) fn set_db_path(path: PathBuf) -> Result<(), String> {
+    let mut guard = DB.lock().map_err(|e| format!("Database lock poisoned: {}", e))?;
+    if let Ok(mut current) = DB_PATH_OVERRIDE.write() {
+        *current = Some(path);
+    }
+    *guard = None;
+    Ok(())
+}
+
+/// Use a database private to this test process (once per process)
+#[cfg(test)]
+pub(crate) fn use_test_db() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("sidstack-test-db-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        set_db_path(dir.join(DB_FILE)).expect("set test database path");
+    });
+}
+
+/// Open a database and bring its schema up to date
+pub fn open(path: &Path) -> Result<Connection, String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create database directory: {}", e))?;
+    }
+    let conn = Connection::open(path).map_err(|e| format!("Failed to open database: {}", e))?;
+    prepare(conn)
+}
+
+/// Open a private in-memory database (tests)
+#[cfg(test)]
+pub(crate) fn open_in_memory() -> Result<Connection, String> {
+    let conn = Connection::open_in_memory()
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+    prepare(conn)
+}
+
+fn prepare(mut conn: Connection) -> Result<Connection, String> {
+    // WAL and a busy timeout for concurrent access with TypeScript processes
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .map_err(|e| format!("Failed to configure database: {}", e))?;
+    conn.pragma_update(None, "busy_timeout", BUSY_TIMEOUT_MS)
+        .map_err(|e| format!("Failed to configure database: {}", e))?;
+
+    let applied = migrations::migrate(&mut conn)?;
+    if applied > 0 {
+        eprintln!(
+            "[Database] Applied {} migration(s), schema version {}",
+            applied,
+            migrations::latest_version()
+        );
+    }
+    Ok(conn)
+}
+
+/// Run a closure with the shared connection
+pub fn with_connection<T>(f: impl FnOnce(&mut Connection) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = DB.lock().map_err(|e| format!("Database lock poisoned: {}", e))?;
+    if guard.is_none() {
+        let path = DB_PATH_OVERRIDE
+            .read()
+            .ok()
+            .and_then(|path| path.clone())
+            .unwrap_or_else(db_path);
+        *guard = Some(open(&path)?);
+    }
+    f(guard.as_mut().expect("connection opened above"))
+}
+
+/// Open the database and import the legacy JSON stores (once)
+///
+/// Called at startup, after project storage was migrated to stable ids.
+pub fn init() -> Result<legacy::ImportReport, String> {
+    with_connection(legacy::import_all)
+}
@@ -0,0 +1,175 @@
+//! Terminal Session Repository
+//!
+//! `SessionMeta` records keyed by (project id, session id). Timestamps are
+//! stored as fixed-width RFC 3339 strings so they sort as text.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::session_storage::{SessionMeta, SessionStatus};
+use crate::session_summary::SummaryBackend;
+
+const COLUMNS: &str = "session_id, project_path, project_hash, role, display_name, \
+    claude_session_id, created_at, last_active_at, status, log_size_bytes, \
+    title, summary, summary_updated_at, summary_source";
+
+pub(crate) fn format_time(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+pub(crate) fn parse_time(value: &str) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn status_str(status: &SessionStatus) -> &'static str {
+    match status {
+        SessionStatus::Active => "active",
+        SessionStatus::Saved => "saved",
+        SessionStatus::Archived => "archived",
+    }
+}
+
+fn parse_status(value: &str) -> SessionStatus {
+    match value {
+        "active" => SessionStatus::Active,
+        "archived" => SessionStatus::Archived,
+        _ => SessionStatus::Saved,
+    }
+}
+
+fn backend_str(backend: SummaryBackend) -> &'static str {
+    match backend {
+        SummaryBackend::Heuristic => "heuristic",
+        SummaryBackend::Claude => "claude",
+    }
+}
+
+fn parse_backend(value: &str) -> Option<SummaryBackend> {
+    match value {
+        "heuristic" => Some(SummaryBackend::Heuristic),
+        "claude" => Some(SummaryBackend::Claude),
+        _ => None,
+    }
+}
+
+fn from_row(row: &Row) -> rusqlite::Result<SessionMeta> {
+    Ok(SessionMeta {
+        session_id: row.get(0)?,
+        project_path: row.get(1)?,
+        project_hash: row.get(2)?,
+        role: row.get(3)?,
+        display_name: row.get(4)?,
+        claude_session_id: row.get(5)?,
+        created_at: parse_time(&row.get::<_, String>(6)?)?,
+        last_active_at: parse_time(&row.get::<_, String>(7)?)?,
+        status: parse_status(&row.get::<_, String>(8)?),
+        log_size_bytes: row.get::<_, i64>(9)? as u64,
+        title: row.get(10)?,
+        summary: row.get(11)?,
+        summary_updated_at: row.get::<_, Option<String>>(12)?.as_deref().map(parse_time).transpose()?,
+        summary_source: row.get::<_, Option<String>>(13)?.as_deref().and_then(parse_backend),
+    })
+}
+
+fn query(conn: &Connection, filter: &str, args: &[&dyn rusqlite::ToSql]) -> Result<Vec<SessionMeta>, String> {
+    let sql = format!(
+        "SELECT {} FROM terminal_sessions {} ORDER BY last_active_at DESC",
+        COLUMNS, filter
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Failed to query sessions: {}", e))?;
+    let rows = stmt
+        .query_map(args, from_row)
+        .map_err(|e| format!("Failed to query sessions: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read sessions: {}", e))
+}
+
+/// Get one session
+pub fn get(conn: &Connection, project_hash: &str, session_id: &str) -> Result<Option<SessionMeta>, String> {
+    let sql = format!(
+        "SELECT {} FROM terminal_sessions WHERE project_hash = ?1 AND session_id = ?2",
+        COLUMNS
+    );
+    conn.query_row(&sql, params![project_hash, session_id], from_row)
+        .optional()
+        .map_err(|e| format!("Failed to read session: {}", e))
+}
+
+/// Insert or replace a session
+pub fn upsert(conn: &Connection, meta: &SessionMeta) -> Result<(), String> {
+    insert(conn, meta, "INSERT OR REPLACE")
+}
+
+/// Insert a session unless one with the same key exists. Returns true if inserted
+pub fn insert_if_missing(conn: &Connection, meta: &SessionMeta) -> Result<bool, String> {
+    insert(conn, meta, "INSERT OR IGNORE")?;
+    Ok(conn.changes() > 0)
+}
+
+fn insert(conn: &Connection, meta: &SessionMeta, verb: &str) -> Result<(), String> {
+    let sql = format!(
+        "{} INTO terminal_sessions ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        verb, COLUMNS
+    );
+    conn.execute(
+        &sql,
+        params![
+            meta.session_id,
+            meta.project_path,
+            meta.project_hash,
+            meta.role,
+            meta.display_name,
+            meta.claude_session_id,
+            format_time(&meta.created_at),
+            format_time(&meta.last_active_at),
+            status_str(&meta.status),
+            meta.log_size_bytes as i64,
+            meta.title,
+            meta.summary,
+            meta.summary_updated_at.as_ref().map(format_time),
+            meta.summary_source.map(backend_str),
+        ],
+    )
+    .map_err(|e| format!("Failed to write session: {}", e))?;
+    Ok(())
+}
+
+/// Delete a session. Returns true if it existed
+pub fn delete(conn: &Connection, project_hash: &str, session_id: &str) -> Result<bool, String> {
+    let deleted = conn
+        .execute(
+            "DELETE FROM terminal_sessions WHERE project_hash = ?1 AND session_id = ?2",
+            params![project_hash, session_id],
+        )
+        .map_err(|e| format!("Failed to delete session: {}", e))?;
+    Ok(deleted > 0)
+}
+
+/// Sessions of one project, most recently active first
+pub fn list_by_project(conn: &Connection, project_hash: &str) -> Result<Vec<SessionMeta>, String> {
+    query(conn, "WHERE project_hash = ?1", &[&project_hash])
+}
+
+/// All sessions, most recently active first
+pub fn list_all(conn: &Connection) -> Result<Vec<SessionMeta>, String> {
+    query(conn, "", &[])
+}
+
+/// Saved or archived sessions last active before `cutoff`
+pub fn list_inactive_before(conn: &Connection, cutoff: &DateTime<Utc>) -> Result<Vec<SessionMeta>, String> {
+    query(
+        conn,
+        "WHERE status != 'active' AND last_active_at < ?1",
+        &[&format_time(cutoff)],
+    )
+}
+
+/// Replace a session's record with one under a new key (project moved)
+pub fn rekey(conn: &Connection, old_project_hash: &str, meta: &SessionMeta) -> Result<(), String> {
+    delete(conn, old_project_hash, &meta.session_id)?;
+    upsert(conn, meta)
+}
@@ -0,0 +1,224 @@
+//! Team Repository
+//!
+//! Team configs and states are stored as JSON documents in `teams`, with the
+//! index columns (name, status, last active, member count) kept next to them.
+//! Recovery events are rows of `team_events`.
+
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+
+use super::sessions::{format_time, parse_time};
+use crate::project_identity;
+use crate::team_storage::{RecoveryEvent, TeamConfig, TeamIndexEntry, TeamState, TeamStatus};
+
+fn status_str(status: &TeamStatus) -> &'static str {
+    match status {
+        TeamStatus::Active => "active",
+        TeamStatus::Paused => "paused",
+        TeamStatus::Archived => "archived",
+    }
+}
+
+fn parse_status(value: &str) -> TeamStatus {
+    match value {
+        "paused" => TeamStatus::Paused,
+        "archived" => TeamStatus::Archived,
+        _ => TeamStatus::Active,
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| format!("Failed to serialize team: {}", e))
+}
+
+fn from_json<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_str(value).map_err(|e| format!("Failed to parse team: {}", e))
+}
+
+/// Whether a project already has a team with this name
+pub fn name_taken(conn: &Connection, project_hash: &str, name: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM teams WHERE project_hash = ?1 AND name = ?2)",
+        params![project_hash, name],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Failed to query teams: {}", e))
+}
+
+/// Insert a team, or update its config (status and state are kept)
+pub fn upsert_config(conn: &Connection, config: &TeamConfig) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO teams (id, project_hash, project_path, name, status, last_active, member_count, config)
+         VALUES (?1, ?2, ?3, ?4, 'active', ?5, ?6, ?7)
+         ON CONFLICT(id) DO UPDATE SET
+            project_hash = excluded.project_hash,
+            project_path = excluded.project_path,
+            name = excluded.name,
+            member_count = excluded.member_count,
+            config = excluded.config",
+        params![
+            config.id,
+            project_identity::project_id(&config.project_path),
+            config.project_path,
+            config.name,
+            format_time(&Utc::now()),
+            (1 + config.workers.len()) as i64,
+            to_json(config)?,
+        ],
+    )
+    .map_err(|e| format!("Failed to write team: {}", e))?;
+    Ok(())
+}
+
+/// Config of a team in a project
+pub fn get_config(conn: &Connection, project_hash: &str, team_id: &str) -> Result<Option<TeamConfig>, String> {
+    let config: Option<String> = conn
+        .query_row(
+            "SELECT config FROM teams WHERE project_hash = ?1 AND id = ?2",
+            params![project_hash, team_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to read team: {}", e))?;
+    config.as_deref().map(from_json).transpose()
+}
+
+/// Saved state of a team (None if the team or its state does not exist)
+pub fn get_state(conn: &Connection, project_hash: &str, team_id: &str) -> Result<Option<TeamState>, String> {
+    let state: Option<Option<String>> = conn
+        .query_row(
+            "SELECT state FROM teams WHERE project_hash = ?1 AND id = ?2",
+            params![project_hash, team_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to read team state: {}", e))?;
+    state.flatten().as_deref().map(from_json).transpose()
+}
+
+/// Save a team's state and mirror its status. Returns false if the team does not exist
+pub fn save_state(conn: &Connection, project_hash: &str, state: &TeamState) -> Result<bool, String> {
+    let updated = conn
+        .execute(
+            "UPDATE teams SET state = ?1, status = ?2, last_active = ?3
+             WHERE project_hash = ?4 AND id = ?5",
+            params![
+                to_json(state)?,
+                status_str(&state.status),
+                format_time(&state.last_active),
+                project_hash,
+                state.team_id,
+            ],
+        )
+        .map_err(|e| format!("Failed to write team state: {}", e))?;
+    Ok(updated > 0)
+}
+
+/// Index entries of a project's teams, in creation order
+pub fn list(conn: &Connection, project_hash: &str, status: Option<&TeamStatus>) -> Result<Vec<TeamIndexEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, status, last_active, member_count FROM teams
+             WHERE project_hash = ?1 AND (?2 IS NULL OR status = ?2) ORDER BY rowid",
+        )
+        .map_err(|e| format!("Failed to query teams: {}", e))?;
+    let rows = stmt
+        .query_map(params![project_hash, status.map(status_str)], |row| {
+            Ok(TeamIndexEntry {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                status: parse_status(&row.get::<_, String>(2)?),
+                last_active: parse_time(&row.get::<_, String>(3)?)?,
+                member_count: row.get::<_, i64>(4)? as usize,
+            })
+        })
+        .map_err(|e| format!("Failed to query teams: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read teams: {}", e))
+}
+
+/// Every stored team with its state, across projects
+pub fn list_all(conn: &Connection) -> Result<Vec<(TeamConfig, Option<TeamState>)>, String> {
+    let mut stmt = conn
+        .prepare("SELECT config, state FROM teams ORDER BY rowid")
+        .map_err(|e| format!("Failed to query teams: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)))
+        .map_err(|e| format!("Failed to query teams: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read teams: {}", e))?;
+
+    rows.into_iter()
+        .map(|(config, state)| Ok((from_json(&config)?, state.as_deref().map(from_json).transpose()?)))
+        .collect()
+}
+
+/// Delete a team and its events. Returns true if it existed
+pub fn delete(conn: &Connection, project_hash: &str, team_id: &str) -> Result<bool, String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to delete team: {}", e))?;
+    tx.execute("DELETE FROM team_events WHERE team_id = ?1", params![team_id])
+        .map_err(|e| format!("Failed to delete team events: {}", e))?;
+    let deleted = tx
+        .execute(
+            "DELETE FROM teams WHERE project_hash = ?1 AND id = ?2",
+            params![project_hash, team_id],
+        )
+        .map_err(|e| format!("Failed to delete team: {}", e))?;
+    tx.commit().map_err(|e| format!("Failed to delete team: {}", e))?;
+    Ok(deleted > 0)
+}
+
+// ============================================================================
+// Recovery Events
+// ============================================================================
+
+/// Recovery events of a team, newest first
+pub fn list_events(conn: &Connection, team_id: &str) -> Result<Vec<RecoveryEvent>, String> {
+    let mut stmt = conn
+        .prepare("SELECT event FROM team_events WHERE team_id = ?1 ORDER BY timestamp DESC, rowid DESC")
+        .map_err(|e| format!("Failed to query team events: {}", e))?;
+    let rows = stmt
+        .query_map(params![team_id], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Failed to query team events: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read team events: {}", e))?;
+    rows.iter().map(|event| from_json(event)).collect()
+}
+
+/// Record an event, keeping only the newest `keep` events of the team
+pub fn add_event(conn: &Connection, team_id: &str, event: &RecoveryEvent, keep: usize) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO team_events (id, team_id, timestamp, event) VALUES (?1, ?2, ?3, ?4)",
+        params![event.id, team_id, format_time(&event.timestamp), to_json(event)?],
+    )
+    .map_err(|e| format!("Failed to write team event: {}", e))?;
+    conn.execute(
+        "DELETE FROM team_events WHERE team_id = ?1 AND id NOT IN (
+            SELECT id FROM team_events WHERE team_id = ?1
+            ORDER BY timestamp DESC, rowid DESC LIMIT ?2
+         )",
+        params![team_id, keep as i64],
+    )
+    .map_err(|e| format!("Failed to prune team events: {}", e))?;
+    Ok(())
+}
+
+/// Replace all events of a team
+pub fn replace_events(conn: &Connection, team_id: &str, events: &[RecoveryEvent]) -> Result<(), String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to write team events: {}", e))?;
+    tx.execute("DELETE FROM team_events WHERE team_id = ?1", params![team_id])
+        .map_err(|e| format!("Failed to write team events: {}", e))?;
+    for event in events {
+        tx.execute(
+            "INSERT OR REPLACE INTO team_events (id, team_id, timestamp, event) VALUES (?1, ?2, ?3, ?4)",
+            params![event.id, team_id, format_time(&event.timestamp), to_json(event)?],
+        )
+        .map_err(|e| format!("Failed to write team event: {}", e))?;
+    }
+    tx.commit().map_err(|e| format!("Failed to write team events: {}", e))
+}
@@ -0,0 +1,479 @@
+//! Test Room Repository
+//!
+//! Rooms, checklist items, messages and artifacts of the Test Room feature.
+//! The tables are shared with @sidstack/shared, so their camelCase column
+//! names are kept.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TestRoom {
+    pub id: String,
+    #[serde(rename = "moduleId")]
+    pub module_id: String,
+    #[serde(rename = "specId")]
+    pub spec_id: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub status: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TestItem {
+    pub id: String,
+    #[serde(rename = "roomId")]
+    pub room_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+    #[serde(rename = "orderIndex")]
+    pub order_index: i32,
+    #[serde(rename = "resultNotes")]
+    pub result_notes: Option<String>,
+    #[serde(rename = "testedAt")]
+    pub tested_at: Option<i64>,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TestMessage {
+    pub id: String,
+    #[serde(rename = "roomId")]
+    pub room_id: String,
+    pub sender: String,
+    #[serde(rename = "messageType")]
+    pub message_type: String,
+    pub content: String,
+    pub metadata: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TestArtifact {
+    pub id: String,
+    #[serde(rename = "roomId")]
+    pub room_id: String,
+    #[serde(rename = "messageId")]
+    pub message_id: Option<String>,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub artifact_type: String,
+    pub path: Option<String>,
+    pub content: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestRoomSummary {
+    #[serde(rename = "totalItems")]
+    pub total_items: i32,
+    #[serde(rename = "passedItems")]
+    pub passed_items: i32,
+    #[serde(rename = "failedItems")]
+    pub failed_items: i32,
+    #[serde(rename = "pendingItems")]
+    pub pending_items: i32,
+    #[serde(rename = "skippedItems")]
+    pub skipped_items: i32,
+    #[serde(rename = "inProgressItems")]
+    pub in_progress_items: i32,
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+const ROOM_COLUMNS: &str = "id, moduleId, specId, name, description, status, createdAt, updatedAt";
+const ITEM_COLUMNS: &str =
+    "id, roomId, title, description, status, orderIndex, resultNotes, testedAt, createdAt";
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
+
+fn generate_id(prefix: &str) -> String {
+    let random: u32 = rand::random();
+    format!("{}_{:x}{:x}", prefix, now_millis(), random)
+}
+
+fn room_from_row(row: &Row) -> rusqlite::Result<TestRoom> {
+    Ok(TestRoom {
+        id: row.get(0)?,
+        module_id: row.get(1)?,
+        spec_id: row.get(2)?,
+        name: row.get(3)?,
+        description: row.get(4)?,
+        status: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn item_from_row(row: &Row) -> rusqlite::Result<TestItem> {
+    Ok(TestItem {
+        id: row.get(0)?,
+        room_id: row.get(1)?,
+        title: row.get(2)?,
+        description: row.get(3)?,
+        status: row.get(4)?,
+        order_index: row.get(5)?,
+        result_notes: row.get(6)?,
+        tested_at: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+fn touch_room(conn: &Connection, room_id: &str, now: i64) {
+    conn.execute("UPDATE test_rooms SET updatedAt = ? WHERE id = ?", params![now, room_id])
+        .ok();
+}
+
+// =============================================================================
+// Rooms
+// =============================================================================
+
+pub fn get_by_module(conn: &Connection, module_id: &str) -> Result<Option<TestRoom>, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM test_rooms WHERE moduleId = ?", ROOM_COLUMNS),
+        params![module_id],
+        room_from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+pub fn get_by_id(conn: &Connection, id: &str) -> Result<TestRoom, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM test_rooms WHERE id = ?", ROOM_COLUMNS),
+        params![id],
+        room_from_row,
+    )
+    .map_err(|e| e.to_string())
+}
+
+pub fn list(conn: &Connection) -> Result<Vec<TestRoom>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM test_rooms ORDER BY updatedAt DESC", ROOM_COLUMNS))
+        .map_err(|e| e.to_string())?;
+    let rooms = stmt
+        .query_map([], room_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rooms)
+}
+
+pub fn create(
+    conn: &Connection,
+    module_id: String,
+    name: Option<String>,
+    spec_id: Option<String>,
+) -> Result<TestRoom, String> {
+    let now = now_millis();
+    let id = generate_id("troom");
+    let room_name = name.unwrap_or_else(|| format!("Test Room: {}", module_id));
+
+    conn.execute(
+        "INSERT INTO test_rooms (id, moduleId, specId, name, status, createdAt, updatedAt) VALUES (?, ?, ?, ?, 'active', ?, ?)",
+        params![id, module_id, spec_id, room_name, now, now],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(TestRoom {
+        id,
+        module_id,
+        spec_id,
+        name: room_name,
+        description: None,
+        status: "active".to_string(),
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+pub fn update(
+    conn: &Connection,
+    id: &str,
+    name: Option<String>,
+    spec_id: Option<String>,
+    status: Option<String>,
+) -> Result<TestRoom, String> {
+    let mut updates = vec!["updatedAt = ?"];
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(now_millis())];
+
+    if let Some(n) = name {
+        updates.push("name = ?");
+        params.push(Box::new(n));
+    }
+    if let Some(s) = spec_id {
+        updates.push("specId = ?");
+        params.push(Box::new(s));
+    }
+    if let Some(st) = status {
+        updates.push("status = ?");
+        params.push(Box::new(st));
+    }
+
+    params.push(Box::new(id.to_string()));
+
+    let sql = format!("UPDATE test_rooms SET {} WHERE id = ?", updates.join(", "));
+    conn.execute(&sql, rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())))
+        .map_err(|e| e.to_string())?;
+
+    get_by_id(conn, id)
+}
+
+pub fn summary(conn: &Connection, room_id: &str) -> Result<TestRoomSummary, String> {
+    conn.query_row(
+        "SELECT
+            COUNT(*) as total,
+            COALESCE(SUM(CASE WHEN status = 'passed' THEN 1 ELSE 0 END), 0) as passed,
+            COALESCE(SUM(CASE WHEN status = 'failed' THEN 1 ELSE 0 END), 0) as failed,
+            COALESCE(SUM(CASE WHEN status = 'pending' THEN 1 ELSE 0 END), 0) as pending,
+            COALESCE(SUM(CASE WHEN status = 'skipped' THEN 1 ELSE 0 END), 0) as skipped,
+            COALESCE(SUM(CASE WHEN status = 'in_progress' THEN 1 ELSE 0 END), 0) as in_progress
+        FROM test_items WHERE roomId = ?",
+        params![room_id],
+        |row| {
+            Ok(TestRoomSummary {
+                total_items: row.get(0)?,
+                passed_items: row.get(1)?,
+                failed_items: row.get(2)?,
+                pending_items: row.get(3)?,
+                skipped_items: row.get(4)?,
+                in_progress_items: row.get(5)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+// =============================================================================
+// Items
+// =============================================================================
+
+pub fn list_items(conn: &Connection, room_id: &str) -> Result<Vec<TestItem>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM test_items WHERE roomId = ? ORDER BY orderIndex ASC",
+            ITEM_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let items = stmt
+        .query_map(params![room_id], item_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(items)
+}
+
+pub fn create_item(
+    conn: &Connection,
+    room_id: String,
+    title: String,
+    description: Option<String>,
+) -> Result<TestItem, String> {
+    let now = now_millis();
+    let id = generate_id("titem");
+
+    // Next order index
+    let order_index: i32 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(orderIndex), -1) + 1 FROM test_items WHERE roomId = ?",
+            params![room_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    conn.execute(
+        "INSERT INTO test_items (id, roomId, title, description, status, orderIndex, createdAt) VALUES (?, ?, ?, ?, 'pending', ?, ?)",
+        params![id, room_id, title, description, order_index, now],
+    )
+    .map_err(|e| e.to_string())?;
+    touch_room(conn, &room_id, now);
+
+    Ok(TestItem {
+        id,
+        room_id,
+        title,
+        description,
+        status: "pending".to_string(),
+        order_index,
+        result_notes: None,
+        tested_at: None,
+        created_at: now,
+    })
+}
+
+pub fn update_item(
+    conn: &Connection,
+    id: &str,
+    status: Option<String>,
+    result_notes: Option<String>,
+) -> Result<TestItem, String> {
+    let now = now_millis();
+    let mut updates = Vec::new();
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(s) = status {
+        // Set testedAt for pass/fail
+        let tested = s == "passed" || s == "failed";
+        updates.push("status = ?");
+        params_vec.push(Box::new(s));
+        if tested {
+            updates.push("testedAt = ?");
+            params_vec.push(Box::new(now));
+        }
+    }
+
+    if let Some(notes) = result_notes {
+        updates.push("resultNotes = ?");
+        params_vec.push(Box::new(notes));
+    }
+
+    if updates.is_empty() {
+        return Err("No updates provided".to_string());
+    }
+
+    params_vec.push(Box::new(id.to_string()));
+
+    let sql = format!("UPDATE test_items SET {} WHERE id = ?", updates.join(", "));
+    conn.execute(&sql, rusqlite::params_from_iter(params_vec.iter().map(|p| p.as_ref())))
+        .map_err(|e| e.to_string())?;
+
+    let item = conn
+        .query_row(
+            &format!("SELECT {} FROM test_items WHERE id = ?", ITEM_COLUMNS),
+            params![id],
+            item_from_row,
+        )
+        .map_err(|e| e.to_string())?;
+    touch_room(conn, &item.room_id, now);
+
+    Ok(item)
+}
+
+pub fn delete_item(conn: &Connection, id: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM test_items WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// =============================================================================
+// Messages & Artifacts
+// =============================================================================
+
+pub fn list_messages(conn: &Connection, room_id: &str, limit: i32) -> Result<Vec<TestMessage>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, roomId, sender, messageType, content, metadata, createdAt FROM test_messages WHERE roomId = ? ORDER BY createdAt ASC LIMIT ?")
+        .map_err(|e| e.to_string())?;
+    let messages = stmt
+        .query_map(params![room_id, limit], |row| {
+            Ok(TestMessage {
+                id: row.get(0)?,
+                room_id: row.get(1)?,
+                sender: row.get(2)?,
+                message_type: row.get(3)?,
+                content: row.get(4)?,
+                metadata: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(messages)
+}
+
+pub fn create_message(
+    conn: &Connection,
+    room_id: String,
+    sender: String,
+    message_type: String,
+    content: String,
+    metadata: Option<String>,
+) -> Result<TestMessage, String> {
+    let now = now_millis();
+    let id = generate_id("tmsg");
+
+    conn.execute(
+        "INSERT INTO test_messages (id, roomId, sender, messageType, content, metadata, createdAt) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        params![id, room_id, sender, message_type, content, metadata, now],
+    )
+    .map_err(|e| e.to_string())?;
+    touch_room(conn, &room_id, now);
+
+    Ok(TestMessage {
+        id,
+        room_id,
+        sender,
+        message_type,
+        content,
+        metadata,
+        created_at: now,
+    })
+}
+
+pub fn list_artifacts(conn: &Connection, room_id: &str) -> Result<Vec<TestArtifact>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, roomId, messageId, name, type, path, content, createdAt FROM test_artifacts WHERE roomId = ? ORDER BY createdAt DESC")
+        .map_err(|e| e.to_string())?;
+    let artifacts = stmt
+        .query_map(params![room_id], |row| {
+            Ok(TestArtifact {
+                id: row.get(0)?,
+                room_id: row.get(1)?,
+                message_id: row.get(2)?,
+                name: row.get(3)?,
+                artifact_type: row.get(4)?,
+                path: row.get(5)?,
+                content: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(artifacts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_room_items_and_summary() {
+        let conn = crate::db::open_in_memory().unwrap();
+        let room = create(&conn, "auth".to_string(), None, None).unwrap();
+        assert_eq!(get_by_module(&conn, "auth").unwrap().unwrap().id, room.id);
+
+        let empty = summary(&conn, &room.id).unwrap();
+        assert_eq!(empty.total_items, 0);
+        assert_eq!(empty.passed_items, 0);
+
+        let first = create_item(&conn, room.id.clone(), "Login".to_string(), None).unwrap();
+        let second = create_item(&conn, room.id.clone(), "Logout".to_string(), None).unwrap();
+        assert_eq!((first.order_index, second.order_index), (0, 1));
+
+        let passed = update_item(&conn, &first.id, Some("passed".to_string()), None).unwrap();
+        assert!(passed.tested_at.is_some());
+
+        let counts = summary(&conn, &room.id).unwrap();
+        assert_eq!((counts.total_items, counts.passed_items, counts.pending_items), (2, 1, 1));
+    }
+}
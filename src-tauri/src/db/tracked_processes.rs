@@ -0,0 +1,162 @@
+//! Tracked Process Repository
+//!
+//! Agent sessions and services started by the app, each recorded with the
+//! PID of the app run that started it, so a later run can terminate what a
+//! crashed run left behind (see `session_tracker`).
+
+use rusqlite::{params, Connection, Row};
+
+use super::sessions::{format_time, parse_time};
+use crate::session_tracker::{TrackedService, TrackedSession};
+
+const SESSION_COLUMNS: &str = "session_id, app_pid, pid, terminal_id, role, cwd, started_at, process_start";
+const SERVICE_COLUMNS: &str = "name, app_pid, pid, started_at, process_start";
+
+fn session_from_row(row: &Row) -> rusqlite::Result<(u32, TrackedSession)> {
+    let session = TrackedSession {
+        session_id: row.get(0)?,
+        pid: row.get(2)?,
+        terminal_id: row.get(3)?,
+        role: row.get(4)?,
+        cwd: row.get(5)?,
+        started_at: parse_time(&row.get::<_, String>(6)?)?,
+        process_start: row.get(7)?,
+    };
+    Ok((row.get(1)?, session))
+}
+
+fn service_from_row(row: &Row) -> rusqlite::Result<(u32, TrackedService)> {
+    let service = TrackedService {
+        name: row.get(0)?,
+        pid: row.get(2)?,
+        started_at: parse_time(&row.get::<_, String>(3)?)?,
+        process_start: row.get(4)?,
+    };
+    Ok((row.get(1)?, service))
+}
+
+/// Every tracked session as (app PID, session)
+pub fn list_sessions(conn: &Connection) -> Result<Vec<(u32, TrackedSession)>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM tracked_sessions", SESSION_COLUMNS))
+        .map_err(|e| format!("Failed to query tracked sessions: {}", e))?;
+    let rows = stmt
+        .query_map([], session_from_row)
+        .map_err(|e| format!("Failed to query tracked sessions: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read tracked sessions: {}", e))
+}
+
+/// Every tracked service as (app PID, service)
+pub fn list_services(conn: &Connection) -> Result<Vec<(u32, TrackedService)>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM tracked_services", SERVICE_COLUMNS))
+        .map_err(|e| format!("Failed to query tracked services: {}", e))?;
+    let rows = stmt
+        .query_map([], service_from_row)
+        .map_err(|e| format!("Failed to query tracked services: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read tracked services: {}", e))
+}
+
+/// Track a session started by the app run `app_pid`
+pub fn upsert_session(conn: &Connection, app_pid: u32, session: &TrackedSession) -> Result<(), String> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO tracked_sessions ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            SESSION_COLUMNS
+        ),
+        params![
+            session.session_id,
+            app_pid,
+            session.pid,
+            session.terminal_id,
+            session.role,
+            session.cwd,
+            format_time(&session.started_at),
+            session.process_start,
+        ],
+    )
+    .map_err(|e| format!("Failed to save tracked session: {}", e))?;
+    Ok(())
+}
+
+/// Track a service started by the app run `app_pid`, replacing one of the same name
+pub fn upsert_service(conn: &Connection, app_pid: u32, service: &TrackedService) -> Result<(), String> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO tracked_services ({}) VALUES (?1, ?2, ?3, ?4, ?5)",
+            SERVICE_COLUMNS
+        ),
+        params![
+            service.name,
+            app_pid,
+            service.pid,
+            format_time(&service.started_at),
+            service.process_start,
+        ],
+    )
+    .map_err(|e| format!("Failed to save tracked service: {}", e))?;
+    Ok(())
+}
+
+/// Stop tracking a session
+pub fn delete_session(conn: &Connection, session_id: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM tracked_sessions WHERE session_id = ?1", params![session_id])
+        .map_err(|e| format!("Failed to delete tracked session: {}", e))?;
+    Ok(())
+}
+
+/// Stop tracking a service
+pub fn delete_service(conn: &Connection, name: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM tracked_services WHERE name = ?1", params![name])
+        .map_err(|e| format!("Failed to delete tracked service: {}", e))?;
+    Ok(())
+}
+
+/// Stop tracking everything the app run `app_pid` started
+pub fn delete_for_app(conn: &Connection, app_pid: u32) -> Result<(), String> {
+    conn.execute("DELETE FROM tracked_sessions WHERE app_pid = ?1", params![app_pid])
+        .and_then(|_| conn.execute("DELETE FROM tracked_services WHERE app_pid = ?1", params![app_pid]))
+        .map_err(|e| format!("Failed to delete tracked processes: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn session(id: &str, pid: u32) -> TrackedSession {
+        TrackedSession {
+            session_id: id.to_string(),
+            pid,
+            terminal_id: None,
+            role: Some("dev".to_string()),
+            cwd: "/tmp".to_string(),
+            started_at: Utc::now(),
+            process_start: Some("123".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_delete_for_app_keeps_other_runs() {
+        let conn = crate::db::open_in_memory().unwrap();
+        upsert_session(&conn, 100, &session("s1", 1001)).unwrap();
+        upsert_session(&conn, 200, &session("s2", 2001)).unwrap();
+        let service = TrackedService {
+            name: "api-server".to_string(),
+            pid: 1002,
+            started_at: Utc::now(),
+            process_start: None,
+        };
+        upsert_service(&conn, 100, &service).unwrap();
+
+        delete_for_app(&conn, 100).unwrap();
+        let sessions = list_sessions(&conn).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!((sessions[0].0, sessions[0].1.session_id.as_str()), (200, "s2"));
+        assert_eq!(sessions[0].1.process_start.as_deref(), Some("123"));
+        assert!(list_services(&conn).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+//! Claude Transcript Repository
+//!
+//! One row per transcript file, holding the summary `claude_transcripts`
+//! extracted and how far into the file it has read.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use super::sessions::{format_time, parse_time};
+use crate::claude_transcripts::TranscriptInfo;
+
+const COLUMNS: &str = "path, session_id, project_dir, cwd, first_prompt, model, turn_count, \
+    cost_usd, started_at, last_modified, size_bytes, byte_offset";
+
+fn from_row(row: &Row) -> rusqlite::Result<TranscriptInfo> {
+    Ok(TranscriptInfo {
+        path: row.get(0)?,
+        session_id: row.get(1)?,
+        project_dir: row.get(2)?,
+        cwd: row.get(3)?,
+        first_prompt: row.get(4)?,
+        model: row.get(5)?,
+        turn_count: row.get::<_, i64>(6)? as u32,
+        cost_usd: row.get(7)?,
+        started_at: row.get::<_, Option<String>>(8)?.as_deref().map(parse_time).transpose()?,
+        last_modified: parse_time(&row.get::<_, String>(9)?)?,
+        size_bytes: row.get::<_, i64>(10)? as u64,
+        byte_offset: row.get::<_, i64>(11)? as u64,
+    })
+}
+
+/// Every indexed transcript, most recently modified first
+pub fn list_all(conn: &Connection) -> Result<Vec<TranscriptInfo>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM claude_transcripts ORDER BY last_modified DESC",
+            COLUMNS
+        ))
+        .map_err(|e| format!("Failed to query transcripts: {}", e))?;
+    let rows = stmt
+        .query_map([], from_row)
+        .map_err(|e| format!("Failed to query transcripts: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read transcripts: {}", e))
+}
+
+/// The transcript of a Claude session id (the most recent, if it was copied)
+pub fn get_by_session(conn: &Connection, session_id: &str) -> Result<Option<TranscriptInfo>, String> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM claude_transcripts WHERE session_id = ?1 ORDER BY last_modified DESC LIMIT 1",
+            COLUMNS
+        ),
+        params![session_id],
+        from_row,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to read transcript: {}", e))
+}
+
+/// Insert or replace transcripts and drop the ones whose files are gone
+pub fn apply(conn: &mut Connection, updated: &[TranscriptInfo], removed: &[String]) -> Result<(), String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to update transcript index: {}", e))?;
+    let sql = format!(
+        "INSERT OR REPLACE INTO claude_transcripts ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        COLUMNS
+    );
+    for info in updated {
+        tx.execute(
+            &sql,
+            params![
+                info.path,
+                info.session_id,
+                info.project_dir,
+                info.cwd,
+                info.first_prompt,
+                info.model,
+                info.turn_count as i64,
+                info.cost_usd,
+                info.started_at.as_ref().map(format_time),
+                format_time(&info.last_modified),
+                info.size_bytes as i64,
+                info.byte_offset as i64,
+            ],
+        )
+        .map_err(|e| format!("Failed to write transcript: {}", e))?;
+    }
+    for path in removed {
+        tx.execute("DELETE FROM claude_transcripts WHERE path = ?1", params![path])
+            .map_err(|e| format!("Failed to delete transcript: {}", e))?;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to update transcript index: {}", e))
+}
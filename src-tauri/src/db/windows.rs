@@ -0,0 +1,80 @@
+//! Window State Repository
+//!
+//! Saved position and size of task windows, by window label.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::commands::window::{WindowPosition, WindowState};
+
+const COLUMNS: &str = "label, title, x, y, width, height, task_id";
+
+fn from_row(row: &Row) -> rusqlite::Result<WindowState> {
+    let geometry: (Option<i32>, Option<i32>, Option<u32>, Option<u32>) =
+        (row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?);
+    Ok(WindowState {
+        label: row.get(0)?,
+        title: row.get(1)?,
+        position: match geometry {
+            (Some(x), Some(y), Some(width), Some(height)) => Some(WindowPosition { x, y, width, height }),
+            _ => None,
+        },
+        task_id: row.get(6)?,
+    })
+}
+
+/// The saved state of a window
+pub fn get(conn: &Connection, label: &str) -> Result<Option<WindowState>, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM window_states WHERE label = ?1", COLUMNS),
+        params![label],
+        from_row,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to read window state: {}", e))
+}
+
+/// Insert or replace the state of a window
+pub fn upsert(conn: &Connection, state: &WindowState) -> Result<(), String> {
+    let position = state.position.as_ref();
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO window_states ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            COLUMNS
+        ),
+        params![
+            state.label,
+            state.title,
+            position.map(|p| p.x),
+            position.map(|p| p.y),
+            position.map(|p| p.width),
+            position.map(|p| p.height),
+            state.task_id,
+        ],
+    )
+    .map_err(|e| format!("Failed to save window state: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upsert_replaces_state() {
+        let conn = crate::db::open_in_memory().unwrap();
+        let mut state = WindowState {
+            label: "task-1".to_string(),
+            title: "Task 1".to_string(),
+            position: None,
+            task_id: Some("1".to_string()),
+        };
+        upsert(&conn, &state).unwrap();
+        assert!(get(&conn, "task-1").unwrap().unwrap().position.is_none());
+
+        state.position = Some(WindowPosition { x: -20, y: 40, width: 1000, height: 700 });
+        upsert(&conn, &state).unwrap();
+        let stored = get(&conn, "task-1").unwrap().unwrap();
+        assert_eq!(stored.position.map(|p| (p.x, p.width)), Some((-20, 1000)));
+        assert!(get(&conn, "task-2").unwrap().is_none());
+    }
+}
@@ -0,0 +1,465 @@
+//! Failure Patterns - Recognizes failing agents from their output
+//!
+//! A `FailureDetector` is fed a member session's `ClaudeEvent`s and stderr
+//! lines (or, for a session that already exited, the tail of its transcript)
+//! and reports the first rule that fires. Built-in rules:
+//! - `api-overloaded` / `rate-limited`: API errors Claude gave up retrying (notify)
+//! - `context-exhausted`: the conversation no longer fits the context window (recover)
+//! - `crash`: crash traces on stderr (recover)
+//! - `error-result`: a turn ended with `is_error` (notify)
+//! - `repeated-tool-failure`: the same tool error several times in a row (recover)
+//!
+//! Rules are regexes matched against one kind of text (`source`); by default
+//! the error texts only, never the assistant's own prose. Projects
+//! add rules, replace built-ins (same `id`) or turn them off in the
+//! `failure_patterns` section of config.yaml. `recover` rules make the
+//! watchdog replace the member; `notify` rules are only reported.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::claude_process::{ClaudeEvent, ContentBlock, UserMessageContent};
+
+/// Rule ID reported for repeated identical tool failures
+pub const REPEATED_TOOL_FAILURE: &str = "repeated-tool-failure";
+
+/// How much of a transcript's end `scan_transcript` reads
+const TRANSCRIPT_TAIL_BYTES: u64 = 64 * 1024;
+
+/// Longest excerpt of the matched text kept in a `FailureMatch`
+const EXCERPT_CHARS: usize = 200;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// What happens when a rule fires
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FailureSeverity {
+    /// Report it to the user
+    #[default]
+    Notify,
+    /// Replace the member
+    Recover,
+}
+
+/// Which text a rule is matched against
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternSource {
+    /// Text of assistant messages
+    Output,
+    /// Output of failed tool calls
+    ToolError,
+    /// Error events and results of turns that ended with `is_error`
+    Error,
+    /// Lines the CLI wrote to stderr
+    Stderr,
+    /// `error`, `tool_error` and `stderr`
+    #[default]
+    Errors,
+    /// Everything, assistant messages included
+    Any,
+}
+
+impl PatternSource {
+    /// Whether a rule with this source checks text from `source`
+    fn covers(self, source: PatternSource) -> bool {
+        match self {
+            PatternSource::Any => true,
+            PatternSource::Errors => source != PatternSource::Output,
+            _ => self == source,
+        }
+    }
+}
+
+/// A failure signature
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FailureRule {
+    pub id: String,
+    /// Regex; use `(?i)` for case-insensitive matching
+    pub pattern: String,
+    #[serde(default)]
+    pub source: PatternSource,
+    #[serde(default)]
+    pub severity: FailureSeverity,
+    /// Matches needed before the rule fires
+    #[serde(default = "default_occurrences")]
+    pub occurrences: u32,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+fn default_occurrences() -> u32 { 1 }
+
+/// Failure pattern settings (the `failure_patterns` section of config.yaml)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FailurePatternSettings {
+    pub enabled: bool,
+    /// Identical tool errors in a row that count as a failure (0 = off)
+    pub repeated_tool_failures: u32,
+    /// Rules added to the built-in ones; a rule with a built-in's ID replaces it
+    pub rules: Vec<FailureRule>,
+    /// IDs of built-in rules to turn off
+    pub disabled: Vec<String>,
+}
+
+impl Default for FailurePatternSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            repeated_tool_failures: 3,
+            rules: Vec::new(),
+            disabled: Vec::new(),
+        }
+    }
+}
+
+impl FailurePatternSettings {
+    /// Check that every rule compiles
+    pub fn validate(&self) -> Result<(), String> {
+        for rule in &self.rules {
+            if rule.id.trim().is_empty() {
+                return Err("failure_patterns.rules: every rule needs an id".to_string());
+            }
+            if rule.occurrences == 0 {
+                return Err(format!("failure_patterns rule {}: occurrences must be at least 1", rule.id));
+            }
+            Regex::new(&rule.pattern)
+                .map_err(|e| format!("failure_patterns rule {}: invalid pattern: {}", rule.id, e))?;
+        }
+        Ok(())
+    }
+
+    /// Built-in rules (minus the disabled ones) followed by the configured rules
+    pub fn effective_rules(&self) -> Vec<FailureRule> {
+        let mut rules: Vec<FailureRule> = builtin_rules()
+            .into_iter()
+            .filter(|r| !self.disabled.contains(&r.id) && !self.rules.iter().any(|c| c.id == r.id))
+            .collect();
+        rules.extend(self.rules.iter().cloned());
+        rules
+    }
+}
+
+/// A rule that fired
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureMatch {
+    pub rule_id: String,
+    pub severity: FailureSeverity,
+    pub description: String,
+    /// Start of the text that matched
+    pub excerpt: String,
+}
+
+fn rule(id: &str, pattern: &str, source: PatternSource, severity: FailureSeverity, description: &str) -> FailureRule {
+    FailureRule {
+        id: id.to_string(),
+        pattern: pattern.to_string(),
+        source,
+        severity,
+        occurrences: 1,
+        description: Some(description.to_string()),
+    }
+}
+
+/// Rules every project starts with
+pub fn builtin_rules() -> Vec<FailureRule> {
+    use FailureSeverity::{Notify, Recover};
+    use PatternSource::{Error, Errors, Stderr};
+    vec![
+        rule("api-overloaded", r"(?i)overloaded_error|API Error: 529", Errors, Notify, "Claude API overloaded"),
+        rule(
+            "rate-limited",
+            r"(?i)rate_limit_error|API Error: 429|usage limit reached",
+            Errors,
+            Notify,
+            "Claude API rate limit reached",
+        ),
+        rule(
+            "context-exhausted",
+            r"(?i)prompt is too long|context_length_exceeded|context window (is )?(full|exceeded)",
+            Errors,
+            Recover,
+            "Context window exhausted",
+        ),
+        rule(
+            "crash",
+            r"(?i)panicked at|uncaught (exception|error)|unhandled( promise)? rejection|fatal error|segmentation fault|heap out of memory",
+            Stderr,
+            Recover,
+            "Claude CLI crashed",
+        ),
+        rule("error-result", "", Error, Notify, "Turn ended with an error"),
+    ]
+}
+
+// ============================================================================
+// Detector
+// ============================================================================
+
+/// Matches a session's output against the rules
+pub struct FailureDetector {
+    rules: Vec<(FailureRule, Regex)>,
+    /// Matches so far by rule ID, for rules that need several
+    counts: HashMap<String, u32>,
+    repeated_tool_failures: u32,
+    /// Last tool error and how many times in a row it occurred
+    last_tool_error: Option<(String, u32)>,
+}
+
+impl FailureDetector {
+    pub fn new(settings: &FailurePatternSettings) -> Self {
+        let rules = settings
+            .effective_rules()
+            .into_iter()
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(regex) => Some((rule, regex)),
+                Err(e) => {
+                    eprintln!("[FailurePatterns] Skipping rule {}: {}", rule.id, e);
+                    None
+                }
+            })
+            .collect();
+        Self {
+            rules,
+            counts: HashMap::new(),
+            repeated_tool_failures: settings.repeated_tool_failures,
+            last_tool_error: None,
+        }
+    }
+
+    /// Check an event; returns the first rule that fires
+    pub fn observe(&mut self, event: &ClaudeEvent) -> Option<FailureMatch> {
+        let mut found = None;
+        for (source, text) in event_texts(event) {
+            let fired = match source {
+                PatternSource::ToolError => self.check_repeated(&text).or_else(|| self.check(source, &text)),
+                _ => self.check(source, &text),
+            };
+            found = found.or(fired);
+        }
+        if is_successful_tool_result(event) {
+            self.last_tool_error = None;
+        }
+        found
+    }
+
+    /// Check a line the CLI wrote to stderr
+    pub fn observe_stderr(&mut self, line: &str) -> Option<FailureMatch> {
+        self.check(PatternSource::Stderr, line)
+    }
+
+    fn check(&mut self, source: PatternSource, text: &str) -> Option<FailureMatch> {
+        for (rule, regex) in &self.rules {
+            if !rule.source.covers(source) || !regex.is_match(text) {
+                continue;
+            }
+            let count = self.counts.entry(rule.id.clone()).or_insert(0);
+            *count += 1;
+            if *count >= rule.occurrences {
+                *count = 0;
+                return Some(FailureMatch {
+                    rule_id: rule.id.clone(),
+                    severity: rule.severity,
+                    description: rule.description.clone().unwrap_or_else(|| rule.id.clone()),
+                    excerpt: excerpt(text),
+                });
+            }
+        }
+        None
+    }
+
+    fn check_repeated(&mut self, error: &str) -> Option<FailureMatch> {
+        let count = match &mut self.last_tool_error {
+            Some((last, count)) if last == error => {
+                *count += 1;
+                *count
+            }
+            _ => {
+                self.last_tool_error = Some((error.to_string(), 1));
+                1
+            }
+        };
+        if self.repeated_tool_failures == 0 || count < self.repeated_tool_failures {
+            return None;
+        }
+        self.last_tool_error = None;
+        Some(FailureMatch {
+            rule_id: REPEATED_TOOL_FAILURE.to_string(),
+            severity: FailureSeverity::Recover,
+            description: format!("Same tool error {} times in a row", count),
+            excerpt: excerpt(error),
+        })
+    }
+}
+
+/// The texts of an event rules can match, by source
+fn event_texts(event: &ClaudeEvent) -> Vec<(PatternSource, String)> {
+    let mut texts = Vec::new();
+    match event {
+        ClaudeEvent::Assistant { message: Some(message), .. } => {
+            for block in message.content.iter().flatten() {
+                if let ContentBlock::Text { text } = block {
+                    texts.push((PatternSource::Output, text.clone()));
+                }
+            }
+        }
+        ClaudeEvent::User { message: Some(message), .. } => {
+            if let Some(UserMessageContent::Array(blocks)) = &message.content {
+                for block in blocks {
+                    if let ContentBlock::ToolResult { content, is_error: Some(true), .. } = block {
+                        texts.push((PatternSource::ToolError, tool_result_text(content)));
+                    }
+                }
+            }
+        }
+        ClaudeEvent::ToolResult { output, is_error: Some(true), .. } => {
+            texts.push((PatternSource::ToolError, output.clone().unwrap_or_default()));
+        }
+        ClaudeEvent::Result { result, is_error: Some(true), .. } => {
+            texts.push((PatternSource::Error, result.clone().unwrap_or_default()));
+        }
+        ClaudeEvent::Error { error } => {
+            let error = error.as_ref();
+            let text = error
+                .and_then(|e| e.message.clone().or_else(|| e.code.clone()))
+                .unwrap_or_default();
+            texts.push((PatternSource::Error, text));
+        }
+        _ => {}
+    }
+    texts
+}
+
+fn is_successful_tool_result(event: &ClaudeEvent) -> bool {
+    match event {
+        ClaudeEvent::ToolResult { is_error, .. } => *is_error != Some(true),
+        ClaudeEvent::User { message: Some(message), .. } => match &message.content {
+            Some(UserMessageContent::Array(blocks)) => blocks
+                .iter()
+                .any(|b| matches!(b, ContentBlock::ToolResult { is_error, .. } if *is_error != Some(true))),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Tool result content is a string or a list of text blocks
+fn tool_result_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => other.to_string(),
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(EXCERPT_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+// ============================================================================
+// Transcripts
+// ============================================================================
+
+/// Last rule that fires in the end of a transcript
+/// Used to tell why a session that already exited failed
+pub fn scan_transcript(path: &Path, settings: &FailurePatternSettings) -> Result<Option<FailureMatch>, String> {
+    let mut detector = FailureDetector::new(settings);
+    let mut found = None;
+    for event in crate::claude_transcripts::tail_events(path, TRANSCRIPT_TAIL_BYTES)? {
+        found = detector.observe(&event).or(found);
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(json: &str) -> ClaudeEvent {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_rules_and_severity() {
+        let mut detector = FailureDetector::new(&FailurePatternSettings::default());
+
+        let overloaded = event(r#"{"type":"result","is_error":true,"result":"API Error: 529 {\"type\":\"overloaded_error\"}"}"#);
+        let found = detector.observe(&overloaded).unwrap();
+        assert_eq!(found.rule_id, "api-overloaded");
+        assert_eq!(found.severity, FailureSeverity::Notify);
+
+        // The assistant talking about errors is not an error
+        let prose = event(r#"{"type":"assistant","message":{"content":[{"type":"text","text":"The API returns 'prompt is too long' when context_length_exceeded."}]}}"#);
+        assert_eq!(detector.observe(&prose), None);
+        let exhausted = event(r#"{"type":"error","error":{"message":"Prompt is too long"}}"#);
+        let found = detector.observe(&exhausted).unwrap();
+        assert_eq!((found.rule_id.as_str(), found.severity), ("context-exhausted", FailureSeverity::Recover));
+
+        let found = detector.observe_stderr("thread 'main' panicked at src/main.rs:3:5").unwrap();
+        assert_eq!((found.rule_id.as_str(), found.severity), ("crash", FailureSeverity::Recover));
+
+        let ok = event(r#"{"type":"assistant","message":{"content":[{"type":"text","text":"All tests pass."}]}}"#);
+        assert_eq!(detector.observe(&ok), None);
+        assert_eq!(detector.observe_stderr("warning: unused import"), None);
+    }
+
+    #[test]
+    fn test_repeated_tool_failure() {
+        let failed = event(r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"npm ERR! missing script: tset","is_error":true}]}}"#);
+        let succeeded = event(r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t2","content":"ok"}]}}"#);
+        let mut detector = FailureDetector::new(&FailurePatternSettings::default());
+
+        assert_eq!(detector.observe(&failed), None);
+        assert_eq!(detector.observe(&failed), None);
+        assert_eq!(detector.observe(&succeeded), None);
+        assert_eq!(detector.observe(&failed), None);
+        assert_eq!(detector.observe(&failed), None);
+        let found = detector.observe(&failed).unwrap();
+        assert_eq!(found.rule_id, REPEATED_TOOL_FAILURE);
+        assert_eq!(found.excerpt, "npm ERR! missing script: tset");
+    }
+
+    #[test]
+    fn test_project_rules_replace_and_disable_builtins() {
+        let settings = FailurePatternSettings {
+            rules: vec![FailureRule {
+                id: "rate-limited".to_string(),
+                pattern: "(?i)rate limit".to_string(),
+                source: PatternSource::Any,
+                severity: FailureSeverity::Recover,
+                occurrences: 2,
+                description: None,
+            }],
+            disabled: vec!["error-result".to_string()],
+            ..Default::default()
+        };
+        let ids: Vec<String> = settings.effective_rules().into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, ["api-overloaded", "context-exhausted", "crash", "rate-limited"]);
+
+        let mut detector = FailureDetector::new(&settings);
+        let limited = event(r#"{"type":"result","is_error":true,"result":"Rate limit hit"}"#);
+        assert_eq!(detector.observe(&limited), None);
+        let found = detector.observe(&limited).unwrap();
+        assert_eq!((found.rule_id.as_str(), found.severity), ("rate-limited", FailureSeverity::Recover));
+
+        let invalid = FailurePatternSettings {
+            rules: vec![FailureRule { pattern: "(".to_string(), ..settings.rules[0].clone() }],
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,534 @@
+//! Group Chat Storage
+//!
+//! Durable storage for multi-agent group chat:
+//! - Message log per project (append-only JSONL)
+//! - Per-role inboxes of pending messages
+//! - Threaded replies via message id / parent id
+//!
+//! Layout: ~/.sidstack/agent-manager/groupchat/{project_hash}/
+//!   messages.jsonl  - one GroupChatMessage per line, oldest first
+//!   inbox.json      - role -> pending messages
+//!
+//! The byte offset and thread of every message are kept in memory and
+//! extended as the log grows, so replies and history cursors find their
+//! message without rescanning the log. History is read from the end.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+const MESSAGES_FILE: &str = "messages.jsonl";
+const INBOX_FILE: &str = "inbox.json";
+/// Bucket used for messages sent without a project path
+pub(crate) const GLOBAL_BUCKET: &str = "global";
+/// Chunk size used when reading the message log backwards
+const REVERSE_CHUNK_BYTES: u64 = 64 * 1024;
+
+/// Message index per message log
+static INDEXES: Mutex<Option<HashMap<PathBuf, MessageIndex>>> = Mutex::new(None);
+/// Held while looking up a reply's thread and appending it
+static MESSAGE_WRITER: Mutex<()> = Mutex::new(());
+/// Held for each read-modify-write of an inbox
+static INBOX_WRITER: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Group chat message structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatMessage {
+    /// Unique message ID
+    #[serde(default)]
+    pub id: String,
+    /// Message this one replies to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    /// ID of the root message of the thread (own ID for top-level messages)
+    #[serde(default)]
+    pub thread_id: String,
+    pub from_role: String,
+    pub from_id: String,
+    pub content: String,
+    pub mentions: Vec<String>,
+    pub timestamp: String,
+    /// Optional reply_to field - specifies who should receive responses
+    /// Used to prevent message injection back to sender
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+}
+
+/// Pending message for an agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingMessage {
+    #[serde(default)]
+    pub message_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    pub from_role: String,
+    pub content: String,
+    pub timestamp: String,
+    /// Optional reply_to field - specifies who should receive responses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+}
+
+/// Where a message is in the log
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    offset: u64,
+    thread_id: String,
+}
+
+/// Messages of one log by ID, up to `len` bytes
+#[derive(Debug, Default)]
+struct MessageIndex {
+    len: u64,
+    entries: HashMap<String, IndexEntry>,
+}
+
+/// The fields of a log line the index needs
+#[derive(Deserialize)]
+struct IndexedLine {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    thread_id: String,
+}
+
+/// Filters for reading chat history
+#[derive(Debug, Clone, Default)]
+pub struct HistoryQuery {
+    /// Maximum number of messages to return
+    pub limit: usize,
+    /// Only return messages older than this message ID (pagination cursor)
+    pub before: Option<String>,
+    /// Case-insensitive substring match on content, role or mentions
+    pub search: Option<String>,
+    /// Only return messages belonging to this thread
+    pub thread_id: Option<String>,
+}
+
+// ============================================================================
+// Store
+// ============================================================================
+
+/// Group chat store for a single project
+pub struct GroupChatStore {
+    dir: PathBuf,
+}
+
+/// Get the base group chat directory
+/// ~/.sidstack/agent-manager/groupchat/
+pub fn groupchat_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sidstack")
+        .join("agent-manager")
+        .join("groupchat")
+}
+
+impl GroupChatStore {
+    /// Open the store for a project (or the global bucket when no project is given)
+    pub fn for_project(project_path: Option<&str>) -> Self {
+        let bucket = match project_path {
+            Some(path) if !path.is_empty() => crate::session_storage::hash_project(path),
+            _ => GLOBAL_BUCKET.to_string(),
+        };
+        Self::new(groupchat_dir().join(bucket))
+    }
+
+    /// Open a store rooted at an explicit directory
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn ensure_dir(&self) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create group chat directory: {}", e))
+    }
+
+    fn messages_path(&self) -> PathBuf {
+        self.dir.join(MESSAGES_FILE)
+    }
+
+    fn inbox_path(&self) -> PathBuf {
+        self.dir.join(INBOX_FILE)
+    }
+
+    // ===== Messages =====
+
+    /// Append a message to the log
+    pub fn append_message(&self, message: &GroupChatMessage) -> Result<(), String> {
+        self.ensure_dir()?;
+
+        let line = serde_json::to_string(message)
+            .map_err(|e| format!("Failed to serialize message: {}", e))?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.messages_path())
+            .map_err(|e| format!("Failed to open message log: {}", e))?;
+
+        writeln!(file, "{}", line).map_err(|e| format!("Failed to write message: {}", e))
+    }
+
+    /// Look up a message in the index, catching up on new lines first
+    fn find_indexed(&self, message_id: &str) -> Result<Option<IndexEntry>, String> {
+        let path = self.messages_path();
+        let mut indexes = INDEXES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let index = indexes
+            .get_or_insert_with(HashMap::new)
+            .entry(path.clone())
+            .or_default();
+        update_index(index, &path)?;
+        Ok(index.entries.get(message_id).cloned())
+    }
+
+    /// Append a message and queue it for `recipients`
+    ///
+    /// A reply joins the thread of its parent, which must exist.
+    pub fn post(
+        &self,
+        mut message: GroupChatMessage,
+        recipients: &[String],
+        pending: &PendingMessage,
+    ) -> Result<GroupChatMessage, String> {
+        {
+            let _writer = lock(&MESSAGE_WRITER);
+            if let Some(parent) = &message.parent_id {
+                message.thread_id = self
+                    .find_indexed(parent)?
+                    .map(|entry| entry.thread_id)
+                    .ok_or_else(|| format!("Parent message not found: {}", parent))?;
+            }
+            self.append_message(&message)?;
+        }
+        self.enqueue(recipients, pending)?;
+        Ok(message)
+    }
+
+    /// Query history, newest first
+    pub fn history(&self, query: &HistoryQuery) -> Result<Vec<GroupChatMessage>, String> {
+        let path = self.messages_path();
+
+        // Cursor: only consider messages stored before the given message
+        let end = match &query.before {
+            Some(before) => {
+                self.find_indexed(before)?
+                    .ok_or_else(|| format!("Unknown history cursor: {}", before))?
+                    .offset
+            }
+            None => match fs::metadata(&path) {
+                Ok(metadata) => metadata.len(),
+                Err(_) => return Ok(Vec::new()),
+            },
+        };
+        let file = File::open(&path).map_err(|e| format!("Failed to open message log: {}", e))?;
+
+        let needle = query.search.as_ref().map(|s| s.to_lowercase());
+
+        // Skip unparseable lines (e.g. a partial write from a crash)
+        Ok(ReverseLines::new(file, end)
+            .filter_map(|line| serde_json::from_str::<GroupChatMessage>(&line).ok())
+            .filter(|m| {
+                query
+                    .thread_id
+                    .as_ref()
+                    .is_none_or(|thread| &m.thread_id == thread)
+            })
+            .filter(|m| needle.as_ref().is_none_or(|n| matches_search(m, n)))
+            .take(query.limit)
+            .collect())
+    }
+
+    // ===== Inboxes =====
+
+    /// Load all role inboxes
+    pub fn load_inbox(&self) -> Result<HashMap<String, Vec<PendingMessage>>, String> {
+        let inbox = crate::persist::read_json(&self.inbox_path())
+            .map_err(|e| format!("Failed to read inbox: {}", e))?;
+        Ok(inbox.unwrap_or_default())
+    }
+
+    fn save_inbox(&self, inbox: &HashMap<String, Vec<PendingMessage>>) -> Result<(), String> {
+        self.ensure_dir()?;
+        crate::persist::write_json(&self.inbox_path(), inbox)
+            .map_err(|e| format!("Failed to write inbox: {}", e))
+    }
+
+    /// Queue a message for each role (roles are stored lowercase)
+    pub fn enqueue(&self, roles: &[String], pending: &PendingMessage) -> Result<(), String> {
+        if roles.is_empty() {
+            return Ok(());
+        }
+
+        let _writer = lock(&INBOX_WRITER);
+        let mut inbox = self.load_inbox()?;
+        for role in roles {
+            inbox
+                .entry(role.to_lowercase())
+                .or_default()
+                .push(pending.clone());
+        }
+        self.save_inbox(&inbox)
+    }
+
+    /// Take and clear all pending messages for a role
+    pub fn take_pending(&self, role: &str) -> Result<Vec<PendingMessage>, String> {
+        let _writer = lock(&INBOX_WRITER);
+        let mut inbox = self.load_inbox()?;
+        let pending = inbox.remove(&role.to_lowercase()).unwrap_or_default();
+        if !pending.is_empty() {
+            self.save_inbox(&inbox)?;
+        }
+        Ok(pending)
+    }
+}
+
+fn lock(mutex: &'static Mutex<()>) -> MutexGuard<'static, ()> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Index the complete lines appended to a message log since the last update
+fn update_index(index: &mut MessageIndex, path: &Path) -> Result<(), String> {
+    let len = match fs::metadata(path) {
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    };
+    // The log only grows; anything else means it was replaced
+    if len < index.len {
+        *index = MessageIndex::default();
+    }
+    if len == index.len {
+        return Ok(());
+    }
+
+    let mut file = File::open(path).map_err(|e| format!("Failed to open message log: {}", e))?;
+    file.seek(SeekFrom::Start(index.len))
+        .map_err(|e| format!("Failed to read message log: {}", e))?;
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| format!("Failed to read message log: {}", e))?;
+        // A line without its newline is still being written
+        if read == 0 || line.last() != Some(&b'\n') {
+            break;
+        }
+        if let Ok(indexed) = serde_json::from_slice::<IndexedLine>(&line) {
+            if !indexed.id.is_empty() {
+                let entry = IndexEntry {
+                    offset: index.len,
+                    thread_id: indexed.thread_id,
+                };
+                index.entries.insert(indexed.id, entry);
+            }
+        }
+        index.len += read as u64;
+    }
+    Ok(())
+}
+
+/// Lines of a file before byte `end`, last line first
+struct ReverseLines {
+    file: File,
+    /// Start of the bytes not yet read
+    pos: u64,
+    /// Start of a line that continues before `pos`
+    partial: Vec<u8>,
+    /// Complete lines of the last chunk, in file order
+    lines: Vec<Vec<u8>>,
+}
+
+impl ReverseLines {
+    fn new(file: File, end: u64) -> Self {
+        Self {
+            file,
+            pos: end,
+            partial: Vec::new(),
+            lines: Vec::new(),
+        }
+    }
+
+    /// Read the chunk before `pos`; false at the start of the file or on error
+    fn read_chunk(&mut self) -> bool {
+        if self.pos == 0 {
+            if self.partial.is_empty() {
+                return false;
+            }
+            self.lines.push(std::mem::take(&mut self.partial));
+            return true;
+        }
+
+        let start = self.pos.saturating_sub(REVERSE_CHUNK_BYTES);
+        let mut chunk = vec![0; (self.pos - start) as usize];
+        if self.file.seek(SeekFrom::Start(start)).is_err() || self.file.read_exact(&mut chunk).is_err() {
+            return false;
+        }
+        self.pos = start;
+        chunk.append(&mut self.partial);
+
+        // Bytes before the first newline may belong to a line started earlier
+        let first_line = match chunk.iter().position(|&b| b == b'\n') {
+            _ if start == 0 => 0,
+            Some(newline) => newline + 1,
+            None => chunk.len(),
+        };
+        self.lines = chunk[first_line..]
+            .split(|&b| b == b'\n')
+            .map(<[u8]>::to_vec)
+            .collect();
+        chunk.truncate(first_line);
+        self.partial = chunk;
+        true
+    }
+}
+
+impl Iterator for ReverseLines {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            match self.lines.pop() {
+                Some(line) if line.is_empty() => continue,
+                Some(line) => return Some(String::from_utf8_lossy(&line).into_owned()),
+                None if !self.read_chunk() => return None,
+                None => {}
+            }
+        }
+    }
+}
+
+/// Check whether a message matches a lowercase search needle
+fn matches_search(message: &GroupChatMessage, needle: &str) -> bool {
+    message.content.to_lowercase().contains(needle)
+        || message.from_role.to_lowercase().contains(needle)
+        || message
+            .mentions
+            .iter()
+            .any(|m| m.to_lowercase().contains(needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn message(id: &str, parent: Option<&str>, thread: &str, content: &str) -> GroupChatMessage {
+        GroupChatMessage {
+            id: id.to_string(),
+            parent_id: parent.map(String::from),
+            thread_id: thread.to_string(),
+            from_role: "dev".to_string(),
+            from_id: "dev-1".to_string(),
+            content: content.to_string(),
+            mentions: Vec::new(),
+            timestamp: "2024-12-20T15:30:00Z".to_string(),
+            reply_to: None,
+        }
+    }
+
+    #[test]
+    fn test_history_pagination_and_threads() {
+        let dir = tempdir().unwrap();
+        let store = GroupChatStore::new(dir.path().to_path_buf());
+
+        store.append_message(&message("m1", None, "m1", "first")).unwrap();
+        store.append_message(&message("m2", Some("m1"), "m1", "reply")).unwrap();
+        store.append_message(&message("m3", None, "m3", "Second topic")).unwrap();
+
+        let query = HistoryQuery { limit: 2, ..Default::default() };
+        let page: Vec<_> = store.history(&query).unwrap().into_iter().map(|m| m.id).collect();
+        assert_eq!(page, vec!["m3", "m2"]);
+
+        let query = HistoryQuery { limit: 10, before: Some("m2".to_string()), ..Default::default() };
+        let page: Vec<_> = store.history(&query).unwrap().into_iter().map(|m| m.id).collect();
+        assert_eq!(page, vec!["m1"]);
+
+        let query = HistoryQuery { limit: 10, thread_id: Some("m1".to_string()), ..Default::default() };
+        assert_eq!(store.history(&query).unwrap().len(), 2);
+
+        let query = HistoryQuery { limit: 10, search: Some("second".to_string()), ..Default::default() };
+        assert_eq!(store.history(&query).unwrap()[0].id, "m3");
+    }
+
+    #[test]
+    fn test_inbox_take_clears_role() {
+        let dir = tempdir().unwrap();
+        let store = GroupChatStore::new(dir.path().to_path_buf());
+
+        let pending = PendingMessage {
+            message_id: "m1".to_string(),
+            parent_id: None,
+            from_role: "orchestrator".to_string(),
+            content: "@Dev please review".to_string(),
+            timestamp: "2024-12-20T15:30:00Z".to_string(),
+            reply_to: None,
+        };
+        store.enqueue(&["Dev".to_string()], &pending).unwrap();
+
+        assert_eq!(store.take_pending("dev").unwrap().len(), 1);
+        assert!(store.take_pending("dev").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_post_reply_joins_parent_thread() {
+        let dir = tempdir().unwrap();
+        let store = GroupChatStore::new(dir.path().to_path_buf());
+        let pending = PendingMessage {
+            message_id: "m2".to_string(),
+            parent_id: Some("m1".to_string()),
+            from_role: "dev".to_string(),
+            content: "@qa done".to_string(),
+            timestamp: "2024-12-20T15:30:00Z".to_string(),
+            reply_to: None,
+        };
+
+        store.append_message(&message("m1", None, "m1", "first")).unwrap();
+        let reply = store
+            .post(message("m2", Some("m1"), "m2", "done"), &["qa".to_string()], &pending)
+            .unwrap();
+        assert_eq!(reply.thread_id, "m1");
+        assert_eq!(store.take_pending("qa").unwrap().len(), 1);
+
+        let orphan = message("m3", Some("missing"), "m3", "lost");
+        assert!(store.post(orphan, &[], &pending).is_err());
+    }
+
+    #[test]
+    fn test_history_unknown_cursor_is_an_error() {
+        let dir = tempdir().unwrap();
+        let store = GroupChatStore::new(dir.path().to_path_buf());
+        store.append_message(&message("m1", None, "m1", "first")).unwrap();
+
+        let query = HistoryQuery { limit: 10, before: Some("typo".to_string()), ..Default::default() };
+        assert!(store.history(&query).is_err());
+    }
+
+    #[test]
+    fn test_history_reads_across_chunks() {
+        let dir = tempdir().unwrap();
+        let store = GroupChatStore::new(dir.path().to_path_buf());
+        let filler = "x".repeat(200);
+        for i in 0..1000 {
+            let id = format!("m{}", i);
+            store.append_message(&message(&id, None, &id, &filler)).unwrap();
+        }
+
+        let query = HistoryQuery { limit: 1000, ..Default::default() };
+        let ids: Vec<_> = store.history(&query).unwrap().into_iter().map(|m| m.id).collect();
+        let expected: Vec<_> = (0..1000).rev().map(|i| format!("m{}", i)).collect();
+        assert_eq!(ids, expected);
+
+        let query = HistoryQuery { limit: 2, before: Some("m500".to_string()), ..Default::default() };
+        let ids: Vec<_> = store.history(&query).unwrap().into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec!["m499", "m498"]);
+    }
+}
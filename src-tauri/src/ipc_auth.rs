@@ -0,0 +1,256 @@
+//! IPC Authentication
+//!
+//! Token handshake and per-connection identity for the IPC WebSocket.
+//!
+//! - A random token is generated at startup and written to ~/.sidstack/ipc-token
+//!   (readable by the current user only)
+//! - Clients must send `auth.hello` with the token before any other request
+//! - Each connection is bound to the role/client id it authenticated as
+//! - Privileged calls (marked in the `ipc_rpc` method table) and failed
+//!   handshakes are appended to an audit log
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Identity bound to an authenticated IPC connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientIdentity {
+    /// Role the client acts as (e.g. "orchestrator", "dev", "ui")
+    pub role: String,
+    /// Unique client ID, used as `from_id` in group chat
+    pub client_id: String,
+    /// Optional agent session ID the client belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Remote address of the connection
+    pub peer: String,
+}
+
+impl ClientIdentity {
+    /// Ensure a request's claimed sender matches this connection's identity
+    pub fn verify_sender(&self, from_role: &str, from_id: &str) -> Result<(), String> {
+        if !self.role.eq_ignore_ascii_case(from_role) {
+            return Err(format!(
+                "from_role '{}' does not match authenticated role '{}'",
+                from_role, self.role
+            ));
+        }
+        if self.client_id != from_id {
+            return Err(format!(
+                "from_id '{}' does not match authenticated client '{}'",
+                from_id, self.client_id
+            ));
+        }
+        Ok(())
+    }
+
+    /// Ensure a request acts for this connection's own client (team member or agent ID)
+    pub fn verify_client(&self, id: &str) -> Result<(), String> {
+        if self.client_id == id {
+            Ok(())
+        } else {
+            Err(format!(
+                "'{}' does not match authenticated client '{}'",
+                id, self.client_id
+            ))
+        }
+    }
+
+    /// Ensure a request targets this connection's own role
+    pub fn verify_role(&self, role: &str) -> Result<(), String> {
+        if self.role.eq_ignore_ascii_case(role) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Role '{}' does not match authenticated role '{}'",
+                role, self.role
+            ))
+        }
+    }
+}
+
+/// Audit log entry
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuditEntry<'a> {
+    timestamp: String,
+    peer: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_id: Option<&'a str>,
+    method: &'a str,
+    outcome: &'a str,
+}
+
+/// Get the token file path
+/// ~/.sidstack/ipc-token
+pub fn token_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sidstack")
+        .join("ipc-token")
+}
+
+/// Get the audit log path
+/// ~/.sidstack/agent-manager/ipc-audit.log
+fn audit_log_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sidstack")
+        .join("agent-manager")
+        .join("ipc-audit.log")
+}
+
+/// Generate a new random token (hex encoded, 256 bits)
+pub fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Write the token to the token file, readable by the current user only
+///
+/// The token goes into a fresh temp file created with mode 0600 which is then
+/// renamed over the old file, so it is never readable by others, not even
+/// briefly, and readers never see a partial token.
+pub fn write_token(token: &str) -> Result<PathBuf, String> {
+    let path = token_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let temp = path.with_file_name(format!("ipc-token.tmp.{}", std::process::id()));
+    let _ = fs::remove_file(&temp);
+
+    let mut options = OpenOptions::new();
+    options.create_new(true).write(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let result = (|| {
+        let mut file = options
+            .open(&temp)
+            .map_err(|e| format!("Failed to create token file: {}", e))?;
+        file.write_all(token.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|e| format!("Failed to write token file: {}", e))?;
+        fs::rename(&temp, &path).map_err(|e| format!("Failed to replace token file: {}", e))
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result?;
+
+    Ok(path)
+}
+
+/// Compare tokens in constant time
+pub fn tokens_match(expected: &str, provided: &str) -> bool {
+    if expected.is_empty() || expected.len() != provided.len() {
+        return false;
+    }
+    expected
+        .bytes()
+        .zip(provided.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+/// Check whether a method must be recorded in the audit log
+pub fn is_privileged(method: &str) -> bool {
+    crate::ipc_rpc::find_method(method).is_some_and(|spec| spec.privileged)
+}
+
+/// Append an entry to the audit log
+pub fn audit(identity: Option<&ClientIdentity>, peer: &str, method: &str, outcome: &str) {
+    let entry = AuditEntry {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        peer,
+        role: identity.map(|i| i.role.as_str()),
+        client_id: identity.map(|i| i.client_id.as_str()),
+        method,
+        outcome,
+    };
+
+    let path = audit_log_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    let line = match serde_json::to_string(&entry) {
+        Ok(l) => l,
+        Err(_) => return,
+    };
+
+    match OpenOptions::new().create(true).append(true).open(&path) {
+        Ok(mut file) => {
+            let _ = writeln!(file, "{}", line);
+        }
+        Err(e) => eprintln!("[IPC] Failed to write audit log: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity() -> ClientIdentity {
+        ClientIdentity {
+            role: "dev".to_string(),
+            client_id: "dev-1".to_string(),
+            session_id: None,
+            peer: "127.0.0.1:50000".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert!(tokens_match(&token, &token.clone()));
+        assert!(!tokens_match(&token, "wrong"));
+        assert!(!tokens_match("", ""));
+    }
+
+    #[test]
+    fn test_verify_sender_rejects_spoofing() {
+        let id = identity();
+        assert!(id.verify_sender("Dev", "dev-1").is_ok());
+        assert!(id.verify_sender("orchestrator", "dev-1").is_err());
+        assert!(id.verify_sender("dev", "orchestrator-1").is_err());
+        assert!(id.verify_role("qa").is_err());
+    }
+
+    #[test]
+    fn test_verify_client_binds_ids() {
+        let id = identity();
+        assert!(id.verify_client("dev-1").is_ok());
+        assert!(id.verify_client("dev-2").is_err());
+    }
+
+    #[test]
+    fn test_state_changing_methods_are_audited() {
+        for method in [
+            "groupchat.send",
+            "coordinator.delegateTask",
+            "team.updateMemberTask",
+            "sessions.create",
+            "sessions.rename",
+            "sessions.updateStatus",
+            "sessions.appendOutput",
+            "sessions.delete",
+        ] {
+            assert!(is_privileged(method), "{} is not audited", method);
+        }
+        assert!(!is_privileged("ping"));
+        assert!(!is_privileged("sessions.list"));
+        assert!(!is_privileged("no.such.method"));
+    }
+}
@@ -0,0 +1,288 @@
+//! IPC Method Handlers
+//!
+//! Coordinator, team, session storage and OpenSpec operations exposed over the
+//! IPC WebSocket, so an orchestrator agent can delegate, report progress and
+//! read specs without the UI in the loop.
+//!
+//! Each handler calls the matching Tauri command, so IPC callers get the same
+//! behaviour (and the same frontend events) as the UI.
+
+use serde::Serialize;
+use std::fmt::Display;
+use tauri::{AppHandle, Manager};
+
+use crate::agent_coordinator::{self, SharedAgentCoordinator};
+use crate::commands::{openspec, team};
+use crate::ipc_server::{IpcRequest, IpcResponse};
+use crate::session_storage;
+use crate::team_manager::SharedTeamManager;
+
+/// Default number of log lines returned by `sessions.loadOutput`
+const DEFAULT_OUTPUT_LINES: usize = 200;
+
+/// Convert a command result into an IPC response
+fn respond<T: Serialize, E: Display>(result: Result<T, E>, code: &str) -> IpcResponse {
+    match result {
+        Ok(value) => IpcResponse::Success {
+            data: serde_json::to_value(value).unwrap_or(serde_json::Value::Null),
+        },
+        Err(e) => IpcResponse::Error {
+            message: e.to_string(),
+            code: Some(code.to_string()),
+        },
+    }
+}
+
+fn unavailable(what: &str) -> IpcResponse {
+    IpcResponse::Error {
+        message: format!("{} is not available", what),
+        code: Some("UNAVAILABLE".to_string()),
+    }
+}
+
+/// Handle a coordinator, team, session storage or OpenSpec request
+pub async fn handle(request: IpcRequest, app_handle: &AppHandle) -> IpcResponse {
+    match request {
+        // ===== Coordinator =====
+        IpcRequest::CoordinatorDelegateTask {
+            task_id,
+            description,
+            context,
+            dependencies,
+            preferred_specialist,
+        } => {
+            let Some(state) = app_handle.try_state::<SharedAgentCoordinator>() else {
+                return unavailable("Coordinator");
+            };
+            let result = agent_coordinator::coordinator_delegate_task(
+                task_id,
+                description,
+                context,
+                dependencies,
+                preferred_specialist,
+                app_handle.clone(),
+                state,
+            )
+            .await
+            .map(|worker_id| serde_json::json!({ "workerId": worker_id }));
+            respond(result, "DELEGATE_ERROR")
+        }
+
+        IpcRequest::CoordinatorUpdateStatus {
+            agent_id,
+            status,
+            current_task,
+            progress,
+        } => {
+            let Some(state) = app_handle.try_state::<SharedAgentCoordinator>() else {
+                return unavailable("Coordinator");
+            };
+            let result = agent_coordinator::coordinator_update_status(
+                agent_id,
+                status,
+                current_task,
+                progress,
+                app_handle.clone(),
+                state,
+            )
+            .await
+            .map(|_| serde_json::json!({ "updated": true }));
+            respond(result, "STATUS_ERROR")
+        }
+
+        IpcRequest::CoordinatorListAgents => {
+            let Some(state) = app_handle.try_state::<SharedAgentCoordinator>() else {
+                return unavailable("Coordinator");
+            };
+            respond(
+                agent_coordinator::coordinator_list_agents(state).await,
+                "COORDINATOR_ERROR",
+            )
+        }
+
+        // ===== Team =====
+        IpcRequest::TeamGetMembers { project_path, team_id } => {
+            let Some(state) = app_handle.try_state::<SharedTeamManager>() else {
+                return unavailable("Team manager");
+            };
+            let result = team::team_get_members(state, project_path, team_id)
+                .await
+                .map_err(|e| e.error);
+            respond(result, "TEAM_ERROR")
+        }
+
+        IpcRequest::TeamUpdateMemberTask {
+            project_path,
+            team_id,
+            member_id,
+            task_id,
+            spec_id,
+            phase,
+            progress,
+        } => {
+            let Some(state) = app_handle.try_state::<SharedTeamManager>() else {
+                return unavailable("Team manager");
+            };
+            let result = team::team_update_member_task(
+                state,
+                project_path,
+                team_id,
+                member_id,
+                task_id,
+                spec_id,
+                phase,
+                progress,
+            )
+            .await
+            .map(|_| serde_json::json!({ "updated": true }))
+            .map_err(|e| e.error);
+            respond(result, "TEAM_ERROR")
+        }
+
+        IpcRequest::TeamHeartbeat {
+            project_path,
+            team_id,
+            member_id,
+        } => {
+            let Some(state) = app_handle.try_state::<SharedTeamManager>() else {
+                return unavailable("Team manager");
+            };
+            let result = team::team_member_heartbeat(state, project_path, team_id, member_id)
+                .await
+                .map(|_| serde_json::json!({ "recorded": true }))
+                .map_err(|e| e.error);
+            respond(result, "TEAM_ERROR")
+        }
+
+        // ===== Session storage =====
+        IpcRequest::SessionsList { project_path } => respond(
+            session_storage::session_storage_list(project_path),
+            "SESSION_ERROR",
+        ),
+
+        IpcRequest::SessionsGet {
+            project_path,
+            session_id,
+        } => respond(
+            session_storage::session_storage_get(project_path, session_id),
+            "SESSION_ERROR",
+        ),
+
+        IpcRequest::SessionsCreate {
+            session_id,
+            project_path,
+            role,
+            claude_session_id,
+        } => respond(
+            session_storage::session_storage_create(session_id, project_path, role, claude_session_id),
+            "SESSION_ERROR",
+        ),
+
+        IpcRequest::SessionsRename {
+            project_path,
+            session_id,
+            display_name,
+        } => respond(
+            session_storage::session_storage_rename(project_path, session_id, display_name)
+                .map(|_| serde_json::json!({ "renamed": true })),
+            "SESSION_ERROR",
+        ),
+
+        IpcRequest::SessionsDelete {
+            project_path,
+            session_id,
+            delete_logs,
+        } => respond(
+            session_storage::session_storage_delete(project_path, session_id, delete_logs)
+                .map(|_| serde_json::json!({ "deleted": true })),
+            "SESSION_ERROR",
+        ),
+
+        IpcRequest::SessionsUpdateStatus {
+            project_path,
+            session_id,
+            status,
+        } => respond(
+            session_storage::session_storage_update_status(project_path, session_id, status)
+                .map(|_| serde_json::json!({ "updated": true })),
+            "SESSION_ERROR",
+        ),
+
+        IpcRequest::SessionsUpdateRole {
+            project_path,
+            session_id,
+            role,
+        } => respond(
+            session_storage::session_storage_update_role(project_path, session_id, role)
+                .map(|_| serde_json::json!({ "updated": true })),
+            "SESSION_ERROR",
+        ),
+
+        IpcRequest::SessionsUpdateClaudeId {
+            project_path,
+            session_id,
+            claude_session_id,
+        } => respond(
+            session_storage::session_storage_update_claude_id(
+                project_path,
+                session_id,
+                claude_session_id,
+            )
+            .map(|_| serde_json::json!({ "updated": true })),
+            "SESSION_ERROR",
+        ),
+
+        IpcRequest::SessionsExport {
+            project_path,
+            session_id,
+            format,
+        } => {
+            let format = format.unwrap_or_default();
+            respond(
+                session_storage::session_storage_export(project_path, session_id, Some(format))
+                    .map(|content| serde_json::json!({ "format": format, "content": content })),
+                "EXPORT_ERROR",
+            )
+        }
+
+        IpcRequest::SessionsLoadOutput {
+            project_path,
+            session_id,
+            lines,
+        } => respond(
+            session_storage::session_storage_load_output(
+                project_path,
+                session_id,
+                lines.unwrap_or(DEFAULT_OUTPUT_LINES),
+            ),
+            "SESSION_ERROR",
+        ),
+
+        IpcRequest::SessionsAppendOutput {
+            project_path,
+            session_id,
+            data,
+        } => respond(
+            session_storage::session_storage_append_output(project_path, session_id, data)
+                .map(|_| serde_json::json!({ "appended": true })),
+            "SESSION_ERROR",
+        ),
+
+        IpcRequest::SessionsCleanup { days } => respond(
+            session_storage::session_storage_cleanup(days)
+                .map(|deleted| serde_json::json!({ "deleted": deleted })),
+            "SESSION_ERROR",
+        ),
+
+        // ===== OpenSpec =====
+        IpcRequest::OpenSpecChanges { project_path } => respond(
+            openspec::get_openspec_changes(project_path).await,
+            "OPENSPEC_ERROR",
+        ),
+
+        other => IpcResponse::Error {
+            message: format!("Method not handled: {}", other.method_name()),
+            code: Some("METHOD_NOT_FOUND".to_string()),
+        },
+    }
+}
@@ -0,0 +1,773 @@
+//! IPC Protocol
+//!
+//! Wire formats spoken on the IPC WebSocket:
+//! - JSON-RPC 2.0: requests, notifications (no `id`), batches and standard error codes
+//! - Legacy: `{id, method, params}` envelope answered with `status: success|error`
+//!
+//! The format is detected per message - arrays and objects carrying
+//! `"jsonrpc": "2.0"` are JSON-RPC, anything else is treated as legacy.
+//! This module also holds the method table served by `rpc.discover`.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::ipc_server::{IpcMessage, IpcRequest, IpcResponse};
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+// Standard JSON-RPC error codes
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+// Server-defined error codes (JSON-RPC reserves -32000 to -32099)
+pub const SERVER_ERROR: i64 = -32000;
+pub const UNAUTHORIZED: i64 = -32001;
+pub const FORBIDDEN: i64 = -32003;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Wire format used by a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Protocol {
+    #[default]
+    Legacy,
+    JsonRpc,
+}
+
+/// JSON-RPC error object
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+/// A single decoded JSON-RPC call
+#[derive(Debug)]
+pub struct Call {
+    /// Request ID - `None` for notifications, which never get a response
+    pub id: Option<Value>,
+    pub request: Result<IpcRequest, RpcError>,
+}
+
+/// A decoded incoming WebSocket message
+#[derive(Debug)]
+pub enum Incoming {
+    /// Legacy envelope (always answered, errors carry the legacy `code` string)
+    Legacy {
+        id: String,
+        request: Result<IpcRequest, String>,
+    },
+    /// One JSON-RPC call, or a batch of them
+    JsonRpc { calls: Vec<Call>, batch: bool },
+    /// Top-level JSON-RPC error that has no call to attach to (e.g. empty batch)
+    JsonRpcError(RpcError),
+}
+
+/// JSON-RPC response object
+#[derive(Debug, Clone, Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+    id: Value,
+}
+
+// ============================================================================
+// Decoding
+// ============================================================================
+
+/// Decode a raw message, returning the parse error if it is not valid JSON
+pub fn decode(text: &str) -> Result<Incoming, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+
+    match value {
+        Value::Array(items) => {
+            if items.is_empty() {
+                return Ok(Incoming::JsonRpcError(RpcError::new(
+                    INVALID_REQUEST,
+                    "Empty batch",
+                )));
+            }
+            Ok(Incoming::JsonRpc {
+                calls: items.into_iter().map(decode_call).collect(),
+                batch: true,
+            })
+        }
+        Value::Object(ref obj) if obj.contains_key("jsonrpc") => Ok(Incoming::JsonRpc {
+            calls: vec![decode_call(value)],
+            batch: false,
+        }),
+        _ => Ok(decode_legacy(value)),
+    }
+}
+
+fn decode_legacy(value: Value) -> Incoming {
+    let id = value
+        .get("id")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown")
+        .to_string();
+
+    match serde_json::from_value::<IpcMessage>(value) {
+        Ok(msg) => Incoming::Legacy {
+            id: msg.id,
+            request: Ok(msg.request),
+        },
+        Err(e) => Incoming::Legacy {
+            id,
+            request: Err(format!("Failed to parse message: {}", e)),
+        },
+    }
+}
+
+fn decode_call(value: Value) -> Call {
+    // Invalid requests are always answered, with a null ID if none can be read
+    let invalid = |id: Option<Value>, message: &str| Call {
+        id: Some(id.unwrap_or(Value::Null)),
+        request: Err(RpcError::new(INVALID_REQUEST, message)),
+    };
+
+    let Value::Object(mut obj) = value else {
+        return invalid(None, "Request must be an object");
+    };
+
+    let id = obj.remove("id");
+    if let Some(id) = &id {
+        if !(id.is_string() || id.is_number() || id.is_null()) {
+            return invalid(None, "id must be a string, number or null");
+        }
+    }
+
+    if obj.get("jsonrpc").and_then(Value::as_str) != Some(JSONRPC_VERSION) {
+        return invalid(id, "jsonrpc must be \"2.0\"");
+    }
+
+    let Some(method) = obj.get("method").and_then(Value::as_str).map(String::from) else {
+        return invalid(id, "method must be a string");
+    };
+
+    let params = obj.remove("params");
+    if let Some(params) = &params {
+        if !(params.is_object() || params.is_array()) {
+            return invalid(id, "params must be an object or array");
+        }
+    }
+
+    let Some(spec) = find_method(&method) else {
+        return Call {
+            id,
+            request: Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {}", method),
+            )),
+        };
+    };
+
+    Call {
+        id,
+        request: build_request(spec, params),
+    }
+}
+
+/// Turn a method + params into an `IpcRequest`
+fn build_request(spec: &MethodSpec, params: Option<Value>) -> Result<IpcRequest, RpcError> {
+    // Parameterless methods ignore params; others default to an empty object
+    let envelope = if spec.params.is_empty() {
+        json!({ "method": spec.name })
+    } else {
+        json!({ "method": spec.name, "params": params.unwrap_or_else(|| json!({})) })
+    };
+
+    serde_json::from_value(envelope)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid params: {}", e)))
+}
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+/// Map a legacy error code string to a JSON-RPC error code
+fn error_code(code: Option<&str>) -> i64 {
+    match code {
+        Some("PARSE_ERROR") => PARSE_ERROR,
+        Some("INVALID_PARAMS") => INVALID_PARAMS,
+        Some("METHOD_NOT_FOUND") => METHOD_NOT_FOUND,
+        Some("UNAUTHORIZED") | Some("ALREADY_AUTHENTICATED") => UNAUTHORIZED,
+        Some("FORBIDDEN") => FORBIDDEN,
+        _ => SERVER_ERROR,
+    }
+}
+
+/// Convert a handler response into a JSON-RPC result or error
+pub fn to_rpc_result(response: IpcResponse) -> Result<Value, RpcError> {
+    match response {
+        IpcResponse::Success { data } => Ok(data),
+        IpcResponse::Error { message, code } => Err(RpcError {
+            code: error_code(code.as_deref()),
+            message,
+            // Keep the descriptive legacy code for clients that want it
+            data: code.map(|c| json!({ "code": c })),
+        }),
+    }
+}
+
+/// Build a JSON-RPC response object
+pub fn response_value(id: Value, result: Result<Value, RpcError>) -> Value {
+    let (result, error) = match result {
+        Ok(value) => (Some(value), None),
+        Err(error) => (None, Some(error)),
+    };
+    serde_json::to_value(RpcResponse {
+        jsonrpc: JSONRPC_VERSION,
+        result,
+        error,
+        id,
+    })
+    .unwrap_or(Value::Null)
+}
+
+/// Serialize a JSON-RPC error with a null ID (parse errors, empty batches)
+pub fn error_json(error: RpcError) -> String {
+    response_value(Value::Null, Err(error)).to_string()
+}
+
+/// Serialize a server-push notification (a JSON-RPC notification has no ID)
+pub fn notification_json<T: Serialize>(method: &str, params: T) -> Option<String> {
+    serde_json::to_string(&json!({
+        "jsonrpc": JSONRPC_VERSION,
+        "method": method,
+        "params": params,
+    }))
+    .ok()
+}
+
+// ============================================================================
+// Discovery
+// ============================================================================
+
+/// Parameter types used in the method table
+#[derive(Debug, Clone, Copy)]
+pub enum ParamType {
+    String,
+    Integer,
+    Boolean,
+    Object,
+    StringArray,
+    Topics,
+    SessionStatus,
+}
+
+impl ParamType {
+    fn schema(&self) -> Value {
+        match self {
+            ParamType::String => json!({ "type": "string" }),
+            ParamType::Integer => json!({ "type": "integer", "minimum": 0 }),
+            ParamType::Boolean => json!({ "type": "boolean" }),
+            ParamType::Object => json!({ "type": "object" }),
+            ParamType::StringArray => json!({ "type": "array", "items": { "type": "string" } }),
+            ParamType::Topics => json!({
+                "type": "array",
+                "items": {
+                    "type": "string",
+                    "enum": ["groupchat", "mentions", "team.recovery", "coordinator"]
+                }
+            }),
+            ParamType::SessionStatus => json!({
+                "type": "string",
+                "enum": ["active", "saved", "archived"]
+            }),
+        }
+    }
+}
+
+/// A method parameter
+#[derive(Debug, Clone, Copy)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub kind: ParamType,
+    pub required: bool,
+    pub description: &'static str,
+}
+
+/// An IPC method (one per `IpcRequest` variant)
+#[derive(Debug, Clone, Copy)]
+pub struct MethodSpec {
+    pub name: &'static str,
+    pub summary: &'static str,
+    /// Whether the method can only be called after `auth.hello`
+    pub requires_auth: bool,
+    /// Whether calls change or expose shared state (recorded in the audit log)
+    pub privileged: bool,
+    pub params: &'static [ParamSpec],
+}
+
+const fn param(
+    name: &'static str,
+    kind: ParamType,
+    required: bool,
+    description: &'static str,
+) -> ParamSpec {
+    ParamSpec {
+        name,
+        kind,
+        required,
+        description,
+    }
+}
+
+/// Every method understood by the IPC server
+pub const METHODS: &[MethodSpec] = &[
+    MethodSpec {
+        name: "auth.hello",
+        summary: "Authenticate the connection with the token from ~/.sidstack/ipc-token",
+        requires_auth: false,
+        privileged: false,
+        params: &[
+            param("token", ParamType::String, true, "IPC auth token"),
+            param("role", ParamType::String, true, "Role this connection acts as"),
+            param("client_id", ParamType::String, false, "Client ID used as from_id (generated if omitted)"),
+            param("session_id", ParamType::String, false, "Agent session the client belongs to"),
+        ],
+    },
+    MethodSpec {
+        name: "groupchat.send",
+        summary: "Post a group chat message; @mentions are queued for the mentioned roles",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("from_role", ParamType::String, true, "Sender role (must match the connection)"),
+            param("from_id", ParamType::String, true, "Sender client ID (must match the connection)"),
+            param("content", ParamType::String, true, "Message text"),
+            param("reply_to", ParamType::String, false, "Who should receive responses"),
+            param("project_path", ParamType::String, false, "Project the conversation belongs to"),
+            param("parent_id", ParamType::String, false, "Message being replied to"),
+        ],
+    },
+    MethodSpec {
+        name: "groupchat.history",
+        summary: "Read group chat history, newest first",
+        requires_auth: true,
+        privileged: false,
+        params: &[
+            param("limit", ParamType::Integer, false, "Maximum messages (default 50)"),
+            param("project_path", ParamType::String, false, "Project to read"),
+            param("before", ParamType::String, false, "Only messages older than this message ID"),
+            param("query", ParamType::String, false, "Case-insensitive text search"),
+            param("thread_id", ParamType::String, false, "Only messages in this thread"),
+        ],
+    },
+    MethodSpec {
+        name: "subscribe",
+        summary: "Receive server-push notifications for topics",
+        requires_auth: true,
+        privileged: false,
+        params: &[param("topics", ParamType::Topics, true, "Topics to add")],
+    },
+    MethodSpec {
+        name: "unsubscribe",
+        summary: "Stop notifications for topics",
+        requires_auth: true,
+        privileged: false,
+        params: &[param("topics", ParamType::Topics, false, "Topics to remove (all if omitted)")],
+    },
+    MethodSpec {
+        name: "agent.healthCheck",
+        summary: "Ping agents",
+        requires_auth: true,
+        privileged: false,
+        params: &[
+            param("from_role", ParamType::String, true, "Caller role"),
+            param("from_id", ParamType::String, true, "Caller client ID"),
+        ],
+    },
+    MethodSpec {
+        name: "agent.getPending",
+        summary: "Take and clear pending messages for a role",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("role", ParamType::String, true, "Role to read (must match the connection)"),
+            param("project_path", ParamType::String, false, "Project inbox to read"),
+        ],
+    },
+    MethodSpec {
+        name: "session.save",
+        summary: "Save a project's role -> Claude session mapping (replaced sessions go to history)",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project the sessions belong to"),
+            param("roles", ParamType::Object, true, "Role -> session info"),
+        ],
+    },
+    MethodSpec {
+        name: "session.load",
+        summary: "Load a project's role -> Claude session mapping, with role history and invalid roles",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project path"),
+            param("validate", ParamType::Boolean, false, "Check sessions still exist and are recent (default true)"),
+        ],
+    },
+    MethodSpec {
+        name: "session.clear",
+        summary: "Clear a project's role -> Claude session mapping",
+        requires_auth: true,
+        privileged: true,
+        params: &[param("project_path", ParamType::String, true, "Project path")],
+    },
+    MethodSpec {
+        name: "coordinator.delegateTask",
+        summary: "Delegate a task to an idle worker; returns the worker ID",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("task_id", ParamType::String, true, "Task ID"),
+            param("description", ParamType::String, true, "What the worker should do"),
+            param("context", ParamType::StringArray, false, "Context items for the worker"),
+            param("dependencies", ParamType::StringArray, false, "Task IDs this task depends on"),
+            param("preferred_specialist", ParamType::String, false, "Prefer a worker with this specialty"),
+        ],
+    },
+    MethodSpec {
+        name: "coordinator.updateStatus",
+        summary: "Report an agent's coordination status and progress",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("agent_id", ParamType::String, true, "Agent ID (must be the authenticated client_id)"),
+            param("status", ParamType::String, true, "idle, working, waiting_for_input, waiting_for_dependency, blocked, completed or error"),
+            param("current_task", ParamType::String, false, "Task being worked on"),
+            param("progress", ParamType::Integer, false, "Progress percentage (0-100)"),
+        ],
+    },
+    MethodSpec {
+        name: "coordinator.listAgents",
+        summary: "List coordinated agents",
+        requires_auth: true,
+        privileged: false,
+        params: &[],
+    },
+    MethodSpec {
+        name: "team.getMembers",
+        summary: "List team members with their current state",
+        requires_auth: true,
+        privileged: false,
+        params: &[
+            param("project_path", ParamType::String, true, "Project the team belongs to"),
+            param("team_id", ParamType::String, true, "Team ID"),
+        ],
+    },
+    MethodSpec {
+        name: "team.updateMemberTask",
+        summary: "Set (or clear) a member's current task and progress",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project the team belongs to"),
+            param("team_id", ParamType::String, true, "Team ID"),
+            param("member_id", ParamType::String, true, "Member ID (must be the authenticated client_id)"),
+            param("task_id", ParamType::String, false, "Task ID (clears the task if omitted)"),
+            param("spec_id", ParamType::String, false, "OpenSpec change the task belongs to"),
+            param("phase", ParamType::String, false, "Current phase (clears the task if omitted)"),
+            param("progress", ParamType::Integer, false, "Progress percentage"),
+        ],
+    },
+    MethodSpec {
+        name: "team.heartbeat",
+        summary: "Record a heartbeat for a team member",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project the team belongs to"),
+            param("team_id", ParamType::String, true, "Team ID"),
+            param("member_id", ParamType::String, true, "Member ID (must be the authenticated client_id)"),
+        ],
+    },
+    MethodSpec {
+        name: "sessions.list",
+        summary: "List stored sessions (all projects if project_path is omitted)",
+        requires_auth: true,
+        privileged: false,
+        params: &[param("project_path", ParamType::String, false, "Project to list")],
+    },
+    MethodSpec {
+        name: "sessions.get",
+        summary: "Get stored session metadata",
+        requires_auth: true,
+        privileged: false,
+        params: &[
+            param("project_path", ParamType::String, true, "Project path"),
+            param("session_id", ParamType::String, true, "Session ID"),
+        ],
+    },
+    MethodSpec {
+        name: "sessions.create",
+        summary: "Create a stored session",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("session_id", ParamType::String, true, "Session ID"),
+            param("project_path", ParamType::String, true, "Project path"),
+            param("role", ParamType::String, false, "Agent role"),
+            param("claude_session_id", ParamType::String, false, "Claude session ID"),
+        ],
+    },
+    MethodSpec {
+        name: "sessions.rename",
+        summary: "Rename a stored session",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project path"),
+            param("session_id", ParamType::String, true, "Session ID"),
+            param("display_name", ParamType::String, true, "New display name"),
+        ],
+    },
+    MethodSpec {
+        name: "sessions.delete",
+        summary: "Delete a stored session",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project path"),
+            param("session_id", ParamType::String, true, "Session ID"),
+            param("delete_logs", ParamType::Boolean, false, "Also delete the output log"),
+        ],
+    },
+    MethodSpec {
+        name: "sessions.updateStatus",
+        summary: "Update a stored session's status",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project path"),
+            param("session_id", ParamType::String, true, "Session ID"),
+            param("status", ParamType::SessionStatus, true, "New status"),
+        ],
+    },
+    MethodSpec {
+        name: "sessions.updateRole",
+        summary: "Update a stored session's role",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project path"),
+            param("session_id", ParamType::String, true, "Session ID"),
+            param("role", ParamType::String, true, "New role"),
+        ],
+    },
+    MethodSpec {
+        name: "sessions.updateClaudeId",
+        summary: "Record the Claude session ID for a stored session",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project path"),
+            param("session_id", ParamType::String, true, "Session ID"),
+            param("claude_session_id", ParamType::String, true, "Claude session ID"),
+        ],
+    },
+    MethodSpec {
+        name: "sessions.export",
+        summary: "Export a stored session as markdown, HTML or JSONL",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project path"),
+            param("session_id", ParamType::String, true, "Session ID"),
+            param("format", ParamType::String, false, "markdown (default), html or jsonl"),
+        ],
+    },
+    MethodSpec {
+        name: "sessions.loadOutput",
+        summary: "Read the last lines of a session's output log",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project path"),
+            param("session_id", ParamType::String, true, "Session ID"),
+            param("lines", ParamType::Integer, false, "Number of lines (default 200)"),
+        ],
+    },
+    MethodSpec {
+        name: "sessions.appendOutput",
+        summary: "Append to a session's output log",
+        requires_auth: true,
+        privileged: true,
+        params: &[
+            param("project_path", ParamType::String, true, "Project path"),
+            param("session_id", ParamType::String, true, "Session ID"),
+            param("data", ParamType::String, true, "Output to append"),
+        ],
+    },
+    MethodSpec {
+        name: "sessions.cleanup",
+        summary: "Delete stored sessions older than the given number of days",
+        requires_auth: true,
+        privileged: true,
+        params: &[param("days", ParamType::Integer, true, "Age threshold in days")],
+    },
+    MethodSpec {
+        name: "openspec.changes",
+        summary: "List OpenSpec changes with their tasks",
+        requires_auth: true,
+        privileged: false,
+        params: &[param("project_path", ParamType::String, true, "Project path")],
+    },
+    MethodSpec {
+        name: "ping",
+        summary: "Check the server is alive",
+        requires_auth: false,
+        privileged: false,
+        params: &[],
+    },
+    MethodSpec {
+        name: "rpc.discover",
+        summary: "Describe every method, its params and the error codes",
+        requires_auth: false,
+        privileged: false,
+        params: &[],
+    },
+];
+
+/// Look up a method by wire name
+pub fn find_method(name: &str) -> Option<&'static MethodSpec> {
+    METHODS.iter().find(|m| m.name == name)
+}
+
+/// Build the `rpc.discover` document (OpenRPC-style)
+pub fn discover() -> Value {
+    let methods: Vec<Value> = METHODS
+        .iter()
+        .map(|method| {
+            let params: Vec<Value> = method
+                .params
+                .iter()
+                .map(|p| {
+                    json!({
+                        "name": p.name,
+                        "description": p.description,
+                        "required": p.required,
+                        "schema": p.kind.schema(),
+                    })
+                })
+                .collect();
+            json!({
+                "name": method.name,
+                "summary": method.summary,
+                "requiresAuth": method.requires_auth,
+                "audited": method.privileged,
+                "paramStructure": "either",
+                "params": params,
+            })
+        })
+        .collect();
+
+    json!({
+        "openrpc": "1.2.6",
+        "info": {
+            "title": "SidStack IPC",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "protocols": ["jsonrpc-2.0", "legacy"],
+        "methods": methods,
+        "errors": [
+            { "code": PARSE_ERROR, "message": "Parse error" },
+            { "code": INVALID_REQUEST, "message": "Invalid request" },
+            { "code": METHOD_NOT_FOUND, "message": "Method not found" },
+            { "code": INVALID_PARAMS, "message": "Invalid params" },
+            { "code": INTERNAL_ERROR, "message": "Internal error" },
+            { "code": SERVER_ERROR, "message": "Server error (see data.code)" },
+            { "code": UNAUTHORIZED, "message": "Not authenticated" },
+            { "code": FORBIDDEN, "message": "Identity mismatch" },
+        ],
+        "notification": {
+            "method": "notification",
+            "topics": ["groupchat", "mentions", "team.recovery", "coordinator", "subscription.lagged"],
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(kind: ParamType) -> Value {
+        match kind {
+            ParamType::String => json!("x"),
+            ParamType::Integer => json!(1),
+            ParamType::Boolean => json!(true),
+            ParamType::Object => json!({}),
+            ParamType::StringArray => json!(["x"]),
+            ParamType::Topics => json!(["groupchat"]),
+            ParamType::SessionStatus => json!("active"),
+        }
+    }
+
+    #[test]
+    fn test_every_method_decodes() {
+        for spec in METHODS {
+            let params: serde_json::Map<String, Value> = spec
+                .params
+                .iter()
+                .filter(|p| p.required)
+                .map(|p| (p.name.to_string(), sample(p.kind)))
+                .collect();
+            let text = json!({ "jsonrpc": "2.0", "id": 1, "method": spec.name, "params": params })
+                .to_string();
+
+            let Ok(Incoming::JsonRpc { mut calls, batch: false }) = decode(&text) else {
+                panic!("{} did not decode as JSON-RPC", spec.name);
+            };
+            let request = calls.remove(0).request.expect(spec.name);
+            assert_eq!(request.method_name(), spec.name);
+        }
+    }
+
+    #[test]
+    fn test_batch_errors_and_notifications() {
+        let text = r#"[
+            {"jsonrpc": "2.0", "method": "ping"},
+            {"jsonrpc": "2.0", "id": "a", "method": "nope"},
+            {"jsonrpc": "2.0", "id": 2, "method": "subscribe", "params": {"topics": "x"}},
+            1
+        ]"#;
+        let Ok(Incoming::JsonRpc { calls, batch: true }) = decode(text) else {
+            panic!("expected batch");
+        };
+
+        assert!(calls[0].id.is_none() && calls[0].request.is_ok());
+        assert_eq!(calls[1].request.as_ref().unwrap_err().code, METHOD_NOT_FOUND);
+        assert_eq!(calls[2].request.as_ref().unwrap_err().code, INVALID_PARAMS);
+        assert_eq!(calls[3].id, Some(Value::Null));
+        assert_eq!(calls[3].request.as_ref().unwrap_err().code, INVALID_REQUEST);
+
+        assert!(matches!(decode("[]"), Ok(Incoming::JsonRpcError(_))));
+        assert!(decode("{not json").is_err());
+        assert!(matches!(
+            decode(r#"{"id": "1", "method": "ping"}"#),
+            Ok(Incoming::Legacy { request: Ok(IpcRequest::Ping), .. })
+        ));
+    }
+}
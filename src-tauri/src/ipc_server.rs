@@ -1,31 +1,42 @@
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{broadcast, mpsc, watch, RwLock};
 use tokio_tungstenite::{accept_async, tungstenite::Message};
 
-const IPC_PORT: u16 = 17432;
+/// Outgoing message queue per connection (responses + notifications)
+const OUTGOING_QUEUE_SIZE: usize = 256;
+/// Disconnect a subscriber after this many consecutive dropped notifications
+const MAX_DROPPED_NOTIFICATIONS: usize = 500;
 
-/// Group chat message structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GroupChatMessage {
-    pub from_role: String,
-    pub from_id: String,
-    pub content: String,
-    pub mentions: Vec<String>,
-    pub timestamp: String,
-    /// Optional reply_to field - specifies who should receive responses
-    /// Used to prevent message injection back to sender
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub reply_to: Option<String>,
-}
+use crate::agent_coordinator::{AgentMessage, SharedAgentCoordinator};
+pub use crate::groupchat_storage::{GroupChatMessage, PendingMessage};
+use crate::groupchat_storage::{GroupChatStore, HistoryQuery};
+use crate::ipc_auth::{self, ClientIdentity};
+use crate::ipc_rpc::{self, Incoming, Protocol};
+use crate::recovery_watchdog::RecoveryNotification;
 
 /// IPC Request from MCP tools
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "method", content = "params")]
 pub enum IpcRequest {
+    /// Handshake - must be the first request on a connection
+    #[serde(rename = "auth.hello")]
+    AuthHello {
+        token: String,
+        /// Role this connection acts as
+        role: String,
+        /// Client ID used as `from_id` (generated if omitted)
+        client_id: Option<String>,
+        session_id: Option<String>,
+    },
+
     #[serde(rename = "groupchat.send")]
     GroupChatSend {
         from_role: String,
@@ -34,11 +45,34 @@ pub enum IpcRequest {
         /// Optional reply_to - specifies who should receive responses (e.g., "@orchestrator")
         /// If set, message will not be injected back to sender even if mentioned
         reply_to: Option<String>,
+        /// Project the conversation belongs to (global chat if omitted)
+        project_path: Option<String>,
+        /// Message being replied to (starts or continues a thread)
+        parent_id: Option<String>,
     },
 
     #[serde(rename = "groupchat.history")]
     GroupChatHistory {
         limit: Option<usize>,
+        project_path: Option<String>,
+        /// Pagination cursor - only return messages older than this message ID
+        before: Option<String>,
+        /// Case-insensitive text search
+        query: Option<String>,
+        /// Only return messages in this thread
+        thread_id: Option<String>,
+    },
+
+    /// Register for server-push notifications on the given topics
+    #[serde(rename = "subscribe")]
+    Subscribe {
+        topics: Vec<SubscriptionTopic>,
+    },
+
+    /// Stop notifications for the given topics (all topics if omitted)
+    #[serde(rename = "unsubscribe")]
+    Unsubscribe {
+        topics: Option<Vec<SubscriptionTopic>>,
     },
 
     #[serde(rename = "agent.healthCheck")]
@@ -50,6 +84,7 @@ pub enum IpcRequest {
     #[serde(rename = "agent.getPending")]
     AgentGetPending {
         role: String,
+        project_path: Option<String>,
     },
 
     #[serde(rename = "session.save")]
@@ -59,13 +94,237 @@ pub enum IpcRequest {
     },
 
     #[serde(rename = "session.load")]
-    SessionLoad,
+    SessionLoad {
+        project_path: String,
+        /// Check each role's session against Claude's session files (default true)
+        validate: Option<bool>,
+    },
 
     #[serde(rename = "session.clear")]
-    SessionClear,
+    SessionClear { project_path: String },
+
+    // ===== Coordinator =====
+    #[serde(rename = "coordinator.delegateTask")]
+    CoordinatorDelegateTask {
+        task_id: String,
+        description: String,
+        #[serde(default)]
+        context: Vec<String>,
+        #[serde(default)]
+        dependencies: Vec<String>,
+        preferred_specialist: Option<String>,
+    },
+
+    #[serde(rename = "coordinator.updateStatus")]
+    CoordinatorUpdateStatus {
+        agent_id: String,
+        status: String,
+        current_task: Option<String>,
+        progress: Option<u8>,
+    },
+
+    #[serde(rename = "coordinator.listAgents")]
+    CoordinatorListAgents,
+
+    // ===== Team =====
+    #[serde(rename = "team.getMembers")]
+    TeamGetMembers {
+        project_path: String,
+        team_id: String,
+    },
+
+    #[serde(rename = "team.updateMemberTask")]
+    TeamUpdateMemberTask {
+        project_path: String,
+        team_id: String,
+        member_id: String,
+        /// Task assignment is cleared unless both task_id and phase are given
+        task_id: Option<String>,
+        spec_id: Option<String>,
+        phase: Option<String>,
+        progress: Option<u32>,
+    },
+
+    #[serde(rename = "team.heartbeat")]
+    TeamHeartbeat {
+        project_path: String,
+        team_id: String,
+        member_id: String,
+    },
+
+    // ===== Session storage =====
+    #[serde(rename = "sessions.list")]
+    SessionsList { project_path: Option<String> },
+
+    #[serde(rename = "sessions.get")]
+    SessionsGet {
+        project_path: String,
+        session_id: String,
+    },
+
+    #[serde(rename = "sessions.create")]
+    SessionsCreate {
+        session_id: String,
+        project_path: String,
+        role: Option<String>,
+        claude_session_id: Option<String>,
+    },
+
+    #[serde(rename = "sessions.rename")]
+    SessionsRename {
+        project_path: String,
+        session_id: String,
+        display_name: String,
+    },
+
+    #[serde(rename = "sessions.delete")]
+    SessionsDelete {
+        project_path: String,
+        session_id: String,
+        #[serde(default)]
+        delete_logs: bool,
+    },
+
+    #[serde(rename = "sessions.updateStatus")]
+    SessionsUpdateStatus {
+        project_path: String,
+        session_id: String,
+        status: crate::session_storage::SessionStatus,
+    },
+
+    #[serde(rename = "sessions.updateRole")]
+    SessionsUpdateRole {
+        project_path: String,
+        session_id: String,
+        role: String,
+    },
+
+    #[serde(rename = "sessions.updateClaudeId")]
+    SessionsUpdateClaudeId {
+        project_path: String,
+        session_id: String,
+        claude_session_id: String,
+    },
+
+    #[serde(rename = "sessions.export")]
+    SessionsExport {
+        project_path: String,
+        session_id: String,
+        /// markdown (default), html or jsonl
+        format: Option<crate::session_export::ExportFormat>,
+    },
+
+    #[serde(rename = "sessions.loadOutput")]
+    SessionsLoadOutput {
+        project_path: String,
+        session_id: String,
+        lines: Option<usize>,
+    },
+
+    #[serde(rename = "sessions.appendOutput")]
+    SessionsAppendOutput {
+        project_path: String,
+        session_id: String,
+        data: String,
+    },
+
+    #[serde(rename = "sessions.cleanup")]
+    SessionsCleanup { days: u32 },
+
+    // ===== OpenSpec =====
+    #[serde(rename = "openspec.changes")]
+    OpenSpecChanges { project_path: String },
 
     #[serde(rename = "ping")]
     Ping,
+
+    /// Describe every method (see `ipc_rpc::METHODS`)
+    #[serde(rename = "rpc.discover")]
+    RpcDiscover,
+}
+
+impl IpcRequest {
+    /// Wire name of the request method
+    pub fn method_name(&self) -> &'static str {
+        match self {
+            IpcRequest::AuthHello { .. } => "auth.hello",
+            IpcRequest::GroupChatSend { .. } => "groupchat.send",
+            IpcRequest::GroupChatHistory { .. } => "groupchat.history",
+            IpcRequest::Subscribe { .. } => "subscribe",
+            IpcRequest::Unsubscribe { .. } => "unsubscribe",
+            IpcRequest::AgentHealthCheck { .. } => "agent.healthCheck",
+            IpcRequest::AgentGetPending { .. } => "agent.getPending",
+            IpcRequest::SessionSave { .. } => "session.save",
+            IpcRequest::SessionLoad { .. } => "session.load",
+            IpcRequest::SessionClear { .. } => "session.clear",
+            IpcRequest::CoordinatorDelegateTask { .. } => "coordinator.delegateTask",
+            IpcRequest::CoordinatorUpdateStatus { .. } => "coordinator.updateStatus",
+            IpcRequest::CoordinatorListAgents => "coordinator.listAgents",
+            IpcRequest::TeamGetMembers { .. } => "team.getMembers",
+            IpcRequest::TeamUpdateMemberTask { .. } => "team.updateMemberTask",
+            IpcRequest::TeamHeartbeat { .. } => "team.heartbeat",
+            IpcRequest::SessionsList { .. } => "sessions.list",
+            IpcRequest::SessionsGet { .. } => "sessions.get",
+            IpcRequest::SessionsCreate { .. } => "sessions.create",
+            IpcRequest::SessionsRename { .. } => "sessions.rename",
+            IpcRequest::SessionsDelete { .. } => "sessions.delete",
+            IpcRequest::SessionsUpdateStatus { .. } => "sessions.updateStatus",
+            IpcRequest::SessionsUpdateRole { .. } => "sessions.updateRole",
+            IpcRequest::SessionsUpdateClaudeId { .. } => "sessions.updateClaudeId",
+            IpcRequest::SessionsExport { .. } => "sessions.export",
+            IpcRequest::SessionsLoadOutput { .. } => "sessions.loadOutput",
+            IpcRequest::SessionsAppendOutput { .. } => "sessions.appendOutput",
+            IpcRequest::SessionsCleanup { .. } => "sessions.cleanup",
+            IpcRequest::OpenSpecChanges { .. } => "openspec.changes",
+            IpcRequest::Ping => "ping",
+            IpcRequest::RpcDiscover => "rpc.discover",
+        }
+    }
+
+    /// Ensure the request does not claim to come from another role or client
+    fn verify_identity(&self, identity: &ClientIdentity) -> Result<(), String> {
+        match self {
+            IpcRequest::GroupChatSend { from_role, from_id, .. }
+            | IpcRequest::AgentHealthCheck { from_role, from_id } => {
+                identity.verify_sender(from_role, from_id)
+            }
+            IpcRequest::AgentGetPending { role, .. } => identity.verify_role(role),
+            // Members and agents only report for themselves
+            IpcRequest::TeamUpdateMemberTask { member_id, .. } | IpcRequest::TeamHeartbeat { member_id, .. } => {
+                identity.verify_client(member_id)
+            }
+            IpcRequest::CoordinatorUpdateStatus { agent_id, .. } => identity.verify_client(agent_id),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Notification topics a connection can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SubscriptionTopic {
+    /// Every group chat message
+    #[serde(rename = "groupchat")]
+    GroupChat,
+    /// Group chat messages that @mention the connection's role
+    #[serde(rename = "mentions")]
+    Mentions,
+    /// Team member failures and replacements
+    #[serde(rename = "team.recovery")]
+    TeamRecovery,
+    /// Coordinator messages addressed to this client (or broadcast)
+    #[serde(rename = "coordinator")]
+    Coordinator,
+}
+
+impl SubscriptionTopic {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SubscriptionTopic::GroupChat => "groupchat",
+            SubscriptionTopic::Mentions => "mentions",
+            SubscriptionTopic::TeamRecovery => "team.recovery",
+            SubscriptionTopic::Coordinator => "coordinator",
+        }
+    }
 }
 
 /// IPC Response to MCP tools
@@ -101,6 +360,12 @@ pub struct IpcResponseMessage {
 pub enum IpcEvent {
     #[serde(rename = "groupchat.message")]
     GroupChatMessage {
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        parent_id: Option<String>,
+        thread_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        project_path: Option<String>,
         from_role: String,
         from_id: String,
         content: String,
@@ -111,25 +376,17 @@ pub enum IpcEvent {
         #[serde(skip_serializing_if = "Option::is_none")]
         reply_to: Option<String>,
     },
-}
 
-/// Pending message for an agent
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PendingMessage {
-    pub from_role: String,
-    pub content: String,
-    pub timestamp: String,
-    /// Optional reply_to field - specifies who should receive responses
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub reply_to: Option<String>,
+    #[serde(rename = "team.recovery")]
+    TeamRecovery(RecoveryNotification),
 }
 
 /// IPC Server state
 pub struct IpcServerState {
     pub running: bool,
     pub event_tx: broadcast::Sender<IpcEvent>,
-    pub chat_history: Vec<GroupChatMessage>,
-    pub pending_messages: std::collections::HashMap<String, Vec<PendingMessage>>, // role -> messages
+    /// Token clients must present in `auth.hello`
+    pub auth_token: String,
 }
 
 impl Default for IpcServerState {
@@ -138,9 +395,100 @@ impl Default for IpcServerState {
         Self {
             running: false,
             event_tx,
-            chat_history: Vec::new(),
-            pending_messages: std::collections::HashMap::new(),
+            auth_token: ipc_auth::generate_token(),
+        }
+    }
+}
+
+/// Per-connection state
+struct ConnectionContext {
+    peer: String,
+    /// Set once the client completes the `auth.hello` handshake
+    identity: Option<ClientIdentity>,
+    /// Wire format of the last decoded message (used to answer parse errors)
+    protocol: Protocol,
+    /// Current subscriptions, shared with the notification forwarder
+    subscriptions: watch::Sender<SubscriptionFilter>,
+}
+
+/// What a connection wants pushed to it
+#[derive(Debug, Clone, Default)]
+struct SubscriptionFilter {
+    topics: HashSet<SubscriptionTopic>,
+    identity: Option<ClientIdentity>,
+    /// Notifications use the wire format the client subscribed with
+    protocol: Protocol,
+}
+
+/// Server-push notification envelope (responses carry an `id`, notifications don't)
+#[derive(Debug, Clone, Serialize)]
+struct IpcNotification<'a, T: Serialize> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    topic: &'a str,
+    event: T,
+}
+
+impl SubscriptionFilter {
+    /// Serialize a notification in the connection's wire format
+    fn notification<T: Serialize>(&self, topic: &str, event: T) -> Option<String> {
+        match self.protocol {
+            Protocol::Legacy => serde_json::to_string(&IpcNotification {
+                kind: "notification",
+                topic,
+                event,
+            })
+            .ok(),
+            Protocol::JsonRpc => ipc_rpc::notification_json(
+                "notification",
+                serde_json::json!({ "topic": topic, "event": event }),
+            ),
+        }
+    }
+
+    /// Build the notification for an internal event, if the connection wants it
+    fn notify_event(&self, event: &IpcEvent) -> Option<String> {
+        let topic = match event {
+            IpcEvent::GroupChatMessage { from_role, mentions, .. } => {
+                let mentioned = self.identity.as_ref().is_some_and(|identity| {
+                    !identity.role.eq_ignore_ascii_case(from_role)
+                        && mentions.iter().any(|m| m.eq_ignore_ascii_case(&identity.role))
+                });
+                if mentioned && self.topics.contains(&SubscriptionTopic::Mentions) {
+                    SubscriptionTopic::Mentions
+                } else if self.topics.contains(&SubscriptionTopic::GroupChat) {
+                    SubscriptionTopic::GroupChat
+                } else {
+                    return None;
+                }
+            }
+            IpcEvent::TeamRecovery(_) => SubscriptionTopic::TeamRecovery,
+        };
+
+        if !self.topics.contains(&topic) {
+            return None;
         }
+        self.notification(topic.as_str(), event)
+    }
+
+    /// Build the notification for a coordinator message, if addressed to this connection
+    fn notify_coordinator(&self, message: &AgentMessage) -> Option<String> {
+        if !self.topics.contains(&SubscriptionTopic::Coordinator) {
+            return None;
+        }
+        let addressed = match (&message.to_agent, &self.identity) {
+            (None, _) => true,
+            (Some(to), Some(identity)) => {
+                to == &identity.client_id
+                    || identity.session_id.as_deref() == Some(to.as_str())
+                    || to.eq_ignore_ascii_case(&identity.role)
+            }
+            (Some(_), None) => false,
+        };
+        if !addressed {
+            return None;
+        }
+        self.notification(SubscriptionTopic::Coordinator.as_str(), message)
     }
 }
 
@@ -160,9 +508,204 @@ pub fn create_ipc_state() -> SharedIpcServerState {
     Arc::new(RwLock::new(IpcServerState::default()))
 }
 
+/// A group chat message to be posted
+struct NewGroupChatMessage {
+    project_path: Option<String>,
+    from_role: String,
+    from_id: String,
+    content: String,
+    reply_to: Option<String>,
+    parent_id: Option<String>,
+}
+
+/// Persist a group chat message, queue it for mentioned roles and broadcast it
+async fn post_group_chat_message(
+    app_handle: &AppHandle,
+    state: &SharedIpcServerState,
+    new_msg: NewGroupChatMessage,
+) -> Result<serde_json::Value, String> {
+    let NewGroupChatMessage {
+        project_path,
+        from_role,
+        from_id,
+        content,
+        reply_to,
+        parent_id,
+    } = new_msg;
+
+    // Parse @mentions from content
+    let mentions = parse_mentions(&content);
+    let timestamp = chrono::Utc::now().to_rfc3339();
+    let id = uuid::Uuid::new_v4().to_string();
+
+    // Note: Without terminal registry, we can't find target agents
+    // Group chat messages are stored but not delivered to agents
+    let target_ids: Vec<String> = Vec::new();
+
+    let store = GroupChatStore::for_project(project_path.as_deref());
+
+    // Add to pending messages for each mentioned role (excluding sender)
+    let recipients: Vec<String> = mentions
+        .iter()
+        .filter(|m| !m.eq_ignore_ascii_case(&from_role))
+        .cloned()
+        .collect();
+    let pending = PendingMessage {
+        message_id: id.clone(),
+        parent_id: parent_id.clone(),
+        from_role: from_role.clone(),
+        content: content.clone(),
+        timestamp: timestamp.clone(),
+        reply_to: reply_to.clone(),
+    };
+    let chat_msg = GroupChatMessage {
+        id: id.clone(),
+        parent_id: parent_id.clone(),
+        // Replies join the thread of their parent
+        thread_id: id.clone(),
+        from_role: from_role.clone(),
+        from_id: from_id.clone(),
+        content: content.clone(),
+        mentions: mentions.clone(),
+        timestamp: timestamp.clone(),
+        reply_to: reply_to.clone(),
+    };
+    // File IO stays off the async runtime and outside the state lock
+    let chat_msg = tokio::task::spawn_blocking(move || store.post(chat_msg, &recipients, &pending))
+        .await
+        .map_err(|e| format!("Failed to store group chat message: {}", e))??;
+
+    // Create event for broadcasting
+    let event = IpcEvent::GroupChatMessage {
+        id: chat_msg.id,
+        parent_id: chat_msg.parent_id,
+        thread_id: chat_msg.thread_id,
+        project_path,
+        from_role,
+        from_id,
+        content,
+        mentions: mentions.clone(),
+        timestamp: timestamp.clone(),
+        target_ids: target_ids.clone(),
+        reply_to: reply_to.clone(),
+    };
+
+    // Emit to Tauri frontend (for UI display)
+    let _ = app_handle.emit("ipc-groupchat-message", &event);
+
+    // Broadcast internally
+    let _ = state.read().await.event_tx.send(event);
+
+    Ok(serde_json::json!({
+        "sent": true,
+        "id": id,
+        "parentId": parent_id,
+        "mentions": mentions,
+        "targetIds": target_ids,
+        "timestamp": timestamp,
+        "replyTo": reply_to
+    }))
+}
+
+/// Publish an event to the frontend-independent IPC subscribers
+pub async fn publish_event(app_handle: &AppHandle, event: IpcEvent) {
+    if let Some(state) = app_handle.try_state::<SharedIpcServerState>() {
+        let state_read = state.read().await;
+        let _ = state_read.event_tx.send(event);
+    }
+}
+
+/// Receive from an optional broadcast channel (pends forever when absent)
+async fn recv_optional<T: Clone>(rx: &mut Option<broadcast::Receiver<T>>) -> Result<T, RecvError> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Forward subscribed events to a connection's outgoing queue
+///
+/// Notifications never block responses: when the queue is full they are dropped
+/// and the client is told how many it missed once it catches up. A client that
+/// stays full for too long is disconnected.
+async fn forward_notifications(
+    app_handle: AppHandle,
+    state: SharedIpcServerState,
+    mut filter_rx: watch::Receiver<SubscriptionFilter>,
+    out_tx: mpsc::Sender<Message>,
+) {
+    let mut event_rx = state.read().await.event_tx.subscribe();
+    let mut coordinator_rx = app_handle
+        .try_state::<SharedAgentCoordinator>()
+        .map(|coordinator| coordinator.subscribe());
+
+    let mut missed: u64 = 0;
+    let mut consecutive_drops: usize = 0;
+
+    loop {
+        let notification = tokio::select! {
+            event = event_rx.recv() => match event {
+                Ok(event) => filter_rx.borrow().notify_event(&event),
+                Err(RecvError::Lagged(n)) => {
+                    missed += n;
+                    None
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = recv_optional(&mut coordinator_rx) => match message {
+                Ok(message) => filter_rx.borrow().notify_coordinator(&message),
+                Err(RecvError::Lagged(n)) => {
+                    missed += n;
+                    None
+                }
+                Err(RecvError::Closed) => {
+                    coordinator_rx = None;
+                    None
+                }
+            },
+            changed = filter_rx.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                None
+            }
+        };
+
+        let Some(notification) = notification else {
+            continue;
+        };
+
+        // Tell the client about anything it missed before sending new events
+        if missed > 0 {
+            let notice = filter_rx
+                .borrow()
+                .notification("subscription.lagged", serde_json::json!({ "missed": missed }));
+            if let Some(notice) = notice {
+                if out_tx.try_send(Message::Text(notice.into())).is_ok() {
+                    missed = 0;
+                }
+            }
+        }
+
+        match out_tx.try_send(Message::Text(notification.into())) {
+            Ok(()) => consecutive_drops = 0,
+            Err(TrySendError::Full(_)) => {
+                missed += 1;
+                consecutive_drops += 1;
+                if consecutive_drops >= MAX_DROPPED_NOTIFICATIONS {
+                    eprintln!("[IPC] Subscriber too slow, dropped {} notifications - disconnecting", missed);
+                    break;
+                }
+            }
+            Err(TrySendError::Closed(_)) => break,
+        }
+    }
+}
+
 /// Handle a single WebSocket connection
 async fn handle_connection(
     stream: TcpStream,
+    peer: SocketAddr,
     app_handle: AppHandle,
     state: SharedIpcServerState,
 ) {
@@ -176,7 +719,42 @@ async fn handle_connection(
 
     let (mut write, mut read) = ws_stream.split();
 
-    while let Some(msg_result) = read.next().await {
+    // Single writer so responses and notifications can interleave
+    let (out_tx, mut out_rx) = mpsc::channel::<Message>(OUTGOING_QUEUE_SIZE);
+    let writer = tokio::spawn(async move {
+        while let Some(message) = out_rx.recv().await {
+            if let Err(e) = write.send(message).await {
+                eprintln!("[IPC] Failed to send message: {}", e);
+                break;
+            }
+        }
+    });
+
+    let (filter_tx, filter_rx) = watch::channel(SubscriptionFilter::default());
+    let mut forwarder = tokio::spawn(forward_notifications(
+        app_handle.clone(),
+        state.clone(),
+        filter_rx,
+        out_tx.clone(),
+    ));
+
+    let mut ctx = ConnectionContext {
+        peer: peer.to_string(),
+        identity: None,
+        protocol: Protocol::default(),
+        subscriptions: filter_tx,
+    };
+
+    loop {
+        let msg_result = tokio::select! {
+            msg = read.next() => match msg {
+                Some(m) => m,
+                None => break,
+            },
+            // Forwarder only stops on its own when the client can't keep up
+            _ = &mut forwarder => break,
+        };
+
         let msg = match msg_result {
             Ok(m) => m,
             Err(e) => {
@@ -186,39 +764,191 @@ async fn handle_connection(
         };
 
         if let Message::Text(text) = msg {
-            let response = process_message(&text, &app_handle, &state).await;
-            if let Err(e) = write.send(Message::Text(response.into())).await {
-                eprintln!("[IPC] Failed to send response: {}", e);
+            // JSON-RPC notifications (and batches of them) get no response
+            let Some(response) = process_message(&text, &mut ctx, &app_handle, &state).await
+            else {
+                continue;
+            };
+            if out_tx.send(Message::Text(response.into())).await.is_err() {
                 break;
             }
         }
     }
+
+    forwarder.abort();
+    drop(out_tx);
+    let _ = writer.await;
+}
+
+/// Serialize a response envelope
+fn response_json(id: String, response: IpcResponse) -> String {
+    serde_json::to_string(&IpcResponseMessage { id, response }).unwrap_or_default()
 }
 
-/// Process an incoming IPC message
+/// Build an error response
+fn error_response(message: String, code: &str) -> IpcResponse {
+    IpcResponse::Error {
+        message,
+        code: Some(code.to_string()),
+    }
+}
+
+/// Handle the `auth.hello` handshake
+async fn authenticate(
+    request: IpcRequest,
+    ctx: &mut ConnectionContext,
+    state: &SharedIpcServerState,
+) -> IpcResponse {
+    let IpcRequest::AuthHello { token, role, client_id, session_id } = request else {
+        return error_response("Expected auth.hello".to_string(), "UNAUTHORIZED");
+    };
+
+    if ctx.identity.is_some() {
+        return error_response("Connection already authenticated".to_string(), "ALREADY_AUTHENTICATED");
+    }
+
+    let valid = {
+        let state_read = state.read().await;
+        ipc_auth::tokens_match(&state_read.auth_token, &token)
+    };
+    if !valid {
+        ipc_auth::audit(None, &ctx.peer, "auth.hello", "rejected: invalid token");
+        return error_response("Invalid token".to_string(), "UNAUTHORIZED");
+    }
+    if role.trim().is_empty() {
+        return error_response("Role is required".to_string(), "INVALID_PARAMS");
+    }
+
+    let identity = ClientIdentity {
+        role,
+        client_id: client_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        session_id,
+        peer: ctx.peer.clone(),
+    };
+    ipc_auth::audit(Some(&identity), &ctx.peer, "auth.hello", "ok");
+
+    let data = serde_json::json!({
+        "authenticated": true,
+        "role": identity.role,
+        "clientId": identity.client_id,
+    });
+    ctx.identity = Some(identity);
+
+    IpcResponse::Success { data }
+}
+
+/// Process an incoming IPC message (legacy or JSON-RPC)
+///
+/// Returns `None` when nothing should be sent back (JSON-RPC notifications).
 async fn process_message(
     text: &str,
+    ctx: &mut ConnectionContext,
     app_handle: &AppHandle,
     state: &SharedIpcServerState,
-) -> String {
-    // Parse the message
-    let msg: IpcMessage = match serde_json::from_str(text) {
-        Ok(m) => m,
+) -> Option<String> {
+    let incoming = match ipc_rpc::decode(text) {
+        Ok(incoming) => incoming,
         Err(e) => {
-            return serde_json::to_string(&IpcResponseMessage {
-                id: "unknown".to_string(),
-                response: IpcResponse::Error {
-                    message: format!("Failed to parse message: {}", e),
-                    code: Some("PARSE_ERROR".to_string()),
-                },
-            })
-            .unwrap_or_default();
+            let message = format!("Failed to parse message: {}", e);
+            return Some(match ctx.protocol {
+                Protocol::Legacy => {
+                    response_json("unknown".to_string(), error_response(message, "PARSE_ERROR"))
+                }
+                Protocol::JsonRpc => {
+                    ipc_rpc::error_json(ipc_rpc::RpcError::new(ipc_rpc::PARSE_ERROR, message))
+                }
+            });
+        }
+    };
+
+    match incoming {
+        Incoming::Legacy { id, request } => {
+            ctx.protocol = Protocol::Legacy;
+            let response = match request {
+                Ok(request) => dispatch(request, ctx, app_handle, state).await,
+                Err(e) => error_response(e, "PARSE_ERROR"),
+            };
+            Some(response_json(id, response))
+        }
+
+        Incoming::JsonRpcError(error) => {
+            ctx.protocol = Protocol::JsonRpc;
+            Some(ipc_rpc::error_json(error))
+        }
+
+        Incoming::JsonRpc { calls, batch } => {
+            ctx.protocol = Protocol::JsonRpc;
+
+            // Batch calls run in order so `auth.hello` can lead a batch
+            let mut responses = Vec::new();
+            for call in calls {
+                let result = match call.request {
+                    Ok(request) => ipc_rpc::to_rpc_result(dispatch(request, ctx, app_handle, state).await),
+                    Err(error) => Err(error),
+                };
+                if let Some(id) = call.id {
+                    responses.push(ipc_rpc::response_value(id, result));
+                }
+            }
+
+            if responses.is_empty() {
+                None
+            } else if batch {
+                Some(serde_json::Value::Array(responses).to_string())
+            } else {
+                responses.pop().map(|r| r.to_string())
+            }
+        }
+    }
+}
+
+/// Execute a decoded request
+async fn dispatch(
+    request: IpcRequest,
+    ctx: &mut ConnectionContext,
+    app_handle: &AppHandle,
+    state: &SharedIpcServerState,
+) -> IpcResponse {
+    let method = request.method_name();
+
+    // Ping and discovery are allowed before the handshake so clients can probe the server
+    match request {
+        IpcRequest::Ping => {
+            return IpcResponse::Success {
+                data: serde_json::json!({ "pong": true }),
+            };
+        }
+        IpcRequest::RpcDiscover => {
+            return IpcResponse::Success {
+                data: ipc_rpc::discover(),
+            };
+        }
+        IpcRequest::AuthHello { .. } => return authenticate(request, ctx, state).await,
+        _ => {}
+    }
+
+    let identity = match &ctx.identity {
+        Some(identity) => identity.clone(),
+        None => {
+            if ipc_auth::is_privileged(method) {
+                ipc_auth::audit(None, &ctx.peer, method, "rejected: unauthenticated");
+            }
+            return error_response(
+                "Not authenticated - send auth.hello first".to_string(),
+                "UNAUTHORIZED",
+            );
         }
     };
 
-    let response = match msg.request {
-        IpcRequest::Ping => IpcResponse::Success {
-            data: serde_json::json!({ "pong": true }),
+    if let Err(e) = request.verify_identity(&identity) {
+        ipc_auth::audit(Some(&identity), &ctx.peer, method, "rejected: identity mismatch");
+        return error_response(e, "FORBIDDEN");
+    }
+
+    let response = match request {
+        // Handled above
+        IpcRequest::Ping | IpcRequest::RpcDiscover | IpcRequest::AuthHello { .. } => IpcResponse::Success {
+            data: serde_json::Value::Null,
         },
 
         IpcRequest::GroupChatSend {
@@ -226,98 +956,84 @@ async fn process_message(
             from_id,
             content,
             reply_to,
+            project_path,
+            parent_id,
         } => {
-            // Parse @mentions from content
-            let mentions = parse_mentions(&content);
-            let timestamp = chrono::Utc::now().to_rfc3339();
-
-            // Note: Without terminal registry, we can't find target agents
-            // Group chat messages are stored but not delivered to agents
-            let target_ids: Vec<String> = Vec::new();
-
-            // Create chat message with reply_to field
-            let chat_msg = GroupChatMessage {
-                from_role: from_role.clone(),
-                from_id: from_id.clone(),
-                content: content.clone(),
-                mentions: mentions.clone(),
-                timestamp: timestamp.clone(),
-                reply_to: reply_to.clone(),
+            let new_msg = NewGroupChatMessage {
+                project_path,
+                from_role,
+                from_id,
+                content,
+                reply_to,
+                parent_id,
             };
-
-            // Store in history and add to pending messages for mentioned roles
-            {
-                let mut state_write = state.write().await;
-                state_write.chat_history.push(chat_msg);
-                // Keep last 100 messages
-                if state_write.chat_history.len() > 100 {
-                    state_write.chat_history.remove(0);
-                }
-
-                // Add to pending messages for each mentioned role (excluding sender)
-                for mention in &mentions {
-                    // Skip if mention is the sender (prevents self-injection)
-                    if mention.eq_ignore_ascii_case(&from_role) {
-                        continue;
-                    }
-                    let pending = PendingMessage {
-                        from_role: from_role.clone(),
-                        content: content.clone(),
-                        timestamp: timestamp.clone(),
-                        reply_to: reply_to.clone(),
-                    };
-                    state_write
-                        .pending_messages
-                        .entry(mention.to_lowercase())
-                        .or_insert_with(Vec::new)
-                        .push(pending);
-                }
+            match post_group_chat_message(app_handle, state, new_msg).await {
+                Ok(data) => IpcResponse::Success { data },
+                Err(e) => IpcResponse::Error {
+                    message: e,
+                    code: Some("SEND_ERROR".to_string()),
+                },
             }
+        }
 
-            // Create event for broadcasting
-            let event = IpcEvent::GroupChatMessage {
-                from_role: from_role.clone(),
-                from_id: from_id.clone(),
-                content: content.clone(),
-                mentions: mentions.clone(),
-                timestamp: timestamp.clone(),
-                target_ids: target_ids.clone(),
-                reply_to: reply_to.clone(),
+        IpcRequest::GroupChatHistory {
+            limit,
+            project_path,
+            before,
+            query,
+            thread_id,
+        } => {
+            let history_query = HistoryQuery {
+                limit: limit.unwrap_or(50),
+                before,
+                search: query,
+                thread_id,
             };
 
-            // Emit to Tauri frontend (for UI display)
-            let _ = app_handle.emit("ipc-groupchat-message", &event);
-
-            // Broadcast internally
-            {
-                let state_read = state.read().await;
-                let _ = state_read.event_tx.send(event);
+            match read_group_chat_history(project_path, history_query).await {
+                Ok(history) => IpcResponse::Success {
+                    data: serde_json::to_value(history).unwrap_or(serde_json::json!([])),
+                },
+                Err(e) => IpcResponse::Error {
+                    message: e,
+                    code: Some("HISTORY_ERROR".to_string()),
+                },
             }
+        }
 
+        IpcRequest::Subscribe { topics } => {
+            let protocol = ctx.protocol;
+            ctx.subscriptions.send_modify(|filter| {
+                filter.identity = Some(identity.clone());
+                filter.protocol = protocol;
+                filter.topics.extend(topics);
+            });
+            let subscribed: Vec<&str> = ctx
+                .subscriptions
+                .borrow()
+                .topics
+                .iter()
+                .map(|t| t.as_str())
+                .collect();
             IpcResponse::Success {
-                data: serde_json::json!({
-                    "sent": true,
-                    "mentions": mentions,
-                    "targetIds": target_ids,
-                    "timestamp": timestamp,
-                    "replyTo": reply_to
-                }),
+                data: serde_json::json!({ "subscribed": subscribed }),
             }
         }
 
-        IpcRequest::GroupChatHistory { limit } => {
-            let state_read = state.read().await;
-            let history_limit = limit.unwrap_or(50);
-            let history: Vec<_> = state_read
-                .chat_history
+        IpcRequest::Unsubscribe { topics } => {
+            ctx.subscriptions.send_modify(|filter| match topics {
+                Some(topics) => filter.topics.retain(|t| !topics.contains(t)),
+                None => filter.topics.clear(),
+            });
+            let subscribed: Vec<&str> = ctx
+                .subscriptions
+                .borrow()
+                .topics
                 .iter()
-                .rev()
-                .take(history_limit)
-                .cloned()
+                .map(|t| t.as_str())
                 .collect();
-
             IpcResponse::Success {
-                data: serde_json::to_value(history).unwrap_or(serde_json::json!([])),
+                data: serde_json::json!({ "subscribed": subscribed }),
             }
         }
 
@@ -332,41 +1048,39 @@ async fn process_message(
             }
         }
 
-        IpcRequest::AgentGetPending { role } => {
-            let role_lower = role.to_lowercase();
-            let mut state_write = state.write().await;
+        IpcRequest::AgentGetPending { role, project_path } => {
+            let store = GroupChatStore::for_project(project_path.as_deref());
 
             // Get and clear pending messages for this role
-            let pending = state_write
-                .pending_messages
-                .remove(&role_lower)
-                .unwrap_or_default();
+            let pending = tokio::task::spawn_blocking(move || store.take_pending(&role))
+                .await
+                .map_err(|e| format!("Failed to read pending messages: {}", e))
+                .and_then(|pending| pending);
 
-            if pending.is_empty() {
-                IpcResponse::Success {
+            match pending {
+                Ok(pending) if pending.is_empty() => IpcResponse::Success {
                     data: serde_json::json!({
                         "hasPending": false,
                         "messages": [],
                         "count": 0
                     }),
-                }
-            } else {
-                IpcResponse::Success {
+                },
+                Ok(pending) => IpcResponse::Success {
                     data: serde_json::json!({
                         "hasPending": true,
                         "messages": pending,
                         "count": pending.len()
                     }),
-                }
+                },
+                Err(e) => IpcResponse::Error {
+                    message: e,
+                    code: Some("PENDING_ERROR".to_string()),
+                },
             }
         }
 
         IpcRequest::SessionSave { project_path, roles } => {
-            let mapping = crate::session::RoleSessionMapping {
-                project_path,
-                saved_at: chrono::Utc::now().to_rfc3339(),
-                roles,
-            };
+            let mapping = crate::session::RoleSessionMapping::new(project_path, roles);
 
             match crate::session::save_role_sessions(&mapping) {
                 Ok(_) => IpcResponse::Success {
@@ -382,10 +1096,23 @@ async fn process_message(
             }
         }
 
-        IpcRequest::SessionLoad => match crate::session::load_role_sessions() {
-            Ok(Some(mapping)) => IpcResponse::Success {
-                data: serde_json::to_value(mapping).unwrap_or(serde_json::json!(null)),
-            },
+        IpcRequest::SessionLoad {
+            project_path,
+            validate,
+        } => match crate::session::load_role_sessions(&project_path) {
+            Ok(Some(mapping)) => {
+                let invalid_roles = if validate.unwrap_or(true) {
+                    mapping.invalid_roles(crate::session::SESSION_VALID_HOURS)
+                } else {
+                    Vec::new()
+                };
+                let mut data = serde_json::to_value(mapping).unwrap_or(serde_json::json!({}));
+                if let Some(object) = data.as_object_mut() {
+                    object.insert("found".to_string(), serde_json::json!(true));
+                    object.insert("invalidRoles".to_string(), serde_json::json!(invalid_roles));
+                }
+                IpcResponse::Success { data }
+            }
             Ok(None) => IpcResponse::Success {
                 data: serde_json::json!({ "found": false }),
             },
@@ -395,7 +1122,7 @@ async fn process_message(
             },
         },
 
-        IpcRequest::SessionClear => match crate::session::clear_role_sessions() {
+        IpcRequest::SessionClear { project_path } => match crate::session::clear_role_sessions(&project_path) {
             Ok(_) => IpcResponse::Success {
                 data: serde_json::json!({ "cleared": true }),
             },
@@ -404,24 +1131,38 @@ async fn process_message(
                 code: Some("CLEAR_ERROR".to_string()),
             },
         },
+
+        // Coordinator, team, session storage and OpenSpec operations
+        other => crate::ipc_methods::handle(other, app_handle).await,
     };
 
-    serde_json::to_string(&IpcResponseMessage {
-        id: msg.id,
-        response,
-    })
-    .unwrap_or_default()
+    if ipc_auth::is_privileged(method) {
+        let outcome = match &response {
+            IpcResponse::Success { .. } => "ok",
+            IpcResponse::Error { .. } => "error",
+        };
+        ipc_auth::audit(Some(&identity), &ctx.peer, method, outcome);
+    }
+
+    response
 }
 
 /// Start the IPC WebSocket server
 pub async fn start_ipc_server(app_handle: AppHandle, state: SharedIpcServerState) {
-    // Mark as running
+    // Publish the auth token for local clients, then mark as running
     {
         let mut state_write = state.write().await;
+        match ipc_auth::write_token(&state_write.auth_token) {
+            Ok(path) => println!("[IPC] Auth token written to {:?}", path),
+            Err(e) => eprintln!("[IPC] Failed to write auth token: {}", e),
+        }
+        if let Err(e) = crate::config::write_ports_file() {
+            eprintln!("[IPC] {}", e);
+        }
         state_write.running = true;
     }
 
-    let addr = format!("127.0.0.1:{}", IPC_PORT);
+    let addr = format!("127.0.0.1:{}", crate::config::ports().ipc);
     let listener = match TcpListener::bind(&addr).await {
         Ok(l) => {
             println!("[IPC] Server listening on ws://{}", addr);
@@ -440,7 +1181,7 @@ pub async fn start_ipc_server(app_handle: AppHandle, state: SharedIpcServerState
                 let app_handle_clone = app_handle.clone();
                 let state_clone = state.clone();
                 tokio::spawn(async move {
-                    handle_connection(stream, app_handle_clone, state_clone).await;
+                    handle_connection(stream, addr, app_handle_clone, state_clone).await;
                 });
             }
             Err(e) => {
@@ -463,86 +1204,67 @@ pub async fn ipc_subscribe(
     }
 }
 
+/// Tauri command to get the IPC auth token
+/// The frontend uses it to authenticate its own IPC connection
+#[tauri::command]
+pub async fn ipc_get_auth_token(
+    state: tauri::State<'_, SharedIpcServerState>,
+) -> Result<String, String> {
+    let state_read = state.read().await;
+    Ok(state_read.auth_token.clone())
+}
+
 /// Tauri command to send a group chat message from frontend
 /// This allows agents to reply via the UI
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn send_group_chat_message(
     from_role: String,
     from_id: String,
     content: String,
     reply_to: Option<String>,
+    project_path: Option<String>,
+    parent_id: Option<String>,
     app: tauri::AppHandle,
     state: tauri::State<'_, SharedIpcServerState>,
 ) -> Result<serde_json::Value, String> {
-    // Parse @mentions from content
-    let mentions = parse_mentions(&content);
-    let timestamp = chrono::Utc::now().to_rfc3339();
-
-    // Note: Without terminal registry, we can't find target agents
-    let target_ids: Vec<String> = Vec::new();
-
-    // Create chat message
-    let chat_msg = GroupChatMessage {
-        from_role: from_role.clone(),
-        from_id: from_id.clone(),
-        content: content.clone(),
-        mentions: mentions.clone(),
-        timestamp: timestamp.clone(),
-        reply_to: reply_to.clone(),
+    let new_msg = NewGroupChatMessage {
+        project_path,
+        from_role,
+        from_id,
+        content,
+        reply_to,
+        parent_id,
     };
+    post_group_chat_message(&app, state.inner(), new_msg).await
+}
 
-    // Store in history and add to pending messages for mentioned roles
-    {
-        let mut state_write = state.write().await;
-        state_write.chat_history.push(chat_msg);
-        if state_write.chat_history.len() > 100 {
-            state_write.chat_history.remove(0);
-        }
-
-        // Add to pending messages for each mentioned role (excluding sender)
-        for mention in &mentions {
-            if mention.eq_ignore_ascii_case(&from_role) {
-                continue;
-            }
-            let pending = PendingMessage {
-                from_role: from_role.clone(),
-                content: content.clone(),
-                timestamp: timestamp.clone(),
-                reply_to: reply_to.clone(),
-            };
-            state_write
-                .pending_messages
-                .entry(mention.to_lowercase())
-                .or_insert_with(Vec::new)
-                .push(pending);
-        }
-    }
+/// Read group chat history without blocking the async runtime
+async fn read_group_chat_history(
+    project_path: Option<String>,
+    query: HistoryQuery,
+) -> Result<Vec<GroupChatMessage>, String> {
+    tokio::task::spawn_blocking(move || {
+        GroupChatStore::for_project(project_path.as_deref()).history(&query)
+    })
+    .await
+    .map_err(|e| format!("Failed to read group chat history: {}", e))?
+}
 
-    // Create event for broadcasting
-    let event = IpcEvent::GroupChatMessage {
-        from_role: from_role.clone(),
-        from_id: from_id.clone(),
-        content: content.clone(),
-        mentions: mentions.clone(),
-        timestamp: timestamp.clone(),
-        target_ids: target_ids.clone(),
-        reply_to: reply_to.clone(),
+/// Tauri command to read persisted group chat history (newest first)
+#[tauri::command]
+pub async fn get_group_chat_history(
+    project_path: Option<String>,
+    limit: Option<usize>,
+    before: Option<String>,
+    query: Option<String>,
+    thread_id: Option<String>,
+) -> Result<Vec<GroupChatMessage>, String> {
+    let query = HistoryQuery {
+        limit: limit.unwrap_or(50),
+        before,
+        search: query,
+        thread_id,
     };
-
-    // Emit to Tauri frontend
-    let _ = app.emit("ipc-groupchat-message", &event);
-
-    // Broadcast internally
-    {
-        let state_read = state.read().await;
-        let _ = state_read.event_tx.send(event);
-    }
-
-    Ok(serde_json::json!({
-        "sent": true,
-        "mentions": mentions,
-        "targetIds": target_ids,
-        "timestamp": timestamp,
-        "replyTo": reply_to
-    }))
+    read_group_chat_history(project_path, query).await
 }
@@ -1,15 +1,36 @@
 mod commands;
+mod config;
 mod agent_coordinator;
 mod api_server;
 mod claude_process;
 mod ipc_server;
+mod ipc_auth;
+mod ipc_methods;
+mod ipc_rpc;
+mod groupchat_storage;
 mod session;
 mod session_storage;
+mod session_search;
+mod session_export;
+mod session_summary;
+mod project_identity;
+mod project_relocate;
+mod persist;
+mod process_metrics;
+mod process_reaper;
+mod db;
+mod zip_archive;
 mod workspace_storage;
+mod workspace_schema;
 mod team_storage;
 mod team_manager;
+mod team_templates;
+mod team_launcher;
 mod claude_session;
+mod claude_transcripts;
 mod recovery_watchdog;
+mod failure_patterns;
+mod recovery_context;
 mod singleton;
 mod session_tracker;
 mod sdk_sidecar;
@@ -35,8 +56,10 @@ use commands::claude::{
     // Persistent session commands
     claude_spawn_session, claude_send_input, claude_has_session,
     claude_get_session, claude_list_sessions, claude_terminate_session,
+    claude_resume_conversation,
 };
 use claude_process::{create_process_manager, claude_load_session_history};
+use claude_transcripts::{claude_transcripts_list, claude_transcripts_refresh};
 use agent_coordinator::{
     create_coordinator,
     coordinator_register_agent, coordinator_unregister_agent,
@@ -53,9 +76,10 @@ use commands::team::{
     team_create, team_list, team_get, team_update, team_archive,
     team_add_member, team_remove_member, team_update_member_session,
     team_update_member_status, team_update_member_task, team_get_members,
-    team_pause, team_resume,
+    team_pause, team_resume, team_start, team_stop,
     team_report_member_failure, team_create_replacement,
     team_get_recovery_context, team_get_recovery_history, team_member_heartbeat,
+    team_template_list, team_template_validate, team_create_from_template,
 };
 use commands::openspec::{
     get_openspec_summary, get_openspec_changes, get_openspec_specs, get_openspec_file_content,
@@ -77,7 +101,7 @@ use commands::test_room::{
     test_message_list, test_message_create,
     test_artifact_list,
 };
-use ipc_server::{create_ipc_state, start_ipc_server, ipc_subscribe, send_group_chat_message};
+use ipc_server::{create_ipc_state, start_ipc_server, ipc_subscribe, send_group_chat_message, get_group_chat_history, ipc_get_auth_token};
 use session_tracker::create_session_tracker;
 use team_manager::create_team_manager;
 use recovery_watchdog::{create_watchdog_handle, start_watchdog};
@@ -91,9 +115,18 @@ use session_storage::{
     session_storage_rename, session_storage_delete, session_storage_update_status,
     session_storage_update_role, session_storage_update_claude_id, session_storage_export,
     session_storage_load_output, session_storage_append_output, session_storage_cleanup,
+    session_storage_enforce_quota,
 };
+use session_search::{session_search, session_search_reindex};
+use session_summary::session_summary_generate;
+use config::{config_get, config_ports};
+use process_metrics::process_metrics_get;
+use session_export::{session_storage_export_bundle, session_storage_import_bundle};
+use project_identity::{project_identity_list, project_identity_resolve};
+use project_relocate::project_relocate;
 use workspace_storage::{
-    workspace_exists, workspace_init, workspace_session_load, workspace_session_save,
+    workspace_exists, workspace_init, workspace_session_load, workspace_session_restore,
+    workspace_session_save,
     workspace_get_history_path, workspace_get_config, workspace_validate_cwd,
 };
 use std::sync::Arc;
@@ -137,6 +170,31 @@ pub fn run() {
         std::process::exit(1);
     }
 
+    // Move storage created under the old project hashing scheme (one-time)
+    match project_identity::migrate_legacy_storage() {
+        Ok(report) if !report.moved.is_empty() => {
+            eprintln!("[ProjectIdentity] Migrated {} storage director(ies)", report.moved.len());
+        }
+        Err(e) => {
+            eprintln!("[ProjectIdentity] Migration error: {}", e);
+        }
+        _ => {}
+    }
+
+    // Bring the database schema up to date and import the JSON stores (one-time)
+    match db::init() {
+        Ok(report) if report.sessions + report.teams + report.windows + report.tracked_processes > 0 => {
+            eprintln!(
+                "[Database] Imported {} session(s), {} team(s), {} window(s) and {} tracked process(es) from JSON storage",
+                report.sessions, report.teams, report.windows, report.tracked_processes
+            );
+        }
+        Err(e) => {
+            eprintln!("[Database] Initialization error: {}", e);
+        }
+        _ => {}
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
@@ -147,7 +205,7 @@ pub fn run() {
         .manage(ClaudeSessionManagerState(Arc::new(Mutex::new(ClaudeSessionManager::new()))))
         .manage(create_ipc_state())
         .manage(create_process_manager())
-        .manage(create_coordinator(5)) // Max 5 concurrent workers
+        .manage(create_coordinator(config::get().coordinator.max_workers))
         .manage(create_team_manager().expect("Failed to create team manager"))
         .manage(create_watchdog_handle())
         .manage(create_api_server_state())
@@ -157,7 +215,7 @@ pub fn run() {
             // Create session tracker and cleanup orphaned processes from previous runs
             let (tracker, orphaned) = create_session_tracker();
             if !orphaned.is_empty() {
-                eprintln!("[Agent Manager] Cleaned up {} orphaned agent session(s) from previous run", orphaned.len());
+                eprintln!("[Agent Manager] Cleaned up {} orphaned process(es) from previous run", orphaned.len());
             }
             tracker
         })
@@ -167,8 +225,9 @@ pub fn run() {
 
             // Start API server
             let api_state = app.state::<api_server::SharedApiServerState>().inner().clone();
+            let api_tracker = app.state::<session_tracker::SharedSessionTracker>().inner().clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = start_api_server(api_state).await {
+                if let Err(e) = start_api_server(api_state, api_tracker).await {
                     eprintln!("[Agent Manager] Failed to start API server: {}", e);
                 }
             });
@@ -183,14 +242,30 @@ pub fn run() {
             // Start recovery watchdog
             let watchdog_handle = app.state::<recovery_watchdog::SharedWatchdog>().inner().clone();
             let team_manager = app.state::<team_manager::SharedTeamManager>().inner().clone();
+            let watchdog_processes = app.state::<claude_process::SharedClaudeProcessManager>().inner().clone();
             let watchdog_app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                start_watchdog(watchdog_handle, team_manager, watchdog_app_handle).await;
+                start_watchdog(watchdog_handle, team_manager, watchdog_processes, watchdog_app_handle).await;
             });
 
-            // Cleanup old sessions (30 days)
+            // Sample agent CPU/memory and enforce per-role limits
+            process_metrics::start(
+                app.handle().clone(),
+                app.state::<claude_process::SharedClaudeProcessManager>().inner().clone(),
+                app.state::<session_tracker::SharedSessionTracker>().inner().clone(),
+            );
+
+            // Reload settings when a config.yaml changes
+            config::watch(
+                app.handle().clone(),
+                app.state::<recovery_watchdog::SharedWatchdog>().inner().clone(),
+                app.state::<agent_coordinator::SharedAgentCoordinator>().inner().clone(),
+            );
+
+            // Cleanup old sessions (sessions.cleanup_after_days)
+            let cleanup_after_days = config::get().sessions.cleanup_after_days;
             tauri::async_runtime::spawn(async move {
-                match session_storage::cleanup_old_sessions(30) {
+                match session_storage::cleanup_old_sessions(cleanup_after_days) {
                     Ok(count) if count > 0 => {
                         eprintln!("[SessionStorage] Cleaned up {} old session(s)", count);
                     }
@@ -201,6 +276,17 @@ pub fn run() {
                 }
             });
 
+            // Index session logs written while the search index was not running
+            tauri::async_runtime::spawn_blocking(|| match session_search::index_all() {
+                Ok(count) if count > 0 => {
+                    eprintln!("[SessionSearch] Indexed {} new log line(s)", count);
+                }
+                Err(e) => {
+                    eprintln!("[SessionSearch] Indexing error: {}", e);
+                }
+                _ => {}
+            });
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -268,6 +354,8 @@ pub fn run() {
             // IPC server
             ipc_subscribe,
             send_group_chat_message,
+            get_group_chat_history,
+            ipc_get_auth_token,
             // Claude process manager (stream-json)
             claude_spawn,
             claude_get_process,
@@ -282,6 +370,10 @@ pub fn run() {
             claude_list_sessions,
             claude_terminate_session,
             claude_load_session_history,
+            claude_resume_conversation,
+            // Claude transcript index
+            claude_transcripts_list,
+            claude_transcripts_refresh,
             // Agent coordinator
             coordinator_register_agent,
             coordinator_unregister_agent,
@@ -307,11 +399,16 @@ pub fn run() {
             team_get_members,
             team_pause,
             team_resume,
+            team_start,
+            team_stop,
             team_report_member_failure,
             team_create_replacement,
             team_get_recovery_context,
             team_get_recovery_history,
             team_member_heartbeat,
+            team_template_list,
+            team_template_validate,
+            team_create_from_template,
             // OpenSpec commands
             get_openspec_summary,
             get_openspec_changes,
@@ -341,6 +438,20 @@ pub fn run() {
             session_storage_load_output,
             session_storage_append_output,
             session_storage_cleanup,
+            session_storage_enforce_quota,
+            session_storage_export_bundle,
+            session_storage_import_bundle,
+            // Session search commands
+            session_search,
+            session_search_reindex,
+            session_summary_generate,
+            config_get,
+            config_ports,
+            process_metrics_get,
+            // Project identity commands
+            project_identity_resolve,
+            project_identity_list,
+            project_relocate,
             // Slash commands
             slash_search_files,
             resolve_file_mention,
@@ -357,6 +468,7 @@ pub fn run() {
             workspace_exists,
             workspace_init,
             workspace_session_load,
+            workspace_session_restore,
             workspace_session_save,
             workspace_get_history_path,
             workspace_get_config,
@@ -382,25 +494,22 @@ pub fn run() {
                 // Stop API server on exit
                 let api_state = app_handle.state::<api_server::SharedApiServerState>().inner().clone();
                 let sidecar_state = app_handle.state::<sdk_sidecar::SharedSidecarState>().inner().clone();
+                let tracker = app_handle.state::<session_tracker::SharedSessionTracker>().inner().clone();
                 tauri::async_runtime::block_on(async {
-                    stop_api_server(api_state).await;
+                    stop_api_server(api_state, tracker.clone()).await;
                     // Stop SDK sidecar
                     let mut sidecar = sidecar_state.lock().await;
                     if let Some(pid) = sidecar.pid.take() {
                         println!("[Agent Manager] Stopping SDK sidecar (PID: {})", pid);
-                        #[cfg(unix)]
-                        {
-                            use std::process::Command as StdCommand;
-                            let _ = StdCommand::new("kill").arg("-TERM").arg(pid.to_string()).output();
-                        }
-                        #[cfg(windows)]
-                        {
-                            use std::process::Command as StdCommand;
-                            let _ = StdCommand::new("taskkill").args(["/PID", &pid.to_string(), "/F"]).output();
-                        }
+                        process_reaper::terminate(pid, process_reaper::GRACE_PERIOD);
                     }
                 });
 
+                // Terminate agent sessions still running (with their process groups)
+                if let Ok(mut tracker) = tracker.lock() {
+                    tracker.shutdown();
+                }
+
                 // Cleanup singleton lockfile
                 singleton::cleanup_singleton();
             }
@@ -0,0 +1,310 @@
+//! Crash-safe persistence for JSON state files
+//!
+//! Every write goes to a temp file in the same directory, is fsynced, then
+//! renamed over the target (and the directory fsynced), so readers see either
+//! the old or the new contents, never a torn file. Before a valid file is
+//! replaced it is kept as `{name}.bak`; reads that find the file corrupt (not
+//! JSON at all) fall back to the backup and restore it. Valid JSON that does
+//! not match the expected type is reported to the caller and left alone, so
+//! a file from a newer build is never rolled back to the backup.
+//!
+//! Writers and readers take an advisory `flock` on `{name}.lock`, which
+//! serializes access between the app's threads and any other instance using
+//! this module. The lock file is left in place so every holder locks the same
+//! inode.
+
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Paths
+// ============================================================================
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Last good copy of a file
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling(path, "bak")
+}
+
+/// Lock file guarding a file
+pub fn lock_path(path: &Path) -> PathBuf {
+    sibling(path, "lock")
+}
+
+// ============================================================================
+// Locking
+// ============================================================================
+
+/// Advisory lock on a file's `.lock` sibling, released on drop
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Block until the exclusive (writer) lock is held
+    pub fn exclusive(path: &Path) -> io::Result<Self> {
+        Self::acquire(path, true)
+    }
+
+    /// Block until a shared (reader) lock is held
+    pub fn shared(path: &Path) -> io::Result<Self> {
+        Self::acquire(path, false)
+    }
+
+    fn acquire(path: &Path, exclusive: bool) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path(path))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::io::AsRawFd;
+            let operation = if exclusive { libc::LOCK_EX } else { libc::LOCK_SH };
+            loop {
+                // Safe: the descriptor stays open for the lifetime of `file`
+                if unsafe { libc::flock(file.as_raw_fd(), operation) } == 0 {
+                    break;
+                }
+                let error = io::Error::last_os_error();
+                if error.kind() != io::ErrorKind::Interrupted {
+                    return Err(error);
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = exclusive;
+
+        // The lock is released when the descriptor is closed
+        Ok(Self { _file: file })
+    }
+}
+
+// ============================================================================
+// Writing
+// ============================================================================
+
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(handle) = File::open(dir) {
+        let _ = handle.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+/// Write a temp file, fsync it and rename it into place
+fn replace(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp = sibling(path, &format!("tmp.{}", std::process::id()));
+    let result = (|| {
+        let mut file = File::create(&temp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result?;
+
+    if let Some(parent) = path.parent() {
+        sync_dir(parent);
+    }
+    Ok(())
+}
+
+/// Keep the current file as the backup if it is valid JSON
+fn backup_current(path: &Path) {
+    let valid = fs::read(path)
+        .ok()
+        .is_some_and(|bytes| serde_json::from_slice::<IgnoredAny>(&bytes).is_ok());
+    if !valid {
+        return;
+    }
+
+    let backup = backup_path(path);
+    let staged = sibling(path, "bak.tmp");
+    let _ = fs::remove_file(&staged);
+    // A hard link costs nothing; the rename below then leaves it as the old copy
+    let linked = fs::hard_link(path, &staged).or_else(|_| fs::copy(path, &staged).map(|_| ()));
+    if linked.is_ok() && fs::rename(&staged, &backup).is_err() {
+        let _ = fs::remove_file(&staged);
+    }
+}
+
+/// Atomically replace a file's contents
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let _lock = FileLock::exclusive(path)?;
+    backup_current(path);
+    replace(path, contents)
+}
+
+/// Atomically write a value as pretty JSON
+pub fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(value).map_err(io::Error::other)?;
+    write_atomic(path, &json)
+}
+
+// ============================================================================
+// Reading
+// ============================================================================
+
+/// Contents of a JSON file that parsed as JSON, or why not
+enum Parsed<T> {
+    Value(T),
+    Corrupt(serde_json::Error),
+}
+
+/// Parse a file's contents; valid JSON of the wrong shape is an error
+fn parse<T: DeserializeOwned>(path: &Path, bytes: &[u8]) -> io::Result<Parsed<T>> {
+    match serde_json::from_slice(bytes) {
+        Ok(value) => Ok(Parsed::Value(value)),
+        Err(e) if serde_json::from_slice::<IgnoredAny>(bytes).is_ok() => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{:?} does not match the expected format: {}", path, e),
+        )),
+        Err(e) => Ok(Parsed::Corrupt(e)),
+    }
+}
+
+/// Read a JSON file, falling back to (and restoring) its backup if corrupt
+///
+/// Returns None if the file does not exist.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> io::Result<Option<T>> {
+    let primary = {
+        let _lock = FileLock::shared(path)?;
+        match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        }
+    };
+
+    if let Parsed::Value(value) = parse(path, &primary)? {
+        return Ok(Some(value));
+    }
+
+    // Another writer may have replaced the file since it was read, so decide
+    // again under the writer lock before touching anything
+    let _lock = FileLock::exclusive(path)?;
+    let primary = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let error = match parse(path, &primary)? {
+        Parsed::Value(value) => return Ok(Some(value)),
+        Parsed::Corrupt(e) => e,
+    };
+
+    let restored = fs::read(backup_path(path))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<T>(&bytes).ok().map(|value| (bytes, value)));
+    let Some((bytes, value)) = restored else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+    };
+
+    eprintln!(
+        "[Persist] {:?} is corrupt ({}); restored last good copy",
+        path, error
+    );
+    // Keep the corrupt file for inspection
+    let _ = fs::rename(path, sibling(path, "corrupt"));
+    replace(path, &bytes)?;
+    Ok(Some(value))
+}
+
+/// Remove a file together with its backup
+///
+/// The lock file stays: deleting it would let a waiting holder and a new one
+/// lock different inodes.
+pub fn remove(path: &Path) -> io::Result<()> {
+    let _lock = FileLock::exclusive(path)?;
+    match fs::remove_file(path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    let _ = fs::remove_file(backup_path(path));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_write_and_restore_backup() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        write_json(&path, &vec![1]).unwrap();
+        write_json(&path, &vec![1, 2]).unwrap();
+        assert_eq!(read_json::<Vec<u32>>(&backup_path(&path)).unwrap(), Some(vec![1]));
+
+        // A torn write is replaced by the last good copy
+        fs::write(&path, "[1, 2").unwrap();
+        assert_eq!(read_json::<Vec<u32>>(&path).unwrap(), Some(vec![1]));
+        assert_eq!(read_json::<Vec<u32>>(&path).unwrap(), Some(vec![1]));
+
+        remove(&path).unwrap();
+        assert_eq!(read_json::<Vec<u32>>(&path).unwrap(), None);
+        assert!(lock_path(&path).exists());
+    }
+
+    #[test]
+    fn test_corrupt_file_restore() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        write_json(&path, &vec![1]).unwrap();
+        write_json(&path, &vec![1, 2]).unwrap();
+        fs::write(&path, "{not json").unwrap();
+
+        assert_eq!(read_json::<Vec<u32>>(&path).unwrap(), Some(vec![1]));
+        // The corrupt contents are kept aside and the backup is written back
+        assert_eq!(fs::read_to_string(sibling(&path, "corrupt")).unwrap(), "{not json");
+        let restored: Vec<u32> = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(restored, vec![1]);
+
+        // Without a usable backup the corruption is reported, not papered over
+        fs::write(&path, "[").unwrap();
+        fs::write(backup_path(&path), "]").unwrap();
+        let err = read_json::<Vec<u32>>(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[");
+    }
+
+    #[test]
+    fn test_schema_mismatch_keeps_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        write_json(&path, &vec![1]).unwrap();
+        write_json(&path, &serde_json::json!({"version": "9.0"})).unwrap();
+
+        // Valid JSON of another shape is an error, not corruption
+        let err = read_json::<Vec<u32>>(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(!sibling(&path, "corrupt").exists());
+        let kept: serde_json::Value = read_json(&path).unwrap().unwrap();
+        assert_eq!(kept["version"], "9.0");
+    }
+}
@@ -0,0 +1,527 @@
+//! Process Metrics - Resource usage of agent processes
+//!
+//! Periodically samples every agent the app knows about (one-shot processes
+//! and persistent sessions in `ClaudeProcessManager`, plus sessions only in
+//! `SessionTracker`) together with its process tree: the processes below it
+//! and anything left in its process group after being reparented.
+//!
+//! Per tree: CPU (percent of one core over the last interval), resident
+//! memory, open files and child process count. Read from /proc on Linux and
+//! from `ps` on other Unix systems (no open-file counts there); not sampled
+//! on Windows.
+//!
+//! Emits `process-metrics` after each sample. Limits from the `resources`
+//! section of config.yaml are checked per role; a breach emits
+//! `process-limit-exceeded` once (until usage drops back) and, with
+//! `action: terminate`, stops the agent.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::claude_process::SharedClaudeProcessManager;
+use crate::process_reaper;
+use crate::session_tracker::SharedSessionTracker;
+
+/// Role whose limits apply to roles without their own
+const DEFAULT_ROLE: &str = "default";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// What to do when an agent exceeds a limit
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitAction {
+    #[default]
+    Warn,
+    Terminate,
+}
+
+/// Limits for one role's process tree (unset = unlimited)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ResourceLimits {
+    /// Percent of one core, averaged over the sample interval
+    pub max_cpu_percent: Option<f64>,
+    pub max_memory_mb: Option<u64>,
+    pub max_open_files: Option<u32>,
+    pub max_children: Option<u32>,
+    pub action: LimitAction,
+}
+
+/// Resource monitoring settings (the `resources` section of config.yaml)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResourceSettings {
+    pub enabled: bool,
+    pub sample_interval_secs: u64,
+    /// Limits by agent role; `default` applies to roles not listed
+    pub limits: HashMap<String, ResourceLimits>,
+}
+
+impl Default for ResourceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sample_interval_secs: 10,
+            limits: HashMap::new(),
+        }
+    }
+}
+
+/// Resource usage of one agent and its process tree
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessMetrics {
+    /// Process or session ID in `ClaudeProcessManager` (or the tracker)
+    pub id: String,
+    pub pid: u32,
+    pub role: Option<String>,
+    pub cpu_percent: f64,
+    pub memory_bytes: u64,
+    /// None where the platform doesn't report it
+    pub open_files: Option<u32>,
+    pub child_count: u32,
+    pub sampled_at: String,
+}
+
+/// A limit an agent went over
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitBreach {
+    pub id: String,
+    pub pid: u32,
+    pub role: Option<String>,
+    /// "cpu", "memory", "openFiles" or "children"
+    pub limit: &'static str,
+    pub value: f64,
+    pub max: f64,
+    pub action: LimitAction,
+}
+
+/// One process as sampled
+#[derive(Debug, Clone, PartialEq)]
+struct ProcEntry {
+    pid: u32,
+    ppid: u32,
+    pgid: u32,
+    /// User + system CPU time
+    cpu_ms: u64,
+    rss_bytes: u64,
+}
+
+/// Totals for a process tree
+#[derive(Debug, Clone, PartialEq)]
+struct TreeUsage {
+    pids: Vec<u32>,
+    cpu_ms: u64,
+    rss_bytes: u64,
+}
+
+/// An agent to sample
+struct Target {
+    id: String,
+    pid: u32,
+    /// Start time of tracked processes (see `process_reaper::start_time`)
+    process_start: Option<String>,
+    role: Option<String>,
+    kind: TargetKind,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum TargetKind {
+    Process,
+    Session,
+    Tracked,
+}
+
+/// Latest sample, for `process_metrics_get`
+static LATEST: Mutex<Vec<ProcessMetrics>> = Mutex::new(Vec::new());
+
+// ============================================================================
+// Sampling
+// ============================================================================
+
+/// Parse /proc/<pid>/stat
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_stat(stat: &str, ticks_per_sec: u64, page_size: u64) -> Option<ProcEntry> {
+    // The command name is in parentheses and may itself contain spaces or ')'
+    let (head, rest) = stat.rsplit_once(')')?;
+    let pid = head.split_whitespace().next()?.parse().ok()?;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let field = |i: usize| fields.get(i).and_then(|f| f.parse::<u64>().ok());
+
+    let ticks = field(11)? + field(12)?;
+    Some(ProcEntry {
+        pid,
+        ppid: field(1)? as u32,
+        pgid: field(2)? as u32,
+        cpu_ms: ticks * 1000 / ticks_per_sec.max(1),
+        rss_bytes: field(21)? * page_size,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn snapshot() -> Vec<ProcEntry> {
+    let (ticks_per_sec, page_size) =
+        unsafe { (libc::sysconf(libc::_SC_CLK_TCK) as u64, libc::sysconf(libc::_SC_PAGESIZE) as u64) };
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().bytes().all(|b| b.is_ascii_digit()))
+        .filter_map(|e| std::fs::read_to_string(e.path().join("stat")).ok())
+        .filter_map(|stat| parse_stat(&stat, ticks_per_sec, page_size))
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn open_files(pid: u32) -> Option<u32> {
+    std::fs::read_dir(format!("/proc/{}/fd", pid))
+        .ok()
+        .map(|fds| fds.count() as u32)
+}
+
+/// Parse `ps` cputime: [[dd-]hh:]mm:ss[.ff]
+#[cfg_attr(any(target_os = "linux", not(unix)), allow(dead_code))]
+fn parse_cpu_time(time: &str) -> Option<u64> {
+    let (days, clock) = match time.split_once('-') {
+        Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+        None => (0, time),
+    };
+    let mut secs = 0.0;
+    for part in clock.split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(days * 86_400_000 + (secs * 1000.0) as u64)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn snapshot() -> Vec<ProcEntry> {
+    let Ok(output) = std::process::Command::new("ps")
+        .args(["-axo", "pid=,ppid=,pgid=,rss=,time="])
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [pid, ppid, pgid, rss_kb, time] = fields[..] else {
+                return None;
+            };
+            Some(ProcEntry {
+                pid: pid.parse().ok()?,
+                ppid: ppid.parse().ok()?,
+                pgid: pgid.parse().ok()?,
+                cpu_ms: parse_cpu_time(time)?,
+                rss_bytes: rss_kb.parse::<u64>().ok()? * 1024,
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_files(_pid: u32) -> Option<u32> {
+    None
+}
+
+#[cfg(not(unix))]
+fn snapshot() -> Vec<ProcEntry> {
+    Vec::new()
+}
+
+/// The process, its descendants and whatever is left in its process group
+fn tree_usage(entries: &[ProcEntry], root: u32) -> Option<TreeUsage> {
+    entries.iter().find(|e| e.pid == root)?;
+
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for entry in entries {
+        children.entry(entry.ppid).or_default().push(entry.pid);
+    }
+    let mut members: HashSet<u32> = entries.iter().filter(|e| e.pgid == root).map(|e| e.pid).collect();
+    let mut visited = HashSet::new();
+    let mut stack = vec![root];
+    while let Some(pid) = stack.pop() {
+        if visited.insert(pid) {
+            members.insert(pid);
+            stack.extend(children.get(&pid).into_iter().flatten().copied());
+        }
+    }
+
+    let members: Vec<&ProcEntry> = entries.iter().filter(|e| members.contains(&e.pid)).collect();
+    Some(TreeUsage {
+        pids: members.iter().map(|e| e.pid).collect(),
+        cpu_ms: members.iter().map(|e| e.cpu_ms).sum(),
+        rss_bytes: members.iter().map(|e| e.rss_bytes).sum(),
+    })
+}
+
+// ============================================================================
+// Limits
+// ============================================================================
+
+fn limits_for<'a>(settings: &'a ResourceSettings, role: Option<&str>) -> Option<&'a ResourceLimits> {
+    role.and_then(|r| settings.limits.get(r))
+        .or_else(|| settings.limits.get(DEFAULT_ROLE))
+}
+
+/// Limits the metrics exceed
+fn check_limits(metrics: &ProcessMetrics, limits: &ResourceLimits) -> Vec<LimitBreach> {
+    let checks = [
+        ("cpu", Some(metrics.cpu_percent), limits.max_cpu_percent),
+        (
+            "memory",
+            Some(metrics.memory_bytes as f64 / (1024.0 * 1024.0)),
+            limits.max_memory_mb.map(|mb| mb as f64),
+        ),
+        ("openFiles", metrics.open_files.map(f64::from), limits.max_open_files.map(f64::from)),
+        ("children", Some(f64::from(metrics.child_count)), limits.max_children.map(f64::from)),
+    ];
+    checks
+        .into_iter()
+        .filter_map(|(limit, value, max)| match (value, max) {
+            (Some(value), Some(max)) if value > max => Some(LimitBreach {
+                id: metrics.id.clone(),
+                pid: metrics.pid,
+                role: metrics.role.clone(),
+                limit,
+                value,
+                max,
+                action: limits.action,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+// ============================================================================
+// Monitor
+// ============================================================================
+
+async fn targets(processes: &SharedClaudeProcessManager, tracker: &SharedSessionTracker) -> Vec<Target> {
+    let mut targets = Vec::new();
+    {
+        let manager = processes.lock().await;
+        for (info, kind) in manager
+            .list()
+            .await
+            .into_iter()
+            .map(|p| (p, TargetKind::Process))
+            .chain(manager.list_sessions().await.into_iter().map(|s| (s, TargetKind::Session)))
+        {
+            targets.push(Target {
+                id: info.id,
+                pid: info.pid,
+                process_start: None,
+                role: Some(info.role),
+                kind,
+            });
+        }
+    }
+
+    let tracked = tracker.lock().map(|t| t.list_sessions()).unwrap_or_default();
+    for session in tracked {
+        if targets.iter().all(|t| t.pid != session.pid) {
+            targets.push(Target {
+                id: session.session_id,
+                pid: session.pid,
+                process_start: session.process_start,
+                role: session.role,
+                kind: TargetKind::Tracked,
+            });
+        }
+    }
+    targets
+}
+
+async fn terminate(target: &Target, processes: &SharedClaudeProcessManager, tracker: &SharedSessionTracker) {
+    let manager = processes.lock().await;
+    let result = match target.kind {
+        TargetKind::Session => manager.terminate_session(&target.id).await,
+        TargetKind::Process => manager.terminate(&target.id).await,
+        TargetKind::Tracked => {
+            process_reaper::terminate_started_in_background(target.pid, target.process_start.clone());
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("[ProcessMetrics] Failed to terminate {}: {}", target.id, e);
+    }
+    if let Ok(mut tracker) = tracker.lock() {
+        tracker.remove_session(&target.id);
+    }
+}
+
+/// Sample agent processes until the app exits
+pub fn start(app: AppHandle, processes: SharedClaudeProcessManager, tracker: SharedSessionTracker) {
+    tauri::async_runtime::spawn(async move {
+        // CPU time per tree at the previous sample
+        let mut previous: HashMap<u32, (u64, Instant)> = HashMap::new();
+        // (id, limit) already reported, so a breach is reported once
+        let mut reported: HashSet<(String, &'static str)> = HashSet::new();
+        let mut emitted_empty = false;
+
+        loop {
+            let settings = crate::config::get().resources;
+            tokio::time::sleep(Duration::from_secs(settings.sample_interval_secs.max(1))).await;
+            if !settings.enabled {
+                continue;
+            }
+
+            let targets = targets(&processes, &tracker).await;
+            let entries = if targets.is_empty() {
+                Vec::new()
+            } else {
+                tauri::async_runtime::spawn_blocking(snapshot).await.unwrap_or_default()
+            };
+
+            let now = Instant::now();
+            let sampled_at = Utc::now().to_rfc3339();
+            let mut metrics = Vec::new();
+            let mut next = HashMap::new();
+            for target in &targets {
+                let Some(usage) = tree_usage(&entries, target.pid) else {
+                    continue;
+                };
+                let cpu_percent = match previous.get(&target.pid) {
+                    Some((cpu_ms, at)) => {
+                        let elapsed_ms = now.duration_since(*at).as_millis().max(1) as f64;
+                        usage.cpu_ms.saturating_sub(*cpu_ms) as f64 / elapsed_ms * 100.0
+                    }
+                    None => 0.0,
+                };
+                next.insert(target.pid, (usage.cpu_ms, now));
+                metrics.push(ProcessMetrics {
+                    id: target.id.clone(),
+                    pid: target.pid,
+                    role: target.role.clone(),
+                    cpu_percent,
+                    memory_bytes: usage.rss_bytes,
+                    open_files: usage.pids.iter().map(|&pid| open_files(pid)).sum(),
+                    child_count: usage.pids.len().saturating_sub(1) as u32,
+                    sampled_at: sampled_at.clone(),
+                });
+            }
+            previous = next;
+
+            let mut breached = HashSet::new();
+            for m in &metrics {
+                let Some(target) = targets.iter().find(|t| t.id == m.id) else {
+                    continue;
+                };
+                let Some(limits) = limits_for(&settings, m.role.as_deref()) else {
+                    continue;
+                };
+                for breach in check_limits(m, limits) {
+                    let key = (breach.id.clone(), breach.limit);
+                    breached.insert(key.clone());
+                    if !reported.insert(key) {
+                        continue;
+                    }
+                    eprintln!(
+                        "[ProcessMetrics] {} (PID {}) over {} limit: {:.1} > {:.1}",
+                        breach.id, breach.pid, breach.limit, breach.value, breach.max
+                    );
+                    let _ = app.emit("process-limit-exceeded", &breach);
+                    if breach.action == LimitAction::Terminate {
+                        terminate(target, &processes, &tracker).await;
+                        break;
+                    }
+                }
+            }
+            reported.retain(|key| breached.contains(key));
+
+            if !metrics.is_empty() || !emitted_empty {
+                let _ = app.emit("process-metrics", &metrics);
+                emitted_empty = metrics.is_empty();
+            }
+            *LATEST.lock().unwrap_or_else(|e| e.into_inner()) = metrics;
+        }
+    });
+}
+
+/// Latest resource usage of all agents
+#[tauri::command]
+pub fn process_metrics_get() -> Vec<ProcessMetrics> {
+    LATEST.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pid: u32, ppid: u32, pgid: u32, cpu_ms: u64) -> ProcEntry {
+        ProcEntry { pid, ppid, pgid, cpu_ms, rss_bytes: 1024 }
+    }
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = "4242 (node (dev) server) S 4200 4200 4200 0 -1 4194560 1 0 0 0 150 50 0 0 20 0 7 0 100 1000000 300 18446744073709551615";
+        let entry = parse_stat(stat, 100, 4096).unwrap();
+        assert_eq!(entry, ProcEntry { pid: 4242, ppid: 4200, pgid: 4200, cpu_ms: 2000, rss_bytes: 300 * 4096 });
+        assert_eq!(parse_stat("garbage", 100, 4096), None);
+    }
+
+    #[test]
+    fn test_parse_cpu_time() {
+        assert_eq!(parse_cpu_time("0:01.50"), Some(1500));
+        assert_eq!(parse_cpu_time("01:02:03"), Some(3_723_000));
+        assert_eq!(parse_cpu_time("2-00:00:01"), Some(172_801_000));
+    }
+
+    #[test]
+    fn test_tree_includes_descendants_and_group() {
+        let entries = vec![
+            entry(1, 0, 1, 0),
+            entry(100, 1, 100, 1000), // agent
+            entry(101, 100, 100, 200), // test runner
+            entry(102, 101, 100, 300), // its worker
+            entry(103, 1, 100, 400),   // dev server reparented to init
+            entry(200, 1, 200, 5000),  // unrelated
+        ];
+        let usage = tree_usage(&entries, 100).unwrap();
+        let mut pids = usage.pids.clone();
+        pids.sort();
+        assert_eq!(pids, vec![100, 101, 102, 103]);
+        assert_eq!(usage.cpu_ms, 1900);
+        assert_eq!(usage.rss_bytes, 4 * 1024);
+        assert!(tree_usage(&entries, 999).is_none());
+    }
+
+    #[test]
+    fn test_limits_by_role() {
+        let mut settings = ResourceSettings::default();
+        settings.limits.insert(DEFAULT_ROLE.to_string(), ResourceLimits { max_children: Some(50), ..Default::default() });
+        settings.limits.insert(
+            "dev".to_string(),
+            ResourceLimits { max_memory_mb: Some(512), action: LimitAction::Terminate, ..Default::default() },
+        );
+
+        let metrics = ProcessMetrics {
+            id: "s1".to_string(),
+            pid: 100,
+            role: Some("dev".to_string()),
+            cpu_percent: 250.0,
+            memory_bytes: 600 * 1024 * 1024,
+            open_files: None,
+            child_count: 80,
+            sampled_at: String::new(),
+        };
+        let breaches = check_limits(&metrics, limits_for(&settings, Some("dev")).unwrap());
+        assert_eq!(breaches.len(), 1);
+        assert_eq!(breaches[0].limit, "memory");
+        assert_eq!(breaches[0].action, LimitAction::Terminate);
+
+        let breaches = check_limits(&metrics, limits_for(&settings, Some("qa")).unwrap());
+        assert_eq!(breaches.len(), 1);
+        assert_eq!(breaches[0].limit, "children");
+        assert_eq!(breaches[0].action, LimitAction::Warn);
+    }
+}
@@ -0,0 +1,252 @@
+//! Process Reaper - Process-group termination for agents and services
+//!
+//! Claude runs tools as child processes (test runners, dev servers), so
+//! signalling only its PID leaves them behind. Agents and the API server are
+//! started as leaders of their own process group and terminated as a group:
+//! SIGTERM first, then SIGKILL for whatever is still alive after a grace
+//! period. On Windows the whole process tree is killed with `taskkill /T`.
+//!
+//! A PID that is not a group leader (e.g. the SDK sidecar, spawned by the
+//! shell plugin) is signalled on its own.
+//!
+//! PIDs recorded by an earlier run may belong to unrelated processes by now;
+//! `start_time` identifies a process beyond its PID so callers can check it
+//! is still the one they started before signalling it.
+
+use std::process::Command;
+use std::time::Duration;
+#[cfg(unix)]
+use std::time::Instant;
+
+/// How long a group gets to exit after SIGTERM before it is killed
+pub const GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// Interval between liveness checks while waiting for the grace period
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Start the command as the leader of a new process group, so everything it
+/// spawns can be terminated with it
+pub fn own_group(command: &mut Command) -> &mut Command {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0)
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        command.creation_flags(CREATE_NEW_PROCESS_GROUP)
+    }
+}
+
+/// Whether the process, or any process left in its group, is still running
+///
+/// Only checks; a child that exited stays a zombie (and counts as running)
+/// until whoever owns it waits for it.
+pub fn is_running(pid: u32) -> bool {
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(pid as i32), 0) == 0 || libc::kill(pid as i32, 0) == 0
+    }
+
+    #[cfg(windows)]
+    {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+            .output()
+            .map(|output| {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                stdout.contains(&pid.to_string())
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// When the process started, in a platform-specific format (None if it isn't
+/// running or the platform can't tell)
+pub fn start_time(pid: u32) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        // Field 22 of /proc/<pid>/stat, in clock ticks since boot; the
+        // command name (field 2) may contain spaces, so count from its ')'
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        let fields = &stat[stat.rfind(')')? + 1..];
+        fields.split_whitespace().nth(19).map(str::to_string)
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let output = Command::new("ps")
+            .args(["-o", "lstart=", "-p", &pid.to_string()])
+            .output()
+            .ok()?;
+        let started = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !started.is_empty()).then_some(started)
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Whether `pid` is still the process that had `started` as its start time
+///
+/// Without a recorded start time the process can't be told apart from a
+/// later one with the same PID, so it only counts where start times aren't
+/// available at all.
+pub fn is_same_process(pid: u32, started: Option<&str>) -> bool {
+    match (started, start_time(pid)) {
+        (Some(recorded), Some(current)) => recorded == current,
+        (None, None) => is_running(pid),
+        _ => false,
+    }
+}
+
+/// Collect the exit status of processes this app started, so terminated
+/// children don't linger as zombies (no-op for other PIDs)
+#[cfg(unix)]
+fn reap(pids: &[u32]) {
+    for &pid in pids {
+        unsafe {
+            libc::waitpid(pid as i32, std::ptr::null_mut(), libc::WNOHANG);
+        }
+    }
+}
+
+/// Send a signal to the process group led by `pid`, or to `pid` alone if it
+/// doesn't lead a group
+#[cfg(unix)]
+fn signal(pid: u32, sig: libc::c_int) -> bool {
+    unsafe { libc::kill(-(pid as i32), sig) == 0 || libc::kill(pid as i32, sig) == 0 }
+}
+
+/// Wait until the processes, which were just signalled, have exited or the
+/// timeout passes; `pids` is left with the ones still running
+#[cfg(unix)]
+fn wait_exit(pids: &mut Vec<u32>, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    reap(pids);
+    pids.retain(|&pid| is_running(pid));
+    while !pids.is_empty() && Instant::now() < deadline {
+        std::thread::sleep(POLL_INTERVAL);
+        reap(pids);
+        pids.retain(|&pid| is_running(pid));
+    }
+}
+
+/// Terminate several processes with their groups, sharing one grace period
+/// Returns the PIDs that had to be killed
+pub fn terminate_all(pids: &[u32], grace: Duration) -> Vec<u32> {
+    #[cfg(unix)]
+    {
+        let mut pending: Vec<u32> = pids.iter().copied().filter(|&pid| signal(pid, libc::SIGTERM)).collect();
+        wait_exit(&mut pending, grace);
+
+        for &pid in &pending {
+            eprintln!("[ProcessReaper] PID {} ignored SIGTERM for {:?}, killing its group", pid, grace);
+            signal(pid, libc::SIGKILL);
+        }
+        // Give the kernel a moment to deliver SIGKILL before callers check
+        wait_exit(&mut pending.clone(), GRACE_PERIOD);
+        pending
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = grace;
+        for &pid in pids {
+            let _ = Command::new("taskkill")
+                .args(["/PID", &pid.to_string(), "/T", "/F"])
+                .output();
+        }
+        pids.to_vec()
+    }
+}
+
+/// Terminate a process and its group, blocking for at most the grace period
+/// Returns false if it had to be killed
+pub fn terminate(pid: u32, grace: Duration) -> bool {
+    terminate_all(&[pid], grace).is_empty()
+}
+
+/// Terminate a process and its group without waiting for it
+pub fn terminate_in_background(pid: u32) {
+    std::thread::spawn(move || terminate(pid, GRACE_PERIOD));
+}
+
+/// Terminate a process and its group without waiting for it, unless its PID
+/// now belongs to another process (see `is_same_process`)
+pub fn terminate_started_in_background(pid: u32, started: Option<String>) {
+    std::thread::spawn(move || {
+        if is_same_process(pid, started.as_deref()) {
+            terminate(pid, GRACE_PERIOD);
+        } else {
+            eprintln!("[ProcessReaper] PID {} no longer belongs to the process started there, not signalling it", pid);
+        }
+    });
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn spawn_group(script: &str) -> u32 {
+        own_group(Command::new("sh").args(["-c", script]))
+            .spawn()
+            .unwrap()
+            .id()
+    }
+
+    #[test]
+    fn test_terminates_whole_group() {
+        // The backgrounded sleep would survive a signal to the shell alone
+        let pid = spawn_group("sleep 30 & sleep 30");
+        std::thread::sleep(Duration::from_millis(200));
+
+        assert!(terminate(pid, Duration::from_secs(2)));
+        assert!(!is_running(pid));
+    }
+
+    #[test]
+    fn test_escalates_to_sigkill() {
+        let pid = spawn_group("trap '' TERM; sleep 30");
+        std::thread::sleep(Duration::from_millis(200));
+
+        assert_eq!(terminate_all(&[pid], Duration::from_millis(300)), vec![pid]);
+        assert!(!is_running(pid));
+    }
+
+    #[test]
+    fn test_start_time_identifies_process() {
+        let pid = spawn_group("sleep 30");
+        let started = start_time(pid);
+        assert!(started.is_some());
+        assert!(is_same_process(pid, started.as_deref()));
+        assert!(!is_same_process(pid, Some("0")));
+        assert!(!is_same_process(pid, None));
+
+        assert!(terminate(pid, Duration::from_secs(2)));
+        assert!(!is_same_process(pid, started.as_deref()));
+    }
+
+    #[test]
+    fn test_background_termination_checks_start_time() {
+        let pid = spawn_group("sleep 30");
+        let started = start_time(pid);
+
+        terminate_started_in_background(pid, Some("0".to_string()));
+        std::thread::sleep(Duration::from_millis(300));
+        assert!(is_running(pid));
+
+        terminate_started_in_background(pid, started);
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while is_running(pid) && Instant::now() < deadline {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        assert!(!is_running(pid));
+    }
+}
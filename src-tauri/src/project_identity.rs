@@ -0,0 +1,659 @@
+//! Project Identity
+//!
+//! One stable identity for a project, shared by session, team and group chat
+//! storage and by project window labels:
+//!
+//!   id = hex(SHA-256(canonical path as UTF-8))[..16]
+//!
+//! The canonical path is the absolute path with `.` and `..` resolved, no
+//! trailing slash, and symlinks resolved (`fs::canonicalize`) as far as the
+//! path exists: a directory that doesn't exist (yet, or any more) keeps the
+//! resolved form of its deepest existing ancestor, so it gets the same id
+//! before and after it is created. Relative paths have no canonical form.
+//! Unlike the previous `DefaultHasher` ids, this does not change between Rust
+//! releases.
+//!
+//! Every id handed out is recorded in ~/.sidstack/projects.json (id -> path),
+//! so storage directories can be traced back to their project. Directories
+//! created under the old scheme are moved once by `migrate_legacy_storage`.
+//!
+//! Claude CLI's own ~/.claude/projects naming is an external format and is
+//! only mirrored here (`claude_project_dir_name`).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, RwLock};
+
+use crate::groupchat_storage;
+use crate::session_storage::{self, SessionMeta};
+use crate::team_storage::{TeamIndex, TeamStorage};
+
+/// Identifier of the hashing scheme, stored in the mapping file
+const SCHEME: &str = "sha256-canonical-path";
+const MAPPING_VERSION: u32 = 1;
+/// Hex characters kept from the digest
+const ID_LEN: usize = 16;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A recorded project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectRecord {
+    /// Canonical path the id was derived from
+    pub path: String,
+    /// Other spellings of the path that resolved to this project
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Directory names this project had under the old hashing scheme
+    #[serde(default)]
+    pub legacy_ids: Vec<String>,
+    /// Paths the project lived at before being relocated
+    #[serde(default)]
+    pub previous_paths: Vec<String>,
+    pub registered_at: DateTime<Utc>,
+}
+
+/// Contents of ~/.sidstack/projects.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectMapping {
+    pub version: u32,
+    pub scheme: String,
+    /// Set once legacy storage directories have been migrated
+    #[serde(default)]
+    pub legacy_migrated: bool,
+    pub projects: BTreeMap<String, ProjectRecord>,
+}
+
+impl Default for ProjectMapping {
+    fn default() -> Self {
+        Self {
+            version: MAPPING_VERSION,
+            scheme: SCHEME.to_string(),
+            legacy_migrated: false,
+            projects: BTreeMap::new(),
+        }
+    }
+}
+
+/// Resolved identity of a project path
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectIdentity {
+    pub id: String,
+    pub canonical_path: String,
+}
+
+/// A storage directory moved by the migration
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovedDir {
+    pub kind: String,
+    pub project_path: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Result of `migrate_legacy_storage`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub moved: Vec<MovedDir>,
+    /// Directories whose project could not be determined (left in place)
+    pub skipped: Vec<String>,
+}
+
+// ============================================================================
+// Identity
+// ============================================================================
+
+/// Mapping file location
+/// ~/.sidstack/projects.json unless overridden
+pub fn mapping_path() -> PathBuf {
+    if let Some(path) = MAPPING_PATH.read().ok().and_then(|path| path.clone()) {
+        return path;
+    }
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sidstack")
+        .join("projects.json")
+}
+
+/// Canonical form of an absolute project path (see the module docs)
+pub fn canonical_path(path: &str) -> Result<String, String> {
+    if !Path::new(path).is_absolute() {
+        return Err(format!("Project path must be absolute: {}", path));
+    }
+
+    let normalized = normalize(Path::new(path));
+    // Resolve the deepest existing ancestor and keep the rest as written
+    let mut missing = Vec::new();
+    let mut existing = normalized.as_path();
+    let resolved = loop {
+        if let Ok(resolved) = fs::canonicalize(existing) {
+            break resolved;
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => break normalized.clone(),
+        }
+    };
+    let canonical = missing
+        .iter()
+        .rev()
+        .fold(resolved, |path, name| path.join(name));
+    Ok(canonical.to_string_lossy().to_string())
+}
+
+/// Resolve `.` and `..` without touching the disk
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+/// Stable id of a canonical path
+pub fn id_for_canonical(canonical: &str) -> String {
+    let digest = Sha256::digest(canonical.as_bytes());
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    hex[..ID_LEN].to_string()
+}
+
+/// Resolve a project path to its id, recording it in the mapping file
+pub fn project_id(project_path: &str) -> String {
+    resolve(project_path).id
+}
+
+/// Resolve a project path to its id and canonical path
+pub fn resolve(project_path: &str) -> ProjectIdentity {
+    if let Some(identity) = with_registry(|registry| registry.resolved.get(project_path).cloned()) {
+        return identity;
+    }
+
+    // Canonicalizing touches the disk, so it runs without the registry lock
+    let canonical = match canonical_path(project_path) {
+        Ok(canonical) => canonical,
+        Err(e) => {
+            // What a relative path points at depends on the working
+            // directory, so it is neither cached nor recorded
+            eprintln!("[ProjectIdentity] {}", e);
+            let normalized = normalize(Path::new(project_path)).to_string_lossy().to_string();
+            return ProjectIdentity {
+                id: id_for_canonical(&normalized),
+                canonical_path: normalized,
+            };
+        }
+    };
+    let identity = ProjectIdentity {
+        id: id_for_canonical(&canonical),
+        canonical_path: canonical,
+    };
+    let changed = with_registry(|registry| {
+        registry
+            .resolved
+            .insert(project_path.to_string(), identity.clone());
+        registry.record(project_path, &identity)
+    });
+    if changed {
+        save_mapping();
+    }
+    identity
+}
+
+/// Directory name Claude CLI uses for a project under ~/.claude/projects:
+/// every character other than ASCII letters and digits becomes '-'
+pub fn claude_project_dir_name(working_dir: &str) -> String {
+    working_dir
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Directory name a project had under the old `DefaultHasher` scheme
+///
+/// Only stable within one toolchain; used to find legacy directories that
+/// carry no project path of their own.
+fn legacy_id(project_path: &str) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    project_path.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
+// ============================================================================
+// Mapping File
+// ============================================================================
+
+static REGISTRY: Mutex<Option<Registry>> = Mutex::new(None);
+
+/// Held while writing the mapping file, so an older snapshot never
+/// replaces a newer one
+static MAPPING_WRITER: Mutex<()> = Mutex::new(());
+
+/// Replaces the default mapping file location when set
+static MAPPING_PATH: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// In-memory mapping plus a cache of already resolved paths
+struct Registry {
+    mapping: ProjectMapping,
+    resolved: HashMap<String, ProjectIdentity>,
+}
+
+impl Registry {
+    fn load() -> Self {
+        let mapping = match crate::persist::read_json(&mapping_path()) {
+            Ok(mapping) => mapping.unwrap_or_default(),
+            Err(e) => {
+                eprintln!("[ProjectIdentity] Ignoring unreadable mapping file: {}", e);
+                ProjectMapping::default()
+            }
+        };
+        Self {
+            mapping,
+            resolved: HashMap::new(),
+        }
+    }
+
+    /// Record a resolved path
+    /// Returns whether the mapping changed and needs saving
+    fn record(&mut self, project_path: &str, identity: &ProjectIdentity) -> bool {
+        let mut changed = false;
+        let record = self
+            .mapping
+            .projects
+            .entry(identity.id.clone())
+            .or_insert_with(|| {
+                changed = true;
+                ProjectRecord {
+                    path: identity.canonical_path.clone(),
+                    aliases: Vec::new(),
+                    legacy_ids: Vec::new(),
+                    previous_paths: Vec::new(),
+                    registered_at: Utc::now(),
+                }
+            });
+        if project_path != identity.canonical_path
+            && !record.aliases.iter().any(|alias| alias == project_path)
+        {
+            record.aliases.push(project_path.to_string());
+            changed = true;
+        }
+        changed
+    }
+}
+
+/// Record projects in `path` instead of the home directory
+#[cfg(test)]
+pub(crate) fn set_mapping_path(path: PathBuf) {
+    if let Ok(mut current) = MAPPING_PATH.write() {
+        *current = Some(path);
+    }
+    // The next use loads the new file
+    *lock_registry() = None;
+}
+
+/// Use a mapping file private to this test process (once per process)
+#[cfg(test)]
+pub(crate) fn use_test_mapping() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let path = std::env::temp_dir().join(format!("sidstack-test-projects-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        set_mapping_path(path);
+    });
+}
+
+fn write_mapping(mapping: &ProjectMapping) -> Result<(), String> {
+    crate::persist::write_json(&mapping_path(), mapping)
+        .map_err(|e| format!("Failed to write project mapping: {}", e))
+}
+
+/// Write the current mapping to disk, outside the registry lock
+fn save_mapping() {
+    let _writer = MAPPING_WRITER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mapping = with_registry(|registry| registry.mapping.clone());
+    if let Err(e) = write_mapping(&mapping) {
+        eprintln!("[ProjectIdentity] {}", e);
+    }
+}
+
+fn lock_registry() -> MutexGuard<'static, Option<Registry>> {
+    REGISTRY.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn with_registry<T>(f: impl FnOnce(&mut Registry) -> T) -> T {
+    // The first use reads the mapping file without holding the lock
+    let loaded = lock_registry().is_none().then(Registry::load);
+    let mut guard = lock_registry();
+    let registry = match guard.take() {
+        Some(registry) => registry,
+        None => loaded.unwrap_or_else(Registry::load),
+    };
+    f(guard.insert(registry))
+}
+
+/// Snapshot of the recorded projects
+pub fn mapping() -> ProjectMapping {
+    with_registry(|registry| registry.mapping.clone())
+}
+
+// ============================================================================
+// Legacy Migration
+// ============================================================================
+
+/// Move session, team and group chat directories named by the old
+/// `DefaultHasher` scheme to their stable ids. Runs once; later calls return
+/// an empty report.
+pub fn migrate_legacy_storage() -> Result<MigrationReport, String> {
+    if with_registry(|registry| registry.mapping.legacy_migrated) {
+        return Ok(MigrationReport::default());
+    }
+
+    let mut report = MigrationReport::default();
+    // Every project path seen, to recognise group chat directories
+    let mut known_paths: Vec<String> = Vec::new();
+
+    // Sessions: the project path is in each session's metadata
+    for (name, dir) in subdirs(&session_storage::sessions_dir()) {
+        let Some(project_path) = session_project_path(&dir) else {
+            report.skipped.push(dir.to_string_lossy().to_string());
+            continue;
+        };
+        known_paths.push(project_path.clone());
+        let id = project_id(&project_path);
+        if name == id {
+            continue;
+        }
+
+        let target = session_storage::sessions_dir().join(&id);
+        merge_dir(&dir, &target)?;
+        rewrite_session_hashes(&target, &id)?;
+        if let Err(e) = crate::session_search::rename_project(&name, &id, None) {
+            eprintln!("[ProjectIdentity] Failed to update search index: {}", e);
+        }
+        report.moved.push(moved("sessions", &project_path, &dir, &target));
+        record_legacy_id(&id, &name);
+    }
+
+    // Teams: the project path is in teams.json
+    if let Ok(storage) = TeamStorage::new() {
+        for (name, dir) in subdirs(storage.base_dir()) {
+            let Some(mut index) = read_team_index(&dir.join("teams.json")) else {
+                report.skipped.push(dir.to_string_lossy().to_string());
+                continue;
+            };
+            known_paths.push(index.project_path.clone());
+            let id = project_id(&index.project_path);
+            if name == id {
+                continue;
+            }
+
+            let target = storage.base_dir().join(&id);
+            // Both directories may hold teams; merge their indexes
+            if let Some(existing) = read_team_index(&target.join("teams.json")) {
+                for entry in existing.teams {
+                    if !index.teams.iter().any(|t| t.id == entry.id) {
+                        index.teams.push(entry);
+                    }
+                }
+                fs::remove_file(target.join("teams.json"))
+                    .map_err(|e| format!("Failed to merge team index: {}", e))?;
+                let _ = fs::remove_file(crate::persist::backup_path(&target.join("teams.json")));
+            }
+            index.project_hash = id.clone();
+            crate::persist::write_json(&dir.join("teams.json"), &index)
+                .map_err(|e| format!("Failed to write team index: {}", e))?;
+
+            merge_dir(&dir, &target)?;
+            report.moved.push(moved("teams", &index.project_path, &dir, &target));
+            record_legacy_id(&id, &name);
+        }
+    }
+
+    // Group chat: directories carry no path, so match old ids of known projects
+    known_paths.extend(mapping().projects.into_values().map(|record| record.path));
+    let legacy: HashMap<String, String> = known_paths
+        .iter()
+        .map(|path| (legacy_id(path), path.clone()))
+        .collect();
+    let groupchat_dir = groupchat_storage::groupchat_dir();
+    for (name, dir) in subdirs(&groupchat_dir) {
+        if name == groupchat_storage::GLOBAL_BUCKET {
+            continue;
+        }
+        let Some(project_path) = legacy.get(&name) else {
+            if !mapping().projects.contains_key(&name) {
+                report.skipped.push(dir.to_string_lossy().to_string());
+            }
+            continue;
+        };
+        let id = project_id(project_path);
+        let target = groupchat_dir.join(&id);
+        merge_dir(&dir, &target)?;
+        report.moved.push(moved("groupchat", project_path, &dir, &target));
+        record_legacy_id(&id, &name);
+    }
+
+    with_registry(|registry| registry.mapping.legacy_migrated = true);
+    save_mapping();
+
+    Ok(report)
+}
+
+/// Immediate subdirectories of a directory, with their names
+pub(crate) fn subdirs(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path()))
+        .collect()
+}
+
+/// Project path recorded in any session metadata file in a directory
+fn session_project_path(dir: &Path) -> Option<String> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".meta.json"))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str::<SessionMeta>(&content).ok())
+        .map(|meta| meta.project_path)
+        .next()
+}
+
+fn read_team_index(path: &Path) -> Option<TeamIndex> {
+    crate::persist::read_json(path).ok().flatten()
+}
+
+/// Point every session metadata file in a directory at the new id
+fn rewrite_session_hashes(dir: &Path, id: &str) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read directory: {}", e))?;
+    for entry in entries.filter_map(Result::ok) {
+        if !entry.file_name().to_string_lossy().ends_with(".meta.json") {
+            continue;
+        }
+        let Some(mut meta) = fs::read_to_string(entry.path())
+            .ok()
+            .and_then(|content| serde_json::from_str::<SessionMeta>(&content).ok())
+        else {
+            continue;
+        };
+        if meta.project_hash != id {
+            meta.project_hash = id.to_string();
+            crate::persist::write_json(&entry.path(), &meta)
+                .map_err(|e| format!("Failed to write metadata: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Move a directory's contents into another, keeping files already there
+pub(crate) fn merge_dir(from: &Path, to: &Path) -> Result<(), String> {
+    if !to.exists() {
+        return fs::rename(from, to).map_err(|e| format!("Failed to move {:?}: {}", from, e));
+    }
+
+    let entries = fs::read_dir(from).map_err(|e| format!("Failed to read {:?}: {}", from, e))?;
+    for entry in entries.filter_map(Result::ok) {
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        if !target.exists() {
+            fs::rename(&source, &target)
+                .map_err(|e| format!("Failed to move {:?}: {}", source, e))?;
+        } else if source.is_dir() && target.is_dir() {
+            merge_dir(&source, &target)?;
+        } else {
+            eprintln!(
+                "[ProjectIdentity] Keeping {:?}; {:?} already exists",
+                source, target
+            );
+        }
+    }
+
+    // Only succeeds once everything has been moved
+    let _ = fs::remove_dir(from);
+    Ok(())
+}
+
+fn record_legacy_id(id: &str, legacy: &str) {
+    let changed = with_registry(|registry| match registry.mapping.projects.get_mut(id) {
+        Some(record) if !record.legacy_ids.iter().any(|l| l == legacy) => {
+            record.legacy_ids.push(legacy.to_string());
+            true
+        }
+        _ => false,
+    });
+    if changed {
+        save_mapping();
+    }
+}
+
+/// Fold a relocated project's record into its new id
+pub fn record_relocation(old_ids: &[String], new_id: &str, old_path: &str) {
+    with_registry(|registry| {
+        let mut previous = vec![old_path.to_string()];
+        let mut legacy = Vec::new();
+        for old_id in old_ids.iter().filter(|id| id.as_str() != new_id) {
+            if let Some(old) = registry.mapping.projects.remove(old_id) {
+                previous.push(old.path);
+                previous.extend(old.aliases);
+                previous.extend(old.previous_paths);
+                legacy.extend(old.legacy_ids);
+            }
+        }
+        // Cached resolutions of the old path would point at the old id
+        registry.resolved.retain(|_, identity| !old_ids.contains(&identity.id));
+
+        if let Some(record) = registry.mapping.projects.get_mut(new_id) {
+            for path in previous {
+                if path != record.path && !record.previous_paths.contains(&path) {
+                    record.previous_paths.push(path);
+                }
+            }
+            for id in legacy {
+                if !record.legacy_ids.contains(&id) {
+                    record.legacy_ids.push(id);
+                }
+            }
+        }
+    });
+    save_mapping();
+}
+
+fn moved(kind: &str, project_path: &str, from: &Path, to: &Path) -> MovedDir {
+    MovedDir {
+        kind: kind.to_string(),
+        project_path: project_path.to_string(),
+        from: from.to_string_lossy().to_string(),
+        to: to.to_string_lossy().to_string(),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Tauri command: Resolve a project path to its stable id
+#[tauri::command]
+pub fn project_identity_resolve(project_path: String) -> ProjectIdentity {
+    resolve(&project_path)
+}
+
+/// Tauri command: List recorded projects (id -> path)
+#[tauri::command]
+pub fn project_identity_list() -> ProjectMapping {
+    mapping()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_id_is_stable() {
+        use_test_mapping();
+        // Pinned so a change to the scheme cannot slip through unnoticed
+        assert_eq!(id_for_canonical("/path/to/project"), "5fc0f6e748c5bc1b");
+        assert_eq!(project_id("/no/such/dir/../project/"), project_id("/no/such/project"));
+        assert_ne!(project_id("/no/such/project"), project_id("/no/such/other"));
+    }
+
+    #[test]
+    fn test_claude_project_dir_name() {
+        assert_eq!(
+            claude_project_dir_name("/Users/foo/my_app.v2"),
+            "-Users-foo-my-app-v2"
+        );
+    }
+
+    #[test]
+    fn test_resolve_records_mapping() {
+        use_test_mapping();
+        let identity = resolve("/no/such/dir/../mapped-project");
+        assert_eq!(identity.canonical_path, "/no/such/mapped-project");
+
+        let saved: ProjectMapping = crate::persist::read_json(&mapping_path()).unwrap().unwrap();
+        let record = &saved.projects[&identity.id];
+        assert_eq!(record.path, "/no/such/mapped-project");
+        assert_eq!(record.aliases, vec!["/no/such/dir/../mapped-project"]);
+    }
+
+    #[test]
+    fn test_canonical_path_of_missing_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = canonical_path(&dir.path().to_string_lossy()).unwrap();
+        let missing = dir.path().join("sub").join("..").join("later").join("project");
+
+        let before = canonical_path(&missing.to_string_lossy()).unwrap();
+        assert_eq!(before, format!("{}/later/project", root));
+        fs::create_dir_all(dir.path().join("later").join("project")).unwrap();
+        assert_eq!(canonical_path(&missing.to_string_lossy()).unwrap(), before);
+
+        assert!(canonical_path("relative/project").is_err());
+        assert!(canonical_path("./project").is_err());
+    }
+}
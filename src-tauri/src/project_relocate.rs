@@ -0,0 +1,789 @@
+//! Project Relocation
+//!
+//! Rewrites every stored reference to a project after its directory moved:
+//! - Session records (`SessionMeta.project_path`) in the database, and the
+//!   session log and group chat directories keyed by project id
+//! - Team records (`TeamConfig.project_path`) and saved terminal cwds
+//! - The role-session mapping (`RoleSessionMapping.project_path`)
+//! - Workspace tabs (`SessionTab.cwd`) in the project's .sidstack/sessions.json
+//! - Git worktree links and shared-folder symlinks under .worktrees
+//!
+//! Relocation runs in two phases. New file contents are first staged next to
+//! their targets; only when everything staged cleanly are they swapped in,
+//! with the originals kept until the end so a failed swap is rolled back.
+//! The id-keyed directories are moved or merged next, and the database
+//! records are rewritten last in one transaction, so a failure anywhere before
+//! leaves every record pointing at the files it was planned from.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::db;
+use crate::project_identity;
+use crate::session::{self, RoleSessionMapping};
+use crate::session_storage::{self, SessionMeta};
+use crate::team_storage::{TeamConfig, TeamState};
+use crate::workspace_schema;
+use crate::workspace_storage;
+
+const STAGED_SUFFIX: &str = "relocate-tmp";
+const BACKUP_SUFFIX: &str = "relocate-bak";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// One rewritten record
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocationChange {
+    /// session, team, role-sessions, workspace, worktree, symlink or move
+    pub kind: String,
+    /// File or directory changed (`{database}:{table}/{id}` for records)
+    pub path: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Result of a relocation
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocationReport {
+    pub old_path: String,
+    pub new_path: String,
+    pub old_ids: Vec<String>,
+    pub new_id: String,
+    /// True if nothing was written
+    pub dry_run: bool,
+    pub changes: Vec<RelocationChange>,
+}
+
+/// A file whose contents will be replaced
+struct FileWrite {
+    path: PathBuf,
+    contents: String,
+}
+
+/// A symlink that will be re-pointed
+struct LinkWrite {
+    link: PathBuf,
+    target: PathBuf,
+}
+
+/// An id-keyed storage directory (or file) that will be renamed
+struct DirMove {
+    from: PathBuf,
+    to: PathBuf,
+}
+
+/// A database record that will be rewritten
+enum RecordWrite {
+    /// Session stored under its old project id
+    Session { old_hash: String, meta: SessionMeta },
+    Team { config: Box<TeamConfig>, state: Option<TeamState> },
+}
+
+/// Everything a relocation will change
+struct Plan {
+    /// Spellings of the old path to match (as given and canonical)
+    old: Vec<String>,
+    new: String,
+    new_id: String,
+    old_ids: BTreeSet<String>,
+    files: Vec<FileWrite>,
+    links: Vec<LinkWrite>,
+    dirs: Vec<DirMove>,
+    records: Vec<RecordWrite>,
+    changes: Vec<RelocationChange>,
+}
+
+// ============================================================================
+// Planning
+// ============================================================================
+
+impl Plan {
+    fn new(old_path: &str, new_path: &str) -> Result<Self, String> {
+        let mut old = vec![trim_slash(old_path).to_string()];
+        let canonical = project_identity::canonical_path(old_path)?;
+        if !old.contains(&canonical) {
+            old.push(canonical);
+        }
+        let old_ids = old
+            .iter()
+            .map(|path| project_identity::id_for_canonical(path))
+            .collect();
+
+        let new = project_identity::canonical_path(new_path)?;
+        Ok(Self {
+            new_id: project_identity::project_id(&new),
+            new,
+            old,
+            old_ids,
+            files: Vec::new(),
+            links: Vec::new(),
+            dirs: Vec::new(),
+            records: Vec::new(),
+            changes: Vec::new(),
+        })
+    }
+
+    /// The new location of a path at or under the old project path
+    fn rebase(&self, path: &str) -> Option<String> {
+        self.old.iter().find_map(|old| {
+            let rest = path.strip_prefix(old.as_str())?;
+            if rest.is_empty() {
+                Some(self.new.clone())
+            } else if rest.starts_with('/') {
+                Some(format!("{}{}", self.new, rest))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn change(&mut self, kind: &str, path: &Path, from: &str, to: &str) {
+        self.changes.push(RelocationChange {
+            kind: kind.to_string(),
+            path: path.to_string_lossy().to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+
+    fn write_json<T: Serialize>(&mut self, path: &Path, value: &T) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(value)
+            .map_err(|e| format!("Failed to serialize {:?}: {}", path, e))?;
+        self.files.push(FileWrite {
+            path: path.to_path_buf(),
+            contents,
+        });
+        Ok(())
+    }
+
+    /// Remember ids the project itself (not a subdirectory project) had
+    fn track_old_id(&mut self, old_id: &str, new_id: &str) {
+        if new_id == self.new_id {
+            self.old_ids.insert(old_id.to_string());
+        }
+    }
+
+    fn move_dir(&mut self, from: PathBuf, to: PathBuf) {
+        let (from_str, to_str) = (from.to_string_lossy().to_string(), to.to_string_lossy().to_string());
+        self.change("move", &from, &from_str, &to_str);
+        self.dirs.push(DirMove { from, to });
+    }
+
+    fn move_dir_once(&mut self, from: PathBuf, to: PathBuf) {
+        if from.is_dir() && !self.dirs.iter().any(|dir| dir.from == from) {
+            self.move_dir(from, to);
+        }
+    }
+
+    /// Session records, and the session log directories keyed by project id
+    fn plan_sessions(&mut self) -> Result<(), String> {
+        let base = session_storage::sessions_dir();
+        for mut meta in db::with_connection(|conn| db::sessions::list_all(conn))? {
+            let Some(new_path) = self.rebase(&meta.project_path) else {
+                continue;
+            };
+            let path = record_path("terminal_sessions", &meta.session_id);
+            self.change("session", &path, &meta.project_path, &new_path);
+
+            let old_hash = std::mem::replace(&mut meta.project_hash, project_identity::project_id(&new_path));
+            meta.project_path = new_path;
+            if old_hash != meta.project_hash {
+                self.track_old_id(&old_hash, &meta.project_hash);
+                self.move_dir_once(base.join(&old_hash), base.join(&meta.project_hash));
+            }
+            self.records.push(RecordWrite::Session { old_hash, meta });
+        }
+        Ok(())
+    }
+
+    /// Team records and their saved terminal cwds
+    fn plan_teams(&mut self) -> Result<(), String> {
+        for (mut config, mut state) in db::with_connection(|conn| db::teams::list_all(conn))? {
+            let path = record_path("teams", &config.id);
+            let mut changed = false;
+
+            if let Some(new_path) = self.rebase(&config.project_path) {
+                self.change("team", &path, &config.project_path, &new_path);
+                let old_hash = project_identity::project_id(&config.project_path);
+                self.track_old_id(&old_hash, &project_identity::project_id(&new_path));
+                config.project_path = new_path;
+                changed = true;
+            }
+
+            for terminal in state
+                .iter_mut()
+                .filter_map(|state| state.session_info.as_mut())
+                .flat_map(|info| info.terminals.iter_mut())
+            {
+                if let Some(cwd) = self.rebase(&terminal.cwd) {
+                    self.changes.push(RelocationChange {
+                        kind: "team".to_string(),
+                        path: path.to_string_lossy().to_string(),
+                        from: std::mem::replace(&mut terminal.cwd, cwd.clone()),
+                        to: cwd,
+                    });
+                    changed = true;
+                }
+            }
+
+            if changed {
+                self.records.push(RecordWrite::Team { config: Box::new(config), state });
+            }
+        }
+        Ok(())
+    }
+
+    /// Group chat directories carry no path; move the ones keyed by an old id
+    fn plan_groupchat(&mut self) {
+        let base = crate::groupchat_storage::groupchat_dir();
+        let old_ids: Vec<String> = self.old_ids.iter().cloned().collect();
+        for id in old_ids {
+            let dir = base.join(&id);
+            if dir.is_dir() && id != self.new_id {
+                let target = base.join(&self.new_id);
+                self.move_dir(dir, target);
+            }
+        }
+    }
+
+    /// Role-session mappings, one file per project id
+    fn plan_role_sessions(&mut self) -> Result<(), String> {
+        for path in files_ending_with(&session::storage_dir(), ".json") {
+            let Some(mut mapping) = read_json::<RoleSessionMapping>(&path) else {
+                continue;
+            };
+            let Some(new_path) = self.rebase(&mapping.project_path) else {
+                continue;
+            };
+            self.change("role-sessions", &path, &mapping.project_path, &new_path);
+            mapping.project_path = new_path;
+            self.write_json(&path, &mapping)?;
+
+            let target = session::get_storage_path(&mapping.project_path);
+            if target != path {
+                self.move_dir(path, target);
+            }
+        }
+        Ok(())
+    }
+
+    /// Workspace tabs live inside the project, so they moved with it
+    fn plan_workspace(&mut self) -> Result<(), String> {
+        let path = workspace_storage::sessions_file_path(&self.new);
+        // Unreadable or from a newer build: leave it alone
+        let Ok(Some(loaded)) = workspace_schema::read_sessions(&path) else {
+            return Ok(());
+        };
+        let mut state = loaded.value;
+        let mut changed = false;
+        for tab in &mut state.tabs {
+            if let Some(cwd) = self.rebase(&tab.cwd) {
+                self.changes.push(RelocationChange {
+                    kind: "workspace".to_string(),
+                    path: path.to_string_lossy().to_string(),
+                    from: std::mem::replace(&mut tab.cwd, cwd.clone()),
+                    to: cwd,
+                });
+                changed = true;
+            }
+        }
+        if changed {
+            self.write_json(&path, &state)?;
+        }
+        Ok(())
+    }
+
+    /// Git worktree links (both directions) and absolute shared-folder symlinks
+    fn plan_worktrees(&mut self) {
+        let project = PathBuf::from(&self.new);
+
+        // .git/worktrees/<name>/gitdir -> <worktree>/.git
+        for (_, admin) in project_identity::subdirs(&project.join(".git").join("worktrees")) {
+            let gitdir_path = admin.join("gitdir");
+            let Ok(content) = fs::read_to_string(&gitdir_path) else {
+                continue;
+            };
+            let mut worktree_git = PathBuf::from(content.trim());
+            if let Some(rebased) = self.rebase(content.trim()) {
+                self.change("worktree", &gitdir_path, content.trim(), &rebased);
+                self.files.push(FileWrite {
+                    path: gitdir_path,
+                    contents: format!("{}\n", rebased),
+                });
+                worktree_git = PathBuf::from(rebased);
+            }
+
+            // <worktree>/.git -> "gitdir: <repo>/.git/worktrees/<name>"
+            let Ok(content) = fs::read_to_string(&worktree_git) else {
+                continue;
+            };
+            let Some(target) = content.trim().strip_prefix("gitdir: ") else {
+                continue;
+            };
+            if let Some(rebased) = self.rebase(target) {
+                self.change("worktree", &worktree_git, target, &rebased);
+                self.files.push(FileWrite {
+                    path: worktree_git,
+                    contents: format!("gitdir: {}\n", rebased),
+                });
+            }
+        }
+
+        // Shared folders and files are absolute symlinks into the project
+        let worktree_root = project.join(crate::config::for_project(&self.new).worktrees.worktree_root);
+        for (_, worktree) in project_identity::subdirs(&worktree_root) {
+            let Ok(entries) = fs::read_dir(&worktree) else {
+                continue;
+            };
+            for entry in entries.filter_map(Result::ok) {
+                let link = entry.path();
+                let Ok(target) = fs::read_link(&link) else {
+                    continue;
+                };
+                let target_str = target.to_string_lossy().to_string();
+                if let Some(rebased) = self.rebase(&target_str) {
+                    self.change("symlink", &link, &target_str, &rebased);
+                    self.links.push(LinkWrite {
+                        link,
+                        target: PathBuf::from(rebased),
+                    });
+                }
+            }
+        }
+    }
+
+    fn report(&self, old_path: &str, dry_run: bool) -> RelocationReport {
+        RelocationReport {
+            old_path: old_path.to_string(),
+            new_path: self.new.clone(),
+            old_ids: self.old_ids.iter().cloned().collect(),
+            new_id: self.new_id.clone(),
+            dry_run,
+            changes: self.changes.clone(),
+        }
+    }
+}
+
+// ============================================================================
+// Applying
+// ============================================================================
+
+/// A completed step that can be undone
+enum Applied {
+    /// Original moved aside to the backup path
+    Swapped { path: PathBuf, backup: PathBuf },
+    Renamed { from: PathBuf, to: PathBuf },
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Phase 1: write every new file and symlink next to its target
+fn stage(plan: &Plan) -> Result<(), String> {
+    for file in &plan.files {
+        let staged = with_suffix(&file.path, STAGED_SUFFIX);
+        fs::write(&staged, &file.contents)
+            .map_err(|e| format!("Failed to stage {:?}: {}", file.path, e))?;
+    }
+    for link in &plan.links {
+        let staged = with_suffix(&link.link, STAGED_SUFFIX);
+        let _ = fs::remove_file(&staged);
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&link.target, &staged)
+            .map_err(|e| format!("Failed to stage {:?}: {}", link.link, e))?;
+        #[cfg(windows)]
+        {
+            let created = if link.target.is_dir() {
+                std::os::windows::fs::symlink_dir(&link.target, &staged)
+            } else {
+                std::os::windows::fs::symlink_file(&link.target, &staged)
+            };
+            created.map_err(|e| format!("Failed to stage {:?}: {}", link.link, e))?;
+        }
+    }
+    Ok(())
+}
+
+fn discard_staged(plan: &Plan) {
+    let paths = plan.files.iter().map(|f| &f.path).chain(plan.links.iter().map(|l| &l.link));
+    for path in paths {
+        let _ = fs::remove_file(with_suffix(path, STAGED_SUFFIX));
+    }
+}
+
+/// Phase 2: swap staged files in and move directories, undoing on failure
+fn commit(plan: &Plan) -> Result<(), String> {
+    let mut applied: Vec<Applied> = Vec::new();
+    let result = commit_steps(plan, &mut applied);
+
+    if let Err(e) = result {
+        for step in applied.into_iter().rev() {
+            let undone = match &step {
+                Applied::Swapped { path, backup } => fs::rename(backup, path),
+                Applied::Renamed { from, to } => fs::rename(to, from),
+            };
+            if let Err(undo_error) = undone {
+                eprintln!("[ProjectRelocate] Rollback step failed: {}", undo_error);
+            }
+        }
+        discard_staged(plan);
+        return Err(e);
+    }
+
+    // Backups inside moved directories moved with them
+    for step in applied {
+        if let Applied::Swapped { backup, .. } = step {
+            let _ = fs::remove_file(moved_path(&backup, &plan.dirs));
+        }
+    }
+    Ok(())
+}
+
+/// Where a path ended up after the directory moves
+fn moved_path(path: &Path, dirs: &[DirMove]) -> PathBuf {
+    dirs.iter()
+        .find_map(|dir| path.strip_prefix(&dir.from).ok().map(|rest| dir.to.join(rest)))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn commit_steps(plan: &Plan, applied: &mut Vec<Applied>) -> Result<(), String> {
+    let paths = plan.files.iter().map(|f| &f.path).chain(plan.links.iter().map(|l| &l.link));
+    for path in paths {
+        let staged = with_suffix(path, STAGED_SUFFIX);
+        let backup = with_suffix(path, BACKUP_SUFFIX);
+        fs::rename(path, &backup).map_err(|e| format!("Failed to replace {:?}: {}", path, e))?;
+        applied.push(Applied::Swapped {
+            path: path.clone(),
+            backup,
+        });
+        fs::rename(&staged, path).map_err(|e| format!("Failed to replace {:?}: {}", path, e))?;
+    }
+
+    // Directories whose target is free are renamed (undoable); the rest merged last
+    let mut merges = Vec::new();
+    for dir in &plan.dirs {
+        if dir.to.exists() {
+            merges.push(dir);
+            continue;
+        }
+        fs::rename(&dir.from, &dir.to)
+            .map_err(|e| format!("Failed to move {:?}: {}", dir.from, e))?;
+        applied.push(Applied::Renamed {
+            from: dir.from.clone(),
+            to: dir.to.clone(),
+        });
+    }
+
+    for dir in merges {
+        if dir.from.is_dir() {
+            project_identity::merge_dir(&dir.from, &dir.to)?;
+        } else {
+            eprintln!(
+                "[ProjectRelocate] Keeping {:?}; {:?} already exists",
+                dir.from, dir.to
+            );
+        }
+    }
+
+    // Last: the transaction either fails (and everything above is undone) or
+    // commits, after which nothing can fail
+    write_records(&plan.records)
+}
+
+/// Rewrite the planned database records in one transaction
+fn write_records(records: &[RecordWrite]) -> Result<(), String> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    db::with_connection(|conn| {
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start database update: {}", e))?;
+        for record in records {
+            match record {
+                RecordWrite::Session { old_hash, meta } => db::sessions::rekey(&tx, old_hash, meta)?,
+                RecordWrite::Team { config, state } => {
+                    db::teams::upsert_config(&tx, config)?;
+                    if let Some(state) = state {
+                        let project_hash = project_identity::project_id(&config.project_path);
+                        db::teams::save_state(&tx, &project_hash, state)?;
+                    }
+                }
+            }
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to commit database update: {}", e))
+    })
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+fn trim_slash(path: &str) -> &str {
+    if path.len() > 1 {
+        path.trim_end_matches('/')
+    } else {
+        path
+    }
+}
+
+/// Point every stored reference to `old_path` at `new_path`
+///
+/// The project must already have been moved to `new_path`. With `dry_run`
+/// the changes are only reported.
+pub fn relocate(old_path: &str, new_path: &str, dry_run: bool) -> Result<RelocationReport, String> {
+    if !Path::new(new_path).is_dir() {
+        return Err(format!("New project path does not exist: {}", new_path));
+    }
+
+    let mut plan = Plan::new(old_path, new_path)?;
+    if plan.old.contains(&plan.new) {
+        return Err("Old and new project paths are the same".to_string());
+    }
+
+    plan.plan_sessions()?;
+    plan.plan_teams()?;
+    plan.plan_groupchat();
+    plan.plan_role_sessions()?;
+    plan.plan_workspace()?;
+    plan.plan_worktrees();
+
+    if dry_run || plan.changes.is_empty() {
+        return Ok(plan.report(old_path, true));
+    }
+
+    if let Err(e) = stage(&plan) {
+        discard_staged(&plan);
+        return Err(e);
+    }
+    commit(&plan)?;
+
+    let old_ids: Vec<String> = plan.old_ids.iter().cloned().collect();
+    for old_id in &old_ids {
+        if let Err(e) = crate::session_search::rename_project(old_id, &plan.new_id, Some(&plan.new)) {
+            eprintln!("[ProjectRelocate] Failed to update search index: {}", e);
+        }
+    }
+    project_identity::record_relocation(&old_ids, &plan.new_id, trim_slash(old_path));
+
+    eprintln!(
+        "[ProjectRelocate] Relocated {} -> {} ({} change(s))",
+        old_path,
+        plan.new,
+        plan.changes.len()
+    );
+    Ok(plan.report(old_path, false))
+}
+
+/// Path shown in the report for a database record
+fn record_path(table: &str, id: &str) -> PathBuf {
+    let mut path = db::db_path().into_os_string();
+    path.push(format!(":{}/{}", table, id));
+    PathBuf::from(path)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Option<T> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn files_ending_with(dir: &Path, suffix: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(suffix))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Tauri command: Rewrite stored paths after a project moved
+#[tauri::command]
+pub fn project_relocate(
+    old_path: String,
+    new_path: String,
+    dry_run: Option<bool>,
+) -> Result<RelocationReport, String> {
+    relocate(&old_path, &new_path, dry_run.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebase() {
+        let plan = Plan::new("/no/such/old/", "/no/such/new").unwrap();
+        assert_eq!(plan.rebase("/no/such/old").as_deref(), Some("/no/such/new"));
+        assert_eq!(
+            plan.rebase("/no/such/old/.worktrees/task-1").as_deref(),
+            Some("/no/such/new/.worktrees/task-1")
+        );
+        assert_eq!(plan.rebase("/no/such/older"), None);
+    }
+
+    /// A moved project: `old` no longer exists, `new` does
+    fn moved_project() -> (tempfile::TempDir, String, String) {
+        session_storage::use_test_sessions_dir();
+        db::use_test_db();
+        project_identity::use_test_mapping();
+        let root = tempfile::tempdir().unwrap();
+        let base = fs::canonicalize(root.path()).unwrap();
+        let new = base.join("new");
+        fs::create_dir_all(&new).unwrap();
+        let old = base.join("old").to_string_lossy().to_string();
+        (root, old, new.to_string_lossy().to_string())
+    }
+
+    /// Files left behind by staging or swapping
+    fn leftovers(dir: &Path) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+        for entry in fs::read_dir(dir).unwrap().filter_map(Result::ok) {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.ends_with(STAGED_SUFFIX) || name.ends_with(BACKUP_SUFFIX) {
+                found.push(path.clone());
+            }
+            if path.is_dir() && !path.is_symlink() {
+                found.extend(leftovers(&path));
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn test_relocate_rewrites_sessions_and_worktrees() {
+        let (root, old, new) = moved_project();
+        session_storage::create_session("relocate-s1".to_string(), old.clone(), None, None).unwrap();
+        let old_log = session_storage::log_file_path(&old, "relocate-s1");
+        fs::create_dir_all(old_log.parent().unwrap()).unwrap();
+        fs::write(&old_log, "hello\n").unwrap();
+
+        let admin = Path::new(&new).join(".git").join("worktrees").join("wt");
+        let worktree = Path::new(&new).join(".worktrees").join("wt");
+        fs::create_dir_all(&admin).unwrap();
+        fs::create_dir_all(&worktree).unwrap();
+        fs::write(admin.join("gitdir"), format!("{}/.worktrees/wt/.git\n", old)).unwrap();
+        fs::write(worktree.join(".git"), format!("gitdir: {}/.git/worktrees/wt\n", old)).unwrap();
+
+        // A dry run only reports
+        let report = relocate(&old, &new, true).unwrap();
+        assert!(report.dry_run);
+        assert!(report.changes.iter().any(|c| c.kind == "session"));
+        assert_eq!(report.changes.iter().filter(|c| c.kind == "worktree").count(), 2);
+        assert!(old_log.exists());
+        assert!(session_storage::get_session_meta(&old, "relocate-s1").unwrap().is_some());
+
+        let report = relocate(&old, &new, false).unwrap();
+        assert!(!report.dry_run);
+        assert!(session_storage::get_session_meta(&old, "relocate-s1").unwrap().is_none());
+        let meta = session_storage::get_session_meta(&new, "relocate-s1").unwrap().unwrap();
+        assert_eq!(meta.project_path, new);
+        assert!(!old_log.exists());
+        assert_eq!(
+            fs::read_to_string(session_storage::log_file_path(&new, "relocate-s1")).unwrap(),
+            "hello\n"
+        );
+        assert_eq!(
+            fs::read_to_string(admin.join("gitdir")).unwrap(),
+            format!("{}/.worktrees/wt/.git\n", new)
+        );
+        assert_eq!(
+            fs::read_to_string(worktree.join(".git")).unwrap(),
+            format!("gitdir: {}/.git/worktrees/wt\n", new)
+        );
+        assert!(leftovers(root.path()).is_empty());
+        assert!(project_identity::mapping().projects[&report.new_id]
+            .previous_paths
+            .contains(&old));
+    }
+
+    #[test]
+    fn test_relocate_rejects_same_or_missing_path() {
+        let (_root, old, new) = moved_project();
+        assert!(relocate(&new, &new, false).is_err());
+        assert!(relocate(&new, &old, false).is_err());
+    }
+
+    #[test]
+    fn test_commit_rolls_back_after_partial_failure() {
+        let (root, old, new) = moved_project();
+        let base = Path::new(&new).parent().unwrap().to_path_buf();
+        let file = base.join("tabs.json");
+        fs::write(&file, "original").unwrap();
+        let moved = base.join("id-old");
+        fs::create_dir_all(&moved).unwrap();
+        fs::write(moved.join("s1.log"), "log").unwrap();
+
+        let mut plan = Plan::new(&old, &new).unwrap();
+        plan.files.push(FileWrite {
+            path: file.clone(),
+            contents: "rewritten".to_string(),
+        });
+        // The first move succeeds, the second fails partway through the commit
+        plan.dirs.push(DirMove {
+            from: moved.clone(),
+            to: base.join("id-new"),
+        });
+        plan.dirs.push(DirMove {
+            from: base.join("missing"),
+            to: base.join("never"),
+        });
+
+        stage(&plan).unwrap();
+        assert_eq!(fs::read_to_string(with_suffix(&file, STAGED_SUFFIX)).unwrap(), "rewritten");
+        assert!(commit(&plan).is_err());
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "original");
+        assert_eq!(fs::read_to_string(moved.join("s1.log")).unwrap(), "log");
+        assert!(!base.join("id-new").exists());
+        assert!(leftovers(root.path()).is_empty());
+    }
+
+    #[test]
+    fn test_commit_merges_into_existing_directory() {
+        let (root, old, new) = moved_project();
+        let base = Path::new(&new).parent().unwrap().to_path_buf();
+        let file = base.join("tabs.json");
+        fs::write(&file, "original").unwrap();
+        for (dir, log) in [("id-old", "a.log"), ("id-new", "b.log")] {
+            fs::create_dir_all(base.join(dir)).unwrap();
+            fs::write(base.join(dir).join(log), log).unwrap();
+        }
+
+        let mut plan = Plan::new(&old, &new).unwrap();
+        plan.files.push(FileWrite {
+            path: file.clone(),
+            contents: "rewritten".to_string(),
+        });
+        plan.dirs.push(DirMove {
+            from: base.join("id-old"),
+            to: base.join("id-new"),
+        });
+
+        stage(&plan).unwrap();
+        commit(&plan).unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "rewritten");
+        assert!(!base.join("id-old").exists());
+        assert!(base.join("id-new").join("a.log").exists());
+        assert!(base.join("id-new").join("b.log").exists());
+        assert!(leftovers(root.path()).is_empty());
+    }
+}
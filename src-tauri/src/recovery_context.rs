@@ -0,0 +1,493 @@
+//! Recovery Context - What a failed member had done, for its replacement
+//!
+//! `build` gathers, for the failed member (as `TeamManager::recovery_inputs`
+//! describes it):
+//! - Its recent tool calls and last assistant messages, from the end of its
+//!   Claude transcript
+//! - Files changed in the directory it worked in (git status against HEAD,
+//!   untracked files included). Without a worktree of its own, only the files
+//!   its transcript shows it editing are listed.
+//! - The checkbox state of `openspec/changes/<spec>/tasks.md` for its spec
+//!
+//! This reads from disk, so callers release the team manager first and run it
+//! on a blocking thread (`gather`). `render_prompt` turns the context into the
+//! replacement session's first message.
+
+use git2::{Repository, Status, StatusOptions};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::claude_process::{ClaudeEvent, ContentBlock};
+use crate::commands::openspec::{parse_tasks_markdown, OpenSpecTask};
+use crate::team_manager::RecoveryContext;
+use crate::team_storage::TeamMemberConfig;
+
+/// How much of the transcript's end is read
+const TRANSCRIPT_TAIL_BYTES: u64 = 256 * 1024;
+/// Tool calls kept (most recent)
+const MAX_TOOL_CALLS: usize = 30;
+/// Assistant messages kept (most recent)
+const MAX_MESSAGES: usize = 5;
+/// Longest tool call summary and assistant message kept
+const MAX_CALL_CHARS: usize = 160;
+const MAX_MESSAGE_CHARS: usize = 1500;
+/// Changed files listed in the prompt
+const MAX_PROMPT_FILES: usize = 50;
+
+/// Tools whose `file_path` (or `notebook_path`) argument is a file they change
+const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+/// What a failed member was doing, read from its team
+#[derive(Debug, Clone, Default)]
+pub struct RecoveryInputs {
+    pub project_path: String,
+    pub spec_id: Option<String>,
+    pub task_id: Option<String>,
+    pub phase: Option<String>,
+    pub progress: u32,
+    pub claude_session_id: Option<String>,
+    /// Directory it was launched in (see `member_dir`)
+    pub member_dir: Option<PathBuf>,
+    /// Whether it had a worktree of its own
+    pub own_worktree: bool,
+}
+
+/// Tool calls and assistant messages from a transcript
+#[derive(Debug, Default)]
+pub struct TranscriptHistory {
+    /// The directory the conversation ran in
+    pub cwd: Option<String>,
+    pub tool_calls: Vec<String>,
+    pub recent_messages: Vec<String>,
+    /// Files the edit tools were called on
+    pub touched_files: HashSet<PathBuf>,
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// One line per tool call: the tool and its most telling argument
+fn summarize_tool_call(name: &str, input: &Value) -> String {
+    let argument = ["file_path", "path", "command", "pattern", "url", "description"]
+        .iter()
+        .find_map(|key| input.get(key).and_then(Value::as_str));
+    match argument {
+        Some(argument) => truncate(&format!("{}: {}", name, argument.replace('\n', " ")), MAX_CALL_CHARS),
+        None => name.to_string(),
+    }
+}
+
+/// Recent tool calls and assistant messages of a Claude session
+pub fn transcript_history(claude_session_id: &str) -> Result<TranscriptHistory, String> {
+    match crate::claude_transcripts::find(claude_session_id)? {
+        Some(info) => read_history(Path::new(&info.path), info.cwd),
+        None => Ok(TranscriptHistory::default()),
+    }
+}
+
+/// Recent tool calls and assistant messages from the end of a transcript file
+fn read_history(path: &Path, cwd: Option<String>) -> Result<TranscriptHistory, String> {
+    let mut history = TranscriptHistory {
+        cwd,
+        ..Default::default()
+    };
+
+    for event in crate::claude_transcripts::tail_events(path, TRANSCRIPT_TAIL_BYTES)? {
+        let ClaudeEvent::Assistant { message: Some(message), .. } = event else {
+            continue;
+        };
+        let mut text = Vec::new();
+        for block in message.content.unwrap_or_default() {
+            match block {
+                ContentBlock::Text { text: t } if !t.trim().is_empty() => text.push(t),
+                ContentBlock::ToolUse { name, input, .. } => {
+                    if EDIT_TOOLS.contains(&name.as_str()) {
+                        let file = ["file_path", "notebook_path"]
+                            .iter()
+                            .find_map(|key| input.get(key).and_then(Value::as_str));
+                        if let Some(file) = file {
+                            let cwd = history.cwd.as_deref().unwrap_or_default();
+                            history.touched_files.insert(Path::new(cwd).join(file));
+                        }
+                    }
+                    history.tool_calls.push(summarize_tool_call(&name, &input));
+                }
+                _ => {}
+            }
+        }
+        if !text.is_empty() {
+            history.recent_messages.push(truncate(&text.join("\n"), MAX_MESSAGE_CHARS));
+        }
+    }
+
+    let skip = history.tool_calls.len().saturating_sub(MAX_TOOL_CALLS);
+    history.tool_calls.drain(..skip);
+    let skip = history.recent_messages.len().saturating_sub(MAX_MESSAGES);
+    history.recent_messages.drain(..skip);
+    Ok(history)
+}
+
+/// Resolve symlinks so paths from git and from a transcript compare equal
+/// (deleted files keep their name under the resolved parent)
+fn normalize(path: &Path) -> PathBuf {
+    if let Ok(resolved) = fs::canonicalize(path) {
+        return resolved;
+    }
+    match (path.parent().and_then(|parent| fs::canonicalize(parent).ok()), path.file_name()) {
+        (Some(parent), Some(name)) => parent.join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Files changed (or added) under `dir`, relative to the root of its
+/// repository, with a status prefix like `git status --short`
+///
+/// With `touched`, only those files (absolute paths) are listed: in a checkout
+/// shared with other members, the other changes aren't this member's.
+pub fn changed_files(dir: &Path, touched: Option<&HashSet<PathBuf>>) -> Result<Vec<String>, String> {
+    let repo = Repository::discover(dir).map_err(|e| format!("Failed to open repository: {}", e))?;
+    let root = repo
+        .workdir()
+        .map(normalize)
+        .ok_or_else(|| "Failed to read git status: repository has no working directory".to_string())?;
+    let dir = normalize(dir);
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true).recurse_untracked_dirs(true);
+    let statuses = repo
+        .statuses(Some(&mut opts))
+        .map_err(|e| format!("Failed to read git status: {}", e))?;
+
+    let mut files = Vec::new();
+    for entry in statuses.iter() {
+        let status = entry.status();
+        let code = if status.intersects(Status::WT_NEW) {
+            "??"
+        } else if status.intersects(Status::INDEX_NEW) {
+            "A"
+        } else if status.intersects(Status::INDEX_DELETED | Status::WT_DELETED) {
+            "D"
+        } else if status.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+            "R"
+        } else if status.intersects(
+            Status::INDEX_MODIFIED | Status::WT_MODIFIED | Status::INDEX_TYPECHANGE | Status::WT_TYPECHANGE,
+        ) {
+            "M"
+        } else {
+            continue;
+        };
+        let Some(path) = entry.path() else {
+            continue;
+        };
+        let absolute = root.join(path);
+        if !absolute.starts_with(&dir) || touched.is_some_and(|files| !files.contains(&absolute)) {
+            continue;
+        }
+        files.push(format!("{} {}", code, path));
+    }
+    Ok(files)
+}
+
+/// Tasks of an OpenSpec change, if it has a tasks.md
+///
+/// None for spec ids that aren't a plain change name (e.g. `../../etc`).
+pub fn spec_tasks(project_path: &str, spec_id: &str) -> Option<Vec<OpenSpecTask>> {
+    let valid = !spec_id.starts_with('.')
+        && !spec_id.is_empty()
+        && spec_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return None;
+    }
+    let path = Path::new(project_path)
+        .join("openspec")
+        .join("changes")
+        .join(spec_id)
+        .join("tasks.md");
+    let content = fs::read_to_string(path).ok()?;
+    Some(parse_tasks_markdown(&content))
+}
+
+/// Directory a member works in: its worktree (if it has one) and
+/// `working_dir` under the project
+pub fn member_dir(project_path: &str, member: &TeamMemberConfig) -> PathBuf {
+    let launch = member.launch.clone().unwrap_or_default();
+    let mut dir = PathBuf::from(project_path);
+    if let Some(task_id) = &launch.worktree {
+        let worktree_root = crate::config::for_project(project_path).worktrees.worktree_root;
+        dir = dir.join(worktree_root).join(format!("task-{}", task_id));
+    }
+    if let Some(working_dir) = &launch.working_dir {
+        dir = dir.join(working_dir);
+    }
+    dir
+}
+
+/// Gather the recovery context of a failed member
+///
+/// Reads its transcript, git status and tasks.md: don't call it holding the
+/// team manager or on the async runtime (see `gather`).
+pub fn build(inputs: &RecoveryInputs) -> RecoveryContext {
+    // What it did, from its conversation
+    let history = inputs.claude_session_id.as_deref()
+        .map(transcript_history)
+        .transpose()
+        .unwrap_or_else(|e| {
+            eprintln!("[RecoveryContext] Failed to read transcript: {}", e);
+            None
+        })
+        .unwrap_or_default();
+
+    // What it changed, in the directory it worked in
+    let working_dir = history.cwd.clone()
+        .or_else(|| inputs.member_dir.as_ref().map(|dir| dir.to_string_lossy().to_string()))
+        .filter(|dir| Path::new(dir).is_dir());
+    let touched: Option<HashSet<PathBuf>> = (!inputs.own_worktree)
+        .then(|| history.touched_files.iter().map(|file| normalize(file)).collect());
+    let artifacts = working_dir.as_deref()
+        .map(|dir| changed_files(Path::new(dir), touched.as_ref()).unwrap_or_default())
+        .unwrap_or_default();
+
+    // Where it stands in its spec
+    let tasks = inputs.spec_id.as_deref()
+        .and_then(|spec| spec_tasks(&inputs.project_path, spec))
+        .unwrap_or_default();
+    let completed_steps: Vec<String> = tasks.iter()
+        .filter(|t| t.status == "completed")
+        .map(|t| t.title.clone())
+        .collect();
+    let current_step = tasks.iter()
+        .find(|t| t.status == "in_progress")
+        .or_else(|| tasks.iter().find(|t| t.status == "pending"))
+        .map(|t| t.title.clone());
+    let mut progress = inputs.progress;
+    if progress == 0 && !tasks.is_empty() {
+        progress = (completed_steps.len() * 100 / tasks.len()) as u32;
+    }
+
+    let mut context = RecoveryContext {
+        spec_id: inputs.spec_id.clone(),
+        task_id: inputs.task_id.clone(),
+        phase: inputs.phase.clone(),
+        progress,
+        completed_steps,
+        current_step,
+        artifacts,
+        resume_instructions: String::new(),
+        working_dir,
+        tool_calls: history.tool_calls,
+        recent_messages: history.recent_messages,
+    };
+    context.resume_instructions = render_prompt(&context);
+    context
+}
+
+/// `build` on a blocking thread
+pub async fn gather(inputs: RecoveryInputs) -> Result<RecoveryContext, String> {
+    tokio::task::spawn_blocking(move || build(&inputs))
+        .await
+        .map_err(|e| format!("Failed to build recovery context: {}", e))
+}
+
+/// First message for the replacement: what to continue and what was done
+pub fn render_prompt(context: &RecoveryContext) -> String {
+    let mut prompt = String::from("You are replacing a team member that failed. Continue its work; don't start over.\n");
+
+    match (&context.spec_id, &context.task_id) {
+        (Some(spec), Some(task)) => prompt.push_str(&format!("\nWork: task {} of spec '{}'", task, spec)),
+        (Some(spec), None) => prompt.push_str(&format!("\nWork: spec '{}'", spec)),
+        (None, Some(task)) => prompt.push_str(&format!("\nWork: task {}", task)),
+        (None, None) => prompt.push_str("\nWork: check for pending tasks"),
+    }
+    if let Some(phase) = &context.phase {
+        prompt.push_str(&format!(" (phase: {})", phase));
+    }
+    prompt.push_str(&format!(", {}% done\n", context.progress));
+    if let Some(dir) = &context.working_dir {
+        prompt.push_str(&format!("Working directory: {}\n", dir));
+    }
+
+    if !context.completed_steps.is_empty() {
+        prompt.push_str("\nCompleted tasks:\n");
+        for step in &context.completed_steps {
+            prompt.push_str(&format!("- [x] {}\n", step));
+        }
+    }
+    if let Some(step) = &context.current_step {
+        prompt.push_str(&format!("\nNext task: {}\n", step));
+    }
+
+    if !context.artifacts.is_empty() {
+        prompt.push_str("\nUncommitted changes it left (review them before editing):\n");
+        for file in context.artifacts.iter().take(MAX_PROMPT_FILES) {
+            prompt.push_str(&format!("  {}\n", file));
+        }
+        if context.artifacts.len() > MAX_PROMPT_FILES {
+            prompt.push_str(&format!("  ... and {} more\n", context.artifacts.len() - MAX_PROMPT_FILES));
+        }
+    }
+
+    if !context.tool_calls.is_empty() {
+        prompt.push_str("\nIts last tool calls, oldest first:\n");
+        for call in &context.tool_calls {
+            prompt.push_str(&format!("- {}\n", call));
+        }
+    }
+
+    if !context.recent_messages.is_empty() {
+        prompt.push_str("\nIts last messages, oldest first:\n");
+        for message in &context.recent_messages {
+            prompt.push_str(&format!("\n> {}\n", message.replace('\n', "\n> ")));
+        }
+    }
+
+    prompt.push_str(
+        "\nVerify the state of the files before relying on this summary; the failed member may have \
+         stopped in the middle of an edit.",
+    );
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    /// A repository with a committed kept.rs and edited.rs, then edited.rs
+    /// changed and new.rs added
+    fn changed_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git").args(args).current_dir(dir.path()).output().unwrap().status;
+            assert!(status.success(), "git {:?}", args);
+        };
+        git(&["init", "-q"]);
+        fs::write(dir.path().join("kept.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.path().join("edited.rs"), "fn a() {}\n").unwrap();
+        git(&["add", "."]);
+        git(&["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-qm", "init"]);
+
+        fs::write(dir.path().join("edited.rs"), "fn b() {}\n").unwrap();
+        fs::write(dir.path().join("new.rs"), "").unwrap();
+        dir
+    }
+
+    fn write_tasks(project: &Path, spec_id: &str) {
+        let dir = project.join("openspec").join("changes").join(spec_id);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("tasks.md"),
+            "- [x] **1.1** Add theme tokens\n- [-] **1.2** Wire the toggle\n- [ ] **1.3** Document it\n- [ ] **1.4** Ship\n",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_changed_files_reports_status() {
+        let dir = changed_repo();
+        let mut files = changed_files(dir.path(), None).unwrap();
+        files.sort();
+        assert_eq!(files, ["?? new.rs", "M edited.rs"]);
+    }
+
+    #[test]
+    fn test_changed_files_limited_to_touched() {
+        let dir = changed_repo();
+        let touched: HashSet<PathBuf> = [normalize(&dir.path().join("edited.rs"))].into();
+        assert_eq!(changed_files(dir.path(), Some(&touched)).unwrap(), ["M edited.rs"]);
+
+        // Changes outside the member's directory aren't its own either
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub").join("mine.rs"), "").unwrap();
+        assert_eq!(changed_files(&dir.path().join("sub"), None).unwrap(), ["?? sub/mine.rs"]);
+    }
+
+    #[test]
+    fn test_read_history_collects_calls_and_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let lines = [
+            r#"{"type":"user","message":{"role":"user","content":"Add dark mode"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Read","input":{"file_path":"/p/README.md"}}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Edit","input":{"file_path":"src/theme.ts"}}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Tokens done."}]}}"#,
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let history = read_history(&path, Some("/p".to_string())).unwrap();
+        assert_eq!(history.tool_calls, ["Read: /p/README.md", "Edit: src/theme.ts"]);
+        assert_eq!(history.recent_messages, ["Tokens done."]);
+        assert_eq!(history.touched_files, [PathBuf::from("/p/src/theme.ts")].into());
+    }
+
+    #[test]
+    fn test_spec_tasks_rejects_paths() {
+        let project = tempfile::tempdir().unwrap();
+        write_tasks(project.path(), "add-dark-mode");
+        let project_path = project.path().to_string_lossy();
+
+        assert_eq!(spec_tasks(&project_path, "add-dark-mode").unwrap().len(), 4);
+        assert!(spec_tasks(&project_path, "missing").is_none());
+        // A tasks.md reachable through a crafted id is not read
+        write_tasks(&project.path().join("outside"), "x");
+        assert!(spec_tasks(&project_path, "../../outside/openspec/changes/x").is_none());
+        assert!(spec_tasks(&project_path, "..").is_none());
+        assert!(spec_tasks(&project_path, "").is_none());
+    }
+
+    #[test]
+    fn test_build_recovery_context() {
+        let project = changed_repo();
+        write_tasks(project.path(), "add-dark-mode");
+        let inputs = RecoveryInputs {
+            project_path: project.path().to_string_lossy().to_string(),
+            spec_id: Some("add-dark-mode".to_string()),
+            task_id: Some("1.2".to_string()),
+            member_dir: Some(project.path().to_path_buf()),
+            own_worktree: true,
+            ..Default::default()
+        };
+
+        let context = build(&inputs);
+        assert_eq!(context.progress, 25);
+        assert_eq!(context.completed_steps, ["Add theme tokens"]);
+        assert_eq!(context.current_step.as_deref(), Some("Wire the toggle"));
+        assert_eq!(context.working_dir.as_deref(), Some(inputs.project_path.as_str()));
+        assert!(context.artifacts.contains(&"M edited.rs".to_string()));
+        assert!(context.resume_instructions.contains("Next task: Wire the toggle"));
+
+        // In a shared checkout, nothing without a transcript showing the edits
+        let shared = build(&RecoveryInputs { own_worktree: false, ..inputs });
+        assert!(shared.artifacts.is_empty());
+    }
+
+    #[test]
+    fn test_render_prompt_includes_history() {
+        let context = RecoveryContext {
+            spec_id: Some("add-dark-mode".to_string()),
+            task_id: Some("1.2".to_string()),
+            phase: Some("implementation".to_string()),
+            progress: 50,
+            completed_steps: vec!["Add theme tokens".to_string()],
+            current_step: Some("Wire the toggle".to_string()),
+            artifacts: vec!["M src/theme.ts".to_string()],
+            resume_instructions: String::new(),
+            working_dir: Some("/work/app".to_string()),
+            tool_calls: vec![summarize_tool_call("Edit", &serde_json::json!({"file_path": "src/theme.ts"}))],
+            recent_messages: vec!["Tokens done.\nNext the toggle.".to_string()],
+        };
+
+        let prompt = render_prompt(&context);
+        assert!(prompt.contains("Work: task 1.2 of spec 'add-dark-mode' (phase: implementation), 50% done"));
+        assert!(prompt.contains("- [x] Add theme tokens\n"));
+        assert!(prompt.contains("Next task: Wire the toggle"));
+        assert!(prompt.contains("  M src/theme.ts\n"));
+        assert!(prompt.contains("- Edit: src/theme.ts\n"));
+        assert!(prompt.contains("> Tokens done.\n> Next the toggle."));
+    }
+}
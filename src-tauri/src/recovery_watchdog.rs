@@ -2,21 +2,40 @@
 //!
 //! Monitors agent health and triggers automatic recovery when failures are detected.
 //! Detects: terminal closure, stale heartbeats, and failure patterns in output.
+//!
+//! Every check emits `member-health` with a report per monitored member. An
+//! active member is unhealthy when it reported failure, its Claude process
+//! has exited, or its last heartbeat (`TeamManager::record_heartbeat`, or the
+//! team's last activity before the first one) is older than
+//! `heartbeat_timeout_secs`; recovery then runs after `recovery_delay_ms`.
+//! Output of a member session counts as a heartbeat, recorded at most every
+//! `ACTIVITY_HEARTBEAT_SECS`, so agents busy working are never timed out.
+//!
+//! Output of member sessions also goes through the failure pattern engine
+//! (`failure_patterns`): every match emits `failure-detected`, and matches of
+//! `recover` rules trigger recovery at once. When a process has exited, the
+//! end of its transcript is scanned to tell why.
 
 #![allow(dead_code)]
 
+use crate::claude_process::{ClaudeEvent, SharedClaudeProcessManager};
+use crate::failure_patterns::{self, FailureDetector, FailureMatch, FailureSeverity};
 use crate::team_manager::SharedTeamManager;
 use crate::team_storage::{MemberStatus, RecoveryContextSummary};
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast::{self, error::RecvError};
 use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio::time;
 
-/// Watchdog configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Minimum time between heartbeats recorded for a member's output
+const ACTIVITY_HEARTBEAT_SECS: u64 = 15;
+
+/// Watchdog configuration (the `watchdog` section of config.yaml)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct WatchdogConfig {
     /// Health check interval in seconds
     pub check_interval_secs: u64,
@@ -46,6 +65,7 @@ pub struct MemberHealth {
     pub team_id: String,
     pub is_healthy: bool,
     pub last_heartbeat: Option<DateTime<Utc>>,
+    /// False once the member's Claude process has exited
     pub terminal_alive: bool,
     pub failure_detected: bool,
     pub failure_reason: Option<String>,
@@ -63,6 +83,17 @@ pub struct RecoveryNotification {
     pub success: bool,
 }
 
+/// Failure pattern match in a member's output, emitted as `failure-detected`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureNotification {
+    pub team_id: String,
+    pub member_id: String,
+    #[serde(flatten)]
+    pub failure: FailureMatch,
+    pub timestamp: DateTime<Utc>,
+}
+
 /// Commands for the watchdog
 pub enum WatchdogCommand {
     StartMonitoring { team_id: String, project_path: String },
@@ -76,22 +107,36 @@ pub enum WatchdogCommand {
 struct MonitoredTeam {
     project_path: String,
     last_check: DateTime<Utc>,
-    pending_recoveries: HashMap<String, DateTime<Utc>>, // member_id -> scheduled_time
+    pending_recoveries: HashMap<String, (DateTime<Utc>, String)>, // member_id -> (scheduled_time, reason)
 }
 
 /// Recovery Watchdog implementation
 pub struct RecoveryWatchdog {
     config: WatchdogConfig,
     team_manager: SharedTeamManager,
+    processes: SharedClaudeProcessManager,
     monitored_teams: HashMap<String, MonitoredTeam>,
     command_tx: Option<mpsc::Sender<WatchdogCommand>>,
 }
 
+/// Handles a health check or recovery works with, cloned out of the watchdog
+/// so its lock isn't held while they wait on the team and process managers
+#[derive(Clone)]
+struct HealthChecker {
+    config: WatchdogConfig,
+    team_manager: SharedTeamManager,
+    processes: SharedClaudeProcessManager,
+}
+
+/// A recovery taken off the pending list: (team_id, project_path, member_id, reason)
+type DueRecovery = (String, String, String, String);
+
 impl RecoveryWatchdog {
-    pub fn new(team_manager: SharedTeamManager) -> Self {
+    pub fn new(team_manager: SharedTeamManager, processes: SharedClaudeProcessManager) -> Self {
         Self {
-            config: WatchdogConfig::default(),
+            config: crate::config::get().watchdog,
             team_manager,
+            processes,
             monitored_teams: HashMap::new(),
             command_tx: None,
         }
@@ -110,89 +155,170 @@ impl RecoveryWatchdog {
         tx
     }
 
-    /// Check health of all monitored teams
-    async fn check_health(&mut self) -> Vec<MemberHealth> {
-        let mut all_health = Vec::new();
-
-        for (team_id, monitored) in &self.monitored_teams {
-            let mut manager = self.team_manager.lock().await;
-            if let Ok(team) = manager.get_members_with_state(&monitored.project_path, team_id) {
-                for member in team {
-                    let is_stale = member.status == MemberStatus::Active
-                        && self.is_heartbeat_stale(&member.id);
-
-                    let health = MemberHealth {
-                        member_id: member.id.clone(),
-                        team_id: team_id.clone(),
-                        is_healthy: member.status != MemberStatus::Failed && !is_stale,
-                        last_heartbeat: None, // Would come from actual heartbeat tracking
-                        terminal_alive: member.terminal_id.is_some(),
-                        failure_detected: member.status == MemberStatus::Failed || is_stale,
-                        failure_reason: if member.status == MemberStatus::Failed {
-                            Some("Agent reported failure".to_string())
-                        } else if is_stale {
-                            Some("Heartbeat timeout".to_string())
-                        } else {
-                            None
-                        },
-                    };
-
-                    all_health.push(health);
-                }
-            }
-            drop(manager);
+    fn checker(&self) -> HealthChecker {
+        HealthChecker {
+            config: self.config.clone(),
+            team_manager: self.team_manager.clone(),
+            processes: self.processes.clone(),
         }
+    }
 
-        all_health
+    /// Monitored teams as (team_id, project_path)
+    fn monitored(&self) -> Vec<(String, String)> {
+        self.monitored_teams
+            .iter()
+            .map(|(team_id, monitored)| (team_id.clone(), monitored.project_path.clone()))
+            .collect()
     }
 
-    /// Check if a member's heartbeat is stale
-    fn is_heartbeat_stale(&self, _member_id: &str) -> bool {
-        // Would check against actual heartbeat timestamps
-        // For now, return false (assume healthy)
-        false
+    /// Note a finished health check and schedule recoveries for unhealthy members
+    fn record_health(&mut self, health_reports: &[MemberHealth], checked_at: DateTime<Utc>) {
+        for health in health_reports {
+            if let Some(monitored) = self.monitored_teams.get_mut(&health.team_id) {
+                monitored.last_check = checked_at;
+            }
+            if let Some(reason) = health.failure_reason.as_deref().filter(|_| health.failure_detected) {
+                self.schedule_recovery(&health.team_id, &health.member_id, reason);
+            }
+        }
     }
 
-    /// Schedule recovery for a member
-    fn schedule_recovery(&mut self, team_id: &str, member_id: &str) {
+    /// Schedule recovery for a member, unless one is already pending
+    fn schedule_recovery(&mut self, team_id: &str, member_id: &str, reason: &str) {
         if let Some(monitored) = self.monitored_teams.get_mut(team_id) {
             let recovery_time = Utc::now() + Duration::milliseconds(self.config.recovery_delay_ms as i64);
-            monitored.pending_recoveries.insert(member_id.to_string(), recovery_time);
+            monitored.pending_recoveries
+                .entry(member_id.to_string())
+                .or_insert_with(|| (recovery_time, reason.to_string()));
         }
     }
 
-    /// Execute pending recoveries
-    async fn execute_pending_recoveries(&mut self, app_handle: &AppHandle) {
+    /// Take the pending recoveries that are due
+    fn take_due_recoveries(&mut self) -> Vec<DueRecovery> {
         let now = Utc::now();
-        let mut recoveries_to_execute = Vec::new();
+        let mut due = Vec::new();
 
-        // Collect recoveries that are due
         for (team_id, monitored) in &mut self.monitored_teams {
-            let due_recoveries: Vec<String> = monitored.pending_recoveries
+            let due_members: Vec<String> = monitored.pending_recoveries
                 .iter()
-                .filter(|(_, scheduled)| **scheduled <= now)
+                .filter(|(_, (scheduled, _))| *scheduled <= now)
                 .map(|(member_id, _)| member_id.clone())
                 .collect();
 
-            for member_id in due_recoveries {
-                monitored.pending_recoveries.remove(&member_id);
-                recoveries_to_execute.push((
-                    team_id.clone(),
-                    monitored.project_path.clone(),
-                    member_id,
-                ));
+            for member_id in due_members {
+                if let Some((_, reason)) = monitored.pending_recoveries.remove(&member_id) {
+                    due.push((team_id.clone(), monitored.project_path.clone(), member_id, reason));
+                }
             }
         }
 
-        // Execute recoveries
-        for (team_id, project_path, member_id) in recoveries_to_execute {
-            self.trigger_recovery(&team_id, &project_path, &member_id, "Scheduled recovery", app_handle).await;
+        due
+    }
+
+    /// Take a member's recovery off the pending list to run it now
+    ///
+    /// A recovery the health check scheduled for the member is covered by this
+    /// one. Returns None if the team isn't monitored.
+    fn take_recovery(&mut self, team_id: &str, member_id: &str, reason: String) -> Option<DueRecovery> {
+        let monitored = self.monitored_teams.get_mut(team_id)?;
+        monitored.pending_recoveries.remove(member_id);
+        Some((team_id.to_string(), monitored.project_path.clone(), member_id.to_string(), reason))
+    }
+}
+
+impl HealthChecker {
+    /// Check health of the given (team_id, project_path) teams
+    async fn check_health(&self, teams: Vec<(String, String)>) -> Vec<MemberHealth> {
+        let mut all_health = Vec::new();
+        let now = Utc::now();
+
+        for (team_id, project_path) in teams {
+            let members = {
+                let mut manager = self.team_manager.lock().await;
+                manager.get_team(&project_path, &team_id).and_then(|team| {
+                    let members = manager.get_members_with_state(&project_path, &team_id)?;
+                    Ok((members, team.state.last_active))
+                })
+            };
+            let Ok((members, team_last_active)) = members else {
+                continue;
+            };
+
+            for member in members {
+                let terminal_alive = match &member.terminal_id {
+                    Some(terminal_id) => self.is_terminal_alive(terminal_id).await,
+                    None => false,
+                };
+                // Members without a heartbeat yet are timed from the team's last activity (e.g. their launch)
+                let last_seen = member.last_heartbeat.unwrap_or(team_last_active);
+                let failure_reason = match member.status {
+                    MemberStatus::Failed => Some("Agent reported failure".to_string()),
+                    MemberStatus::Active if member.terminal_id.is_some() && !terminal_alive => {
+                        let terminal_id = member.terminal_id.as_deref().unwrap_or_default();
+                        let cause = self
+                            .exit_cause(&project_path, terminal_id, member.claude_session_id.as_deref())
+                            .await;
+                        Some(match cause {
+                            Some(failure) => format!("Agent process exited: {}", failure.description),
+                            None => "Agent process exited".to_string(),
+                        })
+                    }
+                    MemberStatus::Active if self.is_heartbeat_stale(last_seen, now) => Some(format!(
+                        "No heartbeat for {}s",
+                        self.config.heartbeat_timeout_secs
+                    )),
+                    _ => None,
+                };
+
+                all_health.push(MemberHealth {
+                    member_id: member.id,
+                    team_id: team_id.clone(),
+                    is_healthy: failure_reason.is_none(),
+                    last_heartbeat: member.last_heartbeat,
+                    terminal_alive,
+                    failure_detected: failure_reason.is_some(),
+                    failure_reason,
+                });
+            }
         }
+
+        all_health
+    }
+
+    /// Whether the member's session is still running. Terminals that aren't
+    /// Claude sessions started by the app can't be checked and count as alive.
+    async fn is_terminal_alive(&self, terminal_id: &str) -> bool {
+        match self.processes.lock().await.get_session(terminal_id).await {
+            Some(session) => crate::process_reaper::is_running(session.pid),
+            None => true,
+        }
+    }
+
+    /// Why an exited session failed, from the end of its transcript
+    async fn exit_cause(&self, project_path: &str, terminal_id: &str, claude_session_id: Option<&str>) -> Option<FailureMatch> {
+        let session = self.processes.lock().await.get_session(terminal_id).await?;
+        let claude_session_id = session.session_id.as_deref().or(claude_session_id)?;
+        let path = crate::claude_transcripts::claude_projects_dir()
+            .join(crate::project_identity::claude_project_dir_name(&session.working_dir))
+            .join(format!("{}.jsonl", claude_session_id));
+        let settings = crate::config::for_project(project_path).failure_patterns;
+        if !settings.enabled || !path.is_file() {
+            return None;
+        }
+        failure_patterns::scan_transcript(&path, &settings).unwrap_or_else(|e| {
+            eprintln!("[Watchdog] {}", e);
+            None
+        })
+    }
+
+    /// Check if a member was last seen longer ago than the heartbeat timeout
+    fn is_heartbeat_stale(&self, last_seen: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        now.signed_duration_since(last_seen) > Duration::seconds(self.config.heartbeat_timeout_secs as i64)
     }
 
     /// Trigger recovery for a specific member
     async fn trigger_recovery(
-        &mut self,
+        &self,
         team_id: &str,
         project_path: &str,
         member_id: &str,
@@ -210,24 +336,22 @@ impl RecoveryWatchdog {
             }
         };
 
-        // Check if auto-recovery is enabled
-        if !team.config.auto_recovery {
+        // Check auto-recovery and max recovery attempts
+        if !team.recovery_allowed(member_id) {
+            if team.config.auto_recovery {
+                eprintln!("Max recovery attempts reached for member {}", member_id);
+            }
             return;
         }
 
-        // Check max recovery attempts
-        let member = team.config.workers.iter()
-            .find(|w| w.id == member_id)
-            .or_else(|| if team.config.orchestrator.id == member_id {
-                Some(&team.config.orchestrator)
-            } else {
-                None
-            });
-
-        if let Some(member) = member {
-            if member.failure_count >= team.config.max_recovery_attempts {
-                eprintln!("Max recovery attempts reached for member {}", member_id);
-                return;
+        // Stop what is left of the failed session (e.g. an agent that stopped sending heartbeats)
+        // Only members the app launched get their replacement launched too
+        let mut launched = false;
+        if let Some(terminal_id) = team.state.members.get(member_id).and_then(|s| s.terminal_id.as_ref()) {
+            let processes = self.processes.lock().await;
+            if processes.has_session(terminal_id).await {
+                launched = true;
+                let _ = processes.terminate_session(terminal_id).await;
             }
         }
 
@@ -237,16 +361,27 @@ impl RecoveryWatchdog {
             return;
         }
 
-        // Get recovery context before creating replacement
-        let context = manager.build_recovery_context(project_path, team_id, member_id).ok();
+        // Note what the failed member was doing before its replacement takes its place
+        let inputs = manager.recovery_inputs(project_path, team_id, member_id);
+        let replacement = manager.create_replacement_member(project_path, team_id, member_id);
+        drop(manager);
+
+        // Reading its transcript and git status doesn't need the team manager
+        let context = match inputs {
+            Ok(inputs) => crate::recovery_context::gather(inputs).await.ok(),
+            Err(e) => {
+                eprintln!("Failed to read recovery context: {}", e);
+                None
+            }
+        };
         let context_summary = context.as_ref().map(|c| RecoveryContextSummary {
             progress: c.progress,
             artifacts: c.artifacts.clone(),
             current_step: c.current_step.clone(),
         });
 
-        // Create replacement
-        let replacement = match manager.create_replacement_member(project_path, team_id, member_id) {
+        let mut manager = self.team_manager.lock().await;
+        let replacement = match replacement {
             Ok(r) => r,
             Err(e) => {
                 eprintln!("Failed to create replacement member: {}", e);
@@ -279,6 +414,16 @@ impl RecoveryWatchdog {
 
         drop(manager);
 
+        // Start the replacement with what the failed member had done
+        if launched {
+            let prompt = context.as_ref()
+                .map(|c| c.resume_instructions.clone())
+                .unwrap_or_else(|| "You are replacing a failed agent. Check for pending tasks and continue work.".to_string());
+            if let Err(e) = crate::team_launcher::launch_replacement(app_handle, project_path, team_id, &replacement.id, &prompt).await {
+                eprintln!("Failed to start replacement member {}: {}", replacement.id, e);
+            }
+        }
+
         // Emit recovery notification to frontend
         let notification = RecoveryNotification {
             team_id: team_id.to_string(),
@@ -291,10 +436,14 @@ impl RecoveryWatchdog {
         };
 
         let _ = app_handle.emit("recovery-event", &notification);
+        crate::ipc_server::publish_event(app_handle, crate::ipc_server::IpcEvent::TeamRecovery(notification)).await;
     }
 }
 
 /// Main watchdog loop
+///
+/// The watchdog lock only guards its own bookkeeping: checks and recoveries
+/// run on a `HealthChecker` taken out of it, after the lock is released.
 async fn run_watchdog_loop(
     watchdog: Arc<RwLock<RecoveryWatchdog>>,
     mut rx: mpsc::Receiver<WatchdogCommand>,
@@ -310,20 +459,31 @@ async fn run_watchdog_loop(
     loop {
         tokio::select! {
             _ = interval.tick() => {
-                let mut wd = watchdog.write().await;
-                if wd.config.enabled {
-                    // Check health
-                    let health_reports = wd.check_health().await;
-
-                    // Schedule recoveries for unhealthy members
-                    for health in health_reports {
-                        if health.failure_detected && health.failure_reason.is_some() {
-                            wd.schedule_recovery(&health.team_id, &health.member_id);
-                        }
+                let (checker, teams) = {
+                    let wd = watchdog.read().await;
+                    if !wd.config.enabled {
+                        continue;
                     }
+                    (wd.checker(), wd.monitored())
+                };
+
+                // Check health
+                let checked_at = Utc::now();
+                let health_reports = checker.check_health(teams).await;
+                if !health_reports.is_empty() {
+                    let _ = app_handle.emit("member-health", &health_reports);
+                }
+
+                // Schedule recoveries for unhealthy members and take the due ones
+                let due = {
+                    let mut wd = watchdog.write().await;
+                    wd.record_health(&health_reports, checked_at);
+                    wd.take_due_recoveries()
+                };
 
-                    // Execute pending recoveries
-                    wd.execute_pending_recoveries(&app_handle).await;
+                // Execute pending recoveries
+                for (team_id, project_path, member_id, reason) in due {
+                    checker.trigger_recovery(&team_id, &project_path, &member_id, &reason, &app_handle).await;
                 }
             }
 
@@ -344,15 +504,20 @@ async fn run_watchdog_loop(
                     }
 
                     WatchdogCommand::TriggerRecovery { team_id, member_id, reason } => {
-                        let mut wd = watchdog.write().await;
-                        if let Some(monitored) = wd.monitored_teams.get(&team_id) {
-                            let project_path = monitored.project_path.clone();
-                            wd.trigger_recovery(&team_id, &project_path, &member_id, &reason, &app_handle).await;
+                        let recovery = {
+                            let mut wd = watchdog.write().await;
+                            wd.take_recovery(&team_id, &member_id, reason).map(|recovery| (wd.checker(), recovery))
+                        };
+                        if let Some((checker, (team_id, project_path, member_id, reason))) = recovery {
+                            checker.trigger_recovery(&team_id, &project_path, &member_id, &reason, &app_handle).await;
                         }
                     }
 
                     WatchdogCommand::UpdateConfig(config) => {
                         let mut wd = watchdog.write().await;
+                        if config.check_interval_secs != wd.config.check_interval_secs {
+                            interval = time::interval(time::Duration::from_secs(config.check_interval_secs.max(1)));
+                        }
                         wd.config = config;
                     }
 
@@ -365,6 +530,84 @@ async fn run_watchdog_loop(
     }
 }
 
+/// Record output of a member session as its heartbeat
+async fn record_activity(team_manager: &SharedTeamManager, project_path: &str, team_id: &str, member_id: &str) {
+    if let Err(e) = team_manager.lock().await.record_heartbeat(project_path, team_id, member_id) {
+        eprintln!("[Watchdog] Failed to record activity of member {}: {}", member_id, e);
+    }
+}
+
+/// Follow a member session's output until the session ends or a `recover`
+/// rule fires: output counts as a heartbeat and goes through the failure
+/// pattern engine
+pub fn watch_member_output(
+    app_handle: AppHandle,
+    project_path: String,
+    team_id: String,
+    member_id: String,
+    mut events: broadcast::Receiver<ClaudeEvent>,
+    mut stderr: broadcast::Receiver<String>,
+) {
+    let settings = crate::config::for_project(&project_path).failure_patterns;
+    let team_manager = app_handle.state::<SharedTeamManager>().inner().clone();
+
+    tauri::async_runtime::spawn(async move {
+        let mut detector = settings.enabled.then(|| FailureDetector::new(&settings));
+        let mut stderr_open = true;
+        let mut last_activity: Option<time::Instant> = None;
+
+        loop {
+            let failure = tokio::select! {
+                event = events.recv() => match event {
+                    Ok(event) => {
+                        let due = last_activity
+                            .is_none_or(|at| at.elapsed() >= time::Duration::from_secs(ACTIVITY_HEARTBEAT_SECS));
+                        if due {
+                            record_activity(&team_manager, &project_path, &team_id, &member_id).await;
+                            last_activity = Some(time::Instant::now());
+                        }
+                        detector.as_mut().and_then(|d| d.observe(&event))
+                    }
+                    Err(RecvError::Lagged(_)) => None,
+                    Err(RecvError::Closed) => break,
+                },
+                line = stderr.recv(), if stderr_open => match line {
+                    Ok(line) => detector.as_mut().and_then(|d| d.observe_stderr(&line)),
+                    Err(RecvError::Lagged(_)) => None,
+                    Err(RecvError::Closed) => {
+                        stderr_open = false;
+                        None
+                    }
+                },
+            };
+            let Some(failure) = failure else {
+                continue;
+            };
+
+            eprintln!("[Watchdog] Member {} of team {}: {} ({})", member_id, team_id, failure.description, failure.rule_id);
+            let recover = failure.severity == FailureSeverity::Recover;
+            let reason = failure.description.clone();
+            let _ = app_handle.emit("failure-detected", &FailureNotification {
+                team_id: team_id.clone(),
+                member_id: member_id.clone(),
+                failure,
+                timestamp: Utc::now(),
+            });
+
+            if recover {
+                let handle = app_handle.state::<SharedWatchdog>().inner().clone();
+                send_watchdog_command(&handle, WatchdogCommand::TriggerRecovery {
+                    team_id: team_id.clone(),
+                    member_id: member_id.clone(),
+                    reason,
+                })
+                .await;
+                break;
+            }
+        }
+    });
+}
+
 /// Shared watchdog state
 pub type SharedWatchdog = Arc<Mutex<Option<mpsc::Sender<WatchdogCommand>>>>;
 
@@ -377,9 +620,10 @@ pub fn create_watchdog_handle() -> SharedWatchdog {
 pub async fn start_watchdog(
     handle: SharedWatchdog,
     team_manager: SharedTeamManager,
+    processes: SharedClaudeProcessManager,
     app_handle: AppHandle,
 ) {
-    let watchdog = RecoveryWatchdog::new(team_manager);
+    let watchdog = RecoveryWatchdog::new(team_manager, processes);
     let sender = watchdog.start(app_handle);
 
     let mut guard = handle.lock().await;
@@ -395,3 +639,60 @@ pub async fn send_watchdog_command(handle: &SharedWatchdog, cmd: WatchdogCommand
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::team_manager::{CreateTeamInput, MemberInput, TeamManager};
+    use crate::team_storage::TeamStorage;
+
+    #[tokio::test]
+    async fn test_member_output_counts_as_heartbeat() {
+        crate::db::use_test_db();
+        crate::project_identity::use_test_mapping();
+        let project = "/no/such/watchdog-activity-project";
+        let mut manager = TeamManager::new().unwrap();
+        let team = manager
+            .create_team(CreateTeamInput {
+                name: "activity-test".to_string(),
+                project_path: project.to_string(),
+                auto_recovery: true,
+                max_recovery_attempts: 2,
+                recovery_delay_ms: 0,
+                orchestrator: None,
+                members: vec![MemberInput {
+                    role: "dev".to_string(),
+                    agent_type: "dev-agent".to_string(),
+                    launch: None,
+                }],
+                description: None,
+                tags: Vec::new(),
+            })
+            .unwrap();
+        let team_id = team.config.id.clone();
+        let member_id = team.config.workers[0].id.clone();
+
+        // An active member last heard from long ago
+        let storage = TeamStorage::new().unwrap();
+        let (_, mut state) = storage.get_team(project, &team_id).unwrap();
+        let member = state.members.get_mut(&member_id).unwrap();
+        member.status = MemberStatus::Active;
+        member.last_heartbeat = Some(Utc::now() - Duration::minutes(10));
+        storage.save_state(project, &state).unwrap();
+
+        let checker = HealthChecker {
+            config: WatchdogConfig::default(),
+            team_manager: Arc::new(Mutex::new(manager)),
+            processes: crate::claude_process::create_process_manager(),
+        };
+        let member_health = |reports: Vec<MemberHealth>| {
+            reports.into_iter().find(|h| h.member_id == member_id).unwrap()
+        };
+        let teams = vec![(team_id.clone(), project.to_string())];
+
+        assert!(!member_health(checker.check_health(teams.clone()).await).is_healthy);
+
+        record_activity(&checker.team_manager, project, &team_id, &member_id).await;
+        assert!(member_health(checker.check_health(teams).await).is_healthy);
+    }
+}
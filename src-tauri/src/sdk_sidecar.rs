@@ -4,13 +4,33 @@
 //! The sidecar provides a WebSocket interface to the Claude Agent SDK.
 
 use std::sync::Arc;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_shell::ShellExt;
 use tokio::sync::Mutex;
 
-const SIDECAR_PORT: u16 = 17433;
+use crate::process_reaper;
+use crate::session_tracker::SharedSessionTracker;
+
 const SIDECAR_NAME: &str = "agent-sdk-sidecar";
 
+/// Port from the `ports.sidecar` setting
+fn sidecar_port() -> u16 {
+    crate::config::ports().sidecar
+}
+
+/// Record the sidecar for orphan cleanup, or forget it once it has exited
+fn track(app: &AppHandle, pid: u32, running: bool) {
+    let tracker = app.state::<SharedSessionTracker>();
+    let Ok(mut tracker) = tracker.lock() else {
+        return;
+    };
+    if running {
+        tracker.add_service(SIDECAR_NAME, pid);
+    } else {
+        tracker.remove_service(SIDECAR_NAME, pid);
+    }
+}
+
 /// State for managing the sidecar process
 pub struct SidecarState {
     /// Process ID if running
@@ -49,7 +69,7 @@ pub async fn start_sdk_sidecar(
 
     // Already running?
     if sidecar.pid.is_some() {
-        return Ok(sidecar.port.unwrap_or(SIDECAR_PORT));
+        return Ok(sidecar.port.unwrap_or(sidecar_port()));
     }
 
     // Already starting?
@@ -60,14 +80,15 @@ pub async fn start_sdk_sidecar(
     sidecar.starting = true;
     drop(sidecar); // Release lock during spawn
 
-    println!("[SdkSidecar] Starting Agent SDK sidecar on port {}", SIDECAR_PORT);
+    let port = sidecar_port();
+    println!("[SdkSidecar] Starting Agent SDK sidecar on port {}", port);
 
     // Spawn the sidecar process
     let shell = app.shell();
     let command = shell
         .sidecar(SIDECAR_NAME)
         .map_err(|e| format!("Failed to create sidecar command: {}", e))?
-        .env("SIDECAR_PORT", SIDECAR_PORT.to_string());
+        .env("SIDECAR_PORT", port.to_string());
 
     let (mut rx, child) = command
         .spawn()
@@ -75,11 +96,12 @@ pub async fn start_sdk_sidecar(
 
     let pid = child.pid();
     println!("[SdkSidecar] Sidecar started with PID: {}", pid);
+    track(&app, pid, true);
 
     // Update state
     let mut sidecar = state.lock().await;
     sidecar.pid = Some(pid);
-    sidecar.port = Some(SIDECAR_PORT);
+    sidecar.port = Some(port);
     sidecar.starting = false;
 
     // Spawn a task to monitor the process output and handle auto-restart
@@ -100,6 +122,7 @@ pub async fn start_sdk_sidecar(
                 }
                 CommandEvent::Terminated(payload) => {
                     println!("[SdkSidecar] Process terminated: {:?}", payload);
+                    track(&app_clone, pid, false);
 
                     // Reset state
                     {
@@ -139,7 +162,7 @@ pub async fn start_sdk_sidecar(
     // Give the sidecar a moment to start
     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
-    Ok(SIDECAR_PORT)
+    Ok(port)
 }
 
 /// Stop the Agent SDK sidecar process
@@ -152,23 +175,8 @@ pub async fn stop_sdk_sidecar(
     if let Some(pid) = sidecar.pid.take() {
         println!("[SdkSidecar] Stopping sidecar (PID: {})", pid);
 
-        // Kill the process
-        #[cfg(unix)]
-        {
-            use std::process::Command;
-            let _ = Command::new("kill")
-                .arg("-TERM")
-                .arg(pid.to_string())
-                .output();
-        }
-
-        #[cfg(windows)]
-        {
-            use std::process::Command;
-            let _ = Command::new("taskkill")
-                .args(["/PID", &pid.to_string(), "/F"])
-                .output();
-        }
+        // SIGTERM, then SIGKILL if it is still running after the grace period
+        process_reaper::terminate_in_background(pid);
 
         sidecar.port = None;
         println!("[SdkSidecar] Sidecar stopped");
@@ -208,20 +216,21 @@ async fn restart_sidecar(
 
     // Already running or starting?
     if sidecar.pid.is_some() || sidecar.starting {
-        return Ok(sidecar.port.unwrap_or(SIDECAR_PORT));
+        return Ok(sidecar.port.unwrap_or(sidecar_port()));
     }
 
     sidecar.starting = true;
     drop(sidecar);
 
-    println!("[SdkSidecar] Restarting sidecar on port {}", SIDECAR_PORT);
+    let port = sidecar_port();
+    println!("[SdkSidecar] Restarting sidecar on port {}", port);
 
     // Spawn the sidecar process
     let shell = app.shell();
     let command = shell
         .sidecar(SIDECAR_NAME)
         .map_err(|e| format!("Failed to create sidecar command: {}", e))?
-        .env("SIDECAR_PORT", SIDECAR_PORT.to_string());
+        .env("SIDECAR_PORT", port.to_string());
 
     let (mut rx, child) = command
         .spawn()
@@ -229,16 +238,17 @@ async fn restart_sidecar(
 
     let pid = child.pid();
     println!("[SdkSidecar] Sidecar restarted with PID: {}", pid);
+    track(app, pid, true);
 
     // Update state
     let mut sidecar = state.lock().await;
     sidecar.pid = Some(pid);
-    sidecar.port = Some(SIDECAR_PORT);
+    sidecar.port = Some(port);
     sidecar.starting = false;
 
     // Monitor the new process
     let state_clone = state.clone();
-    let _app_clone = app.clone(); // Keep for potential future use
+    let app_clone = app.clone();
     tauri::async_runtime::spawn(async move {
         use tauri_plugin_shell::process::CommandEvent;
 
@@ -254,6 +264,7 @@ async fn restart_sidecar(
                 }
                 CommandEvent::Terminated(payload) => {
                     println!("[SdkSidecar] Process terminated: {:?}", payload);
+                    track(&app_clone, pid, false);
                     let mut sidecar = state_clone.lock().await;
                     sidecar.pid = None;
                     sidecar.port = None;
@@ -268,7 +279,7 @@ async fn restart_sidecar(
 
     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
-    Ok(SIDECAR_PORT)
+    Ok(port)
 }
 
 /// Ensure sidecar is running, starting it if necessary
@@ -280,7 +291,7 @@ pub async fn ensure_sdk_sidecar(
     let sidecar = state.lock().await;
 
     if sidecar.pid.is_some() {
-        return Ok(sidecar.port.unwrap_or(SIDECAR_PORT));
+        return Ok(sidecar.port.unwrap_or(sidecar_port()));
     }
 
     drop(sidecar);
@@ -1,13 +1,22 @@
 //! Session persistence for role-to-Claude-session mapping
 //!
 //! Enables Agent Manager to save and resume Claude Code sessions by role.
-//! Uses a simple JSON file at ~/.sidstack/agent-manager/role-sessions.json
+//! One JSON file per project at
+//! ~/.sidstack/agent-manager/role-sessions/{project_id}.json, keeping the
+//! previous sessions of each role as history.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
 use std::path::PathBuf;
 
+use crate::persist;
+use crate::project_identity;
+
+/// Previous sessions kept per role
+const MAX_ROLE_HISTORY: usize = 20;
+/// Sessions idle for longer than this are reported as invalid
+pub const SESSION_VALID_HOURS: u64 = 7 * 24;
+
 /// Information about a Claude Code session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -31,62 +40,130 @@ pub struct RoleSessionMapping {
     pub saved_at: String,
     /// Role name to session info mapping
     pub roles: HashMap<String, SessionInfo>,
+    /// Role name to previous sessions, newest first
+    #[serde(default)]
+    pub history: HashMap<String, Vec<SessionInfo>>,
+}
+
+impl RoleSessionMapping {
+    pub fn new(project_path: String, roles: HashMap<String, SessionInfo>) -> Self {
+        Self {
+            project_path,
+            saved_at: chrono::Utc::now().to_rfc3339(),
+            roles,
+            history: HashMap::new(),
+        }
+    }
+
+    /// Roles whose current session is missing or idle past `max_age_hours`
+    pub fn invalid_roles(&self, max_age_hours: u64) -> Vec<String> {
+        let mut invalid: Vec<String> = self
+            .roles
+            .iter()
+            .filter(|(_, info)| !crate::claude_session::is_session_valid(&info.session_id, max_age_hours))
+            .map(|(role, _)| role.clone())
+            .collect();
+        invalid.sort();
+        invalid
+    }
+
+    /// Record `previous` in a role's history (newest first, deduplicated)
+    fn push_history(&mut self, role: &str, previous: SessionInfo) {
+        let history = self.history.entry(role.to_string()).or_default();
+        history.retain(|info| info.session_id != previous.session_id);
+        history.insert(0, previous);
+        history.truncate(MAX_ROLE_HISTORY);
+    }
 }
 
-/// Get the storage path for role-sessions.json
-fn get_storage_path() -> PathBuf {
+/// Directory holding one mapping file per project
+pub(crate) fn storage_dir() -> PathBuf {
+    let home = dirs::home_dir().expect("No home directory found");
+    home.join(".sidstack/agent-manager/role-sessions")
+}
+
+/// Get the storage path for a project's mapping
+pub(crate) fn get_storage_path(project_path: &str) -> PathBuf {
+    storage_dir().join(format!("{}.json", project_identity::project_id(project_path)))
+}
+
+/// The single global file used before mappings were kept per project
+fn legacy_storage_path() -> PathBuf {
     let home = dirs::home_dir().expect("No home directory found");
     home.join(".sidstack/agent-manager/role-sessions.json")
 }
 
-/// Save role-session mapping to disk
-///
-/// Creates the directory structure if it doesn't exist.
-pub fn save_role_sessions(mapping: &RoleSessionMapping) -> Result<(), String> {
-    let path = get_storage_path();
+/// Move the legacy global mapping into its project's file (once)
+fn migrate_legacy_file() -> Result<(), String> {
+    let legacy = legacy_storage_path();
+    if !legacy.exists() {
+        return Ok(());
+    }
 
-    // Ensure directory exists
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let mapping: RoleSessionMapping = persist::read_json(&legacy)
+        .map_err(|e| format!("Failed to read file: {}", e))?
+        .ok_or_else(|| "Legacy role-session file disappeared".to_string())?;
+
+    if !get_storage_path(&mapping.project_path).exists() {
+        write_mapping(&mapping)?;
     }
+    persist::remove(&legacy).map_err(|e| format!("Failed to delete file: {}", e))
+}
 
-    let json =
-        serde_json::to_string_pretty(mapping).map_err(|e| format!("Failed to serialize: {}", e))?;
+fn read_mapping(project_path: &str) -> Result<Option<RoleSessionMapping>, String> {
+    let path = get_storage_path(project_path);
 
-    fs::write(&path, json).map_err(|e| format!("Failed to write file: {}", e))?;
+    persist::read_json(&path).map_err(|e| format!("Failed to read file: {}", e))
+}
 
-    Ok(())
+fn write_mapping(mapping: &RoleSessionMapping) -> Result<(), String> {
+    let path = get_storage_path(&mapping.project_path);
+
+    persist::write_json(&path, mapping).map_err(|e| format!("Failed to write file: {}", e))
 }
 
-/// Load role-session mapping from disk
+/// Save role-session mapping to disk
 ///
-/// Returns None if the file doesn't exist.
-pub fn load_role_sessions() -> Result<Option<RoleSessionMapping>, String> {
-    let path = get_storage_path();
-
-    if !path.exists() {
-        return Ok(None);
+/// Replaces the project's current roles; sessions that are replaced or whose
+/// role is dropped move to that role's history.
+pub fn save_role_sessions(mapping: &RoleSessionMapping) -> Result<(), String> {
+    migrate_legacy_file()?;
+
+    let mut merged = mapping.clone();
+    if let Some(existing) = read_mapping(&mapping.project_path)? {
+        for (role, history) in existing.history {
+            merged.history.entry(role).or_insert(history);
+        }
+        for (role, previous) in existing.roles {
+            let replaced = merged
+                .roles
+                .get(&role)
+                .is_none_or(|current| current.session_id != previous.session_id);
+            if replaced {
+                merged.push_history(&role, previous);
+            }
+        }
     }
 
-    let json = fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))?;
-
-    let mapping: RoleSessionMapping =
-        serde_json::from_str(&json).map_err(|e| format!("Failed to parse JSON: {}", e))?;
-
-    Ok(Some(mapping))
+    write_mapping(&merged)
 }
 
-/// Clear saved role-session mapping
+/// Load a project's role-session mapping from disk
 ///
-/// Removes the role-sessions.json file if it exists.
-pub fn clear_role_sessions() -> Result<(), String> {
-    let path = get_storage_path();
+/// Returns None if nothing has been saved for the project.
+pub fn load_role_sessions(project_path: &str) -> Result<Option<RoleSessionMapping>, String> {
+    migrate_legacy_file()?;
+    read_mapping(project_path)
+}
 
-    if path.exists() {
-        fs::remove_file(&path).map_err(|e| format!("Failed to delete file: {}", e))?;
-    }
+/// Clear a project's saved role-session mapping
+///
+/// Removes the project's file (history included) if it exists.
+pub fn clear_role_sessions(project_path: &str) -> Result<(), String> {
+    migrate_legacy_file()?;
+    let path = get_storage_path(project_path);
 
-    Ok(())
+    persist::remove(&path).map_err(|e| format!("Failed to delete file: {}", e))
 }
 
 #[cfg(test)]
@@ -122,10 +199,27 @@ mod tests {
             project_path: "/test/project".to_string(),
             saved_at: "2024-12-20T15:30:00Z".to_string(),
             roles,
+            history: HashMap::new(),
         };
 
         let json = serde_json::to_string_pretty(&mapping).unwrap();
         assert!(json.contains("projectPath"));
         assert!(json.contains("orchestrator"));
     }
+
+    #[test]
+    fn test_push_history() {
+        let info = |id: &str| SessionInfo {
+            session_id: id.to_string(),
+            session_name: None,
+            last_active: "2024-12-20T15:30:00Z".to_string(),
+        };
+        let mut mapping = RoleSessionMapping::new("/test/project".to_string(), HashMap::new());
+        mapping.push_history("dev", info("a"));
+        mapping.push_history("dev", info("b"));
+        mapping.push_history("dev", info("a"));
+
+        let ids: Vec<&str> = mapping.history["dev"].iter().map(|i| i.session_id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+    }
 }
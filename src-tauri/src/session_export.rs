@@ -0,0 +1,717 @@
+//! Session Export
+//!
+//! Export formats for stored sessions, beyond the Markdown in `session_storage`:
+//! - HTML: self-contained transcript with ANSI colors rendered
+//! - JSONL: one event per line (session metadata, then output chunks)
+//! - Bundle: `.sidstack-session` zip with meta, logs, the Claude transcript
+//!   and the git diff at export time, importable on another machine
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::session_storage::{self, SessionMeta, SessionStatus};
+use crate::zip_archive::{ZipReader, ZipWriter};
+
+pub const BUNDLE_EXTENSION: &str = "sidstack-session";
+const BUNDLE_FORMAT: &str = "sidstack-session";
+const BUNDLE_VERSION: u32 = 1;
+
+// Bundle entry names
+const MANIFEST_ENTRY: &str = "manifest.json";
+const META_ENTRY: &str = "meta.json";
+const ACTIVE_LOG_ENTRY: &str = "logs/active.log";
+const TRANSCRIPT_ENTRY: &str = "transcript.jsonl";
+const GIT_DIFF_ENTRY: &str = "git.diff";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Text export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Html,
+    Jsonl,
+}
+
+/// Bundle manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub session_id: String,
+    pub project_path: String,
+    pub claude_session_id: Option<String>,
+    /// Commit checked out when the bundle was made
+    pub git_head: Option<String>,
+    /// Rotated log segment numbers included under logs/
+    pub log_segments: Vec<u32>,
+    pub has_transcript: bool,
+    pub has_git_diff: bool,
+}
+
+/// A JSONL export event
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ExportEvent<'a> {
+    Session(&'a SessionMeta),
+    /// Lines written by one `append_output` call (they share a timestamp)
+    #[serde(rename_all = "camelCase")]
+    Output {
+        timestamp: Option<String>,
+        first_line: usize,
+        /// Raw text, ANSI escapes included
+        text: String,
+        /// Text with ANSI escapes removed
+        plain: String,
+    },
+}
+
+// ============================================================================
+// Text Exports
+// ============================================================================
+
+/// Export a session in the given format
+pub fn export(project_path: &str, session_id: &str, format: ExportFormat) -> Result<String, String> {
+    match format {
+        ExportFormat::Markdown => session_storage::export_session(project_path, session_id),
+        ExportFormat::Html => export_html(project_path, session_id),
+        ExportFormat::Jsonl => export_jsonl(project_path, session_id),
+    }
+}
+
+fn load_session(project_path: &str, session_id: &str) -> Result<(SessionMeta, Vec<String>), String> {
+    let meta = session_storage::get_session_meta(project_path, session_id)?
+        .ok_or_else(|| "Session not found".to_string())?;
+    let logs = session_storage::load_output(project_path, session_id, usize::MAX)?;
+    Ok((meta, logs))
+}
+
+/// Export a session as a self-contained HTML page
+pub fn export_html(project_path: &str, session_id: &str) -> Result<String, String> {
+    let (meta, logs) = load_session(project_path, session_id)?;
+    let title = meta.label();
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>Session: {}</title>\n", escape_html(title)));
+    html.push_str(HTML_STYLE);
+    html.push_str("</head>\n<body>\n<header>\n");
+    html.push_str(&format!("<h1>Session: {}</h1>\n<dl>\n", escape_html(title)));
+
+    let mut field = |name: &str, value: &str| {
+        html.push_str(&format!(
+            "<dt>{}</dt><dd>{}</dd>\n",
+            name,
+            escape_html(value)
+        ));
+    };
+    field("Session ID", &meta.session_id);
+    if let Some(summary) = &meta.summary {
+        field("Summary", summary);
+    }
+    field("Project", &meta.project_path);
+    if let Some(role) = &meta.role {
+        field("Role", role);
+    }
+    if let Some(claude_id) = &meta.claude_session_id {
+        field("Claude Session", claude_id);
+    }
+    field("Created", &meta.created_at.to_rfc3339());
+    field("Last Active", &meta.last_active_at.to_rfc3339());
+    field("Status", &format!("{:?}", meta.status));
+
+    html.push_str("</dl>\n</header>\n<pre class=\"terminal\">");
+
+    let mut renderer = AnsiRenderer::default();
+    for line in &logs {
+        let (timestamp, content) = session_storage::parse_log_line(line);
+        match timestamp {
+            Some(ts) => html.push_str(&format!(
+                "<span class=\"ts\" title=\"{0}\">{1}</span>",
+                escape_html(ts),
+                escape_html(ts.get(11..19).unwrap_or(ts))
+            )),
+            None => html.push_str("<span class=\"ts\"></span>"),
+        }
+        html.push_str(&renderer.render_line(content));
+        html.push('\n');
+    }
+
+    html.push_str("</pre>\n</body>\n</html>\n");
+    Ok(html)
+}
+
+/// Export a session as JSONL events
+pub fn export_jsonl(project_path: &str, session_id: &str) -> Result<String, String> {
+    let (meta, logs) = load_session(project_path, session_id)?;
+
+    let mut out = String::new();
+    let mut push = |event: &ExportEvent| -> Result<(), String> {
+        let line = serde_json::to_string(event)
+            .map_err(|e| format!("Failed to serialize event: {}", e))?;
+        out.push_str(&line);
+        out.push('\n');
+        Ok(())
+    };
+
+    push(&ExportEvent::Session(&meta))?;
+
+    // Group consecutive lines with the same timestamp back into one output event
+    let mut index = 0;
+    while index < logs.len() {
+        let (timestamp, _) = session_storage::parse_log_line(&logs[index]);
+        let first_line = index + 1;
+        let mut lines = Vec::new();
+        while index < logs.len() {
+            let (ts, content) = session_storage::parse_log_line(&logs[index]);
+            if ts != timestamp {
+                break;
+            }
+            lines.push(content);
+            index += 1;
+        }
+
+        let text = lines.join("\n");
+        push(&ExportEvent::Output {
+            timestamp: timestamp.map(String::from),
+            first_line,
+            plain: strip_ansi(&text),
+            text,
+        })?;
+    }
+
+    Ok(out)
+}
+
+// ============================================================================
+// Bundles
+// ============================================================================
+
+/// Write a `.sidstack-session` bundle
+///
+/// `output_path` may be a directory (the bundle is named after the session)
+/// or a file path. Returns the path written.
+pub fn export_bundle(project_path: &str, session_id: &str, output_path: &Path) -> Result<PathBuf, String> {
+    let meta = session_storage::get_session_meta(project_path, session_id)?
+        .ok_or_else(|| "Session not found".to_string())?;
+
+    let target = if output_path.is_dir() {
+        output_path.join(format!("{}.{}", session_id, BUNDLE_EXTENSION))
+    } else if output_path.extension().is_some_and(|ext| ext == BUNDLE_EXTENSION) {
+        output_path.to_path_buf()
+    } else {
+        output_path.with_extension(BUNDLE_EXTENSION)
+    };
+
+    let mut writer = ZipWriter::new(Vec::new());
+
+    let meta_json = serde_json::to_vec_pretty(&meta)
+        .map_err(|e| format!("Failed to serialize metadata: {}", e))?;
+    writer.add_file(META_ENTRY, &meta_json)?;
+
+    // Logs are copied as stored: the active segment plus gzipped rotated segments
+    let active = session_storage::log_file_path(project_path, session_id);
+    if active.exists() {
+        let data = fs::read(&active).map_err(|e| format!("Failed to read log: {}", e))?;
+        writer.add_file(ACTIVE_LOG_ENTRY, &data)?;
+    }
+    let mut log_segments = Vec::new();
+    for segment in session_storage::rotated_segments(project_path, session_id) {
+        let Some(index) = session_storage::segment_index(&segment, session_id) else {
+            continue;
+        };
+        let data = fs::read(&segment).map_err(|e| format!("Failed to read log segment: {}", e))?;
+        writer.add_file(&segment_entry(index), &data)?;
+        log_segments.push(index);
+    }
+
+    let transcript = meta
+        .claude_session_id
+        .as_deref()
+        .map(|id| crate::claude_process::claude_transcript_path(project_path, id))
+        .filter(|path| path.exists());
+    if let Some(path) = &transcript {
+        let data = fs::read(path).map_err(|e| format!("Failed to read Claude transcript: {}", e))?;
+        writer.add_file(TRANSCRIPT_ENTRY, &data)?;
+    }
+
+    let (git_head, git_diff) = git_snapshot(project_path);
+    if let Some(diff) = &git_diff {
+        writer.add_file(GIT_DIFF_ENTRY, diff.as_bytes())?;
+    }
+
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        session_id: meta.session_id.clone(),
+        project_path: meta.project_path.clone(),
+        claude_session_id: meta.claude_session_id.clone(),
+        git_head,
+        log_segments,
+        has_transcript: transcript.is_some(),
+        has_git_diff: git_diff.is_some(),
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    writer.add_file(MANIFEST_ENTRY, &manifest_json)?;
+
+    let bytes = writer.finish()?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    fs::write(&target, bytes).map_err(|e| format!("Failed to write bundle: {}", e))?;
+
+    Ok(target)
+}
+
+/// Restore a bundle into session storage
+///
+/// The session is restored under `project_path` if given, otherwise under the
+/// path it was exported from. The session always gets a new ID: ids from the
+/// bundle are untrusted and end up in file names. The git diff is
+/// informational and not applied.
+pub fn import_bundle(bundle_path: &Path, project_path: Option<&str>) -> Result<SessionMeta, String> {
+    let data = fs::read(bundle_path).map_err(|e| format!("Failed to read bundle: {}", e))?;
+    let reader = ZipReader::new(data)?;
+
+    let manifest: BundleManifest = read_json(&reader, MANIFEST_ENTRY)?;
+    if manifest.format != BUNDLE_FORMAT {
+        return Err(format!("Not a session bundle: {}", manifest.format));
+    }
+    if manifest.version > BUNDLE_VERSION {
+        return Err(format!(
+            "Bundle version {} is newer than supported version {}",
+            manifest.version, BUNDLE_VERSION
+        ));
+    }
+
+    let mut meta: SessionMeta = read_json(&reader, META_ENTRY)?;
+    let target_project = project_path.unwrap_or(&manifest.project_path).to_string();
+
+    if let Some(claude_id) = &meta.claude_session_id {
+        if uuid::Uuid::parse_str(claude_id).is_err() {
+            return Err(format!("Invalid Claude session id in bundle: {}", claude_id));
+        }
+    }
+    let session_id = uuid::Uuid::new_v4().to_string();
+
+    // Logs
+    let segments_dir = session_storage::log_file_path(&target_project, &session_id);
+    if let Some(dir) = segments_dir.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create session directory: {}", e))?;
+    }
+    for index in &manifest.log_segments {
+        let data = reader
+            .read(&segment_entry(*index))?
+            .ok_or_else(|| format!("Bundle is missing log segment {}", index))?;
+        let path = session_storage::segment_file_path(&target_project, &session_id, *index);
+        fs::write(&path, data).map_err(|e| format!("Failed to write log segment: {}", e))?;
+    }
+    if let Some(data) = reader.read(ACTIVE_LOG_ENTRY)? {
+        fs::write(&segments_dir, data).map_err(|e| format!("Failed to write log: {}", e))?;
+    }
+
+    // Claude transcript, unless this machine already has it
+    if let (Some(claude_id), Some(data)) = (&meta.claude_session_id, reader.read(TRANSCRIPT_ENTRY)?) {
+        let path = crate::claude_process::claude_transcript_path(&target_project, claude_id);
+        if !path.exists() {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)
+                    .map_err(|e| format!("Failed to create Claude project directory: {}", e))?;
+            }
+            fs::write(&path, data).map_err(|e| format!("Failed to write Claude transcript: {}", e))?;
+        }
+    }
+
+    meta.session_id = session_id;
+    meta.project_hash = session_storage::hash_project(&target_project);
+    meta.project_path = target_project;
+    // Imported sessions have no live terminal behind them
+    meta.status = SessionStatus::Saved;
+    meta.log_size_bytes = session_storage::session_log_size(&meta.project_path, &meta.session_id);
+    session_storage::save_session_meta(&meta)?;
+
+    if let Err(e) = crate::session_search::index_session(&meta.project_path, &meta.session_id) {
+        eprintln!("[SessionExport] Failed to index imported session: {}", e);
+    }
+
+    Ok(meta)
+}
+
+fn segment_entry(index: u32) -> String {
+    format!("logs/{}.log.gz", index)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(reader: &ZipReader, name: &str) -> Result<T, String> {
+    let data = reader
+        .read(name)?
+        .ok_or_else(|| format!("Bundle is missing {}", name))?;
+    serde_json::from_slice(&data).map_err(|e| format!("Failed to parse {}: {}", name, e))
+}
+
+/// HEAD commit and uncommitted diff (incl. untracked files) of the project
+fn git_snapshot(project_path: &str) -> (Option<String>, Option<String>) {
+    let Ok(repo) = git2::Repository::open(project_path) else {
+        return (None, None);
+    };
+
+    let head = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let head_id = head.as_ref().map(|c| c.id().to_string());
+    let head_tree = head.as_ref().and_then(|c| c.tree().ok());
+
+    let mut opts = git2::DiffOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+
+    let Ok(diff) = repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts)) else {
+        return (head_id, None);
+    };
+
+    let mut patch = String::new();
+    let printed = diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin());
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    });
+
+    if printed.is_err() || patch.is_empty() {
+        (head_id, None)
+    } else {
+        (head_id, Some(patch))
+    }
+}
+
+// ============================================================================
+// ANSI Rendering
+// ============================================================================
+
+const HTML_STYLE: &str = r#"<style>
+body { margin: 0; background: #1e1e1e; color: #d4d4d4; font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; }
+header { padding: 16px 24px; border-bottom: 1px solid #333; }
+h1 { font-size: 18px; margin: 0 0 8px; }
+dl { display: grid; grid-template-columns: max-content 1fr; gap: 2px 16px; margin: 0; font-size: 13px; }
+dt { color: #888; }
+dd { margin: 0; }
+pre.terminal { margin: 0; padding: 16px 24px; font: 13px/1.4 "SF Mono", Menlo, Consolas, monospace; white-space: pre-wrap; word-break: break-all; }
+.ts { color: #666; margin-right: 12px; user-select: none; }
+</style>
+"#;
+
+/// Standard 16-color palette (xterm defaults)
+const PALETTE: [&str; 16] = [
+    "#000000", "#cd3131", "#0dbc79", "#e5e510", "#2472c8", "#bc3fbc", "#11a8cd", "#e5e5e5",
+    "#666666", "#f14c4c", "#23d18b", "#f5f543", "#3b8eea", "#d670d6", "#29b8db", "#ffffff",
+];
+
+/// Current SGR text style
+#[derive(Debug, Clone, Default, PartialEq)]
+struct AnsiStyle {
+    fg: Option<String>,
+    bg: Option<String>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl AnsiStyle {
+    fn css(&self) -> String {
+        let mut css = Vec::new();
+        if let Some(fg) = &self.fg {
+            css.push(format!("color:{}", fg));
+        }
+        if let Some(bg) = &self.bg {
+            css.push(format!("background:{}", bg));
+        }
+        if self.bold {
+            css.push("font-weight:bold".to_string());
+        }
+        if self.dim {
+            css.push("opacity:0.7".to_string());
+        }
+        if self.italic {
+            css.push("font-style:italic".to_string());
+        }
+        if self.underline {
+            css.push("text-decoration:underline".to_string());
+        }
+        css.join(";")
+    }
+
+    /// Apply an SGR parameter list
+    fn apply(&mut self, params: &[u16]) {
+        if params.is_empty() {
+            *self = AnsiStyle::default();
+            return;
+        }
+        let mut i = 0;
+        while i < params.len() {
+            match params[i] {
+                0 => *self = AnsiStyle::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                n @ 30..=37 => self.fg = Some(PALETTE[(n - 30) as usize].to_string()),
+                n @ 90..=97 => self.fg = Some(PALETTE[(n - 90 + 8) as usize].to_string()),
+                n @ 40..=47 => self.bg = Some(PALETTE[(n - 40) as usize].to_string()),
+                n @ 100..=107 => self.bg = Some(PALETTE[(n - 100 + 8) as usize].to_string()),
+                39 => self.fg = None,
+                49 => self.bg = None,
+                n @ (38 | 48) => {
+                    let (color, used) = extended_color(&params[i + 1..]);
+                    if n == 38 {
+                        self.fg = color;
+                    } else {
+                        self.bg = color;
+                    }
+                    i += used;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+/// Parse `5;n` (256 colors) or `2;r;g;b` (truecolor), returning params consumed
+fn extended_color(params: &[u16]) -> (Option<String>, usize) {
+    match params {
+        [5, n, ..] => (Some(color_256(*n)), 2),
+        [2, r, g, b, ..] => (Some(format!("#{:02x}{:02x}{:02x}", r, g, b)), 4),
+        _ => (None, params.len()),
+    }
+}
+
+fn color_256(n: u16) -> String {
+    match n {
+        0..=15 => PALETTE[n as usize].to_string(),
+        16..=231 => {
+            let n = n - 16;
+            let level = |v: u16| if v == 0 { 0 } else { 55 + v * 40 };
+            format!("#{:02x}{:02x}{:02x}", level(n / 36), level((n / 6) % 6), level(n % 6))
+        }
+        _ => {
+            let gray = 8 + (n.min(255) - 232) * 10;
+            format!("#{:02x}{:02x}{:02x}", gray, gray, gray)
+        }
+    }
+}
+
+/// Renders terminal output to HTML, keeping SGR state across lines
+#[derive(Debug, Default)]
+struct AnsiRenderer {
+    style: AnsiStyle,
+}
+
+impl AnsiRenderer {
+    fn render_line(&mut self, line: &str) -> String {
+        let mut out = String::new();
+        let mut open = self.open_span(&mut out);
+
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1b' => match chars.next() {
+                    Some('[') => {
+                        let mut params = String::new();
+                        let mut terminator = None;
+                        for c in chars.by_ref() {
+                            if ('\x40'..='\x7e').contains(&c) {
+                                terminator = Some(c);
+                                break;
+                            }
+                            params.push(c);
+                        }
+                        if terminator == Some('m') {
+                            let parsed: Vec<u16> = params
+                                .split(';')
+                                .filter(|p| !p.is_empty())
+                                .map(|p| p.parse().unwrap_or(0))
+                                .collect();
+                            if open {
+                                out.push_str("</span>");
+                            }
+                            self.style.apply(&parsed);
+                            open = self.open_span(&mut out);
+                        }
+                    }
+                    // OSC: skip to BEL or ST
+                    Some(']') => {
+                        while let Some(c) = chars.next() {
+                            if c == '\x07' || (c == '\x1b' && chars.peek() == Some(&'\\')) {
+                                if c == '\x1b' {
+                                    chars.next();
+                                }
+                                break;
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                '\t' => out.push('\t'),
+                c if c.is_control() => {}
+                c => push_escaped(&mut out, c),
+            }
+        }
+
+        if open {
+            out.push_str("</span>");
+        }
+        out
+    }
+
+    fn open_span(&self, out: &mut String) -> bool {
+        if self.style == AnsiStyle::default() {
+            return false;
+        }
+        out.push_str(&format!("<span style=\"{}\">", self.style.css()));
+        true
+    }
+}
+
+/// Remove ANSI escape sequences and control characters
+pub fn strip_ansi(text: &str) -> String {
+    let re = regex::Regex::new(r"\x1b\[[0-9;?]*[\x40-\x7e]|\x1b\][^\x07\x1b]*(\x07|\x1b\\)|\x1b.").unwrap();
+    re.replace_all(text, "")
+        .chars()
+        .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+        .collect()
+}
+
+fn push_escaped(out: &mut String, c: char) {
+    match c {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        '"' => out.push_str("&quot;"),
+        '\'' => out.push_str("&#39;"),
+        c => out.push(c),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        push_escaped(&mut out, c);
+    }
+    out
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Tauri command: Export a session as a `.sidstack-session` bundle
+#[tauri::command]
+pub fn session_storage_export_bundle(
+    project_path: String,
+    session_id: String,
+    output_path: String,
+) -> Result<String, String> {
+    export_bundle(&project_path, &session_id, Path::new(&output_path))
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+/// Tauri command: Import a `.sidstack-session` bundle
+#[tauri::command]
+pub fn session_storage_import_bundle(
+    bundle_path: String,
+    project_path: Option<String>,
+) -> Result<SessionMeta, String> {
+    import_bundle(Path::new(&bundle_path), project_path.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi_to_html() {
+        let mut renderer = AnsiRenderer::default();
+        assert_eq!(
+            renderer.render_line("\x1b[1;31merror\x1b[0m: <bad>"),
+            "<span style=\"color:#cd3131;font-weight:bold\">error</span>: &lt;bad&gt;"
+        );
+
+        // Style carries over to the next line until reset
+        renderer.render_line("\x1b[38;5;196mred");
+        assert_eq!(renderer.render_line("still"), "<span style=\"color:#ff0000\">still</span>");
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[32mok\x1b[0m \x1b]0;title\x07done\r"), "ok done");
+    }
+
+    /// Copy of a bundle with its metadata replaced
+    fn with_meta(bundle: &Path, meta: &SessionMeta, out: &Path) {
+        let reader = ZipReader::new(fs::read(bundle).unwrap()).unwrap();
+        let mut writer = ZipWriter::new(Vec::new());
+        for name in [MANIFEST_ENTRY, ACTIVE_LOG_ENTRY] {
+            writer.add_file(name, &reader.read(name).unwrap().unwrap()).unwrap();
+        }
+        writer.add_file(META_ENTRY, &serde_json::to_vec(meta).unwrap()).unwrap();
+        fs::write(out, writer.finish().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_bundle_round_trip_assigns_fresh_ids() {
+        let project = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        session_storage::use_test_sessions_dir();
+        crate::project_identity::use_test_mapping();
+        crate::db::use_test_db();
+        let project_path = project.path().to_string_lossy().to_string();
+
+        let meta = session_storage::create_session("s1".to_string(), project_path.clone(), None, None).unwrap();
+        let log = session_storage::log_file_path(&project_path, "s1");
+        fs::create_dir_all(log.parent().unwrap()).unwrap();
+        fs::write(&log, "hello\n").unwrap();
+
+        let bundle = export_bundle(&project_path, "s1", out.path()).unwrap();
+        let imported = import_bundle(&bundle, None).unwrap();
+        assert_ne!(imported.session_id, "s1");
+        assert!(uuid::Uuid::parse_str(&imported.session_id).is_ok());
+        assert_eq!(
+            fs::read_to_string(session_storage::log_file_path(&project_path, &imported.session_id)).unwrap(),
+            "hello\n"
+        );
+
+        // Ids from the bundle never reach a file name
+        let forged = out.path().join("forged.sidstack-session");
+        let malicious = SessionMeta {
+            session_id: "../../escaped".to_string(),
+            ..meta.clone()
+        };
+        with_meta(&bundle, &malicious, &forged);
+        let imported = import_bundle(&forged, None).unwrap();
+        assert!(uuid::Uuid::parse_str(&imported.session_id).is_ok());
+        assert!(!session_storage::sessions_dir().parent().unwrap().join("escaped.log").exists());
+
+        let malicious = SessionMeta {
+            claude_session_id: Some("../../escaped".to_string()),
+            ..meta
+        };
+        with_meta(&bundle, &malicious, &forged);
+        assert!(import_bundle(&forged, None).is_err());
+    }
+}
@@ -0,0 +1,609 @@
+//! Session Log Search
+//!
+//! Full-text index over the terminal output logs of all stored sessions
+//! (SQLite FTS5, bundled with rusqlite).
+//!
+//! - Each log line is one row: `log_lines` holds its position, `log_fts` its text
+//! - `indexed_logs` remembers how far each log file has been indexed, so
+//!   `append_output` only indexes the new lines
+//! - Logs written before the index existed are picked up by `index_all`
+//! - Rotated (gzipped) segments are read only when a session is indexed from scratch
+//!
+//! Database: ~/.sidstack/agent-manager/sessions/search-index.db
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::session_storage::{self, SessionMeta};
+
+const INDEX_FILE: &str = "search-index.db";
+/// Default maximum number of hits
+const DEFAULT_LIMIT: usize = 50;
+/// Default number of context lines before and after a hit
+const DEFAULT_CONTEXT_LINES: usize = 2;
+
+/// Shared index connection, opened on first use
+static INDEX: Mutex<Option<SearchIndex>> = Mutex::new(None);
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Search parameters
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    /// Free text; every word must appear in the line
+    pub text: String,
+    /// Only search sessions of this project
+    pub project_path: Option<String>,
+    pub limit: usize,
+    /// Lines of context returned before and after each hit
+    pub context_lines: usize,
+}
+
+/// A matching log line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchHit {
+    pub session_id: String,
+    pub project_path: String,
+    pub role: Option<String>,
+    /// Metadata of the session (None if the session was deleted but its log kept)
+    pub session: Option<SessionMeta>,
+    /// 1-based line number in the session log
+    pub line_number: u64,
+    /// Timestamp the line was written
+    pub timestamp: Option<String>,
+    pub line: String,
+    /// Line with matches wrapped in `[` `]`
+    pub snippet: String,
+    /// BM25 score (higher is more relevant)
+    pub score: f64,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
+/// Raw hit before session metadata is attached
+struct IndexHit {
+    project_hash: String,
+    project_path: String,
+    session_id: String,
+    line_number: u64,
+    timestamp: Option<String>,
+    line: String,
+    snippet: String,
+    score: f64,
+}
+
+// ============================================================================
+// Index
+// ============================================================================
+
+/// FTS5 index over session logs
+pub struct SearchIndex {
+    conn: Connection,
+}
+
+impl SearchIndex {
+    /// Open (or create) an index database
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create index directory: {}", e))?;
+        }
+
+        let conn = Connection::open(path)
+            .map_err(|e| format!("Failed to open search index: {}", e))?;
+
+        conn.execute_batch(
+            r#"
+            PRAGMA journal_mode = WAL;
+
+            CREATE TABLE IF NOT EXISTS indexed_logs (
+                project_hash TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                project_path TEXT NOT NULL,
+                byte_offset INTEGER NOT NULL DEFAULT 0,
+                line_count INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (project_hash, session_id)
+            );
+
+            CREATE TABLE IF NOT EXISTS log_lines (
+                id INTEGER PRIMARY KEY,
+                project_hash TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                line_no INTEGER NOT NULL,
+                timestamp TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_log_lines_session
+                ON log_lines(project_hash, session_id, line_no);
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS log_fts USING fts5(content, tokenize = 'unicode61');
+            "#,
+        )
+        .map_err(|e| format!("Failed to initialize search index: {}", e))?;
+
+        Ok(Self { conn })
+    }
+
+    /// Index any lines appended to a session log since it was last indexed
+    ///
+    /// Rotated segments are only read when the session has no index state yet
+    /// (new index, or the log was replaced behind our back).
+    /// Returns the number of lines added
+    pub fn index_log(
+        &mut self,
+        project_path: &str,
+        session_id: &str,
+        log_path: &Path,
+        rotated: impl FnOnce() -> Vec<PathBuf>,
+    ) -> Result<usize, String> {
+        let project_hash = session_storage::hash_project(project_path);
+
+        let state: Option<(u64, u64)> = self
+            .conn
+            .query_row(
+                "SELECT byte_offset, line_count FROM indexed_logs WHERE project_hash = ?1 AND session_id = ?2",
+                params![project_hash, session_id],
+                |row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, i64>(1)? as u64)),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to read index state: {}", e))?;
+
+        let file = File::open(log_path).ok();
+        let len = file
+            .as_ref()
+            .and_then(|f| f.metadata().ok())
+            .map_or(0, |m| m.len());
+
+        // Log was truncated or replaced without a rotation - start over
+        let state = match state {
+            Some((offset, _)) if len < offset => {
+                self.remove_session(&project_hash, session_id)?;
+                None
+            }
+            other => other,
+        };
+
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let mut added = 0;
+
+        let (mut offset, mut line_count) = match state {
+            Some(state) => state,
+            None => {
+                let mut line_count = 0;
+                for segment in rotated() {
+                    let reader = session_storage::open_segment(&segment)?;
+                    let (lines, _) =
+                        insert_lines(&tx, &project_hash, session_id, reader, &mut line_count, false)?;
+                    added += lines;
+                }
+                (0, line_count)
+            }
+        };
+
+        if let Some(mut file) = file {
+            file.seek(SeekFrom::Start(offset))
+                .map_err(|e| format!("Failed to seek log: {}", e))?;
+            let (lines, bytes) = insert_lines(
+                &tx,
+                &project_hash,
+                session_id,
+                BufReader::new(file),
+                &mut line_count,
+                true,
+            )?;
+            added += lines;
+            offset += bytes;
+        }
+
+        tx.execute(
+            "INSERT INTO indexed_logs (project_hash, session_id, project_path, byte_offset, line_count)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(project_hash, session_id) DO UPDATE SET
+                project_path = excluded.project_path,
+                byte_offset = excluded.byte_offset,
+                line_count = excluded.line_count",
+            params![project_hash, session_id, project_path, offset as i64, line_count as i64],
+        )
+        .map_err(|e| format!("Failed to save index state: {}", e))?;
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit index: {}", e))?;
+
+        Ok(added)
+    }
+
+    /// The active log was rotated: keep line numbers, restart at its beginning
+    pub fn reset_offset(&mut self, project_hash: &str, session_id: &str) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE indexed_logs SET byte_offset = 0 WHERE project_hash = ?1 AND session_id = ?2",
+                params![project_hash, session_id],
+            )
+            .map(|_| ())
+            .map_err(|e| format!("Failed to update index state: {}", e))
+    }
+
+    /// Re-key a project's entries after its id (and possibly path) changed
+    pub fn rename_project(
+        &mut self,
+        old_hash: &str,
+        new_hash: &str,
+        new_path: Option<&str>,
+    ) -> Result<(), String> {
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        tx.execute(
+            "UPDATE log_lines SET project_hash = ?2 WHERE project_hash = ?1",
+            params![old_hash, new_hash],
+        )
+        .and_then(|_| {
+            tx.execute(
+                "UPDATE indexed_logs SET project_hash = ?2, project_path = COALESCE(?3, project_path)
+                 WHERE project_hash = ?1",
+                params![old_hash, new_hash, new_path],
+            )
+        })
+        .map_err(|e| format!("Failed to update index: {}", e))?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit index: {}", e))
+    }
+
+    /// Drop a session from the index
+    pub fn remove_session(&mut self, project_hash: &str, session_id: &str) -> Result<(), String> {
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        tx.execute(
+            "DELETE FROM log_fts WHERE rowid IN
+                (SELECT id FROM log_lines WHERE project_hash = ?1 AND session_id = ?2)",
+            params![project_hash, session_id],
+        )
+        .and_then(|_| {
+            tx.execute(
+                "DELETE FROM log_lines WHERE project_hash = ?1 AND session_id = ?2",
+                params![project_hash, session_id],
+            )
+        })
+        .and_then(|_| {
+            tx.execute(
+                "DELETE FROM indexed_logs WHERE project_hash = ?1 AND session_id = ?2",
+                params![project_hash, session_id],
+            )
+        })
+        .map_err(|e| format!("Failed to remove session from index: {}", e))?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit index: {}", e))
+    }
+
+    /// Run a ranked search
+    fn search_lines(&self, query: &SearchQuery) -> Result<Vec<IndexHit>, String> {
+        let Some(fts_query) = to_fts_query(&query.text) else {
+            return Ok(Vec::new());
+        };
+        let project_hash = query
+            .project_path
+            .as_deref()
+            .map(session_storage::hash_project);
+
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT l.project_hash, i.project_path, l.session_id, l.line_no, l.timestamp,
+                        log_fts.content, snippet(log_fts, 0, '[', ']', '...', 24), bm25(log_fts)
+                 FROM log_fts
+                 JOIN log_lines l ON l.id = log_fts.rowid
+                 JOIN indexed_logs i ON i.project_hash = l.project_hash AND i.session_id = l.session_id
+                 WHERE log_fts MATCH ?1 AND (?2 IS NULL OR l.project_hash = ?2)
+                 ORDER BY bm25(log_fts)
+                 LIMIT ?3",
+            )
+            .map_err(|e| format!("Failed to prepare search: {}", e))?;
+
+        let rows = stmt
+            .query_map(params![fts_query, project_hash, query.limit as i64], |row| {
+                Ok(IndexHit {
+                    project_hash: row.get(0)?,
+                    project_path: row.get(1)?,
+                    session_id: row.get(2)?,
+                    line_number: row.get::<_, i64>(3)? as u64,
+                    timestamp: row.get(4)?,
+                    line: row.get(5)?,
+                    snippet: row.get(6)?,
+                    // bm25() is lower-is-better; flip it so callers can sort descending
+                    score: -row.get::<_, f64>(7)?,
+                })
+            })
+            .map_err(|e| format!("Search failed: {}", e))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Search failed: {}", e))
+    }
+
+    /// Read lines `from..=to` of a session log from the index
+    fn lines_between(
+        &self,
+        project_hash: &str,
+        session_id: &str,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<String>, String> {
+        if from > to {
+            return Ok(Vec::new());
+        }
+        let mut stmt = self
+            .conn
+            .prepare_cached(
+                "SELECT f.content FROM log_lines l JOIN log_fts f ON f.rowid = l.id
+                 WHERE l.project_hash = ?1 AND l.session_id = ?2 AND l.line_no BETWEEN ?3 AND ?4
+                 ORDER BY l.line_no",
+            )
+            .map_err(|e| format!("Failed to prepare context query: {}", e))?;
+
+        let rows = stmt
+            .query_map(
+                params![project_hash, session_id, from as i64, to as i64],
+                |row| row.get::<_, String>(0),
+            )
+            .map_err(|e| format!("Failed to read context: {}", e))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read context: {}", e))
+    }
+
+    /// Search and attach context lines and session metadata
+    pub fn search(&self, query: &SearchQuery) -> Result<Vec<SessionSearchHit>, String> {
+        let context = query.context_lines as u64;
+
+        self.search_lines(query)?
+            .into_iter()
+            .map(|hit| {
+                let context_before = self.lines_between(
+                    &hit.project_hash,
+                    &hit.session_id,
+                    hit.line_number.saturating_sub(context).max(1),
+                    hit.line_number - 1,
+                )?;
+                let context_after = self.lines_between(
+                    &hit.project_hash,
+                    &hit.session_id,
+                    hit.line_number + 1,
+                    hit.line_number + context,
+                )?;
+                let session = session_storage::get_session_meta(&hit.project_path, &hit.session_id)
+                    .ok()
+                    .flatten();
+
+                Ok(SessionSearchHit {
+                    role: session.as_ref().and_then(|s| s.role.clone()),
+                    session,
+                    session_id: hit.session_id,
+                    project_path: hit.project_path,
+                    line_number: hit.line_number,
+                    timestamp: hit.timestamp,
+                    line: hit.line,
+                    snippet: hit.snippet,
+                    score: hit.score,
+                    context_before,
+                    context_after,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Index lines from a reader, numbering them after `line_count`
+///
+/// With `complete_only`, a trailing line without a newline is left for later.
+/// Returns the number of lines added and the bytes consumed.
+fn insert_lines(
+    tx: &Transaction,
+    project_hash: &str,
+    session_id: &str,
+    mut reader: impl BufRead,
+    line_count: &mut u64,
+    complete_only: bool,
+) -> Result<(usize, u64), String> {
+    let mut insert_line = tx
+        .prepare_cached(
+            "INSERT INTO log_lines (project_hash, session_id, line_no, timestamp) VALUES (?1, ?2, ?3, ?4)",
+        )
+        .map_err(|e| format!("Failed to prepare insert: {}", e))?;
+    let mut insert_fts = tx
+        .prepare_cached("INSERT INTO log_fts (rowid, content) VALUES (?1, ?2)")
+        .map_err(|e| format!("Failed to prepare insert: {}", e))?;
+
+    let mut added = 0;
+    let mut consumed = 0;
+    let mut buf = String::new();
+    loop {
+        buf.clear();
+        let read = reader
+            .read_line(&mut buf)
+            .map_err(|e| format!("Failed to read log: {}", e))?;
+        // Stop at EOF or at a partially written line (picked up next time)
+        if read == 0 || (complete_only && !buf.ends_with('\n')) {
+            break;
+        }
+        consumed += read as u64;
+        *line_count += 1;
+
+        let (timestamp, content) = session_storage::parse_log_line(buf.trim_end_matches(['\n', '\r']));
+        insert_line
+            .execute(params![project_hash, session_id, *line_count as i64, timestamp])
+            .map_err(|e| format!("Failed to index line: {}", e))?;
+        insert_fts
+            .execute(params![tx.last_insert_rowid(), content])
+            .map_err(|e| format!("Failed to index line: {}", e))?;
+        added += 1;
+    }
+
+    Ok((added, consumed))
+}
+
+/// Turn free text into an FTS5 query: every word quoted, all required
+fn to_fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+// ============================================================================
+// Shared Index
+// ============================================================================
+
+/// Get the index database path
+fn index_path() -> PathBuf {
+    session_storage::sessions_dir().join(INDEX_FILE)
+}
+
+/// Run a closure against the shared index, opening it on first use
+fn with_index<T>(f: impl FnOnce(&mut SearchIndex) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = INDEX
+        .lock()
+        .map_err(|_| "Search index lock poisoned".to_string())?;
+    if guard.is_none() {
+        *guard = Some(SearchIndex::open(&index_path())?);
+    }
+    match guard.as_mut() {
+        Some(index) => f(index),
+        None => Err("Search index unavailable".to_string()),
+    }
+}
+
+/// Index new lines of a session log (called after every append)
+pub fn index_session(project_path: &str, session_id: &str) -> Result<usize, String> {
+    let log_path = session_storage::log_file_path(project_path, session_id);
+    with_index(|index| {
+        index.index_log(project_path, session_id, &log_path, || {
+            session_storage::rotated_segments(project_path, session_id)
+        })
+    })
+}
+
+/// Record that a session's active log was rotated into a segment
+pub fn log_rotated(project_path: &str, session_id: &str) -> Result<(), String> {
+    let project_hash = session_storage::hash_project(project_path);
+    with_index(|index| index.reset_offset(&project_hash, session_id))
+}
+
+/// Remove a session from the index
+pub fn remove_session(project_path: &str, session_id: &str) -> Result<(), String> {
+    let project_hash = session_storage::hash_project(project_path);
+    with_index(|index| index.remove_session(&project_hash, session_id))
+}
+
+/// Catch up on every stored session log
+/// Returns the number of lines added
+pub fn index_all() -> Result<usize, String> {
+    let mut added = 0;
+    for meta in session_storage::list_all_sessions()? {
+        added += index_session(&meta.project_path, &meta.session_id)?;
+    }
+    Ok(added)
+}
+
+/// Move a project's index entries to a new project id, and path if given
+pub fn rename_project(old_hash: &str, new_hash: &str, new_path: Option<&str>) -> Result<(), String> {
+    with_index(|index| index.rename_project(old_hash, new_hash, new_path))
+}
+
+/// Search all indexed session logs
+pub fn search(query: &SearchQuery) -> Result<Vec<SessionSearchHit>, String> {
+    with_index(|index| index.search(query))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Tauri command: Full-text search across session logs
+#[tauri::command]
+pub fn session_search(
+    query: String,
+    project_path: Option<String>,
+    limit: Option<usize>,
+    context_lines: Option<usize>,
+) -> Result<Vec<SessionSearchHit>, String> {
+    search(&SearchQuery {
+        text: query,
+        project_path,
+        limit: limit.unwrap_or(DEFAULT_LIMIT),
+        context_lines: context_lines.unwrap_or(DEFAULT_CONTEXT_LINES),
+    })
+}
+
+/// Tauri command: Index any session logs not yet in the search index
+#[tauri::command]
+pub fn session_search_reindex() -> Result<usize, String> {
+    index_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_incremental_index_and_context() {
+        crate::db::use_test_db();
+        crate::project_identity::use_test_mapping();
+        let dir = tempdir().unwrap();
+        let log_path = dir.path().join("s1.log");
+        let mut index = SearchIndex::open(&dir.path().join("index.db")).unwrap();
+
+        let mut log = File::create(&log_path).unwrap();
+        writeln!(log, "[2024-12-20T15:30:00+00:00] cargo build").unwrap();
+        writeln!(log, "[2024-12-20T15:30:01+00:00] error: linker failed").unwrap();
+        assert_eq!(index.index_log("/tmp/project", "s1", &log_path, Vec::new).unwrap(), 2);
+
+        // Only new complete lines are indexed
+        writeln!(log, "[2024-12-20T15:30:02+00:00] retrying build").unwrap();
+        write!(log, "partial").unwrap();
+        assert_eq!(index.index_log("/tmp/project", "s1", &log_path, Vec::new).unwrap(), 1);
+
+        let query = SearchQuery {
+            text: "linker".to_string(),
+            limit: 10,
+            context_lines: 1,
+            ..Default::default()
+        };
+        let hits = index.search(&query).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].line_number, 2);
+        assert_eq!(hits[0].timestamp.as_deref(), Some("2024-12-20T15:30:01+00:00"));
+        assert_eq!(hits[0].context_before, vec!["cargo build"]);
+        assert_eq!(hits[0].context_after, vec!["retrying build"]);
+
+        let other = SearchQuery {
+            project_path: Some("/tmp/other".to_string()),
+            ..query
+        };
+        assert!(index.search(&other).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fts_query_quotes_terms() {
+        assert_eq!(to_fts_query("foo \"bar"), Some("\"foo\" \"\"\"bar\"".to_string()));
+        assert_eq!(to_fts_query("   "), None);
+    }
+}
@@ -1,17 +1,43 @@
 //! Session Storage Module
 //!
 //! Handles persistence of terminal session data including:
-//! - Session metadata (role, status, timestamps)
-//! - Terminal output logs
+//! - Session metadata (role, status, timestamps), kept in the app database
+//!   (see `db::sessions`)
+//! - Terminal output logs (rotated and gzipped by size)
+//! - Per-project disk quota for logs
 //! - Session lifecycle management
+//!
+//! Log layout per session:
+//!   {session_id}.log         - active segment
+//!   {session_id}.{n}.log.gz  - rotated segments, n = 1 is the oldest
 
 use chrono::{DateTime, Duration, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde::{Deserialize, Serialize};
-use std::collections::hash_map::DefaultHasher;
 use std::fs::{self, File, OpenOptions};
-use std::hash::{Hash, Hasher};
-use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, RwLock};
+
+use crate::db;
+use crate::session_summary::SummaryBackend;
+
+/// Rotate the active log once it grows past this size
+const MAX_LOG_SEGMENT_BYTES: u64 = 8 * 1024 * 1024;
+/// Log disk quota per project (rotated segments included)
+const MAX_PROJECT_LOG_BYTES: u64 = 512 * 1024 * 1024;
+/// Chunk size used when reading a log backwards
+const TAIL_CHUNK_BYTES: u64 = 64 * 1024;
+
+/// Replaces the default sessions directory when set
+static SESSIONS_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Held while appending to, rotating or deleting an active log, so a log is
+/// never removed while another thread is still writing to it. Indexing and
+/// metadata updates happen after it is released.
+static LOG_APPENDER: Mutex<()> = Mutex::new(());
 
 // ============================================================================
 // Types
@@ -26,7 +52,7 @@ pub enum SessionStatus {
     Archived,
 }
 
-/// Session metadata stored in JSON
+/// Session metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionMeta {
@@ -39,7 +65,28 @@ pub struct SessionMeta {
     pub created_at: DateTime<Utc>,
     pub last_active_at: DateTime<Utc>,
     pub status: SessionStatus,
+    /// Size on disk of all log segments (active + rotated)
     pub log_size_bytes: u64,
+    /// Generated from the Claude transcript (see `session_summary`)
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub summary_updated_at: Option<DateTime<Utc>>,
+    /// Backend that produced the title and summary
+    #[serde(default)]
+    pub summary_source: Option<SummaryBackend>,
+}
+
+impl SessionMeta {
+    /// Name to show: the user's name, else the generated title, else the id
+    pub fn label(&self) -> &str {
+        self.display_name
+            .as_deref()
+            .or(self.title.as_deref())
+            .unwrap_or(&self.session_id)
+    }
 }
 
 // ============================================================================
@@ -47,8 +94,11 @@ pub struct SessionMeta {
 // ============================================================================
 
 /// Get the base sessions directory
-/// ~/.sidstack/agent-manager/sessions/
+/// ~/.sidstack/agent-manager/sessions/ unless overridden
 pub fn sessions_dir() -> PathBuf {
+    if let Some(dir) = SESSIONS_DIR.read().ok().and_then(|dir| dir.clone()) {
+        return dir;
+    }
     dirs::home_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join(".sidstack")
@@ -56,11 +106,28 @@ pub fn sessions_dir() -> PathBuf {
         .join("sessions")
 }
 
-/// Hash project path to create a safe directory name
+/// Store session logs under `dir` instead of the home directory
+#[cfg(test)]
+pub(crate) fn set_sessions_dir(dir: PathBuf) {
+    if let Ok(mut current) = SESSIONS_DIR.write() {
+        *current = Some(dir);
+    }
+}
+
+/// Use a sessions directory private to this test process (once per process)
+#[cfg(test)]
+pub(crate) fn use_test_sessions_dir() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("sidstack-test-sessions-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        set_sessions_dir(dir);
+    });
+}
+
+/// Stable project id used as the directory name (see `project_identity`)
 pub fn hash_project(project_path: &str) -> String {
-    let mut hasher = DefaultHasher::new();
-    project_path.hash(&mut hasher);
-    format!("{:x}", hasher.finish())
+    crate::project_identity::project_id(project_path)
 }
 
 /// Get the directory for a specific project's sessions
@@ -69,15 +136,40 @@ fn project_sessions_dir(project_path: &str) -> PathBuf {
 }
 
 /// Get the log file path for a session
-fn log_file_path(project_path: &str, session_id: &str) -> PathBuf {
+pub(crate) fn log_file_path(project_path: &str, session_id: &str) -> PathBuf {
     project_sessions_dir(project_path).join(format!("{}.log", session_id))
 }
 
-/// Get the metadata file path for a session
-fn meta_file_path(project_path: &str, session_id: &str) -> PathBuf {
-    project_sessions_dir(project_path).join(format!("{}.meta.json", session_id))
+/// Get the rotated log segments for a session, oldest first
+pub(crate) fn rotated_segments(project_path: &str, session_id: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(project_sessions_dir(project_path)) else {
+        return Vec::new();
+    };
+
+    let prefix = format!("{}.", session_id);
+    let mut segments: Vec<(u32, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let index = name
+                .strip_prefix(&prefix)?
+                .strip_suffix(".log.gz")?
+                .parse::<u32>()
+                .ok()?;
+            Some((index, entry.path()))
+        })
+        .collect();
+
+    segments.sort_by_key(|(index, _)| *index);
+    segments.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Get the path of a session's rotated segment `n`
+pub(crate) fn segment_file_path(project_path: &str, session_id: &str, index: u32) -> PathBuf {
+    project_sessions_dir(project_path).join(format!("{}.{}.log.gz", session_id, index))
 }
 
+/// Get the metadata file path for a session
 /// Ensure the session directory exists
 fn ensure_session_dir(project_path: &str) -> Result<(), String> {
     let dir = project_sessions_dir(project_path);
@@ -88,10 +180,16 @@ fn ensure_session_dir(project_path: &str) -> Result<(), String> {
 // Log File Operations
 // ============================================================================
 
+/// Lock the log appender (a poisoned lock still guards the files)
+fn lock_appender() -> MutexGuard<'static, ()> {
+    LOG_APPENDER.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
 /// Append output to the session log file
 /// Each line is prefixed with ISO timestamp
 pub fn append_output(project_path: &str, session_id: &str, data: &str) -> Result<(), String> {
     ensure_session_dir(project_path)?;
+    let appender = lock_appender();
 
     let path = log_file_path(project_path, session_id);
     let mut file = OpenOptions::new()
@@ -106,40 +204,219 @@ pub fn append_output(project_path: &str, session_id: &str, data: &str) -> Result
         writeln!(file, "[{}] {}", timestamp, line)
             .map_err(|e| format!("Failed to write to log: {}", e))?;
     }
+    let active_size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    drop(file);
+
+    let rotated = active_size > MAX_LOG_SEGMENT_BYTES;
+    if rotated {
+        rotate_log(project_path, session_id, &appender)?;
+    }
+    drop(appender);
+
+    // Keep the search index current (a failure here must not lose output)
+    if let Err(e) = crate::session_search::index_session(project_path, session_id) {
+        eprintln!("[SessionStorage] Failed to index log: {}", e);
+    }
+
+    if rotated {
+        match enforce_project_quota(project_path, MAX_PROJECT_LOG_BYTES) {
+            Ok(evicted) if evicted > 0 => {
+                eprintln!("[SessionStorage] Evicted {} archived session(s) over quota", evicted);
+            }
+            Err(e) => eprintln!("[SessionStorage] Quota check failed: {}", e),
+            _ => {}
+        }
+    }
 
     // Update metadata with new log size
-    if let Ok(metadata) = fs::metadata(&path) {
-        let _ = update_log_size(project_path, session_id, metadata.len());
+    let _ = update_log_size(project_path, session_id, session_log_size(project_path, session_id));
+
+    Ok(())
+}
+
+/// Gzip the active log into the next rotated segment and start a new one
+/// Takes the appender's guard so a rotation can't race a write
+pub fn rotate_log(
+    project_path: &str,
+    session_id: &str,
+    _appender: &MutexGuard<'static, ()>,
+) -> Result<(), String> {
+    let active = log_file_path(project_path, session_id);
+    if !active.exists() {
+        return Ok(());
+    }
+
+    let next_index = rotated_segments(project_path, session_id)
+        .last()
+        .and_then(|path| segment_index(path, session_id))
+        .unwrap_or(0)
+        + 1;
+    let segment = segment_file_path(project_path, session_id, next_index);
+    let partial = segment.with_extension("gz.tmp");
+
+    // Index what is left of the active log; the search index only reads
+    // rotated segments when it starts over
+    if let Err(e) = crate::session_search::index_session(project_path, session_id) {
+        eprintln!("[SessionStorage] Failed to index log: {}", e);
+    }
+
+    // Compress to a temp file first so a crash never leaves a truncated segment
+    {
+        let mut input =
+            File::open(&active).map_err(|e| format!("Failed to open log file: {}", e))?;
+        let output = File::create(&partial)
+            .map_err(|e| format!("Failed to create log segment: {}", e))?;
+        let mut encoder = GzEncoder::new(output, Compression::default());
+        io::copy(&mut input, &mut encoder)
+            .map_err(|e| format!("Failed to compress log: {}", e))?;
+        encoder
+            .finish()
+            .map_err(|e| format!("Failed to compress log: {}", e))?;
+    }
+    fs::rename(&partial, &segment).map_err(|e| format!("Failed to save log segment: {}", e))?;
+    fs::remove_file(&active).map_err(|e| format!("Failed to reset log file: {}", e))?;
+
+    if let Err(e) = crate::session_search::log_rotated(project_path, session_id) {
+        eprintln!("[SessionStorage] Failed to update search index: {}", e);
     }
 
     Ok(())
 }
 
+/// Parse the index out of a rotated segment file name
+pub(crate) fn segment_index(path: &Path, session_id: &str) -> Option<u32> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix(session_id)?
+        .strip_prefix('.')?
+        .strip_suffix(".log.gz")?
+        .parse()
+        .ok()
+}
+
+/// Total size on disk of a session's log segments
+pub fn session_log_size(project_path: &str, session_id: &str) -> u64 {
+    std::iter::once(log_file_path(project_path, session_id))
+        .chain(rotated_segments(project_path, session_id))
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Evict the oldest archived sessions until the project's logs fit the quota
+/// Returns the number of sessions evicted
+pub fn enforce_project_quota(project_path: &str, max_bytes: u64) -> Result<u32, String> {
+    let sessions = list_sessions(project_path)?;
+
+    let mut total: u64 = sessions
+        .iter()
+        .map(|meta| session_log_size(project_path, &meta.session_id))
+        .sum();
+    if total <= max_bytes {
+        return Ok(0);
+    }
+
+    let mut archived: Vec<&SessionMeta> = sessions
+        .iter()
+        .filter(|meta| meta.status == SessionStatus::Archived)
+        .collect();
+    archived.sort_by_key(|meta| meta.last_active_at);
+
+    let mut evicted = 0;
+    for meta in archived {
+        if total <= max_bytes {
+            break;
+        }
+        let size = session_log_size(project_path, &meta.session_id);
+        delete_session(project_path, &meta.session_id, true)?;
+        total = total.saturating_sub(size);
+        evicted += 1;
+    }
+
+    Ok(evicted)
+}
+
 /// Load the last N lines from a session log
+/// Reads the active segment from the end, then older segments only if needed
 pub fn load_output(project_path: &str, session_id: &str, lines: usize) -> Result<Vec<String>, String> {
     let path = log_file_path(project_path, session_id);
 
-    if !path.exists() {
-        return Ok(Vec::new());
+    // Newest segment first; joined once at the end
+    let mut parts = Vec::new();
+    let mut count = 0;
+    if path.exists() {
+        let active = tail_lines(&path, lines)?;
+        count += active.len();
+        parts.push(active);
     }
 
-    let file = File::open(&path).map_err(|e| format!("Failed to open log file: {}", e))?;
-    let reader = BufReader::new(file);
+    for segment in rotated_segments(project_path, session_id).iter().rev() {
+        if count >= lines {
+            break;
+        }
+        let mut older = read_segment(segment)?;
+        let wanted = lines - count;
+        if older.len() > wanted {
+            older.drain(..older.len() - wanted);
+        }
+        count += older.len();
+        parts.push(older);
+    }
 
-    // Read all lines into a buffer
-    let all_lines: Vec<String> = reader
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
+    parts.reverse();
+    Ok(parts.concat())
+}
+
+/// Read the last N lines of a file without loading all of it
+fn tail_lines(path: &Path, lines: usize) -> Result<Vec<String>, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open log file: {}", e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read log file: {}", e))?
+        .len();
+
+    // Read chunks backwards until we have more line breaks than lines wanted
+    let mut pos = len;
+    let mut chunks: Vec<Vec<u8>> = Vec::new();
+    let mut newlines = 0;
+    while pos > 0 && newlines <= lines {
+        let size = TAIL_CHUNK_BYTES.min(pos);
+        pos -= size;
+
+        let mut chunk = vec![0u8; size as usize];
+        file.seek(SeekFrom::Start(pos))
+            .and_then(|_| file.read_exact(&mut chunk))
+            .map_err(|e| format!("Failed to read log file: {}", e))?;
+
+        newlines += chunk.iter().filter(|&&b| b == b'\n').count();
+        chunks.push(chunk);
+    }
 
-    // Return last N lines
-    let start = if all_lines.len() > lines {
-        all_lines.len() - lines
-    } else {
-        0
-    };
+    // Chunks were read last first; join them once
+    chunks.reverse();
+    let buf = chunks.concat();
+    let text = String::from_utf8_lossy(&buf);
+    let mut all: Vec<&str> = text.lines().collect();
+    // The first line is partial unless we reached the start of the file
+    if pos > 0 && !all.is_empty() {
+        all.remove(0);
+    }
 
-    Ok(all_lines[start..].to_vec())
+    let start = all.len().saturating_sub(lines);
+    Ok(all[start..].iter().map(|line| line.to_string()).collect())
+}
+
+/// Read every line of a rotated (gzipped) segment
+fn read_segment(path: &Path) -> Result<Vec<String>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open log segment: {}", e))?;
+    let reader = BufReader::new(GzDecoder::new(file));
+    Ok(reader.lines().map_while(Result::ok).collect())
+}
+
+/// Open a rotated segment as a line reader (used by the search index)
+pub(crate) fn open_segment(path: &Path) -> Result<impl BufRead, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open log segment: {}", e))?;
+    Ok(BufReader::new(GzDecoder::new(file)))
 }
 
 /// Update the log size in metadata
@@ -158,30 +435,13 @@ fn update_log_size(project_path: &str, session_id: &str, size: u64) -> Result<()
 
 /// Get session metadata
 pub fn get_session_meta(project_path: &str, session_id: &str) -> Result<Option<SessionMeta>, String> {
-    let path = meta_file_path(project_path, session_id);
-
-    if !path.exists() {
-        return Ok(None);
-    }
-
-    let content = fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read metadata: {}", e))?;
-
-    let meta: SessionMeta = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse metadata: {}", e))?;
-
-    Ok(Some(meta))
+    let project_hash = hash_project(project_path);
+    db::with_connection(|conn| db::sessions::get(conn, &project_hash, session_id))
 }
 
 /// Save session metadata
 pub fn save_session_meta(meta: &SessionMeta) -> Result<(), String> {
-    ensure_session_dir(&meta.project_path)?;
-
-    let path = meta_file_path(&meta.project_path, &meta.session_id);
-    let content = serde_json::to_string_pretty(meta)
-        .map_err(|e| format!("Failed to serialize metadata: {}", e))?;
-
-    fs::write(&path, content).map_err(|e| format!("Failed to write metadata: {}", e))
+    db::with_connection(|conn| db::sessions::upsert(conn, meta))
 }
 
 /// Create a new session
@@ -203,6 +463,10 @@ pub fn create_session(
         last_active_at: now,
         status: SessionStatus::Active,
         log_size_bytes: 0,
+        title: None,
+        summary: None,
+        summary_updated_at: None,
+        summary_source: None,
     };
 
     save_session_meta(&meta)?;
@@ -216,9 +480,13 @@ pub fn update_session_status(
     status: SessionStatus,
 ) -> Result<(), String> {
     if let Some(mut meta) = get_session_meta(project_path, session_id)? {
+        let went_idle = meta.status == SessionStatus::Active && status != SessionStatus::Active;
         meta.status = status;
         meta.last_active_at = Utc::now();
         save_session_meta(&meta)?;
+        if went_idle {
+            crate::session_summary::refresh_in_background(meta);
+        }
     }
     Ok(())
 }
@@ -278,70 +546,13 @@ pub fn update_session_claude_id(
 
 /// List all sessions for a project
 pub fn list_sessions(project_path: &str) -> Result<Vec<SessionMeta>, String> {
-    let dir = project_sessions_dir(project_path);
-
-    if !dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut sessions = Vec::new();
-
-    let entries = fs::read_dir(&dir)
-        .map_err(|e| format!("Failed to read sessions directory: {}", e))?;
-
-    for entry in entries.filter_map(Result::ok) {
-        let path = entry.path();
-        if path.extension().map_or(false, |ext| ext == "json") {
-            if let Ok(content) = fs::read_to_string(&path) {
-                if let Ok(meta) = serde_json::from_str::<SessionMeta>(&content) {
-                    sessions.push(meta);
-                }
-            }
-        }
-    }
-
-    // Sort by last active time, most recent first
-    sessions.sort_by(|a, b| b.last_active_at.cmp(&a.last_active_at));
-
-    Ok(sessions)
+    let project_hash = hash_project(project_path);
+    db::with_connection(|conn| db::sessions::list_by_project(conn, &project_hash))
 }
 
 /// List all sessions across all projects
 pub fn list_all_sessions() -> Result<Vec<SessionMeta>, String> {
-    let base_dir = sessions_dir();
-
-    if !base_dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut all_sessions = Vec::new();
-
-    let entries = fs::read_dir(&base_dir)
-        .map_err(|e| format!("Failed to read sessions directory: {}", e))?;
-
-    for entry in entries.filter_map(Result::ok) {
-        let path = entry.path();
-        if path.is_dir() {
-            // Read all .meta.json files in this project directory
-            if let Ok(files) = fs::read_dir(&path) {
-                for file in files.filter_map(Result::ok) {
-                    let file_path = file.path();
-                    if file_path.extension().map_or(false, |ext| ext == "json") {
-                        if let Ok(content) = fs::read_to_string(&file_path) {
-                            if let Ok(meta) = serde_json::from_str::<SessionMeta>(&content) {
-                                all_sessions.push(meta);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Sort by last active time, most recent first
-    all_sessions.sort_by(|a, b| b.last_active_at.cmp(&a.last_active_at));
-
-    Ok(all_sessions)
+    db::with_connection(|conn| db::sessions::list_all(conn))
 }
 
 /// Delete a session and optionally its logs
@@ -350,24 +561,45 @@ pub fn delete_session(
     session_id: &str,
     delete_logs: bool,
 ) -> Result<(), String> {
-    let meta_path = meta_file_path(project_path, session_id);
+    let project_hash = hash_project(project_path);
     let log_path = log_file_path(project_path, session_id);
 
-    // Delete metadata
-    if meta_path.exists() {
-        fs::remove_file(&meta_path)
-            .map_err(|e| format!("Failed to delete metadata: {}", e))?;
-    }
+    db::with_connection(|conn| db::sessions::delete(conn, &project_hash, session_id))?;
 
     // Delete log if requested
-    if delete_logs && log_path.exists() {
-        fs::remove_file(&log_path)
-            .map_err(|e| format!("Failed to delete log: {}", e))?;
+    if delete_logs {
+        let _appender = lock_appender();
+        if log_path.exists() {
+            fs::remove_file(&log_path)
+                .map_err(|e| format!("Failed to delete log: {}", e))?;
+        }
+        for segment in rotated_segments(project_path, session_id) {
+            fs::remove_file(&segment)
+                .map_err(|e| format!("Failed to delete log segment: {}", e))?;
+        }
+    }
+
+    if delete_logs {
+        if let Err(e) = crate::session_search::remove_session(project_path, session_id) {
+            eprintln!("[SessionStorage] Failed to remove session from search index: {}", e);
+        }
     }
 
     Ok(())
 }
 
+/// Split a `[timestamp] content` log line
+pub(crate) fn parse_log_line(line: &str) -> (Option<&str>, &str) {
+    if let Some(rest) = line.strip_prefix('[') {
+        if let Some((timestamp, content)) = rest.split_once("] ") {
+            if chrono::DateTime::parse_from_rfc3339(timestamp).is_ok() {
+                return (Some(timestamp), content);
+            }
+        }
+    }
+    (None, line)
+}
+
 /// Export session as markdown
 pub fn export_session(project_path: &str, session_id: &str) -> Result<String, String> {
     let meta = get_session_meta(project_path, session_id)?
@@ -378,7 +610,10 @@ pub fn export_session(project_path: &str, session_id: &str) -> Result<String, St
     let mut md = String::new();
 
     // Header
-    md.push_str(&format!("# Session: {}\n\n", meta.display_name.as_ref().unwrap_or(&meta.session_id)));
+    md.push_str(&format!("# Session: {}\n\n", meta.label()));
+    if let Some(summary) = &meta.summary {
+        md.push_str(&format!("{}\n\n", summary));
+    }
 
     // Metadata
     md.push_str("## Metadata\n\n");
@@ -410,37 +645,14 @@ pub fn export_session(project_path: &str, session_id: &str) -> Result<String, St
 /// Cleanup old sessions
 /// Returns the number of sessions deleted
 pub fn cleanup_old_sessions(days: u32) -> Result<u32, String> {
-    let base_dir = sessions_dir();
-
-    if !base_dir.exists() {
-        return Ok(0);
-    }
-
     let cutoff = Utc::now() - Duration::days(days as i64);
-    let mut deleted_count = 0;
+    // Only cleanup saved/archived sessions, not active ones
+    let expired = db::with_connection(|conn| db::sessions::list_inactive_before(conn, &cutoff))?;
 
-    let entries = fs::read_dir(&base_dir)
-        .map_err(|e| format!("Failed to read sessions directory: {}", e))?;
-
-    for entry in entries.filter_map(Result::ok) {
-        let path = entry.path();
-        if path.is_dir() {
-            if let Ok(files) = fs::read_dir(&path) {
-                for file in files.filter_map(Result::ok) {
-                    let file_path = file.path();
-                    if file_path.extension().map_or(false, |ext| ext == "json") {
-                        if let Ok(content) = fs::read_to_string(&file_path) {
-                            if let Ok(meta) = serde_json::from_str::<SessionMeta>(&content) {
-                                // Only cleanup saved/archived sessions, not active ones
-                                if meta.status != SessionStatus::Active && meta.last_active_at < cutoff {
-                                    let _ = delete_session(&meta.project_path, &meta.session_id, true);
-                                    deleted_count += 1;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    let mut deleted_count = 0;
+    for meta in expired {
+        if delete_session(&meta.project_path, &meta.session_id, true).is_ok() {
+            deleted_count += 1;
         }
     }
 
@@ -531,13 +743,14 @@ pub fn session_storage_update_claude_id(
     update_session_claude_id(&project_path, &session_id, claude_session_id)
 }
 
-/// Tauri command: Export session as markdown
+/// Tauri command: Export session as markdown (default), HTML or JSONL
 #[tauri::command]
 pub fn session_storage_export(
     project_path: String,
     session_id: String,
+    format: Option<crate::session_export::ExportFormat>,
 ) -> Result<String, String> {
-    export_session(&project_path, &session_id)
+    crate::session_export::export(&project_path, &session_id, format.unwrap_or_default())
 }
 
 /// Tauri command: Load output from session log
@@ -565,3 +778,125 @@ pub fn session_storage_append_output(
 pub fn session_storage_cleanup(days: u32) -> Result<u32, String> {
     cleanup_old_sessions(days)
 }
+
+/// Tauri command: Evict oldest archived sessions until the project's logs fit the quota
+#[tauri::command]
+pub fn session_storage_enforce_quota(
+    project_path: String,
+    max_bytes: Option<u64>,
+) -> Result<u32, String> {
+    enforce_project_quota(&project_path, max_bytes.unwrap_or(MAX_PROJECT_LOG_BYTES))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_tail_lines_reads_from_end() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("s1.log");
+        let content: String = (1..=20_000).map(|i| format!("line {}\n", i)).collect();
+        fs::write(&path, content).unwrap();
+
+        assert_eq!(tail_lines(&path, 2).unwrap(), vec!["line 19999", "line 20000"]);
+        let all = tail_lines(&path, usize::MAX).unwrap();
+        assert_eq!(all.len(), 20_000);
+        assert_eq!(all[0], "line 1");
+        assert!(tail_lines(&path, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_segment_index() {
+        let path = PathBuf::from("/tmp/abc.def.12.log.gz");
+        assert_eq!(segment_index(&path, "abc.def"), Some(12));
+        assert_eq!(segment_index(&path, "abc"), None);
+    }
+
+    /// Write `lines` to a session's active log, replacing it
+    fn write_log(project_path: &str, session_id: &str, lines: &[&str]) {
+        ensure_session_dir(project_path).unwrap();
+        let content: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        fs::write(log_file_path(project_path, session_id), content).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_log_starts_new_segment() {
+        use_test_sessions_dir();
+        crate::project_identity::use_test_mapping();
+        let project = tempdir().unwrap();
+        let project_path = project.path().to_string_lossy().to_string();
+
+        write_log(&project_path, "s1", &["one", "two"]);
+        rotate_log(&project_path, "s1", &lock_appender()).unwrap();
+        assert!(!log_file_path(&project_path, "s1").exists());
+        assert_eq!(
+            rotated_segments(&project_path, "s1"),
+            vec![segment_file_path(&project_path, "s1", 1)]
+        );
+        assert_eq!(
+            read_segment(&segment_file_path(&project_path, "s1", 1)).unwrap(),
+            vec!["one", "two"]
+        );
+
+        // Nothing to rotate without an active log
+        rotate_log(&project_path, "s1", &lock_appender()).unwrap();
+        assert_eq!(rotated_segments(&project_path, "s1").len(), 1);
+    }
+
+    #[test]
+    fn test_load_output_spans_segments() {
+        use_test_sessions_dir();
+        crate::project_identity::use_test_mapping();
+        let project = tempdir().unwrap();
+        let project_path = project.path().to_string_lossy().to_string();
+
+        write_log(&project_path, "s1", &["a1", "a2", "a3"]);
+        rotate_log(&project_path, "s1", &lock_appender()).unwrap();
+        write_log(&project_path, "s1", &["b1", "b2", "b3"]);
+        rotate_log(&project_path, "s1", &lock_appender()).unwrap();
+        write_log(&project_path, "s1", &["c1", "c2"]);
+
+        assert_eq!(load_output(&project_path, "s1", 2).unwrap(), vec!["c1", "c2"]);
+        assert_eq!(
+            load_output(&project_path, "s1", 7).unwrap(),
+            vec!["a2", "a3", "b1", "b2", "b3", "c1", "c2"]
+        );
+        assert_eq!(load_output(&project_path, "s1", 100).unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_enforce_project_quota_evicts_oldest_archived() {
+        use_test_sessions_dir();
+        crate::project_identity::use_test_mapping();
+        crate::db::use_test_db();
+        let project = tempdir().unwrap();
+        let project_path = project.path().to_string_lossy().to_string();
+
+        let now = Utc::now();
+        for (id, status, age) in [
+            ("old", SessionStatus::Archived, 3),
+            ("newer", SessionStatus::Archived, 2),
+            ("live", SessionStatus::Active, 1),
+        ] {
+            let mut meta = create_session(id.to_string(), project_path.clone(), None, None).unwrap();
+            meta.status = status;
+            meta.last_active_at = now - Duration::hours(age);
+            save_session_meta(&meta).unwrap();
+            write_log(&project_path, id, &["0123456789"]);
+        }
+        let per_session = session_log_size(&project_path, "old");
+
+        assert_eq!(enforce_project_quota(&project_path, per_session * 3).unwrap(), 0);
+        assert_eq!(enforce_project_quota(&project_path, per_session * 2).unwrap(), 1);
+        assert!(get_session_meta(&project_path, "old").unwrap().is_none());
+        assert!(!log_file_path(&project_path, "old").exists());
+        assert!(get_session_meta(&project_path, "newer").unwrap().is_some());
+
+        // Active sessions are never evicted
+        assert_eq!(enforce_project_quota(&project_path, 0).unwrap(), 1);
+        assert!(get_session_meta(&project_path, "live").unwrap().is_some());
+        assert!(log_file_path(&project_path, "live").exists());
+    }
+}
@@ -0,0 +1,417 @@
+//! Session Summaries
+//!
+//! Derives a title and a short summary for a terminal session from its Claude
+//! transcript, so the session list shows more than ids:
+//! - Digest: first user prompt, files edited, final result text
+//! - Backends: a local heuristic (default), or a one-shot `claude -p` call that
+//!   falls back to the heuristic when it fails or times out
+//! - Regenerated (heuristic) when a session goes idle, unless the stored one
+//!   came from Claude, or on demand
+//!
+//! A name set with `rename_session` always wins over the generated title
+//! (see `SessionMeta::label`).
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, State};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::claude_process::{ClaudeEvent, SharedClaudeProcessManager};
+use crate::claude_transcripts;
+use crate::session_storage::{self, SessionMeta};
+
+const MAX_TITLE_CHARS: usize = 60;
+const MAX_SUMMARY_CHARS: usize = 280;
+/// Edited files named in the heuristic summary before "and N more"
+const MAX_LISTED_FILES: usize = 3;
+/// Tools whose `file_path` (or `notebook_path`) input counts as a touched file
+const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit"];
+/// How long the Claude backend may take before falling back to the heuristic
+const CLAUDE_TIMEOUT: Duration = Duration::from_secs(90);
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// How to turn a digest into a title and summary
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryBackend {
+    /// Built from the digest locally, no model call
+    #[default]
+    Heuristic,
+    /// One-shot Claude call (`ClaudeProcessManager::spawn`)
+    Claude,
+}
+
+/// What a transcript says about a session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptDigest {
+    pub first_prompt: Option<String>,
+    /// Edited files in first-touched order, no duplicates
+    pub files_touched: Vec<String>,
+    /// Text of the final `result` entry, else of the last assistant reply
+    pub final_result: Option<String>,
+}
+
+/// Generated title and summary
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub title: String,
+    pub summary: String,
+}
+
+// ============================================================================
+// Digest
+// ============================================================================
+
+/// Fold one transcript line into the digest
+///
+/// Reads both Claude's transcript entries and stream-json `result` events.
+fn apply_line(digest: &mut TranscriptDigest, last_reply: &mut Option<String>, line: &str) {
+    let Ok(entry) = serde_json::from_str::<Value>(line) else {
+        return;
+    };
+    let flag = |name: &str| entry.get(name).and_then(Value::as_bool).unwrap_or(false);
+    if flag("isSidechain") {
+        return;
+    }
+
+    let content = entry.get("message").and_then(|m| m.get("content"));
+    match entry.get("type").and_then(Value::as_str) {
+        Some("user") if digest.first_prompt.is_none() && !flag("isMeta") => {
+            digest.first_prompt = content.and_then(claude_transcripts::prompt_text);
+        }
+        Some("assistant") => {
+            let blocks = content.and_then(Value::as_array).into_iter().flatten();
+            for block in blocks {
+                match block.get("type").and_then(Value::as_str) {
+                    Some("text") => {
+                        let text = block.get("text").and_then(Value::as_str).unwrap_or("").trim();
+                        if !text.is_empty() {
+                            *last_reply = Some(text.to_string());
+                        }
+                    }
+                    Some("tool_use") => {
+                        let name = block.get("name").and_then(Value::as_str).unwrap_or("");
+                        if !EDIT_TOOLS.contains(&name) {
+                            continue;
+                        }
+                        let input = block.get("input");
+                        let file = input
+                            .and_then(|i| i.get("file_path").or_else(|| i.get("notebook_path")))
+                            .and_then(Value::as_str);
+                        if let Some(file) = file {
+                            if !digest.files_touched.iter().any(|f| f == file) {
+                                digest.files_touched.push(file.to_string());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Some("result") => {
+            let result = entry.get("result").and_then(Value::as_str).map(str::trim);
+            if let Some(result) = result.filter(|r| !r.is_empty()) {
+                digest.final_result = Some(result.to_string());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Digest a transcript file
+pub fn digest_file(path: &Path) -> Result<TranscriptDigest, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open transcript: {}", e))?;
+    let mut digest = TranscriptDigest::default();
+    let mut last_reply = None;
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| format!("Failed to read transcript: {}", e))?;
+        apply_line(&mut digest, &mut last_reply, &line);
+    }
+    if digest.final_result.is_none() {
+        digest.final_result = last_reply;
+    }
+    Ok(digest)
+}
+
+/// Transcript of the session's Claude conversation, if it has one on disk
+fn transcript_path(meta: &SessionMeta) -> Option<PathBuf> {
+    let claude_id = meta.claude_session_id.as_deref()?;
+    let indexed = claude_transcripts::find(claude_id)
+        .ok()
+        .flatten()
+        .map(|t| PathBuf::from(t.path));
+    indexed
+        .or_else(|| Some(crate::claude_process::claude_transcript_path(&meta.project_path, claude_id)))
+        .filter(|path| path.exists())
+}
+
+// ============================================================================
+// Backends
+// ============================================================================
+
+/// Collapse whitespace and cut to `max` characters, at a word boundary if possible
+fn shorten(text: &str, max: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max {
+        return text;
+    }
+    let cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > max / 2 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end_matches(|c: char| c.is_ascii_punctuation()))
+}
+
+/// Path relative to the project when it is inside it
+fn display_file<'a>(file: &'a str, project_path: &str) -> &'a str {
+    file.strip_prefix(project_path)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .unwrap_or(file)
+}
+
+/// Title from the first prompt line; summary from edited files and the outcome
+pub fn heuristic(digest: &TranscriptDigest, project_path: &str) -> Option<Summary> {
+    let prompt = digest.first_prompt.as_deref()?;
+    let first_line = prompt.lines().find(|l| !l.trim().is_empty()).unwrap_or(prompt);
+    let title = shorten(first_line, MAX_TITLE_CHARS);
+
+    let mut parts = Vec::new();
+    let files: Vec<&str> = digest
+        .files_touched
+        .iter()
+        .map(|f| display_file(f, project_path))
+        .collect();
+    match files.len() {
+        0 => {}
+        n if n <= MAX_LISTED_FILES => parts.push(format!("Edited {}.", files.join(", "))),
+        n => parts.push(format!(
+            "Edited {} and {} more.",
+            files[..MAX_LISTED_FILES].join(", "),
+            n - MAX_LISTED_FILES
+        )),
+    }
+    if let Some(result) = &digest.final_result {
+        let paragraph = result.split("\n\n").next().unwrap_or(result);
+        parts.push(format!("Result: {}", paragraph));
+    }
+    if parts.is_empty() {
+        parts.push(prompt.to_string());
+    }
+
+    Some(Summary {
+        title,
+        summary: shorten(&parts.join(" "), MAX_SUMMARY_CHARS),
+    })
+}
+
+fn claude_prompt(digest: &TranscriptDigest) -> String {
+    let files = if digest.files_touched.is_empty() {
+        "(none)".to_string()
+    } else {
+        digest.files_touched.join("\n")
+    };
+    format!(
+        "Write a title (at most 8 words) and a one-sentence summary of this coding session. \
+         Do not use any tools. Reply with exactly two lines:\n\
+         Title: <title>\nSummary: <summary>\n\n\
+         First request:\n{}\n\nFiles edited:\n{}\n\nOutcome:\n{}",
+        digest.first_prompt.as_deref().unwrap_or("(unknown)"),
+        files,
+        digest.final_result.as_deref().unwrap_or("(unknown)"),
+    )
+}
+
+/// Pick the "Title:" and "Summary:" lines out of Claude's reply
+fn parse_reply(reply: &str) -> Option<Summary> {
+    let field = |name: &str| {
+        reply
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(name))
+            .map(|value| value.trim().trim_matches('"').to_string())
+            .filter(|value| !value.is_empty())
+    };
+    Some(Summary {
+        title: shorten(&field("Title:")?, MAX_TITLE_CHARS),
+        summary: shorten(&field("Summary:")?, MAX_SUMMARY_CHARS),
+    })
+}
+
+/// Ask Claude for a title and summary in a one-shot, single-turn run
+///
+/// The prompt quotes the transcript, so the run gets no tools.
+async fn summarize_with_claude(
+    digest: &TranscriptDigest,
+    working_dir: &str,
+    manager: &SharedClaudeProcessManager,
+    app: AppHandle,
+) -> Result<Summary, String> {
+    let (info, mut events) = manager
+        .lock()
+        .await
+        .spawn_without_tools(
+            "summarizer".to_string(),
+            working_dir.to_string(),
+            claude_prompt(digest),
+            app,
+        )
+        .await?;
+    let process_id = info.id;
+
+    let reply = tokio::time::timeout(CLAUDE_TIMEOUT, async {
+        loop {
+            match events.recv().await {
+                Ok(ClaudeEvent::Result { result, is_error, .. }) => {
+                    if is_error.unwrap_or(false) {
+                        return Err(result.unwrap_or_else(|| "Claude reported an error".to_string()));
+                    }
+                    return result.ok_or_else(|| "Claude returned no result".to_string());
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return Err("Claude exited without a result".to_string()),
+            }
+        }
+    })
+    .await
+    .map_err(|_| format!("Claude did not answer within {}s", CLAUDE_TIMEOUT.as_secs()));
+
+    let _ = manager.lock().await.terminate(&process_id).await;
+    let reply = reply??;
+    parse_reply(&reply).ok_or_else(|| format!("Unexpected summarizer reply: {}", shorten(&reply, 200)))
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// Write a summary onto the stored session (re-read so concurrent edits survive)
+fn store(
+    project_path: &str,
+    session_id: &str,
+    summary: Summary,
+    source: SummaryBackend,
+) -> Result<Option<SessionMeta>, String> {
+    let Some(mut meta) = session_storage::get_session_meta(project_path, session_id)? else {
+        return Ok(None);
+    };
+    meta.title = Some(summary.title);
+    meta.summary = Some(summary.summary);
+    meta.summary_updated_at = Some(Utc::now());
+    meta.summary_source = Some(source);
+    session_storage::save_session_meta(&meta)?;
+    Ok(Some(meta))
+}
+
+/// Regenerate a session's title and summary with the heuristic backend
+///
+/// A summary the user asked Claude for is kept. Returns the updated session,
+/// or None if it was kept or there is no transcript to summarize.
+pub fn refresh(meta: &SessionMeta) -> Result<Option<SessionMeta>, String> {
+    if meta.summary_source == Some(SummaryBackend::Claude) {
+        return Ok(None);
+    }
+    let Some(path) = transcript_path(meta) else {
+        return Ok(None);
+    };
+    let digest = digest_file(&path)?;
+    match heuristic(&digest, &meta.project_path) {
+        Some(summary) => store(&meta.project_path, &meta.session_id, summary, SummaryBackend::Heuristic),
+        None => Ok(None),
+    }
+}
+
+/// Regenerate off the caller's thread (a session just went idle)
+pub fn refresh_in_background(meta: SessionMeta) {
+    std::thread::spawn(move || {
+        if let Err(e) = refresh(&meta) {
+            eprintln!("[SessionSummary] Failed to summarize {}: {}", meta.session_id, e);
+        }
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Tauri command: Regenerate a session's title and summary now
+///
+/// Returns the updated session, or None if it has no transcript to summarize.
+#[tauri::command]
+pub async fn session_summary_generate(
+    project_path: String,
+    session_id: String,
+    backend: Option<SummaryBackend>,
+    app: AppHandle,
+    state: State<'_, SharedClaudeProcessManager>,
+) -> Result<Option<SessionMeta>, String> {
+    let meta = session_storage::get_session_meta(&project_path, &session_id)?
+        .ok_or_else(|| "Session not found".to_string())?;
+    let Some(path) = transcript_path(&meta) else {
+        return Ok(None);
+    };
+    let digest = digest_file(&path)?;
+
+    let heuristic_summary = |digest: &TranscriptDigest| {
+        heuristic(digest, &project_path).map(|summary| (summary, SummaryBackend::Heuristic))
+    };
+    let summary = match backend.unwrap_or_default() {
+        SummaryBackend::Heuristic => heuristic_summary(&digest),
+        SummaryBackend::Claude if digest.first_prompt.is_none() => None,
+        SummaryBackend::Claude => match summarize_with_claude(&digest, &project_path, &state, app).await {
+            Ok(summary) => Some((summary, SummaryBackend::Claude)),
+            Err(e) => {
+                eprintln!("[SessionSummary] Claude backend failed, using heuristic: {}", e);
+                heuristic_summary(&digest)
+            }
+        },
+    };
+    match summary {
+        Some((summary, source)) => store(&project_path, &session_id, summary, source),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_and_heuristic() {
+        let lines = [
+            r#"{"type":"user","isMeta":true,"message":{"content":"<command-name>/init</command-name>"}}"#,
+            r#"{"type":"user","message":{"content":"Fix the  login redirect loop\nIt happens after logout"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Edit","input":{"file_path":"/p/src/auth.rs"}}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Read","input":{"file_path":"/p/README.md"}}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Edit","input":{"file_path":"/p/src/auth.rs"}}]}}"#,
+            r#"{"type":"assistant","isSidechain":true,"message":{"content":[{"type":"text","text":"sub-agent"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"The redirect now checks the session.\n\nDetails follow."}]}}"#,
+        ];
+        let mut digest = TranscriptDigest::default();
+        let mut last_reply = None;
+        for line in lines {
+            apply_line(&mut digest, &mut last_reply, line);
+        }
+        assert_eq!(digest.first_prompt.as_deref(), Some("Fix the  login redirect loop\nIt happens after logout"));
+        assert_eq!(digest.files_touched, vec!["/p/src/auth.rs".to_string()]);
+        assert!(digest.final_result.is_none());
+        digest.final_result = last_reply;
+
+        let summary = heuristic(&digest, "/p").unwrap();
+        assert_eq!(summary.title, "Fix the login redirect loop");
+        assert_eq!(summary.summary, "Edited src/auth.rs. Result: The redirect now checks the session.");
+
+        assert_eq!(shorten("word ".repeat(30).as_str(), 20), "word word word…");
+        assert_eq!(
+            parse_reply("Title: Fix login\nSummary: Fixed the loop."),
+            Some(Summary { title: "Fix login".to_string(), summary: "Fixed the loop.".to_string() })
+        );
+    }
+}
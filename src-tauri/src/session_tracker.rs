@@ -1,18 +1,24 @@
 //! Session Tracker - Persists agent session PIDs for orphan cleanup
 //!
 //! This module ensures no Claude agents run in the background without user knowledge.
-//! It persists session information to disk and cleans up orphaned processes on startup.
+//! It records session information in the app database (see
+//! `db::tracked_processes`) and cleans up orphaned processes on startup:
+//! agent sessions (with their process groups) and the API server and SDK sidecar
+//! left behind by an app that crashed. Each process is recorded with its
+//! start time and the PID of the app that started it, and only signalled if
+//! its PID still belongs to that process.
 
 #![allow(dead_code)]
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use rusqlite::Connection;
 use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+use crate::db;
+use crate::process_reaper;
+
 /// Tracked session info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackedSession {
@@ -22,77 +28,49 @@ pub struct TrackedSession {
     pub role: Option<String>,
     pub cwd: String,
     pub started_at: DateTime<Utc>,
+    /// Process start time (see `process_reaper::start_time`)
+    #[serde(default)]
+    pub process_start: Option<String>,
 }
 
-/// Session tracker state
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct SessionTrackerData {
-    /// App PID that created these sessions
-    pub app_pid: u32,
-    /// Active sessions
-    pub sessions: HashMap<String, TrackedSession>,
-    /// Last updated timestamp
-    pub updated_at: DateTime<Utc>,
+/// Background service started by the app (API server, SDK sidecar)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedService {
+    pub name: String,
+    pub pid: u32,
+    pub started_at: DateTime<Utc>,
+    /// Process start time (see `process_reaper::start_time`)
+    #[serde(default)]
+    pub process_start: Option<String>,
 }
 
-/// Session tracker manager
+/// Session tracker manager (the processes of this app run)
 #[derive(Debug)]
 pub struct SessionTracker {
-    data: SessionTrackerData,
-    file_path: PathBuf,
+    /// PID of this app
+    app_pid: u32,
+    /// Active sessions
+    sessions: HashMap<String, TrackedSession>,
+    /// Running services, by name
+    services: HashMap<String, TrackedService>,
 }
 
 impl SessionTracker {
-    /// Create new session tracker with persistence path
+    /// Create a new session tracker for this app run
     pub fn new() -> Self {
-        let file_path = Self::get_sessions_path();
-        let data = SessionTrackerData {
+        Self {
             app_pid: std::process::id(),
             sessions: HashMap::new(),
-            updated_at: Utc::now(),
-        };
-
-        Self { data, file_path }
-    }
-
-    /// Get path to sessions file
-    fn get_sessions_path() -> PathBuf {
-        let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
-        path.push("sidstack");
-        fs::create_dir_all(&path).ok();
-        path.push("active_sessions.json");
-        path
-    }
-
-    /// Load existing sessions from disk
-    pub fn load(&mut self) -> std::io::Result<()> {
-        if !self.file_path.exists() {
-            return Ok(());
-        }
-
-        let file = File::open(&self.file_path)?;
-        let reader = BufReader::new(file);
-        match serde_json::from_reader::<_, SessionTrackerData>(reader) {
-            Ok(data) => {
-                self.data = data;
-                Ok(())
-            }
-            Err(e) => {
-                eprintln!("[SessionTracker] Failed to parse sessions file: {}", e);
-                // Remove corrupted file
-                fs::remove_file(&self.file_path).ok();
-                Ok(())
-            }
+            services: HashMap::new(),
         }
     }
 
-    /// Save sessions to disk
-    pub fn save(&mut self) -> std::io::Result<()> {
-        self.data.updated_at = Utc::now();
-        let file = File::create(&self.file_path)?;
-        let writer = BufWriter::new(file);
-        serde_json::to_writer_pretty(writer, &self.data)?;
-        Ok(())
+    /// Apply a change to the tracked processes in the database
+    fn persist(&self, change: impl FnOnce(&Connection, u32) -> Result<(), String>) {
+        let app_pid = self.app_pid;
+        if let Err(e) = db::with_connection(|conn| change(conn, app_pid)) {
+            eprintln!("[SessionTracker] Failed to save: {}", e);
+        }
     }
 
     /// Add a session to track
@@ -111,140 +89,141 @@ impl SessionTracker {
             role,
             cwd,
             started_at: Utc::now(),
+            process_start: process_reaper::start_time(pid),
         };
-        self.data.sessions.insert(session_id, session);
-        if let Err(e) = self.save() {
-            eprintln!("[SessionTracker] Failed to save: {}", e);
-        }
+        self.persist(|conn, app_pid| db::tracked_processes::upsert_session(conn, app_pid, &session));
+        self.sessions.insert(session_id, session);
     }
 
     /// Remove a session from tracking
     pub fn remove_session(&mut self, session_id: &str) {
-        self.data.sessions.remove(session_id);
-        if let Err(e) = self.save() {
-            eprintln!("[SessionTracker] Failed to save: {}", e);
+        if self.sessions.remove(session_id).is_some() {
+            self.persist(|conn, _| db::tracked_processes::delete_session(conn, session_id));
+        }
+    }
+
+    /// Track a service started by the app, replacing an earlier one of the same name
+    pub fn add_service(&mut self, name: &str, pid: u32) {
+        let service = TrackedService {
+            name: name.to_string(),
+            pid,
+            started_at: Utc::now(),
+            process_start: process_reaper::start_time(pid),
+        };
+        self.persist(|conn, app_pid| db::tracked_processes::upsert_service(conn, app_pid, &service));
+        self.services.insert(name.to_string(), service);
+    }
+
+    /// Stop tracking a service (only if it is still the given process)
+    pub fn remove_service(&mut self, name: &str, pid: u32) {
+        if self.services.get(name).is_some_and(|s| s.pid == pid) {
+            self.services.remove(name);
+            self.persist(|conn, _| db::tracked_processes::delete_service(conn, name));
         }
     }
 
     /// Get all tracked sessions
     pub fn list_sessions(&self) -> Vec<TrackedSession> {
-        self.data.sessions.values().cloned().collect()
+        self.sessions.values().cloned().collect()
     }
 
     /// Get a specific session
     pub fn get_session(&self, session_id: &str) -> Option<&TrackedSession> {
-        self.data.sessions.get(session_id)
+        self.sessions.get(session_id)
     }
 
-    /// Check if a process is still running
-    fn is_process_running(pid: u32) -> bool {
-        #[cfg(unix)]
-        {
-            unsafe { libc::kill(pid as i32, 0) == 0 }
-        }
-
-        #[cfg(windows)]
-        {
-            use std::process::Command;
-            Command::new("tasklist")
-                .args(["/FI", &format!("PID eq {}", pid), "/NH"])
-                .output()
-                .map(|output| {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    stdout.contains(&pid.to_string())
-                })
-                .unwrap_or(false)
-        }
-    }
-
-    /// Terminate a process
-    fn terminate_process(pid: u32) -> bool {
-        eprintln!("[SessionTracker] Terminating orphaned process PID {}", pid);
-
-        #[cfg(unix)]
-        {
-            unsafe {
-                // Try SIGTERM first
-                if libc::kill(pid as i32, libc::SIGTERM) == 0 {
-                    // Wait briefly and check if still running
-                    std::thread::sleep(std::time::Duration::from_millis(100));
-                    if libc::kill(pid as i32, 0) == 0 {
-                        // Still running, force kill
-                        libc::kill(pid as i32, libc::SIGKILL);
-                    }
-                    return true;
-                }
-                false
+    /// Cleanup orphaned sessions and services from previous app runs
+    /// Returns the terminated session IDs and service names
+    ///
+    /// Processes of an earlier run that is still running are left alone.
+    pub fn cleanup_orphaned(&mut self) -> Vec<String> {
+        let tracked = db::with_connection(|conn| {
+            Ok((
+                db::tracked_processes::list_sessions(conn)?,
+                db::tracked_processes::list_services(conn)?,
+            ))
+        });
+        let (sessions, services) = match tracked {
+            Ok(tracked) => tracked,
+            Err(e) => {
+                eprintln!("[SessionTracker] Failed to load tracked processes: {}", e);
+                return Vec::new();
             }
-        }
+        };
 
-        #[cfg(windows)]
-        {
-            use std::process::Command;
-            Command::new("taskkill")
-                .args(["/PID", &pid.to_string(), "/F"])
-                .output()
-                .map(|output| output.status.success())
-                .unwrap_or(false)
+        // Runs that are no longer running (this run hasn't tracked anything
+        // yet, so rows with its PID are from an earlier run that had it)
+        let mut ended_apps: Vec<u32> = sessions
+            .iter()
+            .map(|(app_pid, _)| *app_pid)
+            .chain(services.iter().map(|(app_pid, _)| *app_pid))
+            .filter(|&app_pid| app_pid == self.app_pid || !process_reaper::is_running(app_pid))
+            .collect();
+        ended_apps.sort_unstable();
+        ended_apps.dedup();
+        if ended_apps.is_empty() {
+            return Vec::new();
         }
-    }
+        eprintln!(
+            "[SessionTracker] Previous app(s) {:?} not running, cleaning up orphaned processes",
+            ended_apps
+        );
 
-    /// Cleanup orphaned sessions from previous app runs
-    /// Returns list of terminated session IDs
-    pub fn cleanup_orphaned(&mut self) -> Vec<String> {
         let mut orphaned = Vec::new();
-        let current_app_pid = std::process::id();
-
-        // Check if sessions are from a different app instance
-        if self.data.app_pid != current_app_pid && self.data.app_pid != 0 {
-            // Check if old app is still running
-            let old_app_running = Self::is_process_running(self.data.app_pid);
-
-            if !old_app_running {
+        let mut pids = Vec::new();
+        for (_, session) in sessions.iter().filter(|(app_pid, _)| ended_apps.contains(app_pid)) {
+            if process_reaper::is_same_process(session.pid, session.process_start.as_deref()) {
                 eprintln!(
-                    "[SessionTracker] Previous app (PID {}) is not running, cleaning up orphaned sessions",
-                    self.data.app_pid
+                    "[SessionTracker] Terminating orphaned session '{}' (PID {})",
+                    session.session_id, session.pid
                 );
-
-                // Terminate all sessions from old app
-                for (session_id, session) in self.data.sessions.iter() {
-                    if Self::is_process_running(session.pid) {
-                        eprintln!(
-                            "[SessionTracker] Terminating orphaned session '{}' (PID {})",
-                            session_id, session.pid
-                        );
-                        Self::terminate_process(session.pid);
-                        orphaned.push(session_id.clone());
-                    }
-                }
+                pids.push(session.pid);
+                orphaned.push(session.session_id.clone());
             }
         }
-
-        // Clear all sessions and start fresh
-        if !orphaned.is_empty() {
-            self.data.sessions.clear();
-            self.data.app_pid = current_app_pid;
-            if let Err(e) = self.save() {
-                eprintln!("[SessionTracker] Failed to save after cleanup: {}", e);
-            }
-        } else {
-            // Just update app_pid
-            self.data.app_pid = current_app_pid;
-            if let Err(e) = self.save() {
-                eprintln!("[SessionTracker] Failed to save: {}", e);
+        for (_, service) in services.iter().filter(|(app_pid, _)| ended_apps.contains(app_pid)) {
+            if process_reaper::is_same_process(service.pid, service.process_start.as_deref()) {
+                eprintln!(
+                    "[SessionTracker] Terminating orphaned {} (PID {})",
+                    service.name, service.pid
+                );
+                pids.push(service.pid);
+                orphaned.push(service.name.clone());
             }
         }
 
+        // Terminate everything from the old apps, with one shared grace period
+        process_reaper::terminate_all(&pids, process_reaper::GRACE_PERIOD);
+
+        for app_pid in ended_apps {
+            self.persist(|conn, _| db::tracked_processes::delete_for_app(conn, app_pid));
+        }
+
         orphaned
     }
 
-    /// Clear all sessions (e.g., on app exit)
-    pub fn clear_all(&mut self) {
-        self.data.sessions.clear();
-        // Don't delete file, just clear sessions
-        if let Err(e) = self.save() {
-            eprintln!("[SessionTracker] Failed to save: {}", e);
+    /// Terminate every tracked process still running and clear the tracker (app exit)
+    pub fn shutdown(&mut self) {
+        let pids: Vec<u32> = self
+            .sessions
+            .values()
+            .map(|s| (s.pid, s.process_start.as_deref()))
+            .chain(self.services.values().map(|s| (s.pid, s.process_start.as_deref())))
+            .filter(|&(pid, started)| process_reaper::is_same_process(pid, started))
+            .map(|(pid, _)| pid)
+            .collect();
+        if !pids.is_empty() {
+            eprintln!("[SessionTracker] Terminating {} process(es) on exit", pids.len());
+            process_reaper::terminate_all(&pids, process_reaper::GRACE_PERIOD);
         }
+        self.clear_all();
+    }
+
+    /// Clear all sessions and services (e.g., on app exit)
+    pub fn clear_all(&mut self) {
+        self.sessions.clear();
+        self.services.clear();
+        self.persist(db::tracked_processes::delete_for_app);
     }
 }
 
@@ -255,11 +234,6 @@ pub type SharedSessionTracker = Arc<Mutex<SessionTracker>>;
 pub fn create_session_tracker() -> (SharedSessionTracker, Vec<String>) {
     let mut tracker = SessionTracker::new();
 
-    // Load existing sessions
-    if let Err(e) = tracker.load() {
-        eprintln!("[SessionTracker] Failed to load sessions: {}", e);
-    }
-
     // Cleanup orphaned sessions from previous runs
     let orphaned = tracker.cleanup_orphaned();
 
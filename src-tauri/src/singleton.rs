@@ -9,7 +9,6 @@ use std::net::TcpStream;
 use std::path::PathBuf;
 use std::time::Duration;
 
-const IPC_PORT: u16 = 17432;
 const LOCK_FILE_NAME: &str = ".sidstack.lock";
 
 /// Get the lockfile path in the user's home directory
@@ -22,11 +21,12 @@ fn get_lock_file_path() -> PathBuf {
 /// Returns Ok(()) if this is the first instance, Err(message) otherwise
 pub fn check_singleton() -> Result<(), String> {
     let lock_path = get_lock_file_path();
+    let ipc_port = crate::config::ports().ipc;
 
     // First, try to connect to the IPC port
     // If successful, another instance is running
     if TcpStream::connect_timeout(
-        &format!("127.0.0.1:{}", IPC_PORT).parse().unwrap(),
+        &format!("127.0.0.1:{}", ipc_port).parse().unwrap(),
         Duration::from_millis(500),
     )
     .is_ok()
@@ -34,7 +34,7 @@ pub fn check_singleton() -> Result<(), String> {
         return Err(format!(
             "Another instance of Agent Manager is already running (IPC port {} in use).\n\
              Please close the existing instance before starting a new one.",
-            IPC_PORT
+            ipc_port
         ));
     }
 
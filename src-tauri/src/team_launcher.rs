@@ -0,0 +1,554 @@
+//! Team Launcher
+//!
+//! Starts and stops a team's agents. `TeamManager` only stores configs and
+//! states; this module spawns a persistent Claude session per member and
+//! keeps `MemberState` in step:
+//! - Start: one session per member with `auto_start` (orchestrator first) in
+//!   the project, its worktree or its `working_dir`. The member's terminal ID
+//!   is the session's ID in `ClaudeProcessManager`. New sessions get the
+//!   bootstrap prompt; members of a stopped team resume their conversation.
+//! - Stop: terminates the sessions and saves `SessionInfo` (the Claude
+//!   session ID and cwd of each member), leaving the team paused. Members
+//!   whose session is already gone keep the Claude session ID stored for them.
+//! - Replacement: the watchdog starts the member that replaces a failed one
+//!   with the recovery context appended to its bootstrap prompt.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::claude_process::{ClaudeEvent, SharedClaudeProcessManager};
+use crate::session_tracker::SharedSessionTracker;
+use crate::claude_process::ClaudeProcessInfo;
+use crate::team_manager::{SharedTeamManager, TeamManager};
+use crate::team_storage::{
+    MemberState, MemberStatus, SessionInfo, TeamConfig, TeamMemberConfig, TeamState, TeamStatus,
+    TerminalSessionInfo,
+};
+
+/// Outcome of starting one member
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberLaunch {
+    pub member_id: String,
+    pub role: String,
+    /// Session ID in `ClaudeProcessManager` (also the member's terminal ID)
+    pub terminal_id: Option<String>,
+    pub cwd: Option<String>,
+    /// Continued its previous conversation
+    pub resumed: bool,
+    /// Already running, left alone
+    pub already_running: bool,
+    pub error: Option<String>,
+}
+
+/// Result of `team_start`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamStartReport {
+    pub team_id: String,
+    pub members: Vec<MemberLaunch>,
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Orchestrator first, then workers
+fn members(config: &TeamConfig) -> impl Iterator<Item = &TeamMemberConfig> {
+    std::iter::once(&config.orchestrator).chain(config.workers.iter())
+}
+
+/// Directory a member runs in, creating its worktree if needed
+async fn resolve_cwd(project_path: &str, member: &TeamMemberConfig) -> Result<PathBuf, String> {
+    // Checked when templates are rendered too, but stored teams may predate that
+    if let Some(launch) = &member.launch {
+        launch.validate()?;
+    }
+    let cwd = crate::recovery_context::member_dir(project_path, member);
+
+    if let Some(task_id) = member.launch.as_ref().and_then(|l| l.worktree.as_ref()) {
+        let worktrees = crate::config::for_project(project_path).worktrees;
+        let worktree = Path::new(project_path)
+            .join(&worktrees.worktree_root)
+            .join(format!("task-{}", task_id));
+        if !worktree.is_dir() {
+            crate::commands::workspace::create_workspace(project_path.to_string(), task_id.clone(), None)
+                .await
+                .map_err(|e| format!("Failed to create worktree for task {}: {}", task_id, e))?;
+        }
+    }
+
+    if !cwd.is_dir() {
+        return Err(format!("Working directory {:?} does not exist", cwd));
+    }
+    Ok(cwd)
+}
+
+/// First message to a new member session
+fn bootstrap_prompt(config: &TeamConfig, member: &TeamMemberConfig, recovery: Option<&str>) -> String {
+    let mut prompt = format!(
+        "You are the {} member (agent type {}) of the team \"{}\".\n\
+         Team ID: {}\nMember ID: {}\nProject: {}",
+        member.role, member.agent_type, config.name, config.id, member.id, config.project_path
+    );
+    if let Some(description) = &config.description {
+        prompt.push_str(&format!("\nTeam goal: {}", description));
+    }
+    if let Some(text) = member.launch.as_ref().and_then(|l| l.prompt.as_deref()) {
+        prompt.push_str("\n\n");
+        prompt.push_str(text);
+    }
+    if let Some(recovery) = recovery {
+        prompt.push_str("\n\n");
+        prompt.push_str(recovery);
+    }
+    prompt
+}
+
+/// Record Claude's session ID for the member once the session reports it
+fn watch_claude_session_id(
+    mut events: tokio::sync::broadcast::Receiver<ClaudeEvent>,
+    session_id: String,
+    project_path: String,
+    team_id: String,
+    member_id: String,
+    processes: SharedClaudeProcessManager,
+    teams: SharedTeamManager,
+) {
+    tauri::async_runtime::spawn(async move {
+        use tokio::sync::broadcast::error::RecvError;
+        loop {
+            match events.recv().await {
+                Ok(ClaudeEvent::System { session_id: Some(claude_id), subtype, .. })
+                    if subtype.as_deref() == Some("init") =>
+                {
+                    processes.lock().await.set_claude_session_id(&session_id, claude_id.clone()).await;
+                    let result = teams.lock().await.update_member_session(
+                        &project_path,
+                        &team_id,
+                        &member_id,
+                        None,
+                        Some(claude_id),
+                    );
+                    if let Err(e) = result {
+                        eprintln!("[TeamLauncher] Failed to record Claude session of {}: {}", member_id, e);
+                    }
+                    break;
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Spawn one member's session; returns (terminal ID, cwd, resumed)
+#[allow(clippy::too_many_arguments)]
+async fn launch_member(
+    config: &TeamConfig,
+    member: &TeamMemberConfig,
+    resume_from: Option<&TerminalSessionInfo>,
+    recovery: Option<&str>,
+    app: &AppHandle,
+    processes: &SharedClaudeProcessManager,
+    tracker: &SharedSessionTracker,
+    teams: &SharedTeamManager,
+) -> Result<(String, String, bool), String> {
+    let project_path = &config.project_path;
+
+    // Resume in the directory the conversation ran in, if the transcript is still there
+    let resume = match resume_from.and_then(|t| t.claude_session_id.clone().map(|id| (id, t.cwd.clone()))) {
+        Some((id, cwd)) if Path::new(&cwd).is_dir() => {
+            let lookup = id.clone();
+            let found = tokio::task::spawn_blocking(move || crate::claude_transcripts::find(&lookup))
+                .await
+                .map_err(|e| format!("Failed to look up transcript: {}", e))??;
+            found.map(|_| (id, PathBuf::from(cwd)))
+        }
+        _ => None,
+    };
+    let resumed = resume.is_some();
+    let (resume_session_id, cwd) = match resume {
+        Some((id, cwd)) => (Some(id), cwd),
+        None => (None, resolve_cwd(project_path, member).await?),
+    };
+    let cwd = cwd.to_string_lossy().to_string();
+
+    // The member's MCP server authenticates to the IPC server as this member
+    let env = vec![
+        ("SIDSTACK_AGENT_ROLE".to_string(), member.role.clone()),
+        ("SIDSTACK_MEMBER_ID".to_string(), member.id.clone()),
+        ("SIDSTACK_TEAM_ID".to_string(), config.id.clone()),
+    ];
+    // The process manager is locked per call: other commands shouldn't wait for the launch
+    let info = processes
+        .lock()
+        .await
+        .spawn_session_with_env(member.role.clone(), cwd.clone(), None, None, resume_session_id, env, app.clone())
+        .await?;
+    if let Ok(mut tracker) = tracker.lock() {
+        tracker.add_session(info.id.clone(), info.pid, None, Some(member.role.clone()), cwd.clone());
+    }
+
+    // Subscribe before the first message, so the init event isn't missed
+    let (session_events, output_events, stderr) = {
+        let manager = processes.lock().await;
+        (
+            manager.subscribe_session(&info.id).await,
+            manager.subscribe_session(&info.id).await,
+            manager.subscribe_session_stderr(&info.id).await,
+        )
+    };
+    if let Some(events) = session_events {
+        watch_claude_session_id(
+            events,
+            info.id.clone(),
+            project_path.clone(),
+            config.id.clone(),
+            member.id.clone(),
+            processes.clone(),
+            teams.clone(),
+        );
+    }
+    if let (Some(events), Some(stderr)) = (output_events, stderr) {
+        crate::recovery_watchdog::watch_member_output(
+            app.clone(),
+            project_path.clone(),
+            config.id.clone(),
+            member.id.clone(),
+            events,
+            stderr,
+        );
+    }
+    if !resumed {
+        let prompt = bootstrap_prompt(config, member, recovery);
+        processes.lock().await.send_input(&info.id, &prompt).await?;
+    }
+    Ok((info.id, cwd, resumed))
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Make the team active for `start`: a paused team is resumed. Hands back
+/// the sessions saved when it was stopped that no launch has consumed yet
+fn begin_start(
+    manager: &mut TeamManager,
+    project_path: &str,
+    team_id: &str,
+) -> Result<(TeamConfig, TeamState, Option<SessionInfo>), String> {
+    let team = manager.get_team(project_path, team_id).map_err(|e| e.to_string())?;
+    match team.state.status {
+        TeamStatus::Archived => Err(format!("Team {} is archived", team_id)),
+        TeamStatus::Paused => {
+            let (team, session_info) = manager.resume_team(project_path, team_id).map_err(|e| e.to_string())?;
+            Ok((team.config, team.state, session_info))
+        }
+        TeamStatus::Active => {
+            let session_info = team.state.session_info.clone();
+            Ok((team.config, team.state, session_info))
+        }
+    }
+}
+
+/// Start the team's members; a paused team resumes its saved sessions
+pub async fn start(
+    project_path: &str,
+    team_id: &str,
+    app: &AppHandle,
+    processes: &SharedClaudeProcessManager,
+    tracker: &SharedSessionTracker,
+    teams: &SharedTeamManager,
+) -> Result<TeamStartReport, String> {
+    let (config, state, session_info) = begin_start(&mut *teams.lock().await, project_path, team_id)?;
+
+    let mut launches = Vec::new();
+    for member in members(&config) {
+        let mut launch = MemberLaunch {
+            member_id: member.id.clone(),
+            role: member.role.clone(),
+            terminal_id: None,
+            cwd: None,
+            resumed: false,
+            already_running: false,
+            error: None,
+        };
+
+        let running = state.members.get(&member.id).and_then(|s| s.terminal_id.clone());
+        if let Some(terminal_id) = running {
+            if processes.lock().await.has_session(&terminal_id).await {
+                launch.terminal_id = Some(terminal_id);
+                launch.already_running = true;
+                launches.push(launch);
+                continue;
+            }
+        }
+        if member.launch.as_ref().is_some_and(|l| !l.auto_start) {
+            launches.push(launch);
+            continue;
+        }
+
+        let resume_from = session_info
+            .as_ref()
+            .and_then(|info| info.terminals.iter().find(|t| t.member_id == member.id));
+        match launch_member(&config, member, resume_from, None, app, processes, tracker, teams).await {
+            Ok((terminal_id, cwd, resumed)) => {
+                record_launch(teams, project_path, team_id, &member.id, &terminal_id).await;
+                // A member that failed to launch keeps its saved session for the next start
+                if resume_from.is_some() {
+                    if let Err(e) = teams.lock().await.consume_saved_session(project_path, team_id, &member.id) {
+                        eprintln!("[TeamLauncher] Failed to clear saved session of {}: {}", member.id, e);
+                    }
+                }
+                launch.terminal_id = Some(terminal_id);
+                launch.cwd = Some(cwd);
+                launch.resumed = resumed;
+            }
+            Err(e) => {
+                eprintln!("[TeamLauncher] Failed to start {} ({}): {}", member.role, member.id, e);
+                launch.error = Some(e);
+            }
+        }
+        launches.push(launch);
+    }
+
+    Ok(TeamStartReport {
+        team_id: team_id.to_string(),
+        members: launches,
+    })
+}
+
+/// Start the member that replaced a failed one; `recovery_prompt` (see
+/// `recovery_context::render_prompt`) follows the bootstrap prompt
+pub async fn launch_replacement(
+    app: &AppHandle,
+    project_path: &str,
+    team_id: &str,
+    member_id: &str,
+    recovery_prompt: &str,
+) -> Result<String, String> {
+    let processes = app.state::<SharedClaudeProcessManager>().inner().clone();
+    let tracker = app.state::<SharedSessionTracker>().inner().clone();
+    let teams = app.state::<SharedTeamManager>().inner().clone();
+
+    let config = teams.lock().await.get_team(project_path, team_id).map_err(|e| e.to_string())?.config;
+    let member = members(&config)
+        .find(|m| m.id == member_id)
+        .ok_or_else(|| format!("Member {} not found", member_id))?;
+
+    let (terminal_id, _, _) =
+        launch_member(&config, member, None, Some(recovery_prompt), app, &processes, &tracker, &teams).await?;
+    record_launch(&teams, project_path, team_id, member_id, &terminal_id).await;
+    Ok(terminal_id)
+}
+
+/// Store a started member's terminal ID and mark it active
+async fn record_launch(teams: &SharedTeamManager, project_path: &str, team_id: &str, member_id: &str, terminal_id: &str) {
+    let mut manager = teams.lock().await;
+    let recorded = manager
+        .update_member_session(project_path, team_id, member_id, Some(terminal_id.to_string()), None)
+        .and_then(|_| manager.update_member_status(project_path, team_id, member_id, MemberStatus::Active));
+    if let Err(e) = recorded {
+        eprintln!("[TeamLauncher] Failed to record session of {}: {}", member_id, e);
+    }
+}
+
+/// What `start` needs to resume a member: its running session, or else the
+/// Claude session stored for it (e.g. the app restarted since it was launched)
+fn saved_terminal(
+    member_id: &str,
+    member_state: &MemberState,
+    session: Option<&ClaudeProcessInfo>,
+    fallback_cwd: &Path,
+) -> Option<TerminalSessionInfo> {
+    let (claude_session_id, cwd) = match session {
+        Some(session) => (
+            session.session_id.clone().or_else(|| member_state.claude_session_id.clone()),
+            session.working_dir.clone(),
+        ),
+        None => (
+            Some(member_state.claude_session_id.clone()?),
+            fallback_cwd.to_string_lossy().to_string(),
+        ),
+    };
+    Some(TerminalSessionInfo {
+        member_id: member_id.to_string(),
+        terminal_id: member_state.terminal_id.clone().unwrap_or_default(),
+        claude_session_id,
+        cwd,
+    })
+}
+
+/// Terminate the team's sessions and pause it, saving what `start` needs to resume
+pub async fn stop(
+    project_path: &str,
+    team_id: &str,
+    processes: &SharedClaudeProcessManager,
+    tracker: &SharedSessionTracker,
+    teams: &SharedTeamManager,
+) -> Result<SessionInfo, String> {
+    let team = teams
+        .lock()
+        .await
+        .get_team(project_path, team_id)
+        .map_err(|e| e.to_string())?;
+
+    let mut terminals = Vec::new();
+    for member in members(&team.config) {
+        let Some(member_state) = team.state.members.get(&member.id) else {
+            continue;
+        };
+        let session = match &member_state.terminal_id {
+            Some(terminal_id) => processes.lock().await.get_session(terminal_id).await,
+            None => None,
+        };
+        let fallback_cwd = crate::recovery_context::member_dir(project_path, member);
+        if let Some(terminal) = saved_terminal(&member.id, member_state, session.as_ref(), &fallback_cwd) {
+            terminals.push(terminal);
+        }
+
+        if let (Some(terminal_id), Some(_)) = (&member_state.terminal_id, &session) {
+            if let Err(e) = processes.lock().await.terminate_session(terminal_id).await {
+                eprintln!("[TeamLauncher] Failed to stop {}: {}", member.id, e);
+            }
+            if let Ok(mut tracker) = tracker.lock() {
+                tracker.remove_session(terminal_id);
+            }
+        }
+    }
+
+    let mut manager = teams.lock().await;
+    manager
+        .pause_team(project_path, team_id, terminals.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(SessionInfo {
+        saved_at: chrono::Utc::now(),
+        terminals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::team_storage::MemberLaunchSettings;
+
+    #[test]
+    fn test_bootstrap_prompt_identifies_member() {
+        let mut member = TeamMemberConfig::new("dev".to_string(), "dev-agent".to_string());
+        member.launch = Some(MemberLaunchSettings {
+            prompt: Some("Wait for tasks.".to_string()),
+            ..Default::default()
+        });
+        let config = TeamConfig {
+            id: "team-1".to_string(),
+            name: "Feature: Dark mode".to_string(),
+            project_path: "/work/sidstack".to_string(),
+            created_at: chrono::Utc::now(),
+            created_by: "user".to_string(),
+            orchestrator: TeamMemberConfig::new("orchestrator".to_string(), "orchestrator".to_string()),
+            workers: vec![member.clone()],
+            auto_recovery: true,
+            max_recovery_attempts: 3,
+            recovery_delay_ms: 5000,
+            description: None,
+            tags: Vec::new(),
+        };
+
+        let prompt = bootstrap_prompt(&config, &member, None);
+        assert!(prompt.starts_with("You are the dev member (agent type dev-agent) of the team \"Feature: Dark mode\""));
+        assert!(prompt.contains(&format!("Member ID: {}", member.id)));
+        assert!(prompt.ends_with("\n\nWait for tasks."));
+        let recovery = bootstrap_prompt(&config, &member, Some("Continue task 1.2."));
+        assert!(recovery.ends_with("\n\nWait for tasks.\n\nContinue task 1.2."));
+        assert_eq!(members(&config).map(|m| m.role.as_str()).collect::<Vec<_>>(), ["orchestrator", "dev"]);
+    }
+
+    #[test]
+    fn test_saved_terminal_falls_back_to_stored_session() {
+        let state = MemberState {
+            status: MemberStatus::Active,
+            terminal_id: Some("t-1".to_string()),
+            claude_session_id: Some("c-1".to_string()),
+            current_task: None,
+            last_heartbeat: None,
+        };
+        let fallback = Path::new("/work/sidstack/.worktrees/task-1");
+
+        // Running: where the session runs, and the ID it reported
+        let session = ClaudeProcessInfo {
+            id: "t-1".to_string(),
+            session_id: Some("c-2".to_string()),
+            role: "dev".to_string(),
+            working_dir: "/work/sidstack/app".to_string(),
+            status: crate::claude_process::ProcessStatus::Ready,
+            pid: 1,
+            created_at: String::new(),
+        };
+        let running = saved_terminal("m-1", &state, Some(&session), fallback).unwrap();
+        assert_eq!(running.claude_session_id.as_deref(), Some("c-2"));
+        assert_eq!(running.cwd, "/work/sidstack/app");
+
+        // Gone: the stored conversation in the member's directory
+        let gone = saved_terminal("m-1", &state, None, fallback).unwrap();
+        assert_eq!(gone.terminal_id, "t-1");
+        assert_eq!(gone.claude_session_id.as_deref(), Some("c-1"));
+        assert_eq!(gone.cwd, "/work/sidstack/.worktrees/task-1");
+
+        let never_started = MemberState { claude_session_id: None, ..state };
+        assert!(saved_terminal("m-1", &never_started, None, fallback).is_none());
+    }
+
+    #[test]
+    fn test_begin_start_resumes_paused_team() {
+        crate::db::use_test_db();
+        let mut manager = TeamManager::new().unwrap();
+        let project = "/no/such/team-launcher-project";
+        let team = manager
+            .create_team(crate::team_manager::CreateTeamInput {
+                name: "launcher-test".to_string(),
+                project_path: project.to_string(),
+                auto_recovery: true,
+                max_recovery_attempts: 3,
+                recovery_delay_ms: 0,
+                orchestrator: None,
+                members: Vec::new(),
+                description: None,
+                tags: Vec::new(),
+            })
+            .unwrap();
+        let team_id = team.config.id.clone();
+        let orchestrator_id = team.config.orchestrator.id.clone();
+
+        // An active team starts as it is
+        let (_, state, session_info) = begin_start(&mut manager, project, &team_id).unwrap();
+        assert_eq!(state.status, TeamStatus::Active);
+        assert!(session_info.is_none());
+
+        // A stopped team hands back its saved sessions and becomes active
+        let saved = TerminalSessionInfo {
+            member_id: orchestrator_id.clone(),
+            terminal_id: "t-1".to_string(),
+            claude_session_id: Some("c-1".to_string()),
+            cwd: project.to_string(),
+        };
+        manager.pause_team(project, &team_id, vec![saved]).unwrap();
+        assert_eq!(manager.get_team(project, &team_id).unwrap().state.status, TeamStatus::Paused);
+
+        let (_, state, session_info) = begin_start(&mut manager, project, &team_id).unwrap();
+        assert_eq!(state.status, TeamStatus::Active);
+        assert_eq!(state.members[&orchestrator_id].status, MemberStatus::Idle);
+        let terminals = session_info.unwrap().terminals;
+        assert_eq!(terminals.len(), 1);
+        assert_eq!(terminals[0].claude_session_id.as_deref(), Some("c-1"));
+
+        // Saved sessions are handed back until their members are launched
+        let (_, _, session_info) = begin_start(&mut manager, project, &team_id).unwrap();
+        assert_eq!(session_info.unwrap().terminals.len(), 1);
+        manager.consume_saved_session(project, &team_id, &orchestrator_id).unwrap();
+        let (_, _, session_info) = begin_start(&mut manager, project, &team_id).unwrap();
+        assert!(session_info.is_none());
+
+        manager.archive_team(project, &team_id).unwrap();
+        assert!(begin_start(&mut manager, project, &team_id).is_err());
+    }
+}
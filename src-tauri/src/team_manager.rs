@@ -1,13 +1,14 @@
 #![allow(dead_code)]
 
 use crate::team_storage::{
-    TeamConfig, TeamMemberConfig, TeamState, TeamStatus,
+    TeamConfig, TeamMemberConfig, MemberLaunchSettings, TeamState, TeamStatus,
     TeamStorage, TeamStorageError,
     MemberState, MemberStatus, MemberTaskInfo,
     RecoveryEvent, RecoveryContextSummary,
     SessionInfo, TerminalSessionInfo,
 };
-use chrono::Utc;
+use crate::recovery_context;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -47,19 +48,37 @@ pub struct CreateTeamInput {
     pub auto_recovery: bool,
     #[serde(default = "default_max_recovery_attempts")]
     pub max_recovery_attempts: u32,
+    #[serde(default = "default_recovery_delay_ms")]
+    pub recovery_delay_ms: u64,
+    /// Orchestrator settings (default: role and agent type "orchestrator")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orchestrator: Option<MemberInput>,
     #[serde(default)]
     pub members: Vec<MemberInput>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 fn default_auto_recovery() -> bool { true }
 fn default_max_recovery_attempts() -> u32 { 3 }
+fn default_recovery_delay_ms() -> u64 { 5000 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemberInput {
     pub role: String,
     pub agent_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch: Option<MemberLaunchSettings>,
+}
+
+impl MemberInput {
+    fn into_config(self) -> TeamMemberConfig {
+        let mut member = TeamMemberConfig::new(self.role, self.agent_type);
+        member.launch = self.launch;
+        member
+    }
 }
 
 /// Team summary for listing
@@ -81,6 +100,23 @@ pub struct TeamData {
     pub state: TeamState,
 }
 
+impl TeamData {
+    /// Whether a failed member may be replaced automatically: auto-recovery
+    /// is on and the member's slot has failed fewer than
+    /// `max_recovery_attempts` times
+    pub fn recovery_allowed(&self, member_id: &str) -> bool {
+        if !self.config.auto_recovery {
+            return false;
+        }
+        let member = if self.config.orchestrator.id == member_id {
+            Some(&self.config.orchestrator)
+        } else {
+            self.config.workers.iter().find(|w| w.id == member_id)
+        };
+        member.is_some_and(|m| m.failure_count < self.config.max_recovery_attempts)
+    }
+}
+
 /// Member with session info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemberWithState {
@@ -92,6 +128,7 @@ pub struct MemberWithState {
     pub claude_session_id: Option<String>,
     pub current_task: Option<MemberTaskInfo>,
     pub failure_count: u32,
+    pub last_heartbeat: Option<DateTime<Utc>>,
 }
 
 /// Recovery context for spawning replacement agent
@@ -103,8 +140,18 @@ pub struct RecoveryContext {
     pub progress: u32,
     pub completed_steps: Vec<String>,
     pub current_step: Option<String>,
+    /// Files changed in the member's directory (`git status --short` style)
     pub artifacts: Vec<String>,
+    /// First message for the replacement session
     pub resume_instructions: String,
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    /// Most recent tool calls, oldest first
+    #[serde(default)]
+    pub tool_calls: Vec<String>,
+    /// Most recent assistant messages, oldest first
+    #[serde(default)]
+    pub recent_messages: Vec<String>,
 }
 
 /// Team manager for handling team operations
@@ -127,12 +174,15 @@ impl TeamManager {
     /// Create a new team
     pub fn create_team(&mut self, input: CreateTeamInput) -> Result<TeamData, TeamManagerError> {
         // Create orchestrator member
-        let orchestrator = TeamMemberConfig::new("orchestrator".to_string(), "orchestrator".to_string());
+        let orchestrator = match input.orchestrator {
+            Some(o) => o.into_config(),
+            None => TeamMemberConfig::new("orchestrator".to_string(), "orchestrator".to_string()),
+        };
 
         // Create worker members
         let workers: Vec<TeamMemberConfig> = input.members
-            .iter()
-            .map(|m| TeamMemberConfig::new(m.role.clone(), m.agent_type.clone()))
+            .into_iter()
+            .map(MemberInput::into_config)
             .collect();
 
         let config = TeamConfig {
@@ -145,9 +195,9 @@ impl TeamManager {
             workers,
             auto_recovery: input.auto_recovery,
             max_recovery_attempts: input.max_recovery_attempts,
-            recovery_delay_ms: 5000,
+            recovery_delay_ms: input.recovery_delay_ms,
             description: input.description,
-            tags: Vec::new(),
+            tags: input.tags,
         };
 
         let config = self.storage.create_team(config)?;
@@ -454,8 +504,9 @@ impl TeamManager {
             ));
         }
 
-        // Get session info before clearing
-        let session_info = state.session_info.take();
+        // Saved sessions stay until their members are launched again
+        // (see `consume_saved_session`)
+        let session_info = state.session_info.clone();
 
         // Update all member statuses
         for member_state in state.members.values_mut() {
@@ -474,6 +525,30 @@ impl TeamManager {
         Ok((team_data, session_info))
     }
 
+    /// Drop a member's saved session once the member has been launched
+    pub fn consume_saved_session(
+        &mut self,
+        project_path: &str,
+        team_id: &str,
+        member_id: &str,
+    ) -> Result<(), TeamManagerError> {
+        let (config, mut state) = self.storage.get_team(project_path, team_id)?;
+        let Some(info) = state.session_info.as_mut() else {
+            return Ok(());
+        };
+        info.terminals.retain(|t| t.member_id != member_id);
+        if info.terminals.is_empty() {
+            state.session_info = None;
+        }
+
+        self.storage.save_state(project_path, &state)?;
+        if state.status == TeamStatus::Active {
+            self.active_teams.insert(team_id.to_string(), TeamData { config, state });
+        }
+
+        Ok(())
+    }
+
     // ===== Recovery Operations =====
 
     /// Report member failure
@@ -540,8 +615,12 @@ impl TeamManager {
             failed_member.agent_type.clone(),
         );
         replacement.recovered_from = Some(failed_member_id.to_string());
+        // Failures count per slot, so max_recovery_attempts holds across replacements
+        replacement.failure_count = failed_member.failure_count;
+        replacement.last_failure = failed_member.last_failure;
         replacement.current_task_id = failed_member.current_task_id.clone();
         replacement.current_spec_id = failed_member.current_spec_id.clone();
+        replacement.launch = failed_member.launch.clone();
 
         // Update config - replace failed member
         if config.orchestrator.id == failed_member_id {
@@ -637,6 +716,7 @@ impl TeamManager {
             claude_session_id: orch_state.and_then(|s| s.claude_session_id.clone()),
             current_task: orch_state.and_then(|s| s.current_task.clone()),
             failure_count: config.orchestrator.failure_count,
+            last_heartbeat: orch_state.and_then(|s| s.last_heartbeat),
         });
 
         // Add workers
@@ -651,22 +731,31 @@ impl TeamManager {
                 claude_session_id: worker_state.and_then(|s| s.claude_session_id.clone()),
                 current_task: worker_state.and_then(|s| s.current_task.clone()),
                 failure_count: worker.failure_count,
+                last_heartbeat: worker_state.and_then(|s| s.last_heartbeat),
             });
         }
 
         Ok(members)
     }
 
-    /// Build recovery context for a failed member
-    pub fn build_recovery_context(
+    /// What a failed member was doing, for `recovery_context::build`
+    ///
+    /// Only reads the team; the transcript, git status and tasks.md are read
+    /// by `recovery_context::gather` once the manager is released.
+    pub fn recovery_inputs(
         &self,
         project_path: &str,
         team_id: &str,
         member_id: &str,
-    ) -> Result<RecoveryContext, TeamManagerError> {
-        let state = self.storage.load_state(project_path, team_id)?;
+    ) -> Result<recovery_context::RecoveryInputs, TeamManagerError> {
+        let (config, state) = self.storage.get_team(project_path, team_id)?;
         let member_state = state.members.get(member_id)
             .ok_or_else(|| TeamManagerError::MemberNotFound(member_id.to_string()))?;
+        let member = if config.orchestrator.id == member_id {
+            Some(&config.orchestrator)
+        } else {
+            config.workers.iter().find(|w| w.id == member_id)
+        };
 
         let (spec_id, task_id, phase, progress) = if let Some(task) = &member_state.current_task {
             (task.spec_id.clone(), Some(task.task_id.clone()), Some(task.phase.clone()), task.progress)
@@ -674,26 +763,17 @@ impl TeamManager {
             (None, None, None, 0)
         };
 
-        // Build resume instructions
-        let resume_instructions = if let Some(ref spec) = spec_id {
-            format!(
-                "You are replacing a failed agent. Continue work on spec '{}' from {}% progress. \
-                 Review existing artifacts before proceeding.",
-                spec, progress
-            )
-        } else {
-            "You are replacing a failed agent. Check for pending tasks and continue work.".to_string()
-        };
-
-        Ok(RecoveryContext {
-            spec_id,
+        Ok(recovery_context::RecoveryInputs {
+            project_path: project_path.to_string(),
+            spec_id: spec_id.or_else(|| member.and_then(|m| m.current_spec_id.clone())),
             task_id,
             phase,
             progress,
-            completed_steps: Vec::new(), // Would be populated from progress reports
-            current_step: None,
-            artifacts: Vec::new(), // Would be populated from graph query
-            resume_instructions,
+            claude_session_id: member_state.claude_session_id.clone(),
+            member_dir: member.map(|m| recovery_context::member_dir(project_path, m)),
+            own_worktree: member
+                .and_then(|m| m.launch.as_ref())
+                .is_some_and(|launch| launch.worktree.is_some()),
         })
     }
 }
@@ -704,3 +784,80 @@ pub type SharedTeamManager = Arc<Mutex<TeamManager>>;
 pub fn create_team_manager() -> Result<SharedTeamManager, TeamManagerError> {
     Ok(Arc::new(Mutex::new(TeamManager::new()?)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_team(manager: &mut TeamManager, project: &str) -> TeamData {
+        manager
+            .create_team(CreateTeamInput {
+                name: "recovery-test".to_string(),
+                project_path: project.to_string(),
+                auto_recovery: true,
+                max_recovery_attempts: 2,
+                recovery_delay_ms: 0,
+                orchestrator: None,
+                members: vec![MemberInput {
+                    role: "dev".to_string(),
+                    agent_type: "dev-agent".to_string(),
+                    launch: None,
+                }],
+                description: None,
+                tags: Vec::new(),
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_recovery_stops_after_max_attempts() {
+        crate::db::use_test_db();
+        crate::project_identity::use_test_mapping();
+        let mut manager = TeamManager::new().unwrap();
+        let project = "/no/such/team-manager-recovery-project";
+        let team = create_test_team(&mut manager, project);
+        let team_id = team.config.id.clone();
+        let mut member_id = team.config.workers[0].id.clone();
+
+        for attempt in 1..=2 {
+            assert!(manager.get_team(project, &team_id).unwrap().recovery_allowed(&member_id));
+            manager.report_member_failure(project, &team_id, &member_id, "crashed").unwrap();
+            let replacement = manager.create_replacement_member(project, &team_id, &member_id).unwrap();
+            assert_eq!(replacement.failure_count, attempt);
+            assert_eq!(replacement.recovered_from.as_deref(), Some(member_id.as_str()));
+            member_id = replacement.id;
+        }
+
+        let team = manager.get_team(project, &team_id).unwrap();
+        assert_eq!(team.config.workers[0].id, member_id);
+        assert!(!team.recovery_allowed(&member_id));
+        assert!(!team.recovery_allowed("unknown-member"));
+    }
+
+    #[test]
+    fn test_recovery_inputs_from_member_task() {
+        crate::db::use_test_db();
+        crate::project_identity::use_test_mapping();
+        let mut manager = TeamManager::new().unwrap();
+        let project = "/no/such/team-manager-inputs-project";
+        let team = create_test_team(&mut manager, project);
+        let member_id = team.config.workers[0].id.clone();
+
+        manager
+            .update_member_task(project, &team.config.id, &member_id, Some(MemberTaskInfo {
+                task_id: "1.2".to_string(),
+                spec_id: Some("add-dark-mode".to_string()),
+                phase: "implementation".to_string(),
+                progress: 40,
+            }))
+            .unwrap();
+
+        let inputs = manager.recovery_inputs(project, &team.config.id, &member_id).unwrap();
+        assert_eq!(inputs.spec_id.as_deref(), Some("add-dark-mode"));
+        assert_eq!(inputs.task_id.as_deref(), Some("1.2"));
+        assert_eq!(inputs.progress, 40);
+        assert_eq!(inputs.member_dir, Some(std::path::PathBuf::from(project)));
+        assert!(!inputs.own_worktree);
+        assert!(manager.recovery_inputs(project, &team.config.id, "unknown-member").is_err());
+    }
+}
@@ -3,10 +3,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
 use std::path::PathBuf;
 use uuid::Uuid;
 
+use crate::db;
+
 /// Team member configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeamMemberConfig {
@@ -33,6 +34,77 @@ pub struct TeamMemberConfig {
     pub last_failure: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recovered_from: Option<String>,
+
+    // How to launch the agent (set by team templates)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch: Option<MemberLaunchSettings>,
+}
+
+/// Launch settings for a member's agent
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MemberLaunchSettings {
+    /// Initial prompt sent when the agent starts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    /// Working directory, relative to the project root (or the worktree)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// Run in the git worktree of this task ID, created if missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<String>,
+    /// Started with the team; otherwise only on demand
+    pub auto_start: bool,
+}
+
+impl Default for MemberLaunchSettings {
+    fn default() -> Self {
+        Self {
+            prompt: None,
+            working_dir: None,
+            worktree: None,
+            auto_start: true,
+        }
+    }
+}
+
+impl MemberLaunchSettings {
+    /// Check that `working_dir` stays inside the project and `worktree` is a
+    /// plain task ID (both end up in paths)
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(dir) = &self.working_dir {
+            validate_working_dir(dir)?;
+        }
+        if let Some(task_id) = &self.worktree {
+            validate_worktree(task_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// A relative path without `..`
+pub fn validate_working_dir(dir: &str) -> Result<(), String> {
+    let inside = std::path::Path::new(dir)
+        .components()
+        .all(|c| matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir));
+    if !inside {
+        return Err(format!("working_dir '{}' must be a path inside the project", dir));
+    }
+    Ok(())
+}
+
+/// Letters, digits, `-`, `_` and `.`, not starting with `.`
+pub fn validate_worktree(task_id: &str) -> Result<(), String> {
+    let valid = !task_id.is_empty()
+        && !task_id.starts_with('.')
+        && task_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!(
+            "worktree '{}' must be a task ID (letters, digits, '-', '_' and '.')",
+            task_id
+        ));
+    }
+    Ok(())
 }
 
 impl TeamMemberConfig {
@@ -48,11 +120,12 @@ impl TeamMemberConfig {
             failure_count: 0,
             last_failure: None,
             recovered_from: None,
+            launch: None,
         }
     }
 }
 
-/// Team configuration stored in team.json
+/// Team configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeamConfig {
     pub id: String,
@@ -142,7 +215,7 @@ pub struct TerminalSessionInfo {
     pub cwd: String,
 }
 
-/// Team state stored in state.json
+/// Team state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeamState {
     pub team_id: String,
@@ -194,7 +267,7 @@ pub struct RecoveryContextSummary {
     pub current_step: Option<String>,
 }
 
-/// Team history stored in history.json
+/// Team history (recovery events, newest first)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeamHistory {
     pub team_id: String,
@@ -210,7 +283,7 @@ impl TeamHistory {
     }
 }
 
-/// Team index entry for teams.json
+/// Team index entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeamIndexEntry {
     pub id: String,
@@ -220,7 +293,7 @@ pub struct TeamIndexEntry {
     pub member_count: usize,
 }
 
-/// Team index stored in teams.json
+/// Teams of a project (stored as teams.json before the database)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeamIndex {
     pub project_path: String,
@@ -246,6 +319,7 @@ pub enum TeamStorageError {
     TeamNotFound(String),
     TeamAlreadyExists(String),
     InvalidPath(String),
+    DatabaseError(String),
 }
 
 impl std::fmt::Display for TeamStorageError {
@@ -256,6 +330,7 @@ impl std::fmt::Display for TeamStorageError {
             TeamStorageError::TeamNotFound(id) => write!(f, "Team not found: {}", id),
             TeamStorageError::TeamAlreadyExists(name) => write!(f, "Team already exists: {}", name),
             TeamStorageError::InvalidPath(p) => write!(f, "Invalid path: {}", p),
+            TeamStorageError::DatabaseError(e) => write!(f, "Database error: {}", e),
         }
     }
 }
@@ -273,191 +348,103 @@ impl From<serde_json::Error> for TeamStorageError {
 }
 
 /// Team storage for persisting team data
+///
+/// Teams live in the app database (see `db::teams`). `base_dir` is the
+/// directory of the JSON files used before, kept for migration and import.
 pub struct TeamStorage {
     base_dir: PathBuf,
 }
 
 impl TeamStorage {
-    /// Create new TeamStorage with base directory
+    /// Create new TeamStorage
     pub fn new() -> Result<Self, TeamStorageError> {
         let home = dirs::home_dir().ok_or_else(|| {
             TeamStorageError::InvalidPath("Could not find home directory".to_string())
         })?;
         let base_dir = home.join(".sidstack").join("teams");
-        fs::create_dir_all(&base_dir)?;
         Ok(Self { base_dir })
     }
 
-    /// Get project hash from path
+    /// Get project hash from path (stable id, see `project_identity`)
     pub fn hash_project_path(project_path: &str) -> String {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let mut hasher = DefaultHasher::new();
-        project_path.hash(&mut hasher);
-        format!("{:x}", hasher.finish())
+        crate::project_identity::project_id(project_path)
     }
 
-    /// Get project directory
-    fn project_dir(&self, project_path: &str) -> PathBuf {
-        let hash = Self::hash_project_path(project_path);
-        self.base_dir.join(&hash)
+    /// Legacy JSON directory holding one subdirectory per project
+    pub fn base_dir(&self) -> &std::path::Path {
+        &self.base_dir
     }
 
-    /// Get team directory
-    fn team_dir(&self, project_path: &str, team_id: &str) -> PathBuf {
-        self.project_dir(project_path).join(team_id)
-    }
-
-    /// Ensure project directory exists
-    fn ensure_project_dir(&self, project_path: &str) -> Result<PathBuf, TeamStorageError> {
-        let dir = self.project_dir(project_path);
-        fs::create_dir_all(&dir)?;
-        Ok(dir)
-    }
-
-    /// Ensure team directory exists
-    fn ensure_team_dir(&self, project_path: &str, team_id: &str) -> Result<PathBuf, TeamStorageError> {
-        let dir = self.team_dir(project_path, team_id);
-        fs::create_dir_all(&dir)?;
-        Ok(dir)
+    fn with_db<T>(f: impl FnOnce(&rusqlite::Connection) -> Result<T, String>) -> Result<T, TeamStorageError> {
+        db::with_connection(|conn| f(conn)).map_err(TeamStorageError::DatabaseError)
     }
 
     // ===== Team Index Operations =====
 
     /// Load team index for a project
     pub fn load_index(&self, project_path: &str) -> Result<TeamIndex, TeamStorageError> {
-        let index_path = self.project_dir(project_path).join("teams.json");
-        if index_path.exists() {
-            let content = fs::read_to_string(&index_path)?;
-            Ok(serde_json::from_str(&content)?)
-        } else {
-            Ok(TeamIndex::new(
-                project_path.to_string(),
-                Self::hash_project_path(project_path),
-            ))
-        }
-    }
-
-    /// Save team index
-    pub fn save_index(&self, project_path: &str, index: &TeamIndex) -> Result<(), TeamStorageError> {
-        self.ensure_project_dir(project_path)?;
-        let index_path = self.project_dir(project_path).join("teams.json");
-        let content = serde_json::to_string_pretty(index)?;
-        fs::write(index_path, content)?;
-        Ok(())
-    }
-
-    /// Update index entry
-    pub fn update_index_entry(&self, project_path: &str, entry: TeamIndexEntry) -> Result<(), TeamStorageError> {
-        let mut index = self.load_index(project_path)?;
-        if let Some(existing) = index.teams.iter_mut().find(|t| t.id == entry.id) {
-            *existing = entry;
-        } else {
-            index.teams.push(entry);
-        }
-        self.save_index(project_path, &index)
-    }
-
-    /// Remove from index
-    pub fn remove_from_index(&self, project_path: &str, team_id: &str) -> Result<(), TeamStorageError> {
-        let mut index = self.load_index(project_path)?;
-        index.teams.retain(|t| t.id != team_id);
-        self.save_index(project_path, &index)
+        let project_hash = Self::hash_project_path(project_path);
+        let teams = Self::with_db(|conn| db::teams::list(conn, &project_hash, None))?;
+        Ok(TeamIndex {
+            project_path: project_path.to_string(),
+            project_hash,
+            teams,
+        })
     }
 
     // ===== Team Config Operations =====
 
-    /// Save team config
+    /// Save team config (creates the team if it does not exist)
     pub fn save_config(&self, config: &TeamConfig) -> Result<(), TeamStorageError> {
-        let team_dir = self.ensure_team_dir(&config.project_path, &config.id)?;
-        let config_path = team_dir.join("team.json");
-        let content = serde_json::to_string_pretty(config)?;
-        fs::write(config_path, content)?;
-
-        // Update index
-        let entry = TeamIndexEntry {
-            id: config.id.clone(),
-            name: config.name.clone(),
-            status: TeamStatus::Active,
-            last_active: Utc::now(),
-            member_count: 1 + config.workers.len(),
-        };
-        self.update_index_entry(&config.project_path, entry)?;
-
-        Ok(())
+        Self::with_db(|conn| db::teams::upsert_config(conn, config))
     }
 
     /// Load team config
     pub fn load_config(&self, project_path: &str, team_id: &str) -> Result<TeamConfig, TeamStorageError> {
-        let config_path = self.team_dir(project_path, team_id).join("team.json");
-        if config_path.exists() {
-            let content = fs::read_to_string(&config_path)?;
-            Ok(serde_json::from_str(&content)?)
-        } else {
-            Err(TeamStorageError::TeamNotFound(team_id.to_string()))
-        }
+        let project_hash = Self::hash_project_path(project_path);
+        Self::with_db(|conn| db::teams::get_config(conn, &project_hash, team_id))?
+            .ok_or_else(|| TeamStorageError::TeamNotFound(team_id.to_string()))
     }
 
     // ===== Team State Operations =====
 
-    /// Save team state
+    /// Save team state (and the status shown in the index)
     pub fn save_state(&self, project_path: &str, state: &TeamState) -> Result<(), TeamStorageError> {
-        let team_dir = self.ensure_team_dir(project_path, &state.team_id)?;
-        let state_path = team_dir.join("state.json");
-        let content = serde_json::to_string_pretty(state)?;
-        fs::write(state_path, content)?;
-
-        // Update index status
-        let mut index = self.load_index(project_path)?;
-        if let Some(entry) = index.teams.iter_mut().find(|t| t.id == state.team_id) {
-            entry.status = state.status.clone();
-            entry.last_active = state.last_active;
+        let project_hash = Self::hash_project_path(project_path);
+        if Self::with_db(|conn| db::teams::save_state(conn, &project_hash, state))? {
+            Ok(())
+        } else {
+            Err(TeamStorageError::TeamNotFound(state.team_id.clone()))
         }
-        self.save_index(project_path, &index)?;
-
-        Ok(())
     }
 
     /// Load team state
     pub fn load_state(&self, project_path: &str, team_id: &str) -> Result<TeamState, TeamStorageError> {
-        let state_path = self.team_dir(project_path, team_id).join("state.json");
-        if state_path.exists() {
-            let content = fs::read_to_string(&state_path)?;
-            Ok(serde_json::from_str(&content)?)
-        } else {
-            Ok(TeamState::new(team_id.to_string()))
-        }
+        let project_hash = Self::hash_project_path(project_path);
+        let state = Self::with_db(|conn| db::teams::get_state(conn, &project_hash, team_id))?;
+        Ok(state.unwrap_or_else(|| TeamState::new(team_id.to_string())))
     }
 
     // ===== Team History Operations =====
 
     /// Save team history
-    pub fn save_history(&self, project_path: &str, history: &TeamHistory) -> Result<(), TeamStorageError> {
-        let team_dir = self.ensure_team_dir(project_path, &history.team_id)?;
-        let history_path = team_dir.join("history.json");
-        let content = serde_json::to_string_pretty(history)?;
-        fs::write(history_path, content)?;
-        Ok(())
+    pub fn save_history(&self, _project_path: &str, history: &TeamHistory) -> Result<(), TeamStorageError> {
+        Self::with_db(|conn| db::teams::replace_events(conn, &history.team_id, &history.events))
     }
 
     /// Load team history
-    pub fn load_history(&self, project_path: &str, team_id: &str) -> Result<TeamHistory, TeamStorageError> {
-        let history_path = self.team_dir(project_path, team_id).join("history.json");
-        if history_path.exists() {
-            let content = fs::read_to_string(&history_path)?;
-            Ok(serde_json::from_str(&content)?)
-        } else {
-            Ok(TeamHistory::new(team_id.to_string()))
-        }
+    pub fn load_history(&self, _project_path: &str, team_id: &str) -> Result<TeamHistory, TeamStorageError> {
+        let events = Self::with_db(|conn| db::teams::list_events(conn, team_id))?;
+        Ok(TeamHistory {
+            team_id: team_id.to_string(),
+            events,
+        })
     }
 
     /// Add recovery event to history
-    pub fn add_recovery_event(&self, project_path: &str, team_id: &str, event: RecoveryEvent) -> Result<(), TeamStorageError> {
-        let mut history = self.load_history(project_path, team_id)?;
-        history.events.insert(0, event); // Newest first
+    pub fn add_recovery_event(&self, _project_path: &str, team_id: &str, event: RecoveryEvent) -> Result<(), TeamStorageError> {
         // Keep only last 100 events
-        history.events.truncate(100);
-        self.save_history(project_path, &history)
+        Self::with_db(|conn| db::teams::add_event(conn, team_id, &event, 100))
     }
 
     // ===== Team CRUD Operations =====
@@ -465,8 +452,8 @@ impl TeamStorage {
     /// Create a new team
     pub fn create_team(&self, config: TeamConfig) -> Result<TeamConfig, TeamStorageError> {
         // Check if team with same name exists
-        let index = self.load_index(&config.project_path)?;
-        if index.teams.iter().any(|t| t.name == config.name) {
+        let project_hash = Self::hash_project_path(&config.project_path);
+        if Self::with_db(|conn| db::teams::name_taken(conn, &project_hash, &config.name))? {
             return Err(TeamStorageError::TeamAlreadyExists(config.name.clone()));
         }
 
@@ -493,22 +480,13 @@ impl TeamStorage {
         }
         self.save_state(&config.project_path, &state)?;
 
-        // Initialize history
-        let history = TeamHistory::new(config.id.clone());
-        self.save_history(&config.project_path, &history)?;
-
         Ok(config)
     }
 
     /// List teams for a project
     pub fn list_teams(&self, project_path: &str, status_filter: Option<TeamStatus>) -> Result<Vec<TeamIndexEntry>, TeamStorageError> {
-        let index = self.load_index(project_path)?;
-        let teams = if let Some(status) = status_filter {
-            index.teams.into_iter().filter(|t| t.status == status).collect()
-        } else {
-            index.teams
-        };
-        Ok(teams)
+        let project_hash = Self::hash_project_path(project_path);
+        Self::with_db(|conn| db::teams::list(conn, &project_hash, status_filter.as_ref()))
     }
 
     /// Get full team data
@@ -526,13 +504,11 @@ impl TeamStorage {
         self.save_state(project_path, &state)
     }
 
-    /// Delete a team (removes all files)
+    /// Delete a team and its history
     pub fn delete_team(&self, project_path: &str, team_id: &str) -> Result<(), TeamStorageError> {
-        let team_dir = self.team_dir(project_path, team_id);
-        if team_dir.exists() {
-            fs::remove_dir_all(&team_dir)?;
-        }
-        self.remove_from_index(project_path, team_id)
+        let project_hash = Self::hash_project_path(project_path);
+        Self::with_db(|conn| db::teams::delete(conn, &project_hash, team_id))?;
+        Ok(())
     }
 }
 
@@ -542,6 +518,8 @@ mod tests {
     use tempfile::tempdir;
 
     fn create_test_storage() -> TeamStorage {
+        crate::db::use_test_db();
+        crate::project_identity::use_test_mapping();
         TeamStorage::new().unwrap()
     }
 
@@ -566,6 +544,7 @@ mod tests {
 
     #[test]
     fn test_hash_project_path() {
+        crate::project_identity::use_test_mapping();
         let hash1 = TeamStorage::hash_project_path("/path/to/project");
         let hash2 = TeamStorage::hash_project_path("/path/to/project");
         let hash3 = TeamStorage::hash_project_path("/different/path");
@@ -583,6 +562,30 @@ mod tests {
         assert_eq!(member.failure_count, 0);
     }
 
+    #[test]
+    fn test_create_list_delete_team() {
+        let storage = create_test_storage();
+        let project = "/no/such/team-storage-project";
+        let config = storage.create_team(create_test_config(project)).unwrap();
+        assert!(matches!(
+            storage.create_team(create_test_config(project)),
+            Err(TeamStorageError::TeamAlreadyExists(_))
+        ));
+
+        storage.archive_team(project, &config.id).unwrap();
+        let archived = storage.list_teams(project, Some(TeamStatus::Archived)).unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].member_count, 2);
+        assert!(storage.list_teams(project, Some(TeamStatus::Active)).unwrap().is_empty());
+
+        storage.delete_team(project, &config.id).unwrap();
+        assert!(storage.list_teams(project, None).unwrap().is_empty());
+        assert!(matches!(
+            storage.load_config(project, &config.id),
+            Err(TeamStorageError::TeamNotFound(_))
+        ));
+    }
+
     #[test]
     fn test_team_state_new() {
         let state = TeamState::new("team-123".to_string());
@@ -0,0 +1,584 @@
+//! Team Templates
+//!
+//! Reusable team definitions in YAML, built in or in a project's
+//! `.sidstack/teams/*.yaml` (a project template replaces the built-in with the
+//! same ID). The ID is the file name without extension.
+//!
+//! Strings may use `{{variable}}` placeholders for the template's declared
+//! variables and the built-ins `project_path` and `project_name`; they are
+//! substituted when a team is created from the template. A variable without
+//! a default is required.
+//!
+//! Validation reports problems per file with a line number where one can be
+//! found, in the same shape as knowledge file validation.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::team_manager::{CreateTeamInput, MemberInput};
+use crate::team_storage::{self, MemberLaunchSettings};
+
+const TEAMS_DIR: &str = "teams";
+
+/// Built-in templates (ID, YAML)
+const BUILTIN: &[(&str, &str)] = &[
+    ("feature", include_str!("../../packages/shared/templates/teams/feature.yaml")),
+    ("bugfix", include_str!("../../packages/shared/templates/teams/bugfix.yaml")),
+];
+
+/// Variables every template can use
+const BUILTIN_VARIABLES: &[&str] = &["project_path", "project_name"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A variable the template takes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateVariable {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Required when unset
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+/// Orchestrator or worker definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateMember {
+    /// Required for workers; the orchestrator's is "orchestrator"
+    #[serde(default)]
+    pub role: Option<String>,
+    pub agent_type: String,
+    #[serde(default)]
+    pub launch: Option<MemberLaunchSettings>,
+}
+
+/// Recovery settings of teams created from the template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TemplateRecovery {
+    pub auto_recovery: bool,
+    pub max_recovery_attempts: u32,
+    pub recovery_delay_ms: u64,
+}
+
+impl Default for TemplateRecovery {
+    fn default() -> Self {
+        Self {
+            auto_recovery: true,
+            max_recovery_attempts: 3,
+            recovery_delay_ms: 5000,
+        }
+    }
+}
+
+/// A team template file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TeamTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub variables: Vec<TemplateVariable>,
+    #[serde(default)]
+    pub orchestrator: Option<TemplateMember>,
+    #[serde(default)]
+    pub workers: Vec<TemplateMember>,
+    #[serde(default)]
+    pub recovery: TemplateRecovery,
+}
+
+/// Template as listed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamTemplateInfo {
+    pub id: String,
+    /// "builtin" or "project"
+    pub source: String,
+    /// File it was read from (project templates)
+    pub path: Option<String>,
+    pub template: TeamTemplate,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TemplateValidationResult {
+    pub total_files: usize,
+    pub valid_files: usize,
+    pub invalid_files: usize,
+    pub issues: Vec<TemplateIssue>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TemplateIssue {
+    pub path: String,
+    pub severity: String, // "error", "warning"
+    pub message: String,
+    pub line: Option<usize>,
+}
+
+/// A template file before parsing
+struct TemplateSource {
+    id: String,
+    path: Option<PathBuf>,
+    content: String,
+}
+
+impl TemplateSource {
+    fn display_path(&self) -> String {
+        match &self.path {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => format!("builtin:{}", self.id),
+        }
+    }
+}
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+fn teams_dir(project_path: &str) -> PathBuf {
+    Path::new(project_path).join(".sidstack").join(TEAMS_DIR)
+}
+
+/// Template files of a project, sorted by name
+fn project_sources(project_path: &str) -> Result<Vec<TemplateSource>, String> {
+    let dir = teams_dir(project_path);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read {:?}: {}", dir, e))?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+            let id = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            Ok(TemplateSource { id, path: Some(path), content })
+        })
+        .collect()
+}
+
+fn builtin_sources() -> Vec<TemplateSource> {
+    BUILTIN
+        .iter()
+        .map(|(id, content)| TemplateSource {
+            id: id.to_string(),
+            path: None,
+            content: content.to_string(),
+        })
+        .collect()
+}
+
+/// First line (1-based) containing `needle`
+fn line_of(content: &str, needle: &str) -> Option<usize> {
+    content.lines().position(|line| line.contains(needle)).map(|i| i + 1)
+}
+
+/// Names used in `{{name}}` placeholders
+fn placeholders(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        names.push(rest[start + 2..start + 2 + len].trim());
+        rest = &rest[start + 2 + len + 2..];
+    }
+    names
+}
+
+impl TeamTemplate {
+    /// Orchestrator and workers, with the orchestrator first
+    fn members(&self) -> impl Iterator<Item = &TemplateMember> {
+        self.orchestrator.iter().chain(self.workers.iter())
+    }
+
+    /// Every string that placeholders are substituted in
+    fn strings(&self) -> Vec<&str> {
+        let mut strings = vec![self.name.as_str()];
+        strings.extend(self.description.as_deref());
+        strings.extend(self.tags.iter().map(String::as_str));
+        for member in self.members() {
+            strings.extend(member.role.as_deref());
+            strings.push(&member.agent_type);
+            if let Some(launch) = &member.launch {
+                strings.extend(launch.prompt.as_deref());
+                strings.extend(launch.working_dir.as_deref());
+                strings.extend(launch.worktree.as_deref());
+            }
+        }
+        strings
+    }
+}
+
+/// Check a parsed template; `content` is only used to find line numbers
+fn validate(template: &TeamTemplate, content: &str) -> Vec<TemplateIssue> {
+    let mut issues = Vec::new();
+    let mut issue = |severity: &str, message: String, needle: &str| {
+        issues.push(TemplateIssue {
+            path: String::new(),
+            severity: severity.to_string(),
+            message,
+            line: line_of(content, needle),
+        });
+    };
+
+    if template.name.trim().is_empty() {
+        issue("error", "name must not be empty".to_string(), "name:");
+    }
+    if template.workers.is_empty() {
+        issue("warning", "Template has no workers".to_string(), "workers:");
+    }
+
+    let mut declared = HashSet::new();
+    for variable in &template.variables {
+        let needle = format!("name: {}", variable.name);
+        let valid_name = !variable.name.is_empty()
+            && variable.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            issue("error", format!("Invalid variable name '{}' (use letters, digits and _)", variable.name), &needle);
+        } else if BUILTIN_VARIABLES.contains(&variable.name.as_str()) {
+            issue("error", format!("Variable '{}' is built in and can't be redeclared", variable.name), &needle);
+        } else if !declared.insert(variable.name.as_str()) {
+            issue("error", format!("Variable '{}' is declared twice", variable.name), &needle);
+        }
+    }
+
+    for text in template.strings() {
+        for name in placeholders(text) {
+            if !declared.contains(name) && !BUILTIN_VARIABLES.contains(&name) {
+                issue("error", format!("Undeclared variable '{{{{{}}}}}'", name), &format!("{{{{{}", name));
+            }
+        }
+    }
+
+    if let Some(orchestrator) = &template.orchestrator {
+        if orchestrator.role.as_deref().is_some_and(|r| r != "orchestrator") {
+            issue("error", "The orchestrator's role must be 'orchestrator'".to_string(), "orchestrator:");
+        }
+    }
+    for worker in &template.workers {
+        match worker.role.as_deref().map(str::trim) {
+            None | Some("") => issue(
+                "error",
+                format!("Worker with agent_type '{}' has no role", worker.agent_type),
+                &format!("agent_type: {}", worker.agent_type),
+            ),
+            Some("orchestrator") => issue(
+                "error",
+                "Workers can't have the orchestrator role".to_string(),
+                "role: orchestrator",
+            ),
+            Some(_) => {}
+        }
+    }
+    for member in template.members() {
+        if member.agent_type.trim().is_empty() {
+            issue("error", "agent_type must not be empty".to_string(), "agent_type:");
+        }
+        // Values with placeholders are checked once rendered
+        let Some(launch) = &member.launch else {
+            continue;
+        };
+        if let Some(dir) = launch.working_dir.as_deref().filter(|d| placeholders(d).is_empty()) {
+            if let Err(e) = team_storage::validate_working_dir(dir) {
+                issue("error", e, &format!("working_dir: {}", dir));
+            }
+        }
+        if let Some(task_id) = launch.worktree.as_deref().filter(|t| placeholders(t).is_empty()) {
+            if let Err(e) = team_storage::validate_worktree(task_id) {
+                issue("error", e, &format!("worktree: {}", task_id));
+            }
+        }
+    }
+
+    if template.recovery.auto_recovery && template.recovery.max_recovery_attempts == 0 {
+        issue(
+            "warning",
+            "auto_recovery is on but max_recovery_attempts is 0, so no recovery will happen".to_string(),
+            "max_recovery_attempts:",
+        );
+    }
+
+    issues
+}
+
+/// Parse and validate a template file
+fn parse(source: &TemplateSource) -> Result<(TeamTemplate, Vec<TemplateIssue>), Vec<TemplateIssue>> {
+    let template: TeamTemplate = serde_yaml::from_str(&source.content).map_err(|e| {
+        vec![TemplateIssue {
+            path: String::new(),
+            severity: "error".to_string(),
+            message: format!("Invalid template: {}", e),
+            line: e.location().map(|l| l.line()),
+        }]
+    })?;
+    let issues = validate(&template, &source.content);
+    if issues.iter().any(|i| i.severity == "error") {
+        Err(issues)
+    } else {
+        Ok((template, issues))
+    }
+}
+
+/// Built-in and project templates; project templates replace built-ins
+fn all_sources(project_path: &str) -> Result<Vec<TemplateSource>, String> {
+    let project = project_sources(project_path)?;
+    let mut sources: Vec<TemplateSource> = builtin_sources()
+        .into_iter()
+        .filter(|b| project.iter().all(|p| p.id != b.id))
+        .collect();
+    sources.extend(project);
+    Ok(sources)
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Valid templates available to a project (invalid ones are skipped; see `validate_all`)
+pub fn list(project_path: &str) -> Result<Vec<TeamTemplateInfo>, String> {
+    Ok(all_sources(project_path)?
+        .into_iter()
+        .filter_map(|source| {
+            let (template, _) = parse(&source).ok()?;
+            Some(TeamTemplateInfo {
+                source: if source.path.is_some() { "project" } else { "builtin" }.to_string(),
+                path: source.path.map(|p| p.to_string_lossy().to_string()),
+                id: source.id,
+                template,
+            })
+        })
+        .collect())
+}
+
+/// Validate the project's template files (and the built-ins they don't replace)
+pub fn validate_all(project_path: &str) -> Result<TemplateValidationResult, String> {
+    let sources = all_sources(project_path)?;
+    let mut issues = Vec::new();
+    let mut valid_files = 0;
+
+    for source in &sources {
+        let file_issues = match parse(source) {
+            Ok((_, warnings)) => {
+                valid_files += 1;
+                warnings
+            }
+            Err(errors) => errors,
+        };
+        for mut issue in file_issues {
+            issue.path = source.display_path();
+            issues.push(issue);
+        }
+    }
+
+    Ok(TemplateValidationResult {
+        total_files: sources.len(),
+        valid_files,
+        invalid_files: sources.len() - valid_files,
+        issues,
+    })
+}
+
+/// Substitute `{{name}}` placeholders
+fn substitute(text: &str, values: &HashMap<String, String>) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        let value = values
+            .get(name)
+            .ok_or_else(|| format!("Unknown template variable '{}'", name))?;
+        out.push_str(&rest[..start]);
+        out.push_str(value);
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Team creation input from a template and variable values
+fn render(
+    template: &TeamTemplate,
+    project_path: &str,
+    variables: &HashMap<String, String>,
+    name: Option<String>,
+) -> Result<CreateTeamInput, String> {
+    let mut values = HashMap::new();
+    values.insert("project_path".to_string(), project_path.to_string());
+    values.insert(
+        "project_name".to_string(),
+        Path::new(project_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    );
+    for variable in &template.variables {
+        let value = variables
+            .get(&variable.name)
+            .or(variable.default.as_ref())
+            .ok_or_else(|| format!("Missing value for template variable '{}'", variable.name))?;
+        values.insert(variable.name.clone(), value.clone());
+    }
+    if let Some(unknown) = variables.keys().find(|k| template.variables.iter().all(|v| &v.name != *k)) {
+        return Err(format!("Template has no variable '{}'", unknown));
+    }
+
+    let sub = |text: &str| substitute(text, &values);
+    let member = |m: &TemplateMember, default_role: &str| -> Result<MemberInput, String> {
+        let launch = match &m.launch {
+            Some(launch) => {
+                let launch = MemberLaunchSettings {
+                    prompt: launch.prompt.as_deref().map(sub).transpose()?,
+                    working_dir: launch.working_dir.as_deref().map(sub).transpose()?,
+                    worktree: launch.worktree.as_deref().map(sub).transpose()?,
+                    auto_start: launch.auto_start,
+                };
+                launch.validate()?;
+                Some(launch)
+            }
+            None => None,
+        };
+        Ok(MemberInput {
+            role: sub(m.role.as_deref().unwrap_or(default_role))?,
+            agent_type: sub(&m.agent_type)?,
+            launch,
+        })
+    };
+
+    Ok(CreateTeamInput {
+        name: match name {
+            Some(name) => name,
+            None => sub(&template.name)?,
+        },
+        project_path: project_path.to_string(),
+        auto_recovery: template.recovery.auto_recovery,
+        max_recovery_attempts: template.recovery.max_recovery_attempts,
+        recovery_delay_ms: template.recovery.recovery_delay_ms,
+        orchestrator: template.orchestrator.as_ref().map(|o| member(o, "orchestrator")).transpose()?,
+        members: template.workers.iter().map(|w| member(w, "")).collect::<Result<_, _>>()?,
+        description: template.description.as_deref().map(sub).transpose()?,
+        tags: template.tags.iter().map(|t| sub(t)).collect::<Result<_, _>>()?,
+    })
+}
+
+/// Team creation input from a template of the project
+pub fn instantiate(
+    project_path: &str,
+    template_id: &str,
+    variables: &HashMap<String, String>,
+    name: Option<String>,
+) -> Result<CreateTeamInput, String> {
+    let source = all_sources(project_path)?
+        .into_iter()
+        .find(|s| s.id == template_id)
+        .ok_or_else(|| format!("Team template '{}' not found", template_id))?;
+    let (template, _) = parse(&source).map_err(|issues| {
+        let messages: Vec<String> = issues
+            .iter()
+            .filter(|i| i.severity == "error")
+            .map(|i| match i.line {
+                Some(line) => format!("line {}: {}", line, i.message),
+                None => i.message.clone(),
+            })
+            .collect();
+        format!("Team template '{}' is invalid: {}", template_id, messages.join("; "))
+    })?;
+    render(&template, project_path, variables, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(content: &str) -> TemplateSource {
+        TemplateSource {
+            id: "test".to_string(),
+            path: None,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_builtins_are_valid() {
+        for source in builtin_sources() {
+            let (_, warnings) = parse(&source).unwrap_or_else(|e| panic!("{}: {:?}", source.id, e));
+            assert!(warnings.is_empty(), "{}: {:?}", source.id, warnings);
+        }
+    }
+
+    #[test]
+    fn test_render_substitutes_variables() {
+        let (template, _) = parse(&builtin_sources()[0]).unwrap();
+        let mut variables = HashMap::new();
+        variables.insert("feature".to_string(), "Dark mode".to_string());
+
+        let input = render(&template, "/work/sidstack", &variables, None).unwrap();
+        assert_eq!(input.name, "Feature: Dark mode");
+        assert_eq!(input.members.len(), 2);
+        assert_eq!(input.members[0].role, "dev");
+        let prompt = input.orchestrator.unwrap().launch.unwrap().prompt.unwrap();
+        assert!(prompt.contains("\"Dark mode\" in sidstack"));
+
+        assert!(render(&template, "/work/sidstack", &HashMap::new(), None).is_err());
+        variables.insert("color".to_string(), "blue".to_string());
+        assert!(render(&template, "/work/sidstack", &variables, None).is_err());
+    }
+
+    #[test]
+    fn test_reports_errors_with_lines() {
+        let unknown_field = "name: Team\nworkers:\n  - role: dev\n    agent_type: dev-agent\n    model: opus\n";
+        let issues = parse(&source(unknown_field)).unwrap_err();
+        assert_eq!(issues[0].line, Some(5));
+
+        let bad = "name: Team {{team}}\nworkers:\n  - agent_type: dev-agent\n    launch:\n      working_dir: ../elsewhere\n";
+        let issues = parse(&source(bad)).unwrap_err();
+        let lines: Vec<(Option<usize>, &str)> = issues.iter().map(|i| (i.line, i.severity.as_str())).collect();
+        assert_eq!(lines, vec![(Some(1), "error"), (Some(3), "error"), (Some(5), "error")]);
+
+        let bad_worktree = "name: Team\nworkers:\n  - role: dev\n    agent_type: dev-agent\n    launch:\n      worktree: ../../etc\n";
+        let issues = parse(&source(bad_worktree)).unwrap_err();
+        assert_eq!(issues[0].line, Some(6));
+    }
+
+    #[test]
+    fn test_render_checks_substituted_paths() {
+        let content = "name: Team\nvariables:\n  - name: dir\n  - name: task\n    default: \"42\"\n\
+                       workers:\n  - role: dev\n    agent_type: dev-agent\n    launch:\n      \
+                       working_dir: \"{{dir}}\"\n      worktree: \"{{task}}\"\n";
+        let (template, _) = parse(&source(content)).unwrap();
+        let render_with = |dir: &str, task: &str| {
+            let variables = HashMap::from([("dir".to_string(), dir.to_string()), ("task".to_string(), task.to_string())]);
+            render(&template, "/work/app", &variables, None)
+        };
+
+        let input = render_with("packages/web", "42").unwrap();
+        let launch = input.members[0].launch.clone().unwrap();
+        assert_eq!(launch.working_dir.as_deref(), Some("packages/web"));
+        assert_eq!(launch.worktree.as_deref(), Some("42"));
+
+        assert!(render_with("../outside", "42").is_err());
+        assert!(render_with("/etc", "42").is_err());
+        assert!(render_with("packages/web", "../../x").is_err());
+        assert!(render_with("packages/web", "a/b").is_err());
+    }
+}
@@ -1,14 +1,10 @@
 /// Utility functions for the agent manager
 
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 
 /// Create a short hash from a path for use in window labels
 pub fn hash_path(path: &str) -> String {
-    let mut hasher = DefaultHasher::new();
-    path.hash(&mut hasher);
-    format!("{:x}", hasher.finish())[..8].to_string()
+    crate::project_identity::project_id(path)[..8].to_string()
 }
 
 /// Find Claude CLI executable path
@@ -0,0 +1,280 @@
+//! Workspace File Schemas
+//!
+//! `.sidstack/workspace.json` and `.sidstack/sessions.json` carry a
+//! "major.minor" `version`, checked whenever they are read:
+//! - Older versions are migrated step by step on the raw JSON; the caller
+//!   writes the result back (`persist` keeps the old file as `.bak`)
+//! - Versions newer than this build are rejected, never overwritten
+//! - Fields this build doesn't know are kept (the `extra` maps)
+//!
+//! History:
+//! - workspace.json 1.0: name, created_at, last_opened
+//! - sessions.json 1.0: tabs with id, blockId, type, cwd, title, pinned, cols, rows
+//! - sessions.json 1.1: tabs also record role, launchProfile and claudeSessionId
+//!
+//! A file without a version predates the check and is read as 1.0.
+//! Fixtures for each shape are in tests/fixtures/workspace/.
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::persist;
+use crate::workspace_storage::{SessionState, WorkspaceConfig};
+
+pub const WORKSPACE_VERSION: &str = "1.0";
+pub const SESSIONS_VERSION: &str = "1.1";
+
+/// Version assumed for files written before versions were checked
+const UNVERSIONED: &str = "1.0";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Migration of one version to the next, on the raw JSON object
+type Step = fn(&mut Map<String, Value>);
+
+struct Schema {
+    file: &'static str,
+    current: &'static str,
+    /// (from, to, step), oldest first
+    steps: &'static [(&'static str, &'static str, Step)],
+}
+
+const WORKSPACE: Schema = Schema {
+    file: "workspace.json",
+    current: WORKSPACE_VERSION,
+    steps: &[],
+};
+
+const SESSIONS: Schema = Schema {
+    file: "sessions.json",
+    current: SESSIONS_VERSION,
+    steps: &[("1.0", "1.1", sessions_1_0_to_1_1)],
+};
+
+/// Why a workspace file could not be read
+#[derive(Debug)]
+pub enum SchemaError {
+    /// Written by a newer build; must not be overwritten
+    TooNew {
+        file: &'static str,
+        found: String,
+        supported: &'static str,
+    },
+    Invalid(String),
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaError::TooNew { file, found, supported } => write!(
+                f,
+                "{} is version {}, newer than this app supports ({}). Update SidStack to open this workspace.",
+                file, found, supported
+            ),
+            SchemaError::Invalid(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+/// A file as read, brought up to the current version
+#[derive(Debug)]
+pub struct Loaded<T> {
+    pub value: T,
+    /// Version it was migrated from, if it was not current
+    pub migrated_from: Option<String>,
+}
+
+// ============================================================================
+// Migration
+// ============================================================================
+
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = version.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+impl Schema {
+    fn invalid(&self, msg: impl std::fmt::Display) -> SchemaError {
+        SchemaError::Invalid(format!("Invalid {}: {}", self.file, msg))
+    }
+
+    /// Bring raw JSON up to the current version
+    fn migrate(&self, mut raw: Value) -> Result<(Value, Option<String>), SchemaError> {
+        let obj = raw
+            .as_object_mut()
+            .ok_or_else(|| self.invalid("not a JSON object"))?;
+        let stored = match obj.get("version") {
+            None => None,
+            Some(Value::String(version)) => Some(version.clone()),
+            Some(other) => return Err(self.invalid(format!("version {} is not a string", other))),
+        };
+
+        let from = stored.clone().unwrap_or_else(|| UNVERSIONED.to_string());
+        let mut at = parse_version(&from)
+            .ok_or_else(|| self.invalid(format!("unrecognized version {:?}", from)))?;
+        let current = parse_version(self.current).expect("current version is valid");
+        if at > current {
+            return Err(SchemaError::TooNew {
+                file: self.file,
+                found: from,
+                supported: self.current,
+            });
+        }
+
+        for (step_from, step_to, step) in self.steps {
+            if parse_version(step_from) == Some(at) {
+                step(obj);
+                at = parse_version(step_to).expect("step version is valid");
+            }
+        }
+        if at != current {
+            return Err(self.invalid(format!("no migration from version {}", from)));
+        }
+
+        let migrated = stored.as_deref() != Some(self.current);
+        obj.insert("version".to_string(), Value::String(self.current.to_string()));
+        Ok((raw, migrated.then_some(from)))
+    }
+
+    fn parse<T: DeserializeOwned>(&self, raw: Value) -> Result<Loaded<T>, SchemaError> {
+        let (raw, migrated_from) = self.migrate(raw)?;
+        let value = serde_json::from_value(raw).map_err(|e| self.invalid(e))?;
+        Ok(Loaded { value, migrated_from })
+    }
+
+    fn read<T: DeserializeOwned>(&self, path: &Path) -> Result<Option<Loaded<T>>, SchemaError> {
+        match persist::read_json::<Value>(path) {
+            Ok(Some(raw)) => self.parse(raw).map(Some),
+            Ok(None) => Ok(None),
+            Err(e) => Err(self.invalid(e)),
+        }
+    }
+}
+
+/// 1.1 records how each tab was launched; agent tabs are the ones with a role
+fn sessions_1_0_to_1_1(obj: &mut Map<String, Value>) {
+    let Some(Value::Array(tabs)) = obj.get_mut("tabs") else {
+        return;
+    };
+    for tab in tabs.iter_mut().filter_map(Value::as_object_mut) {
+        if tab.contains_key("launchProfile") {
+            continue;
+        }
+        let is_agent = tab.get("role").is_some_and(Value::is_string);
+        let profile = if is_agent { "claude-session" } else { "shell" };
+        tab.insert("launchProfile".to_string(), Value::String(profile.to_string()));
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Read workspace.json (None if missing)
+pub fn read_workspace(path: &Path) -> Result<Option<Loaded<WorkspaceConfig>>, SchemaError> {
+    WORKSPACE.read(path)
+}
+
+/// Read sessions.json (None if missing)
+pub fn read_sessions(path: &Path) -> Result<Option<Loaded<SessionState>>, SchemaError> {
+    SESSIONS.read(path)
+}
+
+/// Carry fields this build doesn't know from the stored state into a new one
+///
+/// The frontend rebuilds the state from its blocks, so without this a save
+/// would drop whatever another tool or a hand edit added. Files from a newer
+/// version never get here; they are rejected as `TooNew`.
+pub fn keep_unknown_fields(state: &mut SessionState, stored: &SessionState) {
+    for (key, value) in &stored.extra {
+        state.extra.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+    for tab in &mut state.tabs {
+        let Some(old) = stored.tabs.iter().find(|old| old.id == tab.id) else {
+            continue;
+        };
+        for (key, value) in &old.extra {
+            tab.extra.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace_storage::LaunchProfile;
+
+    fn fixture(name: &str) -> Value {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/workspace")
+            .join(name);
+        serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_current_versions_load_unchanged() {
+        let workspace: Loaded<WorkspaceConfig> = WORKSPACE.parse(fixture("workspace-1.0.json")).unwrap();
+        assert_eq!(workspace.migrated_from, None);
+        assert_eq!(workspace.value.name, "sidstack");
+
+        let sessions: Loaded<SessionState> = SESSIONS.parse(fixture("sessions-1.1.json")).unwrap();
+        assert_eq!(sessions.migrated_from, None);
+        let agent = &sessions.value.tabs[1];
+        assert_eq!(agent.launch_profile, LaunchProfile::ClaudeSession);
+        assert_eq!(agent.role.as_deref(), Some("dev"));
+    }
+
+    #[test]
+    fn test_migrates_sessions_1_0() {
+        let sessions: Loaded<SessionState> = SESSIONS.parse(fixture("sessions-1.0.json")).unwrap();
+        assert_eq!(sessions.migrated_from.as_deref(), Some("1.0"));
+        assert_eq!(sessions.value.version, SESSIONS_VERSION);
+        assert_eq!(sessions.value.tabs.len(), 2);
+        assert!(sessions.value.tabs.iter().all(|t| t.launch_profile == LaunchProfile::Shell));
+        assert_eq!(sessions.value.tabs[1].cols, Some(120));
+
+        // Unversioned files are read as 1.0
+        let mut raw = fixture("sessions-1.0.json");
+        raw.as_object_mut().unwrap().remove("version");
+        let sessions: Loaded<SessionState> = SESSIONS.parse(raw).unwrap();
+        assert_eq!(sessions.migrated_from.as_deref(), Some("1.0"));
+    }
+
+    #[test]
+    fn test_rejects_newer_and_invalid_versions() {
+        let mut raw = fixture("sessions-1.1.json");
+        raw["version"] = Value::from("2.0");
+        let err = SESSIONS.parse::<SessionState>(raw).unwrap_err();
+        assert!(matches!(err, SchemaError::TooNew { ref found, .. } if found == "2.0"));
+
+        let mut raw = fixture("workspace-1.0.json");
+        raw["version"] = Value::from("1.7");
+        assert!(matches!(WORKSPACE.parse::<WorkspaceConfig>(raw), Err(SchemaError::TooNew { .. })));
+
+        let mut raw = fixture("sessions-1.0.json");
+        raw["version"] = Value::from("0.9");
+        assert!(matches!(SESSIONS.parse::<SessionState>(raw), Err(SchemaError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_keeps_unknown_fields() {
+        let mut raw = fixture("sessions-1.0.json");
+        raw["layout"] = serde_json::json!({ "split": "vertical" });
+        raw["tabs"][0]["color"] = Value::from("teal");
+        let stored: Loaded<SessionState> = SESSIONS.parse(raw).unwrap();
+
+        let round_trip = serde_json::to_value(&stored.value).unwrap();
+        assert_eq!(round_trip["layout"]["split"], "vertical");
+        assert_eq!(round_trip["tabs"][0]["color"], "teal");
+
+        // A state rebuilt by the frontend gets them back on save
+        let mut rebuilt = stored.value.clone();
+        rebuilt.extra.clear();
+        rebuilt.tabs.iter_mut().for_each(|t| t.extra.clear());
+        keep_unknown_fields(&mut rebuilt, &stored.value);
+        assert_eq!(serde_json::to_value(&rebuilt).unwrap(), round_trip);
+    }
+}
@@ -1,11 +1,21 @@
 //! Workspace Session Persistence
 //!
 //! Stores terminal sessions per workspace (project folder) in `.sidstack/` directory.
-//! Enables session restore when user reopens the app.
+//! Enables session restore when user reopens the app: shell tabs are reopened
+//! by the frontend, agent tabs are relaunched here (resuming their Claude
+//! conversation when its transcript still exists).
 
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::fs;
 use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::claude_process::{ClaudeProcessInfo, SharedClaudeProcessManager};
+use crate::commands::claude::{claude_spawn_session, SpawnSessionOptions};
+use crate::persist;
+use crate::session_tracker::SharedSessionTracker;
+use crate::workspace_schema::{self, SchemaError, SESSIONS_VERSION, WORKSPACE_VERSION};
 
 const SIDSTACK_DIR: &str = ".sidstack";
 const WORKSPACE_FILE: &str = "workspace.json";
@@ -15,10 +25,34 @@ const HISTORY_DIR: &str = "history";
 /// Workspace configuration stored in workspace.json
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkspaceConfig {
+    /// Schema version (see `workspace_schema`)
     pub version: String,
     pub name: String,
     pub created_at: String,
     pub last_opened: String,
+    /// Fields this build doesn't know, kept as they were
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// workspace.json together with the settings in effect for the workspace
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceDetails {
+    #[serde(flatten)]
+    pub workspace: WorkspaceConfig,
+    /// Defaults < ~/.sidstack/config.yaml < .sidstack/config.yaml
+    pub settings: crate::config::ConfigSnapshot,
+}
+
+/// How a tab's process was launched, so restore can launch it again
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LaunchProfile {
+    /// Interactive shell; its PTY belongs to the frontend block
+    #[default]
+    Shell,
+    /// Persistent Claude session (`ClaudeProcessManager::spawn_session`)
+    ClaudeSession,
 }
 
 /// A single terminal tab session
@@ -36,33 +70,77 @@ pub struct SessionTab {
     pub pinned: bool,
     pub cols: Option<u16>,
     pub rows: Option<u16>,
+    /// Agent role, for tabs running an agent
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default, rename = "launchProfile")]
+    pub launch_profile: LaunchProfile,
+    /// Claude conversation to resume on restore
+    #[serde(default, rename = "claudeSessionId")]
+    pub claude_session_id: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 /// Session state stored in sessions.json
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionState {
+    /// Schema version (see `workspace_schema`)
     pub version: String,
     pub tabs: Vec<SessionTab>,
     pub active_tab_id: Option<String>,
     pub last_saved: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Default for SessionState {
     fn default() -> Self {
         Self {
-            version: "1.0".to_string(),
+            version: SESSIONS_VERSION.to_string(),
             tabs: Vec::new(),
             active_tab_id: None,
             last_saved: chrono::Utc::now().to_rfc3339(),
+            extra: Map::new(),
         }
     }
 }
 
+/// Outcome of restoring one tab
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabRestoreResult {
+    pub tab_id: String,
+    pub block_id: Option<String>,
+    pub launch_profile: LaunchProfile,
+    /// Working directory used (the saved one, or the workspace if it is gone)
+    pub cwd: String,
+    pub success: bool,
+    /// Agent process started for the tab
+    pub process: Option<ClaudeProcessInfo>,
+    /// Whether the saved Claude conversation was resumed (false: started fresh)
+    pub resumed: bool,
+    pub error: Option<String>,
+}
+
+/// Outcome of restoring a workspace, tabs in saved order
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub tabs: Vec<TabRestoreResult>,
+    pub active_tab_id: Option<String>,
+}
+
 /// Get .sidstack directory path for a workspace
 fn get_sidstack_dir(workspace_path: &str) -> PathBuf {
     Path::new(workspace_path).join(SIDSTACK_DIR)
 }
 
+/// Get sessions.json path for a workspace
+pub(crate) fn sessions_file_path(workspace_path: &str) -> PathBuf {
+    get_sidstack_dir(workspace_path).join(SESSIONS_FILE)
+}
+
 /// Get history directory path
 fn get_history_dir(workspace_path: &str) -> PathBuf {
     get_sidstack_dir(workspace_path).join(HISTORY_DIR)
@@ -89,24 +167,21 @@ pub fn workspace_init(workspace_path: String, name: String) -> Result<WorkspaceC
     // Create workspace.json
     let now = chrono::Utc::now().to_rfc3339();
     let config = WorkspaceConfig {
-        version: "1.0".to_string(),
+        version: WORKSPACE_VERSION.to_string(),
         name,
         created_at: now.clone(),
         last_opened: now,
+        extra: Map::new(),
     };
 
     let workspace_file = sidstack_dir.join(WORKSPACE_FILE);
-    let json = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize workspace config: {}", e))?;
-    fs::write(&workspace_file, json)
+    persist::write_json(&workspace_file, &config)
         .map_err(|e| format!("Failed to write workspace.json: {}", e))?;
 
     // Create empty sessions.json
     let sessions = SessionState::default();
     let sessions_file = sidstack_dir.join(SESSIONS_FILE);
-    let sessions_json = serde_json::to_string_pretty(&sessions)
-        .map_err(|e| format!("Failed to serialize sessions: {}", e))?;
-    fs::write(&sessions_file, sessions_json)
+    persist::write_json(&sessions_file, &sessions)
         .map_err(|e| format!("Failed to write sessions.json: {}", e))?;
 
     // Add .sidstack/ to .gitignore
@@ -155,34 +230,45 @@ fn add_to_gitignore(workspace_path: &str) -> Result<(), String> {
 }
 
 /// Load session state from workspace
+///
+/// Older versions are migrated (and written back); newer ones are an error.
 #[tauri::command]
 pub fn workspace_session_load(workspace_path: String) -> Result<SessionState, String> {
-    let sessions_file = get_sidstack_dir(&workspace_path).join(SESSIONS_FILE);
-
-    if !sessions_file.exists() {
-        return Ok(SessionState::default());
-    }
+    load_session_state(&workspace_path)
+}
 
-    let content = fs::read_to_string(&sessions_file)
-        .map_err(|e| format!("Failed to read sessions.json: {}", e))?;
+fn load_session_state(workspace_path: &str) -> Result<SessionState, String> {
+    let sessions_file = get_sidstack_dir(workspace_path).join(SESSIONS_FILE);
 
-    let sessions: SessionState = serde_json::from_str(&content).map_err(|e| {
-        eprintln!(
-            "[WorkspaceStorage] Warning: Corrupted sessions.json: {}. Starting fresh.",
-            e
-        );
-        format!("Corrupted sessions.json: {}", e)
-    })?;
+    // A corrupt file falls back to its last good copy
+    let sessions = match workspace_schema::read_sessions(&sessions_file) {
+        Ok(Some(loaded)) => {
+            if let Some(from) = &loaded.migrated_from {
+                write_migrated(&sessions_file, from, &loaded.value)?;
+            }
+            loaded.value
+        }
+        Ok(None) => return Ok(SessionState::default()),
+        Err(e @ SchemaError::TooNew { .. }) => return Err(e.to_string()),
+        Err(e) => {
+            eprintln!(
+                "[WorkspaceStorage] Warning: Corrupted sessions.json: {}. Starting fresh.",
+                e
+            );
+            return Err(format!("Corrupted sessions.json: {}", e));
+        }
+    };
 
-    // Update last_opened in workspace.json
-    let workspace_file = get_sidstack_dir(&workspace_path).join(WORKSPACE_FILE);
-    if let Ok(content) = fs::read_to_string(&workspace_file) {
-        if let Ok(mut config) = serde_json::from_str::<WorkspaceConfig>(&content) {
+    // Update last_opened in workspace.json (left alone if it is from a newer build)
+    let workspace_file = get_sidstack_dir(workspace_path).join(WORKSPACE_FILE);
+    match workspace_schema::read_workspace(&workspace_file) {
+        Ok(Some(loaded)) => {
+            let mut config = loaded.value;
             config.last_opened = chrono::Utc::now().to_rfc3339();
-            if let Ok(json) = serde_json::to_string_pretty(&config) {
-                let _ = fs::write(&workspace_file, json);
-            }
+            let _ = persist::write_json(&workspace_file, &config);
         }
+        Ok(None) => {}
+        Err(e) => eprintln!("[WorkspaceStorage] Warning: {}", e),
     }
 
     println!(
@@ -192,6 +278,105 @@ pub fn workspace_session_load(workspace_path: String) -> Result<SessionState, St
     Ok(sessions)
 }
 
+/// Restore a workspace's saved tabs, in order
+///
+/// Agent tabs get a new persistent Claude session, resuming the saved
+/// conversation if its transcript still exists. Shell tabs are only reported
+/// (with a validated cwd): the frontend block spawns their PTY when it mounts.
+/// A tab that fails does not stop the others.
+#[tauri::command]
+pub async fn workspace_session_restore(
+    workspace_path: String,
+    app: AppHandle,
+    state: State<'_, SharedClaudeProcessManager>,
+    tracker: State<'_, SharedSessionTracker>,
+) -> Result<RestoreReport, String> {
+    let session = load_session_state(&workspace_path)?;
+    let mut results = Vec::with_capacity(session.tabs.len());
+
+    for tab in session.tabs {
+        let cwd = workspace_validate_cwd(tab.cwd.clone(), workspace_path.clone());
+        let mut result = TabRestoreResult {
+            tab_id: tab.id.clone(),
+            block_id: tab.block_id.clone(),
+            launch_profile: tab.launch_profile,
+            cwd: cwd.clone(),
+            success: true,
+            process: None,
+            resumed: false,
+            error: None,
+        };
+
+        if tab.launch_profile == LaunchProfile::ClaudeSession {
+            match restore_agent_tab(&tab, cwd, app.clone(), state.clone(), tracker.clone()).await {
+                Ok((process, resumed)) => {
+                    result.process = Some(process);
+                    result.resumed = resumed;
+                }
+                Err(e) => {
+                    eprintln!("[WorkspaceStorage] Failed to restore tab {}: {}", tab.id, e);
+                    result.success = false;
+                    result.error = Some(e);
+                }
+            }
+        }
+        results.push(result);
+    }
+
+    println!(
+        "[WorkspaceStorage] Restored {}/{} tabs",
+        results.iter().filter(|r| r.success).count(),
+        results.len()
+    );
+    Ok(RestoreReport {
+        tabs: results,
+        active_tab_id: session.active_tab_id,
+    })
+}
+
+/// Relaunch an agent tab. Returns the process and whether it resumed
+async fn restore_agent_tab(
+    tab: &SessionTab,
+    cwd: String,
+    app: AppHandle,
+    state: State<'_, SharedClaudeProcessManager>,
+    tracker: State<'_, SharedSessionTracker>,
+) -> Result<(ClaudeProcessInfo, bool), String> {
+    let role = tab
+        .role
+        .clone()
+        .ok_or_else(|| "Agent tab has no role".to_string())?;
+
+    // `claude --resume` exits at once if the transcript is gone
+    let resume_session_id = match &tab.claude_session_id {
+        Some(id) if crate::claude_transcripts::find(id)?.is_some() => Some(id.clone()),
+        Some(id) => {
+            eprintln!(
+                "[WorkspaceStorage] Transcript for {} not found, starting tab {} fresh",
+                id, tab.id
+            );
+            None
+        }
+        None => None,
+    };
+    let resumed = resume_session_id.is_some();
+
+    let process = claude_spawn_session(
+        SpawnSessionOptions {
+            role,
+            working_dir: cwd,
+            prompt: None,
+            terminal_id: Some(tab.block_id.clone().unwrap_or_else(|| tab.id.clone())),
+            resume_session_id,
+        },
+        app,
+        state,
+        tracker,
+    )
+    .await?;
+    Ok((process, resumed))
+}
+
 /// Save session state to workspace
 #[tauri::command]
 pub fn workspace_session_save(
@@ -205,15 +390,21 @@ pub fn workspace_session_save(
         return Err("Workspace not initialized. Call workspace_init first.".to_string());
     }
 
-    // Update last_saved timestamp
+    // Never overwrite a file from a newer build; keep fields we don't know
+    let sessions_file = sidstack_dir.join(SESSIONS_FILE);
     let mut state = state;
-    state.last_saved = chrono::Utc::now().to_rfc3339();
+    match workspace_schema::read_sessions(&sessions_file) {
+        Ok(Some(stored)) => workspace_schema::keep_unknown_fields(&mut state, &stored.value),
+        Ok(None) => {}
+        Err(e @ SchemaError::TooNew { .. }) => return Err(e.to_string()),
+        Err(e) => eprintln!("[WorkspaceStorage] Warning: replacing unreadable sessions.json: {}", e),
+    }
 
-    let sessions_file = sidstack_dir.join(SESSIONS_FILE);
-    let json = serde_json::to_string_pretty(&state)
-        .map_err(|e| format!("Failed to serialize sessions: {}", e))?;
+    // Stamp the version and last_saved timestamp
+    state.version = SESSIONS_VERSION.to_string();
+    state.last_saved = chrono::Utc::now().to_rfc3339();
 
-    fs::write(&sessions_file, json)
+    persist::write_json(&sessions_file, &state)
         .map_err(|e| format!("Failed to write sessions.json: {}", e))?;
 
     println!(
@@ -241,22 +432,29 @@ pub fn workspace_get_history_path(
     Ok(history_file.to_string_lossy().to_string())
 }
 
-/// Get workspace config
+/// Get workspace config and its effective settings
 #[tauri::command]
-pub fn workspace_get_config(workspace_path: String) -> Result<WorkspaceConfig, String> {
+pub fn workspace_get_config(workspace_path: String) -> Result<WorkspaceDetails, String> {
     let workspace_file = get_sidstack_dir(&workspace_path).join(WORKSPACE_FILE);
 
-    if !workspace_file.exists() {
-        return Err("Workspace not initialized".to_string());
+    let loaded = workspace_schema::read_workspace(&workspace_file)
+        .map_err(|e| format!("Failed to read workspace.json: {}", e))?
+        .ok_or_else(|| "Workspace not initialized".to_string())?;
+    if let Some(from) = &loaded.migrated_from {
+        write_migrated(&workspace_file, from, &loaded.value)?;
     }
+    Ok(WorkspaceDetails {
+        workspace: loaded.value,
+        settings: crate::config::load(Some(&workspace_path)),
+    })
+}
 
-    let content = fs::read_to_string(&workspace_file)
-        .map_err(|e| format!("Failed to read workspace.json: {}", e))?;
-
-    let config: WorkspaceConfig = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse workspace.json: {}", e))?;
-
-    Ok(config)
+/// Write back a file migrated from an older version
+fn write_migrated<T: Serialize>(path: &Path, from: &str, value: &T) -> Result<(), String> {
+    persist::write_json(path, value)
+        .map_err(|e| format!("Failed to write migrated {:?}: {}", path, e))?;
+    println!("[WorkspaceStorage] Migrated {:?} from version {}", path, from);
+    Ok(())
 }
 
 /// Validate that a cwd path exists, return fallback if not
@@ -0,0 +1,343 @@
+//! Zip Archive
+//!
+//! Minimal ZIP reader/writer (deflate via flate2) for session bundles.
+//!
+//! Supports what bundles need and nothing more:
+//! - Deflated or stored entries, no encryption, no ZIP64 (entries < 4 GiB)
+//! - Whole-archive reads into memory, with limits on decompressed sizes
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use std::io::{Read, Write};
+
+const LOCAL_HEADER_SIG: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIG: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIR_SIG: u32 = 0x0605_4b50;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+/// Version 2.0 - deflate
+const VERSION: u16 = 20;
+/// Bit 11 - file names are UTF-8
+const FLAG_UTF8: u16 = 1 << 11;
+/// Largest decompressed entry a reader accepts
+pub const MAX_ENTRY_SIZE: u64 = 512 * 1024 * 1024;
+/// Largest decompressed total of all entries a reader accepts
+pub const MAX_TOTAL_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Central directory record of a written entry
+struct WrittenEntry {
+    name: String,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Streams entries into a ZIP archive
+pub struct ZipWriter<W: Write> {
+    out: W,
+    offset: u64,
+    entries: Vec<WrittenEntry>,
+    dos_time: u16,
+    dos_date: u16,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub fn new(out: W) -> Self {
+        let (dos_time, dos_date) = dos_timestamp(chrono::Local::now().naive_local());
+        Self {
+            out,
+            offset: 0,
+            entries: Vec::new(),
+            dos_time,
+            dos_date,
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.out
+            .write_all(bytes)
+            .map_err(|e| format!("Failed to write archive: {}", e))?;
+        self.offset += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Add a deflated file entry
+    pub fn add_file(&mut self, name: &str, data: &[u8]) -> Result<(), String> {
+        let mut crc = Crc::new();
+        crc.update(data);
+
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(data)
+            .map_err(|e| format!("Failed to compress {}: {}", name, e))?;
+        let compressed = encoder
+            .finish()
+            .map_err(|e| format!("Failed to compress {}: {}", name, e))?;
+
+        let size = to_u32(data.len() as u64, name)?;
+        let compressed_size = to_u32(compressed.len() as u64, name)?;
+        let offset = to_u32(self.offset, name)?;
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        put_u32(&mut header, LOCAL_HEADER_SIG);
+        put_u16(&mut header, VERSION);
+        put_u16(&mut header, FLAG_UTF8);
+        put_u16(&mut header, METHOD_DEFLATE);
+        put_u16(&mut header, self.dos_time);
+        put_u16(&mut header, self.dos_date);
+        put_u32(&mut header, crc.sum());
+        put_u32(&mut header, compressed_size);
+        put_u32(&mut header, size);
+        put_u16(&mut header, name.len() as u16);
+        put_u16(&mut header, 0);
+        header.extend_from_slice(name.as_bytes());
+
+        self.write_bytes(&header)?;
+        self.write_bytes(&compressed)?;
+
+        self.entries.push(WrittenEntry {
+            name: name.to_string(),
+            crc: crc.sum(),
+            compressed_size,
+            size,
+            offset,
+        });
+        Ok(())
+    }
+
+    /// Write the central directory and return the underlying writer
+    pub fn finish(mut self) -> Result<W, String> {
+        let dir_offset = to_u32(self.offset, "central directory")?;
+
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            put_u32(&mut directory, CENTRAL_HEADER_SIG);
+            put_u16(&mut directory, VERSION);
+            put_u16(&mut directory, VERSION);
+            put_u16(&mut directory, FLAG_UTF8);
+            put_u16(&mut directory, METHOD_DEFLATE);
+            put_u16(&mut directory, self.dos_time);
+            put_u16(&mut directory, self.dos_date);
+            put_u32(&mut directory, entry.crc);
+            put_u32(&mut directory, entry.compressed_size);
+            put_u32(&mut directory, entry.size);
+            put_u16(&mut directory, entry.name.len() as u16);
+            put_u16(&mut directory, 0); // extra length
+            put_u16(&mut directory, 0); // comment length
+            put_u16(&mut directory, 0); // disk number
+            put_u16(&mut directory, 0); // internal attributes
+            put_u32(&mut directory, 0); // external attributes
+            put_u32(&mut directory, entry.offset);
+            directory.extend_from_slice(entry.name.as_bytes());
+        }
+        let dir_size = to_u32(directory.len() as u64, "central directory")?;
+        let count = self.entries.len() as u16;
+
+        put_u32(&mut directory, END_OF_CENTRAL_DIR_SIG);
+        put_u16(&mut directory, 0);
+        put_u16(&mut directory, 0);
+        put_u16(&mut directory, count);
+        put_u16(&mut directory, count);
+        put_u32(&mut directory, dir_size);
+        put_u32(&mut directory, dir_offset);
+        put_u16(&mut directory, 0);
+
+        self.write_bytes(&directory)?;
+        self.out
+            .flush()
+            .map_err(|e| format!("Failed to write archive: {}", e))?;
+        Ok(self.out)
+    }
+}
+
+/// An entry listed in the central directory
+#[derive(Debug, Clone)]
+struct ZipEntry {
+    name: String,
+    size: u32,
+    method: u16,
+    crc: u32,
+    compressed_size: u32,
+    offset: u32,
+}
+
+/// Reads entries from an in-memory ZIP archive
+pub struct ZipReader {
+    data: Vec<u8>,
+    entries: Vec<ZipEntry>,
+}
+
+impl ZipReader {
+    /// Parse an archive's central directory, with the default size limits
+    pub fn new(data: Vec<u8>) -> Result<Self, String> {
+        Self::with_limits(data, MAX_ENTRY_SIZE, MAX_TOTAL_SIZE)
+    }
+
+    /// Parse an archive's central directory, rejecting it if an entry would
+    /// decompress to more than `max_entry_size` or all entries together to
+    /// more than `max_total_size`
+    pub fn with_limits(data: Vec<u8>, max_entry_size: u64, max_total_size: u64) -> Result<Self, String> {
+        let invalid = || "Not a valid zip archive".to_string();
+
+        // The end-of-central-directory record sits at the end, before an optional comment
+        let eocd = (0..data.len().saturating_sub(21))
+            .rev()
+            .find(|&i| get_u32(&data, i) == Some(END_OF_CENTRAL_DIR_SIG))
+            .ok_or_else(invalid)?;
+
+        let count = get_u16(&data, eocd + 10).ok_or_else(invalid)? as usize;
+        let mut pos = get_u32(&data, eocd + 16).ok_or_else(invalid)? as usize;
+
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            if get_u32(&data, pos) != Some(CENTRAL_HEADER_SIG) {
+                return Err(invalid());
+            }
+            let name_len = get_u16(&data, pos + 28).ok_or_else(invalid)? as usize;
+            let extra_len = get_u16(&data, pos + 30).ok_or_else(invalid)? as usize;
+            let comment_len = get_u16(&data, pos + 32).ok_or_else(invalid)? as usize;
+            let name = data
+                .get(pos + 46..pos + 46 + name_len)
+                .ok_or_else(invalid)?;
+
+            entries.push(ZipEntry {
+                name: String::from_utf8_lossy(name).to_string(),
+                method: get_u16(&data, pos + 10).ok_or_else(invalid)?,
+                crc: get_u32(&data, pos + 16).ok_or_else(invalid)?,
+                compressed_size: get_u32(&data, pos + 20).ok_or_else(invalid)?,
+                size: get_u32(&data, pos + 24).ok_or_else(invalid)?,
+                offset: get_u32(&data, pos + 42).ok_or_else(invalid)?,
+            });
+            pos += 46 + name_len + extra_len + comment_len;
+        }
+
+        for entry in &entries {
+            if entry.size as u64 > max_entry_size {
+                return Err(format!("Zip entry {} exceeds the size limit", entry.name));
+            }
+        }
+        let total: u64 = entries.iter().map(|e| e.size as u64).sum();
+        if total > max_total_size {
+            return Err("Zip archive exceeds the size limit".to_string());
+        }
+
+        Ok(Self { data, entries })
+    }
+
+    /// Read and verify an entry by name (None if absent)
+    pub fn read(&self, name: &str) -> Result<Option<Vec<u8>>, String> {
+        let Some(entry) = self.entries.iter().find(|e| e.name == name) else {
+            return Ok(None);
+        };
+        let corrupt = || format!("Corrupt zip entry: {}", name);
+
+        let header = entry.offset as usize;
+        if get_u32(&self.data, header) != Some(LOCAL_HEADER_SIG) {
+            return Err(corrupt());
+        }
+        let name_len = get_u16(&self.data, header + 26).ok_or_else(corrupt)? as usize;
+        let extra_len = get_u16(&self.data, header + 28).ok_or_else(corrupt)? as usize;
+        let start = header + 30 + name_len + extra_len;
+        let raw = self
+            .data
+            .get(start..start + entry.compressed_size as usize)
+            .ok_or_else(corrupt)?;
+
+        let contents = match entry.method {
+            METHOD_STORED => raw.to_vec(),
+            METHOD_DEFLATE => {
+                // The header's size is only a claim; never inflate past it
+                let mut out = Vec::with_capacity(entry.size as usize);
+                DeflateDecoder::new(raw)
+                    .take(entry.size as u64 + 1)
+                    .read_to_end(&mut out)
+                    .map_err(|e| format!("Failed to decompress {}: {}", name, e))?;
+                out
+            }
+            other => return Err(format!("Unsupported compression method {} for {}", other, name)),
+        };
+
+        let mut crc = Crc::new();
+        crc.update(&contents);
+        if crc.sum() != entry.crc || contents.len() != entry.size as usize {
+            return Err(corrupt());
+        }
+
+        Ok(Some(contents))
+    }
+}
+
+fn to_u32(value: u64, what: &str) -> Result<u32, String> {
+    u32::try_from(value).map_err(|_| format!("{} is too large for a zip archive", what))
+}
+
+fn put_u16(buf: &mut Vec<u8>, value: u16) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn get_u16(data: &[u8], pos: usize) -> Option<u16> {
+    data.get(pos..pos + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn get_u32(data: &[u8], pos: usize) -> Option<u32> {
+    data.get(pos..pos + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// MS-DOS time and date fields
+fn dos_timestamp(now: chrono::NaiveDateTime) -> (u16, u16) {
+    use chrono::{Datelike, Timelike};
+    let time = ((now.hour() as u16) << 11) | ((now.minute() as u16) << 5) | (now.second() as u16 / 2);
+    let year = (now.year().clamp(1980, 2107) - 1980) as u16;
+    let date = (year << 9) | ((now.month() as u16) << 5) | now.day() as u16;
+    (time, date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut writer = ZipWriter::new(Vec::new());
+        writer.add_file("manifest.json", b"{\"version\":1}").unwrap();
+        writer.add_file("logs/active.log", &b"line\n".repeat(1000)).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let reader = ZipReader::new(bytes).unwrap();
+        assert_eq!(reader.entries.len(), 2);
+        assert_eq!(reader.read("manifest.json").unwrap().unwrap(), b"{\"version\":1}");
+        assert_eq!(reader.read("logs/active.log").unwrap().unwrap().len(), 5000);
+        assert!(reader.read("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_enforces_size_limits() {
+        let mut writer = ZipWriter::new(Vec::new());
+        writer.add_file("a", &[0u8; 4096]).unwrap();
+        writer.add_file("b", &[0u8; 4096]).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        assert!(ZipReader::with_limits(bytes.clone(), 1024, u64::MAX).is_err());
+        assert!(ZipReader::with_limits(bytes.clone(), 4096, 4096).is_err());
+        assert!(ZipReader::with_limits(bytes.clone(), 4096, 8192).is_ok());
+
+        // An entry inflating past its declared size is cut off and rejected
+        let mut forged = bytes;
+        let central = (0..forged.len()).find(|&i| get_u32(&forged, i) == Some(CENTRAL_HEADER_SIG)).unwrap();
+        forged[central + 24..central + 28].copy_from_slice(&16u32.to_le_bytes());
+        let reader = ZipReader::with_limits(forged, 4096, 8192).unwrap();
+        assert!(reader.read("a").is_err());
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        assert!(ZipReader::new(b"not a zip".to_vec()).is_err());
+    }
+}
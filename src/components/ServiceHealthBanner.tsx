@@ -9,6 +9,7 @@ import { useState, useCallback } from "react";
 import { Button } from "@/components/ui/button";
 import { useVisibilityPolling } from "@/hooks/useVisibility";
 import { ipcClient } from "@/lib/ipcClient";
+import { apiBaseUrl, ipcUrl } from "@/lib/servicePorts";
 import { cn } from "@/lib/utils";
 
 interface ServiceStatus {
@@ -20,17 +21,18 @@ interface ServiceStatus {
   icon: typeof Server;
 }
 
-const SERVICES: Omit<ServiceStatus, "healthy">[] = [
+/** Services to check, at the ports of this run */
+const servicesToCheck = (): Omit<ServiceStatus, "healthy">[] => [
   {
     name: "API Server",
-    url: "http://localhost:19432/health",
+    url: `${apiBaseUrl()}/health`,
     type: "http",
     description: "Task management, orchestrator APIs",
     icon: Server,
   },
   {
     name: "IPC Server",
-    url: "ws://localhost:17432",
+    url: ipcUrl(),
     type: "ws",
     description: "Terminal spawning, agent coordination",
     icon: Wifi,
@@ -69,7 +71,7 @@ export function ServiceHealthBanner({ className }: ServiceHealthBannerProps) {
     setChecking(true);
 
     const results: ServiceStatus[] = await Promise.all(
-      SERVICES.map(async (service) => {
+      servicesToCheck().map(async (service) => {
         const healthy = service.type === "http"
           ? await checkHttpHealth(service.url)
           : checkWsHealth(service.url); // Sync function, no await needed
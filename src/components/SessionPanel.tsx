@@ -126,7 +126,10 @@ function SessionItem({
   onExport,
 }: SessionItemProps) {
   const displayName =
-    session.displayName || session.role || `Session ${session.sessionId.slice(0, 8)}`;
+    session.displayName ||
+    session.title ||
+    session.role ||
+    `Session ${session.sessionId.slice(0, 8)}`;
   const isActive = session.status === "active";
   const shortSessionId = session.claudeSessionId
     ? session.claudeSessionId.slice(0, 8)
@@ -169,6 +172,12 @@ function SessionItem({
           )}
         </div>
 
+        {session.summary && (
+          <div className="text-xs text-muted-foreground mt-0.5 line-clamp-2">
+            {session.summary}
+          </div>
+        )}
+
         {/* Row 2: Time info */}
         <div className="flex items-center gap-2 text-xs text-muted-foreground mt-0.5">
           <Clock className="h-3 w-3 flex-shrink-0" />
@@ -354,6 +363,8 @@ export function SessionPanel({
       const matchingSessions = sessions.filter(
         (s) =>
           s.displayName?.toLowerCase().includes(query) ||
+          s.title?.toLowerCase().includes(query) ||
+          s.summary?.toLowerCase().includes(query) ||
           s.role?.toLowerCase().includes(query) ||
           s.sessionId.toLowerCase().includes(query) ||
           project.toLowerCase().includes(query)
@@ -43,6 +43,7 @@ import {
 } from "@/components/ui/select";
 import { useOptionalWorkspaceContext } from "@/contexts/WorkspaceContext";
 import { useBlockNavigation } from "@/hooks/useBlockNavigation";
+import { apiBaseUrl } from "@/lib/servicePorts";
 import { cn } from "@/lib/utils";
 import type { BlockViewProps } from "@/types/block";
 
@@ -121,7 +122,7 @@ interface KnowledgeStats {
 // Constants
 // =============================================================================
 
-const API_BASE = "http://localhost:19432/api/knowledge";
+const knowledgeApi = () => `${apiBaseUrl()}/api/knowledge`;
 
 const TYPE_CONFIG: Record<DocumentType, { label: string; icon: typeof FileText; color: string }> = {
   // Specs
@@ -243,9 +244,9 @@ export const KnowledgeBrowserBlockView = memo(function KnowledgeBrowserBlockView
 
       // Fetch documents and stats in parallel
       const [docsRes, statsRes, treeRes] = await Promise.all([
-        fetch(`${API_BASE}?${params}`),
-        fetch(`${API_BASE}/stats?projectPath=${encodeURIComponent(workspacePath)}`),
-        fetch(`${API_BASE}/tree?projectPath=${encodeURIComponent(workspacePath)}`),
+        fetch(`${knowledgeApi()}?${params}`),
+        fetch(`${knowledgeApi()}/stats?projectPath=${encodeURIComponent(workspacePath)}`),
+        fetch(`${knowledgeApi()}/tree?projectPath=${encodeURIComponent(workspacePath)}`),
       ]);
 
       if (!docsRes.ok) throw new Error("Failed to load documents");
@@ -285,7 +286,7 @@ export const KnowledgeBrowserBlockView = memo(function KnowledgeBrowserBlockView
 
     try {
       const res = await fetch(
-        `${API_BASE}/doc/${docId}?projectPath=${encodeURIComponent(workspacePath)}`
+        `${knowledgeApi()}/doc/${docId}?projectPath=${encodeURIComponent(workspacePath)}`
       );
       if (!res.ok) throw new Error("Failed to load document");
 
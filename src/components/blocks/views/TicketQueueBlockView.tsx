@@ -37,6 +37,7 @@ import {
 } from "@/components/ui/select";
 import { useAutoRefresh } from "@/hooks/useAutoRefresh";
 import { useBlockNavigation } from "@/hooks/useBlockNavigation";
+import { apiBaseUrl } from "@/lib/servicePorts";
 import { cn } from "@/lib/utils";
 import { useAppStore } from "@/stores/appStore";
 import { useProjectSettingsStore } from "@/stores/projectSettingsStore";
@@ -398,13 +399,13 @@ export const TicketQueueBlockView = memo(function TicketQueueBlockView(
                               <code className="flex-1 text-xs bg-[var(--surface-2)] px-3 py-2 rounded font-mono truncate border border-[var(--border-muted)]">
                                 {tunnelInfo.status === 'running' && tunnelInfo.publicUrl
                                   ? `${tunnelInfo.publicUrl}/api/tickets`
-                                  : 'http://localhost:19432/api/tickets'}
+                                  : `${apiBaseUrl()}/api/tickets`}
                               </code>
                               <button
                                 onClick={() => handleCopyExample(
                                   tunnelInfo.status === 'running' && tunnelInfo.publicUrl
                                     ? `${tunnelInfo.publicUrl}/api/tickets`
-                                    : 'http://localhost:19432/api/tickets',
+                                    : `${apiBaseUrl()}/api/tickets`,
                                   "url"
                                 )}
                                 className="p-2 rounded bg-[var(--surface-2)] hover:bg-[var(--surface-3)] border border-[var(--border-muted)] transition-colors"
@@ -431,7 +432,7 @@ export const TicketQueueBlockView = memo(function TicketQueueBlockView(
                                 Example Request
                               </label>
                               <button
-                                onClick={() => handleCopyExample(`curl -X POST ${tunnelInfo.status === 'running' && tunnelInfo.publicUrl ? tunnelInfo.publicUrl : 'http://localhost:19432'}/api/tickets \\
+                                onClick={() => handleCopyExample(`curl -X POST ${tunnelInfo.status === 'running' && tunnelInfo.publicUrl ? tunnelInfo.publicUrl : apiBaseUrl()}/api/tickets \\
   -H "Content-Type: application/json" \\
   -d '{
     "projectId": "${projectId}",
@@ -5,13 +5,12 @@
 import { useState } from 'react';
 import { Play, Plus, FileCode, Loader2 } from 'lucide-react';
 import { Button } from '@/components/ui/button';
+import { apiBaseUrl } from '@/lib/servicePorts';
 import { showSuccess, showError } from '@/lib/toast';
 import { useProjectHubStore } from '@/stores/projectHubStore';
 import { CreateTaskDialog } from './CreateTaskDialog';
 import type { CapabilityDefinition } from '@sidstack/shared';
 
-const API_BASE = 'http://localhost:19432';
-
 interface CapabilityActionsProps {
   capability: CapabilityDefinition;
 }
@@ -26,7 +25,7 @@ export function CapabilityActions({ capability }: CapabilityActionsProps) {
     setIsLaunching(true);
     try {
       const prompt = `Working on capability: ${capability.name} (${capability.id}). Module: ${capability.modules?.join(', ') || 'none'}`;
-      const res = await fetch(`${API_BASE}/api/sessions`, {
+      const res = await fetch(`${apiBaseUrl()}/api/sessions`, {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
         body: JSON.stringify({
@@ -22,11 +22,10 @@ import {
   SelectContent,
   SelectItem,
 } from '@/components/ui/select';
+import { apiBaseUrl } from '@/lib/servicePorts';
 import { showSuccess, showError } from '@/lib/toast';
 import { useOnboardingStore } from '@/stores/onboardingStore';
 
-const API_BASE = 'http://localhost:19432';
-
 const TASK_TYPES = [
   { value: 'feature', label: 'Feature' },
   { value: 'bugfix', label: 'Bugfix' },
@@ -94,7 +93,7 @@ export function CreateTaskDialog({
 
     setIsSubmitting(true);
     try {
-      const res = await fetch(`${API_BASE}/api/tasks`, {
+      const res = await fetch(`${apiBaseUrl()}/api/tasks`, {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
         body: JSON.stringify({
@@ -18,14 +18,13 @@ import {
   Loader2,
   CircleDot,
 } from 'lucide-react';
+import { apiBaseUrl } from '@/lib/servicePorts';
 import { cn } from '@/lib/utils';
 import { Button } from '@/components/ui/button';
 import { useProjectHubStore } from '@/stores/projectHubStore';
 import { CreateTaskDialog } from './CreateTaskDialog';
 import type { HubViewMode, ProjectGoalsDomain, ProjectGoalsSubGoal } from '@/stores/projectHubStore';
 
-const API_BASE = 'http://localhost:19432';
-
 // ============================================================================
 // Types
 // ============================================================================
@@ -104,16 +103,16 @@ export function ProjectOverview() {
 
     const fetchData = async () => {
       const [tasksResult, ticketsResult, impactResult, okrResult] = await Promise.allSettled([
-        fetch(`${API_BASE}/api/tasks?projectId=${encodeURIComponent(projectId)}`).then((r) =>
+        fetch(`${apiBaseUrl()}/api/tasks?projectId=${encodeURIComponent(projectId)}`).then((r) =>
           r.ok ? r.json() : null,
         ),
-        fetch(`${API_BASE}/api/tickets?projectId=${encodeURIComponent(projectId)}`).then((r) =>
+        fetch(`${apiBaseUrl()}/api/tickets?projectId=${encodeURIComponent(projectId)}`).then((r) =>
           r.ok ? r.json() : null,
         ),
-        fetch(`${API_BASE}/api/impact/list/${encodeURIComponent(projectId)}`).then((r) =>
+        fetch(`${apiBaseUrl()}/api/impact/list/${encodeURIComponent(projectId)}`).then((r) =>
           r.ok ? r.json() : null,
         ),
-        fetch(`${API_BASE}/api/projects/okrs?path=${encodeURIComponent(projectPath)}`).then((r) =>
+        fetch(`${apiBaseUrl()}/api/projects/okrs?path=${encodeURIComponent(projectPath)}`).then((r) =>
           r.ok ? r.json() : null,
         ),
       ]);
@@ -21,6 +21,7 @@ import {
 } from "lucide-react";
 import { memo, useState, useCallback } from "react";
 
+import { apiBaseUrl as defaultApiBaseUrl } from "@/lib/servicePorts";
 import { cn } from "@/lib/utils";
 import type { ImplementationGate, GateBlocker, GateWarning, GateApproval } from "@sidstack/shared";
 
@@ -148,7 +149,7 @@ const ApprovalInfo = memo(function ApprovalInfo({
 export const GateFooter = memo(function GateFooter({
   gate,
   analysisId,
-  apiBaseUrl = "http://localhost:19432",
+  apiBaseUrl = defaultApiBaseUrl(),
   onApprove,
   onRefresh,
   className,
@@ -20,6 +20,7 @@ import {
 } from "lucide-react";
 import { memo, useCallback, useEffect, useState } from "react";
 
+import { apiBaseUrl as defaultApiBaseUrl } from "@/lib/servicePorts";
 import { cn } from "@/lib/utils";
 import type {
   ImpactAnalysis,
@@ -115,7 +116,7 @@ function getSeverityStats(analysis: ImpactAnalysis) {
 export const ImpactDashboard = memo(function ImpactDashboard({
   analysisId,
   analysis: initialAnalysis,
-  apiBaseUrl = "http://localhost:19432",
+  apiBaseUrl = defaultApiBaseUrl(),
   onRefresh,
   onGateApprove,
   compact = false,
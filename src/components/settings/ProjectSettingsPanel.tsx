@@ -44,6 +44,7 @@ import {
 } from "@/components/ui/select";
 import { Slider } from "@/components/ui/slider";
 import { Switch } from "@/components/ui/switch";
+import { apiBaseUrl } from "@/lib/servicePorts";
 import { cn } from "@/lib/utils";
 import { useProjectSettingsStore } from "@/stores/projectSettingsStore";
 import { useTunnelStore } from "@/stores/tunnelStore";
@@ -172,7 +173,7 @@ export const ProjectSettingsPanel: React.FC<ProjectSettingsPanelProps> = ({
   };
 
   const handleCopyWebhookUrl = () => {
-    const url = settings.ticket?.tunnelUrl || "http://localhost:19432/api/tickets";
+    const url = settings.ticket?.tunnelUrl || `${apiBaseUrl()}/api/tickets`;
     navigator.clipboard.writeText(url);
     setCopied(true);
     setTimeout(() => setCopied(false), 2000);
@@ -488,7 +489,7 @@ export const ProjectSettingsPanel: React.FC<ProjectSettingsPanelProps> = ({
                   <Label className="text-[11px] text-[var(--text-muted)]">Webhook URL</Label>
                   <div className="flex items-center gap-2 mt-1">
                     <code className="text-xs text-[var(--text-primary)] flex-1 truncate font-mono">
-                      {settings.ticket?.tunnelUrl || "http://localhost:19432/api/tickets"}
+                      {settings.ticket?.tunnelUrl || `${apiBaseUrl()}/api/tickets`}
                     </code>
                     <Button
                       size="sm"
@@ -8,6 +8,7 @@
 
 import { createContext, useContext, ReactNode, useState, useCallback, useRef, useEffect } from "react";
 
+import { apiBaseUrl } from "@/lib/servicePorts";
 import type { BlockData, BlockState, LayoutNode, BlockViewType } from "@/types/block";
 
 // Generate unique IDs
@@ -604,11 +605,11 @@ export function WorkspaceProvider({ workspacePath, isActive, children, workspace
         // This ensures task_create and other MCP tools can find the project
         const projectName = workspacePath.split("/").pop() || "unknown";
         try {
-          const res = await fetch("http://localhost:19432/api/projects/by-path?path=" + encodeURIComponent(workspacePath));
+          const res = await fetch(`${apiBaseUrl()}/api/projects/by-path?path=` + encodeURIComponent(workspacePath));
           if (res.status === 404) {
             // Project not registered, try to create with folder name as ID
             let projectId = projectName;
-            let createRes = await fetch("http://localhost:19432/api/projects", {
+            let createRes = await fetch(`${apiBaseUrl()}/api/projects`, {
               method: "POST",
               headers: { "Content-Type": "application/json" },
               body: JSON.stringify({
@@ -623,7 +624,7 @@ export function WorkspaceProvider({ workspacePath, isActive, children, workspace
             if (createRes.status === 409) {
               const pathHash = Math.abs(workspacePath.split("").reduce((a, c) => ((a << 5) - a + c.charCodeAt(0)) | 0, 0)).toString(16).slice(0, 6);
               projectId = `${projectName}-${pathHash}`;
-              createRes = await fetch("http://localhost:19432/api/projects", {
+              createRes = await fetch(`${apiBaseUrl()}/api/projects`, {
                 method: "POST",
                 headers: { "Content-Type": "application/json" },
                 body: JSON.stringify({
@@ -8,6 +8,8 @@
 import { invoke } from "@tauri-apps/api/core";
 import { useCallback, useEffect, useRef, useState } from "react";
 
+import { getServicePorts } from "@/lib/servicePorts";
+
 // ============================================================================
 // Types from sidecar protocol
 // ============================================================================
@@ -207,7 +209,6 @@ export interface UseAgentSDKOptions {
 // Hook Implementation
 // ============================================================================
 
-const SIDECAR_PORT = 17433;
 const RECONNECT_DELAY = 2000;
 const MAX_RECONNECT_ATTEMPTS = 5;
 
@@ -321,7 +322,7 @@ export function useAgentSDK(options: UseAgentSDKOptions = {}) {
       await invoke("ensure_sdk_sidecar");
 
       // Connect to WebSocket
-      const ws = new WebSocket(`ws://localhost:${SIDECAR_PORT}`);
+      const ws = new WebSocket(`ws://localhost:${getServicePorts().sidecar}`);
 
       ws.onopen = () => {
         console.log("[useAgentSDK] Connected to sidecar");
@@ -0,0 +1,96 @@
+/**
+ * useClaudeTranscripts - Hook for past Claude conversations of a project
+ *
+ * Provides interface to the Tauri transcript index (~/.claude/projects):
+ * - List past conversations (by project or all)
+ * - Resume one (or the most recent) in a new persistent session
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import { useState, useCallback, useEffect } from "react";
+
+import type { ClaudeProcessInfo } from "./useClaudeProcess";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export interface ClaudeTranscript {
+  path: string;
+  sessionId: string;
+  projectDir: string;
+  cwd: string | null;
+  firstPrompt: string | null;
+  model: string | null;
+  turnCount: number;
+  costUsd: number;
+  startedAt: string | null;
+  lastModified: string;
+  sizeBytes: number;
+}
+
+// ============================================================================
+// Hook
+// ============================================================================
+
+export function useClaudeTranscripts(projectPath?: string, limit?: number) {
+  const [transcripts, setTranscripts] = useState<ClaudeTranscript[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  // Load transcripts (the backend rescans changed files first)
+  const loadTranscripts = useCallback(async () => {
+    setLoading(true);
+    setError(null);
+    try {
+      const result = await invoke<ClaudeTranscript[]>("claude_transcripts_list", {
+        projectPath,
+        limit,
+      });
+      setTranscripts(result);
+    } catch (e) {
+      setError(String(e));
+      setTranscripts([]);
+    } finally {
+      setLoading(false);
+    }
+  }, [projectPath, limit]);
+
+  useEffect(() => {
+    loadTranscripts();
+  }, [loadTranscripts]);
+
+  // Resume a conversation (the most recent one if no id is given)
+  const resumeConversation = useCallback(
+    async (
+      role: string,
+      claudeSessionId?: string,
+      terminalId?: string
+    ): Promise<ClaudeProcessInfo | null> => {
+      if (!projectPath) return null;
+      try {
+        return await invoke<ClaudeProcessInfo>("claude_resume_conversation", {
+          projectPath,
+          claudeSessionId,
+          role,
+          terminalId,
+        });
+      } catch (e) {
+        console.error("[useClaudeTranscripts] resumeConversation error:", e);
+        setError(String(e));
+        return null;
+      }
+    },
+    [projectPath]
+  );
+
+  return {
+    transcripts,
+    loading,
+    error,
+    loadTranscripts,
+    resumeConversation,
+  };
+}
+
+export default useClaudeTranscripts;
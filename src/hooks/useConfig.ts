@@ -0,0 +1,126 @@
+/**
+ * useConfig - Hook for the effective SidStack settings
+ *
+ * Settings come from built-in defaults, ~/.sidstack/config.yaml and
+ * <project>/.sidstack/config.yaml (highest precedence). The backend reloads
+ * them when a file changes and emits `config-changed`.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useState, useCallback, useEffect } from "react";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/** Keys mirror config.yaml (snake_case) */
+export interface SidstackConfig {
+  worktrees: {
+    shared_folders: string[];
+    shared_files: string[];
+    worktree_root: string;
+  };
+  sessions: {
+    cleanup_after_days: number;
+  };
+  watchdog: {
+    check_interval_secs: number;
+    heartbeat_timeout_secs: number;
+    recovery_delay_ms: number;
+    enabled: boolean;
+  };
+  coordinator: {
+    max_workers: number;
+  };
+  ports: {
+    ipc: number;
+    sidecar: number;
+    api_server: number;
+  };
+  resources: {
+    enabled: boolean;
+    sample_interval_secs: number;
+    /** By role; `default` applies to roles not listed */
+    limits: Record<string, ResourceLimits>;
+  };
+  failure_patterns: {
+    enabled: boolean;
+    /** Identical tool errors in a row that trigger recovery (0 = off) */
+    repeated_tool_failures: number;
+    /** Added to the built-in rules; a rule with a built-in's id replaces it */
+    rules: FailureRule[];
+    /** Built-in rule ids to turn off */
+    disabled: string[];
+  };
+}
+
+export interface FailureRule {
+  id: string;
+  /** Regex; `(?i)` makes it case-insensitive */
+  pattern: string;
+  source?: "output" | "tool_error" | "error" | "stderr" | "errors" | "any";
+  severity?: "notify" | "recover";
+  occurrences?: number;
+  description?: string | null;
+}
+
+export interface ResourceLimits {
+  max_cpu_percent?: number | null;
+  max_memory_mb?: number | null;
+  max_open_files?: number | null;
+  max_children?: number | null;
+  action?: "warn" | "terminate";
+}
+
+export interface ConfigSnapshot {
+  config: SidstackConfig;
+  /** Files that contributed, lowest precedence first */
+  sources: string[];
+  /** Files skipped (or kept at their last good contents) and why */
+  errors: string[];
+}
+
+export interface ConfigChangedEvent {
+  paths: string[];
+  errors: string[];
+}
+
+// ============================================================================
+// Hook
+// ============================================================================
+
+export function useConfig(projectPath?: string) {
+  const [snapshot, setSnapshot] = useState<ConfigSnapshot | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadConfig = useCallback(async () => {
+    try {
+      const result = await invoke<ConfigSnapshot>("config_get", { projectPath });
+      setSnapshot(result);
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [projectPath]);
+
+  useEffect(() => {
+    loadConfig();
+    const unlisten = listen<ConfigChangedEvent>("config-changed", () => {
+      loadConfig();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [loadConfig]);
+
+  return {
+    config: snapshot?.config ?? null,
+    sources: snapshot?.sources ?? [],
+    errors: snapshot?.errors ?? [],
+    error,
+    loadConfig,
+  };
+}
+
+export default useConfig;
@@ -0,0 +1,71 @@
+/**
+ * useProcessMetrics - Hook for agent resource usage
+ *
+ * Listens to the backend sampler (`process-metrics`), which reports CPU,
+ * memory, open files and child count per agent process tree, and to
+ * `process-limit-exceeded` for per-role limits from config.yaml.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useState, useEffect } from "react";
+
+import type { ResourceLimits } from "./useConfig";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export interface ProcessMetrics {
+  /** Process or session ID */
+  id: string;
+  pid: number;
+  role: string | null;
+  cpuPercent: number;
+  memoryBytes: number;
+  openFiles: number | null;
+  childCount: number;
+  sampledAt: string;
+}
+
+export interface LimitBreach {
+  id: string;
+  pid: number;
+  role: string | null;
+  limit: "cpu" | "memory" | "openFiles" | "children";
+  value: number;
+  max: number;
+  action: NonNullable<ResourceLimits["action"]>;
+}
+
+// ============================================================================
+// Hook
+// ============================================================================
+
+export function useProcessMetrics(onLimitExceeded?: (breach: LimitBreach) => void) {
+  const [metrics, setMetrics] = useState<ProcessMetrics[]>([]);
+
+  useEffect(() => {
+    invoke<ProcessMetrics[]>("process_metrics_get")
+      .then(setMetrics)
+      .catch((e) => console.error("[useProcessMetrics] load error:", e));
+
+    const unlistenMetrics = listen<ProcessMetrics[]>("process-metrics", (event) => {
+      setMetrics(event.payload);
+    });
+    const unlistenBreach = listen<LimitBreach>("process-limit-exceeded", (event) => {
+      onLimitExceeded?.(event.payload);
+    });
+    return () => {
+      unlistenMetrics.then((fn) => fn());
+      unlistenBreach.then((fn) => fn());
+    };
+  }, [onLimitExceeded]);
+
+  // Metrics by process/session ID
+  const byId = (id: string) => metrics.find((m) => m.id === id) ?? null;
+
+  return { metrics, byId };
+}
+
+export default useProcessMetrics;
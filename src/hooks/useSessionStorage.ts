@@ -4,7 +4,7 @@
  * Provides interface to Tauri backend for session operations:
  * - List sessions (by project or all)
  * - Create, rename, delete sessions
- * - Export session as markdown
+ * - Export session as markdown, HTML or JSONL, or as a portable bundle
  * - Load session output history
  */
 
@@ -17,6 +17,9 @@ import { useState, useCallback, useEffect } from "react";
 
 export type SessionStatus = "active" | "saved" | "archived";
 
+export type SessionExportFormat = "markdown" | "html" | "jsonl";
+export type SummaryBackend = "heuristic" | "claude";
+
 export interface SessionMeta {
   sessionId: string;
   projectPath: string;
@@ -28,6 +31,26 @@ export interface SessionMeta {
   lastActiveAt: string;
   status: SessionStatus;
   logSizeBytes: number;
+  /** Generated from the Claude transcript when the session goes idle */
+  title: string | null;
+  summary: string | null;
+  summaryUpdatedAt: string | null;
+  /** Backend that produced the title and summary */
+  summarySource: "heuristic" | "claude" | null;
+}
+
+export interface SessionSearchHit {
+  sessionId: string;
+  projectPath: string;
+  role: string | null;
+  session: SessionMeta | null;
+  lineNumber: number;
+  timestamp: string | null;
+  line: string;
+  snippet: string;
+  score: number;
+  contextBefore: string[];
+  contextAfter: string[];
 }
 
 // ============================================================================
@@ -123,6 +146,29 @@ export function useSessionStorage(projectPath?: string) {
     [projectPath, loadSessions]
   );
 
+  // Regenerate title and summary from the transcript (null if there is none)
+  const generateSummary = useCallback(
+    async (
+      sessionId: string,
+      backend: SummaryBackend = "heuristic"
+    ): Promise<SessionMeta | null> => {
+      if (!projectPath) return null;
+      try {
+        const meta = await invoke<SessionMeta | null>("session_summary_generate", {
+          projectPath,
+          sessionId,
+          backend,
+        });
+        await loadSessions(projectPath);
+        return meta;
+      } catch (e) {
+        console.error("[useSessionStorage] generateSummary error:", e);
+        return null;
+      }
+    },
+    [projectPath, loadSessions]
+  );
+
   // Delete session
   const deleteSession = useCallback(
     async (sessionId: string, deleteLogs: boolean = true): Promise<boolean> => {
@@ -205,12 +251,16 @@ export function useSessionStorage(projectPath?: string) {
 
   // Export session as markdown
   const exportSession = useCallback(
-    async (sessionId: string): Promise<string | null> => {
+    async (
+      sessionId: string,
+      format: SessionExportFormat = "markdown"
+    ): Promise<string | null> => {
       if (!projectPath) return null;
       try {
         return await invoke<string>("session_storage_export", {
           projectPath,
           sessionId,
+          format,
         });
       } catch (e) {
         console.error("[useSessionStorage] exportSession error:", e);
@@ -220,6 +270,42 @@ export function useSessionStorage(projectPath?: string) {
     [projectPath]
   );
 
+  // Export session as a .sidstack-session bundle; returns the written path
+  const exportBundle = useCallback(
+    async (sessionId: string, outputPath: string): Promise<string | null> => {
+      if (!projectPath) return null;
+      try {
+        return await invoke<string>("session_storage_export_bundle", {
+          projectPath,
+          sessionId,
+          outputPath,
+        });
+      } catch (e) {
+        console.error("[useSessionStorage] exportBundle error:", e);
+        return null;
+      }
+    },
+    [projectPath]
+  );
+
+  // Import a .sidstack-session bundle into the current project
+  const importBundle = useCallback(
+    async (bundlePath: string): Promise<SessionMeta | null> => {
+      try {
+        const meta = await invoke<SessionMeta>("session_storage_import_bundle", {
+          bundlePath,
+          projectPath: projectPath ?? null,
+        });
+        await loadSessions(projectPath);
+        return meta;
+      } catch (e) {
+        console.error("[useSessionStorage] importBundle error:", e);
+        return null;
+      }
+    },
+    [projectPath, loadSessions]
+  );
+
   // Load output from session log
   const loadOutput = useCallback(
     async (sessionId: string, lines: number = 100): Promise<string[]> => {
@@ -267,6 +353,27 @@ export function useSessionStorage(projectPath?: string) {
     }
   }, []);
 
+  // Full-text search across session logs (current project, or all if allProjects)
+  const searchLogs = useCallback(
+    async (
+      query: string,
+      options: { allProjects?: boolean; limit?: number; contextLines?: number } = {}
+    ): Promise<SessionSearchHit[]> => {
+      try {
+        return await invoke<SessionSearchHit[]>("session_search", {
+          query,
+          projectPath: options.allProjects ? null : projectPath ?? null,
+          limit: options.limit,
+          contextLines: options.contextLines,
+        });
+      } catch (e) {
+        console.error("[useSessionStorage] searchLogs error:", e);
+        return [];
+      }
+    },
+    [projectPath]
+  );
+
   // Group sessions by project
   const sessionsByProject = sessions.reduce(
     (acc, session) => {
@@ -293,13 +400,17 @@ export function useSessionStorage(projectPath?: string) {
     getSession,
     createSession,
     renameSession,
+    generateSummary,
     deleteSession,
     updateStatus,
     updateRole,
     updateClaudeSessionId,
     exportSession,
+    exportBundle,
+    importBundle,
     loadOutput,
     appendOutput,
     cleanup,
+    searchLogs,
   };
 }
@@ -99,11 +99,11 @@ export function useWorkspace() {
   );
 
   const syncSharedSymlinks = useCallback(
-    async (workspacePath: string): Promise<void> => {
+    async (projectPath: string, workspacePath: string): Promise<void> => {
       setLoading(true);
       setError(null);
       try {
-        await invoke("sync_shared_symlinks", { workspacePath });
+        await invoke("sync_shared_symlinks", { projectPath, workspacePath });
       } catch (err) {
         const message = err instanceof Error ? err.message : String(err);
         setError(message);
@@ -13,11 +13,19 @@ import { useCallback, useEffect, useRef } from "react";
 
 import { useAppStore } from "@/stores/appStore";
 import { useBlockStore } from "@/stores/blockStore";
+import type { BlockData } from "@/types/block";
+
+import type { ClaudeProcessInfo } from "./useClaudeProcess";
 
 // ============================================================================
 // Types (matching Rust structs)
 // ============================================================================
 
+/** sessions.json schema version (see workspace_schema.rs) */
+export const SESSIONS_VERSION = "1.1";
+
+export type LaunchProfile = "shell" | "claude-session";
+
 export interface SessionTab {
   id: string;
   blockId?: string; // Block ID for persistence (reuse when switching back)
@@ -25,6 +33,25 @@ export interface SessionTab {
   cwd: string;
   title: string;
   pinned: boolean;
+  role?: string | null; // Agent role, for agent tabs
+  launchProfile?: LaunchProfile;
+  claudeSessionId?: string | null; // Conversation to resume on restore
+}
+
+export interface TabRestoreResult {
+  tabId: string;
+  blockId: string | null;
+  launchProfile: LaunchProfile;
+  cwd: string;
+  success: boolean;
+  process: ClaudeProcessInfo | null;
+  resumed: boolean;
+  error: string | null;
+}
+
+export interface RestoreReport {
+  tabs: TabRestoreResult[];
+  activeTabId: string | null;
 }
 
 export interface SessionState {
@@ -69,6 +96,13 @@ async function workspaceSessionSave(
   return invoke("workspace_session_save", { workspacePath, state });
 }
 
+/** Relaunch agent tabs (resuming their conversations); shell tabs are only reported */
+export async function workspaceSessionRestore(
+  workspacePath: string
+): Promise<RestoreReport> {
+  return invoke<RestoreReport>("workspace_session_restore", { workspacePath });
+}
+
 export async function workspaceGetHistoryPath(
   workspacePath: string,
   terminalId: string
@@ -86,6 +120,20 @@ async function workspaceValidateCwd(
   return invoke<string>("workspace_validate_cwd", { cwd, fallback });
 }
 
+function blockToTab(block: BlockData, workspacePath: string): SessionTab {
+  return {
+    id: block.id,
+    blockId: block.id,
+    type: block.viewType,
+    cwd: block.cwd || workspacePath,
+    title: block.title || "Block",
+    pinned: false,
+    role: block.agentRole ?? null,
+    launchProfile: block.launchProfile ?? "shell",
+    claudeSessionId: block.claudeSessionId ?? null,
+  };
+}
+
 // ============================================================================
 // Hook
 // ============================================================================
@@ -123,17 +171,10 @@ export function useWorkspacePersistence(
       const blockList = Object.values(useBlockStore.getState().blocks);
       const currentActiveBlockId = useBlockStore.getState().activeBlockId;
 
-      const sessionTabs: SessionTab[] = blockList.map((block) => ({
-        id: block.id,
-        blockId: block.id,
-        type: block.viewType,
-        cwd: block.cwd || workspacePath,
-        title: block.title || "Block",
-        pinned: false,
-      }));
+      const sessionTabs: SessionTab[] = blockList.map((block) => blockToTab(block, workspacePath));
 
       const state: SessionState = {
-        version: "1.0",
+        version: SESSIONS_VERSION,
         tabs: sessionTabs,
         active_tab_id: currentActiveBlockId,
         last_saved: new Date().toISOString(),
@@ -244,17 +285,10 @@ export function useWorkspacePersistence(
     // Schedule save
     saveTimeoutRef.current = setTimeout(async () => {
       try {
-        const sessionTabs: SessionTab[] = blockList.map((block) => ({
-          id: block.id,
-          blockId: block.id,
-          type: block.viewType,
-          cwd: block.cwd || projectPath,
-          title: block.title || "Block",
-          pinned: false,
-        }));
+        const sessionTabs: SessionTab[] = blockList.map((block) => blockToTab(block, projectPath));
 
         const state: SessionState = {
-          version: "1.0",
+          version: SESSIONS_VERSION,
           tabs: sessionTabs,
           active_tab_id: activeBlockId,
           last_saved: new Date().toISOString(),
@@ -290,17 +324,10 @@ export function useWorkspacePersistence(
       // Save immediately
       try {
         const blockList = Object.values(blocks);
-        const sessionTabs: SessionTab[] = blockList.map((block) => ({
-          id: block.id,
-          blockId: block.id,
-          type: block.viewType,
-          cwd: block.cwd || projectPath,
-          title: block.title || "Block",
-          pinned: false,
-        }));
+        const sessionTabs: SessionTab[] = blockList.map((block) => blockToTab(block, projectPath));
 
         const state: SessionState = {
-          version: "1.0",
+          version: SESSIONS_VERSION,
           tabs: sessionTabs,
           active_tab_id: activeBlockId,
           last_saved: new Date().toISOString(),
@@ -327,17 +354,10 @@ export function useWorkspacePersistence(
 
     try {
       const blockList = Object.values(blocks);
-      const sessionTabs: SessionTab[] = blockList.map((block) => ({
-        id: block.id,
-        blockId: block.id,
-        type: block.viewType,
-        cwd: block.cwd || projectPath,
-        title: block.title || "Block",
-        pinned: false,
-      }));
+      const sessionTabs: SessionTab[] = blockList.map((block) => blockToTab(block, projectPath));
 
       const state: SessionState = {
-        version: "1.0",
+        version: SESSIONS_VERSION,
         tabs: sessionTabs,
         active_tab_id: activeBlockId,
         last_saved: new Date().toISOString(),
@@ -7,12 +7,14 @@
  * connections for every request.
  */
 
+import { invoke } from "@tauri-apps/api/core";
+import { ipcUrl } from "./servicePorts";
 import { showError } from "./toast";
 
-const IPC_URL = "ws://127.0.0.1:17432";
 const RECONNECT_DELAY = 1000; // 1 second
 const REQUEST_TIMEOUT = 10000; // 10 seconds
 const MAX_RECONNECT_ATTEMPTS = 5;
+const AUTH_REQUEST_ID = "auth_hello";
 
 interface PendingRequest {
   id: string;
@@ -39,21 +41,38 @@ class IpcClient {
     this.isConnecting = true;
 
     try {
-      this.ws = new WebSocket(IPC_URL);
+      this.ws = new WebSocket(ipcUrl());
 
-      this.ws.onopen = () => {
+      this.ws.onopen = async () => {
         console.log("[IpcClient] Connected to IPC server");
-        this.isConnected = true;
-        this.isConnecting = false;
-        this.reconnectAttempts = 0;
 
-        // Flush queued requests
-        this.flushQueue();
+        // Authenticate before any queued request goes out
+        try {
+          const token = await invoke<string>("ipc_get_auth_token");
+          this.ws?.send(JSON.stringify({ id: AUTH_REQUEST_ID, method: "auth.hello", params: { token, role: "ui" } }));
+        } catch (err) {
+          console.error("[IpcClient] Failed to get IPC auth token:", err);
+        }
       };
 
       this.ws.onmessage = (event) => {
         try {
           const response = JSON.parse(event.data);
+
+          if (response.id === AUTH_REQUEST_ID) {
+            if (response.status === "error") {
+              console.error("[IpcClient] Authentication failed:", response.message);
+              return;
+            }
+            this.isConnected = true;
+            this.isConnecting = false;
+            this.reconnectAttempts = 0;
+
+            // Flush queued requests
+            this.flushQueue();
+            return;
+          }
+
           const pending = this.pendingRequests.get(response.id);
 
           if (pending) {
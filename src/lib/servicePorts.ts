@@ -0,0 +1,41 @@
+/**
+ * Service Ports
+ *
+ * Ports the app's local services (IPC server, Agent SDK sidecar, API server)
+ * listen on in this run. They come from the `ports` section of config.yaml and
+ * are fixed at startup, so they are loaded once before the UI renders.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+
+import type { SidstackConfig } from "@/hooks/useConfig";
+
+export type ServicePorts = SidstackConfig["ports"];
+
+const DEFAULT_PORTS: ServicePorts = { ipc: 17432, sidecar: 17433, api_server: 19432 };
+
+let ports: ServicePorts = DEFAULT_PORTS;
+
+/** Load the ports of this run (defaults are kept if the app can't tell) */
+export async function loadServicePorts(): Promise<ServicePorts> {
+  try {
+    ports = await invoke<ServicePorts>("config_ports");
+  } catch (err) {
+    console.error("[ServicePorts] Failed to load ports, using defaults:", err);
+  }
+  return ports;
+}
+
+export function getServicePorts(): ServicePorts {
+  return ports;
+}
+
+/** Base URL of the API server, e.g. http://localhost:19432 */
+export function apiBaseUrl(): string {
+  return `http://localhost:${ports.api_server}`;
+}
+
+/** URL of the IPC WebSocket server */
+export function ipcUrl(): string {
+  return `ws://127.0.0.1:${ports.ipc}`;
+}
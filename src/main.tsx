@@ -1,11 +1,16 @@
 import ReactDOM from "react-dom/client";
 
-import App from "./App";
+import { loadServicePorts } from "./lib/servicePorts";
 import "./index.css";
 import "@xterm/xterm/css/xterm.css";
 
-ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
-  // StrictMode temporarily disabled - causes terminal PTY to be killed during init
-  // TODO: Fix the effect cleanup to handle StrictMode properly
-  <App />,
-);
+// Stores and the IPC client read the service ports when their modules load,
+// so the app is only imported once the ports are known
+loadServicePorts().then(async () => {
+  const { default: App } = await import("./App");
+  ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
+    // StrictMode temporarily disabled - causes terminal PTY to be killed during init
+    // TODO: Fix the effect cleanup to handle StrictMode properly
+    <App />,
+  );
+});
@@ -14,8 +14,9 @@ import type {
   SessionFilters,
   WindowMode,
 } from "@sidstack/shared";
+import { apiBaseUrl } from "@/lib/servicePorts";
 
-const API_BASE = "http://localhost:19432/api/sessions";
+const apiBase = () => `${apiBaseUrl()}/api/sessions`;
 
 interface ClaudeSessionStore {
   // Data
@@ -139,7 +140,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
       params.append("offset", String(appliedFilters.offset));
 
     try {
-      const res = await fetch(`${API_BASE}?${params}`);
+      const res = await fetch(`${apiBase()}?${params}`);
       const data = await res.json();
 
       if (data.success) {
@@ -162,7 +163,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
     set({ isLoading: true, error: null });
 
     try {
-      const res = await fetch(`${API_BASE}/${id}`);
+      const res = await fetch(`${apiBase()}/${id}`);
       const data = await res.json();
 
       if (data.success) {
@@ -182,7 +183,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
   // Fetch session events
   fetchSessionEvents: async (id: string) => {
     try {
-      const res = await fetch(`${API_BASE}/${id}/events`);
+      const res = await fetch(`${apiBase()}/${id}/events`);
       const data = await res.json();
 
       if (data.success) {
@@ -202,7 +203,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
       if (filters?.taskId) params.append("taskId", filters.taskId);
       if (filters?.moduleId) params.append("moduleId", filters.moduleId);
 
-      const res = await fetch(`${API_BASE}/stats/overview?${params}`);
+      const res = await fetch(`${apiBase()}/stats/overview?${params}`);
       const data = await res.json();
 
       if (data.success) {
@@ -216,7 +217,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
   // Fetch sessions by task
   fetchSessionsByTask: async (taskId: string) => {
     try {
-      const res = await fetch(`${API_BASE}/by-task/${taskId}`);
+      const res = await fetch(`${apiBase()}/by-task/${taskId}`);
       const data = await res.json();
       return data.success ? data.sessions : [];
     } catch (err) {
@@ -228,7 +229,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
   // Fetch sessions by module
   fetchSessionsByModule: async (moduleId: string) => {
     try {
-      const res = await fetch(`${API_BASE}/by-module/${moduleId}`);
+      const res = await fetch(`${apiBase()}/by-module/${moduleId}`);
       const data = await res.json();
       return data.success ? data.sessions : [];
     } catch (err) {
@@ -245,7 +246,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
     const windowMode = options.windowMode || get().windowMode;
 
     try {
-      const res = await fetch(`${API_BASE}/launch`, {
+      const res = await fetch(`${apiBase()}/launch`, {
         method: "POST",
         headers: { "Content-Type": "application/json" },
         body: JSON.stringify({
@@ -280,7 +281,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
   // Update session status - optimized to avoid redundant fetches
   updateStatus: async (id, status, options) => {
     try {
-      const res = await fetch(`${API_BASE}/${id}/status`, {
+      const res = await fetch(`${apiBase()}/${id}/status`, {
         method: "POST",
         headers: { "Content-Type": "application/json" },
         body: JSON.stringify({ status, ...options }),
@@ -313,7 +314,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
     const effectiveWindowMode = windowMode || get().windowMode;
 
     try {
-      const res = await fetch(`${API_BASE}/${id}/resume`, {
+      const res = await fetch(`${apiBase()}/${id}/resume`, {
         method: "POST",
         headers: { "Content-Type": "application/json" },
         body: JSON.stringify({ additionalPrompt, windowMode: effectiveWindowMode }),
@@ -339,7 +340,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
   // Delete session
   deleteSession: async (id) => {
     try {
-      await fetch(`${API_BASE}/${id}`, { method: "DELETE" });
+      await fetch(`${apiBase()}/${id}`, { method: "DELETE" });
 
       set((state) => ({
         sessions: state.sessions.filter((s) => s.id !== id),
@@ -356,7 +357,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
   // Sync single session status with actual terminal state
   syncSession: async (id) => {
     try {
-      const res = await fetch(`${API_BASE}/${id}/sync`, {
+      const res = await fetch(`${apiBase()}/${id}/sync`, {
         method: "POST",
       });
       const data = await res.json();
@@ -376,7 +377,7 @@ export const useClaudeSessionStore = create<ClaudeSessionStore>((set, get) => ({
   // Sync all active sessions
   syncAllSessions: async (workspacePath) => {
     try {
-      const res = await fetch(`${API_BASE}/sync-all`, {
+      const res = await fetch(`${apiBase()}/sync-all`, {
         method: "POST",
         headers: { "Content-Type": "application/json" },
         body: JSON.stringify({ workspacePath }),
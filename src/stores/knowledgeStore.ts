@@ -7,6 +7,7 @@ import {
   KnowledgeStatus,
   extractCodeRefs,
 } from "@/types/knowledge";
+import { apiBaseUrl } from "@/lib/servicePorts";
 
 /**
  * Knowledge Browser Store
@@ -15,7 +16,7 @@ import {
  * Uses REST API (api-server) instead of Tauri IPC for data loading.
  */
 
-const API_BASE = "http://localhost:19432/api/knowledge";
+const apiBase = () => `${apiBaseUrl()}/api/knowledge`;
 
 // ============================================================================
 // Types
@@ -250,7 +251,7 @@ export const useKnowledgeStore = create<KnowledgeStore>()((set, get) => ({
     try {
       // Check if knowledge documents exist via REST API
       const res = await fetch(
-        `${API_BASE}?projectPath=${encodeURIComponent(projectPath)}&limit=1`
+        `${apiBase()}?projectPath=${encodeURIComponent(projectPath)}&limit=1`
       );
 
       if (res.ok) {
@@ -309,7 +310,7 @@ export const useKnowledgeStore = create<KnowledgeStore>()((set, get) => ({
 
     try {
       const res = await fetch(
-        `${API_BASE}?projectPath=${encodeURIComponent(projectPath)}&limit=500`
+        `${apiBase()}?projectPath=${encodeURIComponent(projectPath)}&limit=500`
       );
 
       if (!res.ok) {
@@ -364,7 +365,7 @@ export const useKnowledgeStore = create<KnowledgeStore>()((set, get) => ({
       const docId = path.replace(/\.md$/, "").replace(/\//g, "-");
 
       const res = await fetch(
-        `${API_BASE}/doc/${encodeURIComponent(docId)}?projectPath=${encodeURIComponent(projectPath)}`
+        `${apiBase()}/doc/${encodeURIComponent(docId)}?projectPath=${encodeURIComponent(projectPath)}`
       );
 
       if (!res.ok) {
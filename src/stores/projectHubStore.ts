@@ -7,6 +7,7 @@
 
 import { create } from 'zustand';
 import type { CapabilityDefinition, CapabilityNode, CapabilityRegistryStats, CapabilityRequirement, EnrichedRequirement } from '@sidstack/shared';
+import { apiBaseUrl } from '@/lib/servicePorts';
 
 // ============================================================================
 // Types
@@ -99,8 +100,6 @@ interface ProjectHubState {
   computeProjectGoals: () => void;
 }
 
-const API_BASE = 'http://localhost:19432';
-
 // ============================================================================
 // Store
 // ============================================================================
@@ -152,7 +151,7 @@ export const useProjectHubStore = create<ProjectHubState>((set, get) => ({
     set({ isLoading: true, error: null });
     try {
       const res = await fetch(
-        `${API_BASE}/api/capabilities/hierarchy?projectPath=${encodeURIComponent(projectPath)}`,
+        `${apiBaseUrl()}/api/capabilities/hierarchy?projectPath=${encodeURIComponent(projectPath)}`,
       );
       if (!res.ok) {
         throw new Error(`API error: ${res.status} ${res.statusText}`);
@@ -257,9 +256,9 @@ export const useProjectHubStore = create<ProjectHubState>((set, get) => ({
         const moduleId = moduleIds[0]; // Primary module
 
         const [tasksRes, sessionsRes, knowledgeRes] = await Promise.all([
-          fetch(`${API_BASE}/api/tasks?projectId=${encodeURIComponent(projectId)}&moduleId=${encodeURIComponent(moduleId)}`).catch(() => null),
-          fetch(`${API_BASE}/api/sessions/by-module/${encodeURIComponent(moduleId)}`).catch(() => null),
-          fetch(`${API_BASE}/api/knowledge?projectPath=${encodeURIComponent(projectPath)}&module=${encodeURIComponent(moduleId)}`).catch(() => null),
+          fetch(`${apiBaseUrl()}/api/tasks?projectId=${encodeURIComponent(projectId)}&moduleId=${encodeURIComponent(moduleId)}`).catch(() => null),
+          fetch(`${apiBaseUrl()}/api/sessions/by-module/${encodeURIComponent(moduleId)}`).catch(() => null),
+          fetch(`${apiBaseUrl()}/api/knowledge?projectPath=${encodeURIComponent(projectPath)}&module=${encodeURIComponent(moduleId)}`).catch(() => null),
         ]);
 
         if (tasksRes?.ok) {
@@ -368,8 +367,8 @@ export const useProjectHubStore = create<ProjectHubState>((set, get) => ({
   fetchContextBar: async (projectId) => {
     try {
       const [tasksRes, sessionsRes] = await Promise.all([
-        fetch(`${API_BASE}/api/tasks?projectId=${encodeURIComponent(projectId)}&status=in_progress`),
-        fetch(`${API_BASE}/api/sessions/query/active`),
+        fetch(`${apiBaseUrl()}/api/tasks?projectId=${encodeURIComponent(projectId)}&status=in_progress`),
+        fetch(`${apiBaseUrl()}/api/sessions/query/active`),
       ]);
       const tasksData = await tasksRes.json();
       const sessionsData = await sessionsRes.json();
@@ -14,8 +14,9 @@ import type {
   AgentSettings,
   TicketSettings,
 } from '@sidstack/shared';
+import { apiBaseUrl } from '@/lib/servicePorts';
 
-const API_BASE = 'http://localhost:19432/api/projects';
+const apiBase = () => `${apiBaseUrl()}/api/projects`;
 
 // Default settings (duplicated here to avoid import issues)
 const DEFAULT_SETTINGS: ProjectSettings = {
@@ -94,7 +95,7 @@ export const useProjectSettingsStore = create<ProjectSettingsStore>((set, get) =
 
     try {
       const res = await fetch(
-        `${API_BASE}/settings?path=${encodeURIComponent(projectPath)}`
+        `${apiBase()}/settings?path=${encodeURIComponent(projectPath)}`
       );
       const data = await res.json();
 
@@ -122,7 +123,7 @@ export const useProjectSettingsStore = create<ProjectSettingsStore>((set, get) =
 
     try {
       const res = await fetch(
-        `${API_BASE}/settings?path=${encodeURIComponent(currentProjectPath)}`,
+        `${apiBase()}/settings?path=${encodeURIComponent(currentProjectPath)}`,
         {
           method: 'PUT',
           headers: { 'Content-Type': 'application/json' },
@@ -202,7 +203,7 @@ export const useProjectSettingsStore = create<ProjectSettingsStore>((set, get) =
 
     try {
       const res = await fetch(
-        `${API_BASE}/settings?path=${encodeURIComponent(currentProjectPath)}`,
+        `${apiBase()}/settings?path=${encodeURIComponent(currentProjectPath)}`,
         { method: 'DELETE' }
       );
       const data = await res.json();
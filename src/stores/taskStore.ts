@@ -6,6 +6,7 @@
  */
 
 import { create } from "zustand";
+import { apiBaseUrl } from "@/lib/servicePorts";
 
 // ============================================================================
 // Types
@@ -145,8 +146,6 @@ export interface TaskStats {
   failed: number;
 }
 
-const API_BASE = "http://localhost:19432";
-
 const defaultFilters: TaskFilters = {
   status: "all",
   projectId: "default",
@@ -266,7 +265,7 @@ export const useTaskStore = create<TaskStoreState>((set, get) => ({
     set({ isLoading: true, error: null });
 
     try {
-      const response = await fetch(`${API_BASE}/api/tasks?projectId=${pid}`);
+      const response = await fetch(`${apiBaseUrl()}/api/tasks?projectId=${pid}`);
       if (!response.ok) {
         throw new Error(`Failed to fetch tasks: ${response.statusText}`);
       }
@@ -302,7 +301,7 @@ export const useTaskStore = create<TaskStoreState>((set, get) => ({
     }
 
     try {
-      const response = await fetch(`${API_BASE}/api/tasks/${taskId}/progress`);
+      const response = await fetch(`${apiBaseUrl()}/api/tasks/${taskId}/progress`);
       if (!response.ok) {
         throw new Error(`Failed to fetch progress: ${response.statusText}`);
       }
@@ -28,6 +28,15 @@ export interface TeamMemberConfig {
   failureCount: number;
   lastFailure?: string;
   recoveredFrom?: string;
+  launch?: MemberLaunchSettings;
+}
+
+export interface MemberLaunchSettings {
+  prompt?: string;
+  workingDir?: string;
+  autoStart: boolean;
+  /** Run in the git worktree of this task ID */
+  worktree?: string;
 }
 
 export interface TeamConfig {
@@ -106,6 +115,18 @@ export interface MemberWithState {
   claudeSessionId?: string;
   currentTask?: MemberTaskInfo;
   failureCount: number;
+  lastHeartbeat?: string;
+}
+
+/** Watchdog health report, emitted as `member-health` on every check */
+export interface MemberHealth {
+  memberId: string;
+  teamId: string;
+  isHealthy: boolean;
+  lastHeartbeat?: string;
+  terminalAlive: boolean;
+  failureDetected: boolean;
+  failureReason?: string;
 }
 
 export interface RecoveryContext {
@@ -115,8 +136,13 @@ export interface RecoveryContext {
   progress: number;
   completedSteps: string[];
   currentStep?: string;
+  /** Files changed where the member worked, `git status --short` style */
   artifacts: string[];
+  /** First message for the replacement session */
   resumeInstructions: string;
+  workingDir?: string;
+  toolCalls: string[];
+  recentMessages: string[];
 }
 
 export interface RecoveryEvent {
@@ -145,6 +171,45 @@ export interface CreateTeamInput {
   description?: string;
 }
 
+export interface TeamTemplateInfo {
+  id: string;
+  source: "builtin" | "project";
+  path?: string;
+  template: {
+    name: string;
+    description?: string;
+    tags: string[];
+    variables: { name: string; description?: string; default?: string }[];
+    workers: { role?: string; agentType: string }[];
+  };
+}
+
+export interface MemberLaunch {
+  memberId: string;
+  role: string;
+  terminalId?: string;
+  cwd?: string;
+  resumed: boolean;
+  alreadyRunning: boolean;
+  error?: string;
+}
+
+export interface TeamStartReport {
+  teamId: string;
+  members: MemberLaunch[];
+}
+
+/** Failure pattern found in a member's output, emitted as `failure-detected` */
+export interface FailureNotification {
+  teamId: string;
+  memberId: string;
+  ruleId: string;
+  severity: "notify" | "recover";
+  description: string;
+  excerpt: string;
+  timestamp: string;
+}
+
 // =============================================================================
 // Store State
 // =============================================================================
@@ -155,6 +220,10 @@ interface TeamStoreState {
   activeTeam: TeamData | null;
   teamMembers: MemberWithState[];
   recoveryHistory: RecoveryEvent[];
+  /** Latest watchdog report by member ID */
+  memberHealth: Record<string, MemberHealth>;
+  /** Failure patterns detected since startup, newest first */
+  detectedFailures: FailureNotification[];
 
   // UI State
   isLoading: boolean;
@@ -169,6 +238,8 @@ interface TeamStoreState {
   loadRecoveryHistory: (teamId: string, limit?: number) => Promise<void>;
 
   createTeam: (input: CreateTeamInput) => Promise<TeamData>;
+  listTemplates: () => Promise<TeamTemplateInfo[]>;
+  createTeamFromTemplate: (templateId: string, variables?: Record<string, string>, name?: string) => Promise<TeamData>;
   updateTeam: (teamId: string, updates: { name?: string; autoRecovery?: boolean; maxRecoveryAttempts?: number }) => Promise<void>;
   archiveTeam: (teamId: string) => Promise<void>;
 
@@ -177,6 +248,8 @@ interface TeamStoreState {
 
   pauseTeam: (teamId: string, terminalSessions?: TerminalSessionInfo[]) => Promise<void>;
   resumeTeam: (teamId: string) => Promise<{ team: TeamData; sessionInfo?: SessionInfo }>;
+  startTeam: (teamId: string) => Promise<TeamStartReport>;
+  stopTeam: (teamId: string) => Promise<SessionInfo>;
 
   recoverMember: (teamId: string, memberId: string, reason?: string) => Promise<TeamMemberConfig>;
   getRecoveryContext: (teamId: string, memberId: string) => Promise<RecoveryContext>;
@@ -196,6 +269,8 @@ export const useTeamStore = create<TeamStoreState>((set, get) => ({
   activeTeam: null,
   teamMembers: [],
   recoveryHistory: [],
+  memberHealth: {},
+  detectedFailures: [],
   isLoading: false,
   error: null,
   currentProjectPath: null,
@@ -308,6 +383,34 @@ export const useTeamStore = create<TeamStoreState>((set, get) => ({
     }
   },
 
+  listTemplates: async () => {
+    const path = get().currentProjectPath;
+    if (!path) throw new Error("No project path set");
+    return invoke<TeamTemplateInfo[]>("team_template_list", { projectPath: path });
+  },
+
+  createTeamFromTemplate: async (templateId, variables, name) => {
+    const path = get().currentProjectPath;
+    if (!path) throw new Error("No project path set");
+
+    set({ isLoading: true, error: null });
+    try {
+      const team = await invoke<TeamData>("team_create_from_template", {
+        projectPath: path,
+        templateId,
+        variables,
+        name,
+      });
+      await get().loadTeams();
+      set({ activeTeam: team, isLoading: false });
+      return team;
+    } catch (error) {
+      const msg = error instanceof Error ? error.message : "Failed to create team from template";
+      set({ error: msg, isLoading: false });
+      throw new Error(msg);
+    }
+  },
+
   updateTeam: async (teamId, updates) => {
     const path = get().currentProjectPath;
     if (!path) throw new Error("No project path set");
@@ -420,6 +523,41 @@ export const useTeamStore = create<TeamStoreState>((set, get) => ({
     }
   },
 
+  startTeam: async (teamId) => {
+    const path = get().currentProjectPath;
+    if (!path) throw new Error("No project path set");
+
+    set({ isLoading: true, error: null });
+    try {
+      const report = await invoke<TeamStartReport>("team_start", { projectPath: path, teamId });
+      await get().loadTeam(teamId);
+      await get().loadTeams();
+      set({ isLoading: false });
+      return report;
+    } catch (error) {
+      const msg = error instanceof Error ? error.message : "Failed to start team";
+      set({ error: msg, isLoading: false });
+      throw new Error(msg);
+    }
+  },
+
+  stopTeam: async (teamId) => {
+    const path = get().currentProjectPath;
+    if (!path) throw new Error("No project path set");
+
+    set({ isLoading: true, error: null });
+    try {
+      const sessionInfo = await invoke<SessionInfo>("team_stop", { projectPath: path, teamId });
+      await get().loadTeams();
+      set({ activeTeam: null, isLoading: false });
+      return sessionInfo;
+    } catch (error) {
+      const msg = error instanceof Error ? error.message : "Failed to stop team";
+      set({ error: msg, isLoading: false });
+      throw new Error(msg);
+    }
+  },
+
   recoverMember: async (teamId, memberId, reason) => {
     const path = get().currentProjectPath;
     if (!path) throw new Error("No project path set");
@@ -519,6 +657,25 @@ export const useTeamStore = create<TeamStoreState>((set, get) => ({
     );
     unlistenFns.push(unlistenRecovery);
 
+    // Listen for watchdog health checks
+    const unlistenHealth = await listen<MemberHealth[]>("member-health", (event) => {
+      set((state) => ({
+        memberHealth: {
+          ...state.memberHealth,
+          ...Object.fromEntries(event.payload.map((h) => [h.memberId, h])),
+        },
+      }));
+    });
+    unlistenFns.push(unlistenHealth);
+
+    // Listen for failure patterns in member output
+    const unlistenFailure = await listen<FailureNotification>("failure-detected", (event) => {
+      set((state) => ({
+        detectedFailures: [event.payload, ...state.detectedFailures].slice(0, 100),
+      }));
+    });
+    unlistenFns.push(unlistenFailure);
+
     return unlistenFns;
   },
 
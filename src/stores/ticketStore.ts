@@ -5,6 +5,7 @@
  */
 
 import { create } from "zustand";
+import { apiBaseUrl } from "@/lib/servicePorts";
 
 // ============================================================================
 // Types
@@ -86,8 +87,6 @@ interface TicketStoreState {
   clearError: () => void;
 }
 
-const API_BASE = "http://localhost:19432";
-
 export const useTicketStore = create<TicketStoreState>((set, get) => ({
   // Initial state
   tickets: [],
@@ -120,7 +119,7 @@ export const useTicketStore = create<TicketStoreState>((set, get) => ({
         params.append("priority", filters.priority);
       }
 
-      const response = await fetch(`${API_BASE}/api/tickets?${params}`);
+      const response = await fetch(`${apiBaseUrl()}/api/tickets?${params}`);
       const data = await response.json();
 
       if (!response.ok) {
@@ -174,7 +173,7 @@ export const useTicketStore = create<TicketStoreState>((set, get) => ({
 
   updateTicketStatus: async (ticketId, status) => {
     try {
-      const response = await fetch(`${API_BASE}/api/tickets/${ticketId}`, {
+      const response = await fetch(`${apiBaseUrl()}/api/tickets/${ticketId}`, {
         method: "PATCH",
         headers: { "Content-Type": "application/json" },
         body: JSON.stringify({ status }),
@@ -202,7 +201,7 @@ export const useTicketStore = create<TicketStoreState>((set, get) => ({
   startSession: async (ticketId, workspacePath) => {
     try {
       const response = await fetch(
-        `${API_BASE}/api/tickets/${ticketId}/start-session`,
+        `${apiBaseUrl()}/api/tickets/${ticketId}/start-session`,
         {
           method: "POST",
           headers: { "Content-Type": "application/json" },
@@ -239,7 +238,7 @@ export const useTicketStore = create<TicketStoreState>((set, get) => ({
   convertToTask: async (ticketId) => {
     try {
       const response = await fetch(
-        `${API_BASE}/api/tickets/${ticketId}/convert-to-task`,
+        `${apiBaseUrl()}/api/tickets/${ticketId}/convert-to-task`,
         {
           method: "POST",
           headers: { "Content-Type": "application/json" },
@@ -7,6 +7,7 @@
 
 import { create } from "zustand";
 import { persist, createJSONStorage } from "zustand/middleware";
+import { apiBaseUrl } from "@/lib/servicePorts";
 
 // =============================================================================
 // Types
@@ -52,13 +53,13 @@ export type NavigationTarget =
 // API Helpers
 // =============================================================================
 
-const API_BASE = "http://localhost:19432/api/context";
+const apiBase = () => `${apiBaseUrl()}/api/context`;
 
 async function fetchApi<T>(
   endpoint: string,
   options?: RequestInit
 ): Promise<T> {
-  const response = await fetch(`${API_BASE}${endpoint}`, {
+  const response = await fetch(`${apiBase()}${endpoint}`, {
     ...options,
     headers: {
       "Content-Type": "application/json",
@@ -306,7 +307,7 @@ export const useUnifiedContextStore = create<UnifiedContextStore>()(
 
         try {
           // Fetch task details to extract keywords
-          const taskRes = await fetch(`http://localhost:19432/api/tasks/${taskId}`);
+          const taskRes = await fetch(`${apiBaseUrl()}/api/tasks/${taskId}`);
           if (!taskRes.ok) {
             set({ suggestions: [] });
             return;
@@ -333,7 +334,7 @@ export const useUnifiedContextStore = create<UnifiedContextStore>()(
           // Search knowledge base using keywords
           const query = keywords.join(' ');
           const searchRes = await fetch(
-            `http://localhost:19432/api/knowledge/search?q=${encodeURIComponent(query)}&limit=5&projectPath=${encodeURIComponent(task.projectId || '')}`
+            `${apiBaseUrl()}/api/knowledge/search?q=${encodeURIComponent(query)}&limit=5&projectPath=${encodeURIComponent(task.projectId || '')}`
           );
 
           if (!searchRes.ok) {
@@ -18,6 +18,11 @@ export interface BlockData {
   // Working directory (for sessions, etc.)
   cwd?: string;
 
+  // Agent tabs - relaunched (and resumed) on workspace restore
+  agentRole?: string;
+  launchProfile?: "shell" | "claude-session";
+  claudeSessionId?: string;
+
   // Preview-specific
   filePath?: string;
 
@@ -31,6 +31,7 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
+import { apiBaseUrl } from "@/lib/servicePorts";
 import { cn } from "@/lib/utils";
 import { useAppStore } from "@/stores/appStore";
 
@@ -67,10 +68,8 @@ type PriorityFilter = "all" | "high" | "medium" | "low";
 // API HELPERS
 // =============================================================================
 
-const API_BASE = "http://127.0.0.1:19432";
-
 async function fetchTasks(projectId: string = "default"): Promise<SidStackTask[]> {
-  const url = `${API_BASE}/api/tasks?projectId=${projectId}`;
+  const url = `${apiBaseUrl()}/api/tasks?projectId=${projectId}`;
   try {
     const res = await fetch(url);
     if (!res.ok) throw new Error("Failed to fetch tasks");